@@ -6,6 +6,20 @@ export interface Feed {
   description: string | null
   created_at: string
   updated_at: string
+  category: string | null
+  category_id: number | null
+  paused: boolean
+  site_url: string | null
+}
+
+/** Hierarchical feed category (folder) */
+export interface Category {
+  id: number
+  name: string
+  parent_id: number | null
+  position: number
+  path: string
+  depth: number
 }
 
 /** Article information */
@@ -19,6 +33,119 @@ export interface Article {
   is_read: boolean
   is_favorite: boolean
   created_at: string
+  read_at: string | null
+  first_opened_at: string | null
+  /** Original article when the same story arrived through another feed */
+  duplicate_of: number | null
+  duplicate_count: number
+}
+
+/** Tag with the number of tagged articles */
+export interface TagSummary {
+  id: number
+  name: string
+  created_at: string
+  article_count: number
+}
+
+/** Read-later queue entry (article fields plus queue metadata) */
+export interface QueueEntry extends Article {
+  position: number
+  added_at: string
+}
+
+/** Event recorded in the reading history */
+export type HistoryEvent = 'opened' | 'read' | 'unread'
+
+/** Reading history entry with the article's current state */
+export interface HistoryEntry extends Article {
+  history_id: number
+  event: HistoryEvent
+  occurred_at: string
+}
+
+/** Per-feed numbers in `get_stats` */
+export interface FeedStats {
+  feed_id: number
+  name: string
+  category: string | null
+  total: number
+  unread: number
+  read: number
+  read_ratio: number
+  recent_articles: number
+  articles_per_week: number
+  last_article_at: string | null
+}
+
+/** Per-category numbers in `get_stats` */
+export interface CategoryStats {
+  category: string | null
+  feeds: number
+  total: number
+  unread: number
+  read: number
+  read_ratio: number
+}
+
+/** Subscription and reading statistics (hours and dates are UTC) */
+export interface Stats {
+  generated_at: string
+  options: { stale_days: number; frequency_days: number; activity_days: number }
+  totals: {
+    feeds: number
+    articles: number
+    unread: number
+    read: number
+    favorites: number
+    read_ratio: number
+  }
+  feeds: FeedStats[]
+  categories: CategoryStats[]
+  stale_feeds: {
+    feed_id: number
+    name: string
+    last_article_at: string | null
+    days_since_last_article: number | null
+  }[]
+  publish_hours: number[]
+  reading_hours: number[]
+  reading_activity: { date: string; reads: number }[]
+}
+
+/** Free-form note attached to an article */
+export interface Note {
+  article_id: number
+  body: string
+  created_at: string
+  updated_at: string
+}
+
+/** Highlighted range of an article's content (character offsets) */
+export interface Highlight {
+  id: number
+  article_id: number
+  start: number
+  end: number
+  text: string
+  created_at: string
+}
+
+/** One page of articles loaded with `get_articles_page` */
+export interface ArticlePage {
+  articles: Article[]
+  next_cursor: string | null
+}
+
+/** Per-feed result of `import_opml` */
+export interface OpmlImportEntry {
+  url: string
+  name: string | null
+  category: string | null
+  status:
+    | { kind: 'added'; feed_id: number }
+    | { kind: 'already_subscribed'; feed_id: number }
+    | { kind: 'failed'; error: string }
 }
 
 /** Result of fetching all feeds */
@@ -3,18 +3,44 @@
 //! このモジュールは、rustfeed CLI の各サブコマンドの実装を提供します。
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc};
 use colored::Colorize;
-use rustfeed_core::{db::Database, feed, Article};
+use rustfeed_core::{
+    backup,
+    config::{BackupConfig, EmailConfig},
+    db::{Database, FetchLease},
+    dedup,
+    digest::{self, Digest, DigestFormat, Template},
+    dump::{self, LoadMode},
+    editor,
+    email::{self, Email},
+    export::{self, ExportFormat},
+    feed,
+    import::{self, ImportSource},
+    opml::{self, OpmlImportStatus},
+    publish::{self, FeedMetadata, PublishFormat},
+    stats::{DailyActivity, Stats, StatsOptions},
+    ArticleQuery, Feed, HistoryEvent, Storage,
+};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 // =============================================================================
 // フィード管理コマンド
 // =============================================================================
 
 /// 新しいRSSフィードを追加する
-pub async fn add_feed(db: &Database, url: &str, name: Option<&str>) -> Result<()> {
+pub async fn add_feed(db: &dyn Storage, url: &str, name: Option<&str>) -> Result<()> {
+    // 表記が違うだけの登録済みのフィードは、取得する前に弾く
+    let url = dedup::normalize_feed_url(url)?;
+    if let Some(existing) = db.find_feed_by_url(&url)? {
+        anyhow::bail!("Feed already exists: {} (ID {})", existing.url, existing.id);
+    }
+
     println!("{} {}", "Fetching feed:".blue(), url);
 
-    let (mut feed_info, _articles) = feed::fetch_feed(url)
+    let (mut feed_info, _articles) = feed::fetch_feed(&url)
         .await
         .with_context(|| format!("Failed to fetch feed from {}", url))?;
 
@@ -35,7 +61,7 @@ pub async fn add_feed(db: &Database, url: &str, name: Option<&str>) -> Result<()
 }
 
 /// RSSフィードを削除する
-pub fn remove_feed(db: &Database, id: i64) -> Result<()> {
+pub fn remove_feed(db: &dyn Storage, id: i64) -> Result<()> {
     if db.remove_feed(id)? {
         println!("{} {}", "Removed feed with ID:".green(), id);
     } else {
@@ -45,7 +71,7 @@ pub fn remove_feed(db: &Database, id: i64) -> Result<()> {
 }
 
 /// 登録済みの全フィードを一覧表示する
-pub fn list_feeds(db: &Database, category: Option<&str>) -> Result<()> {
+pub fn list_feeds(db: &dyn Storage, category: Option<&str>) -> Result<()> {
     let feeds = db.get_feeds(category)?;
 
     if feeds.is_empty() {
@@ -77,6 +103,10 @@ pub fn list_feeds(db: &Database, category: Option<&str>) -> Result<()> {
             );
         }
 
+        if feed.paused {
+            info_parts.push("(paused)".yellow().to_string());
+        }
+
         info_parts.push(format!("({})", feed.url).dimmed().to_string());
 
         println!("  {}", info_parts.join(" "));
@@ -91,7 +121,7 @@ pub fn list_feeds(db: &Database, category: Option<&str>) -> Result<()> {
 }
 
 /// フィードの名前を変更する
-pub fn rename_feed(db: &Database, feed_id: i64, name: &str) -> Result<()> {
+pub fn rename_feed(db: &dyn Storage, feed_id: i64, name: &str) -> Result<()> {
     let custom_name = if name.is_empty() { None } else { Some(name) };
 
     db.rename_feed(feed_id, custom_name)?;
@@ -116,8 +146,9 @@ pub fn rename_feed(db: &Database, feed_id: i64, name: &str) -> Result<()> {
 }
 
 /// フィードのURLを更新する
-pub fn update_feed_url(db: &Database, feed_id: i64, new_url: &str) -> Result<()> {
-    db.update_feed_url(feed_id, new_url)?;
+pub fn update_feed_url(db: &dyn Storage, feed_id: i64, new_url: &str) -> Result<()> {
+    let new_url = dedup::normalize_feed_url(new_url)?;
+    db.update_feed_url(feed_id, &new_url)?;
 
     println!(
         "{} {} {} {}",
@@ -131,13 +162,26 @@ pub fn update_feed_url(db: &Database, feed_id: i64, new_url: &str) -> Result<()>
 }
 
 /// フィードのカテゴリを設定する
-pub fn set_feed_category(db: &Database, feed_id: i64, category: &str) -> Result<()> {
+///
+/// `create` が true の場合、存在しないカテゴリを作成してから設定します。
+pub fn set_feed_category(db: &Database, feed_id: i64, category: &str, create: bool) -> Result<()> {
     let cat = if category.is_empty() {
         None
     } else {
         Some(category)
     };
 
+    if let Some(path) = cat {
+        if create {
+            db.create_category(path)?;
+        } else if db.get_category(path)?.is_none() {
+            anyhow::bail!(
+                "Category not found: {}. Use --create or 'rustfeed add-category' to create it.",
+                path
+            );
+        }
+    }
+
     db.set_feed_category(feed_id, cat)?;
 
     if cat.is_some() {
@@ -161,7 +205,7 @@ pub fn set_feed_category(db: &Database, feed_id: i64, category: &str) -> Result<
 }
 
 /// フィードの優先順位を設定する
-pub fn set_feed_priority(db: &Database, feed_id: i64, priority: i64) -> Result<()> {
+pub fn set_feed_priority(db: &dyn Storage, feed_id: i64, priority: i64) -> Result<()> {
     db.set_feed_priority(feed_id, priority)?;
 
     println!(
@@ -175,8 +219,253 @@ pub fn set_feed_priority(db: &Database, feed_id: i64, priority: i64) -> Result<(
     Ok(())
 }
 
+/// フィードを一時停止・再開する
+pub fn set_feed_paused(db: &dyn Storage, feed_id: i64, paused: bool) -> Result<()> {
+    if !db.set_feed_paused(feed_id, paused)? {
+        println!("{} {}", "Feed not found with ID:".yellow(), feed_id);
+        return Ok(());
+    }
+
+    let status = if paused { "paused" } else { "resumed" };
+    println!("{} {} {}", "Feed".green(), feed_id, status.green().bold());
+
+    Ok(())
+}
+
+/// 重複しているフィードを探し、統合を提案する
+///
+/// URLが同じとみなせるフィードと、記事の多い方に対する共通記事の割合が
+/// `min_overlap` 以上のフィードの組をIDつきで表示します。記事の多い方（同じなら先に登録した方）に
+/// 統合する提案で、`apply` にIDを指定した組だけを実際に統合します。
+/// `no_backup` が偽の場合は、統合する前に現在のデータベースをバックアップします。
+pub fn dedupe_feeds(
+    db: &Database,
+    config: &BackupConfig,
+    min_overlap: f64,
+    apply: &[String],
+    no_backup: bool,
+) -> Result<()> {
+    if !(0.0..=1.0).contains(&min_overlap) {
+        anyhow::bail!("--min-overlap must be between 0.0 and 1.0");
+    }
+
+    let overlaps = db.feed_overlaps(min_overlap)?;
+    if overlaps.is_empty() {
+        println!("{}", "No duplicate feeds found.".yellow());
+        return Ok(());
+    }
+
+    let feeds: HashMap<i64, Feed> = db
+        .get_feeds(None)?
+        .into_iter()
+        .map(|feed| (feed.id, feed))
+        .collect();
+    let name = |id: i64| {
+        feeds
+            .get(&id)
+            .map(|feed| {
+                feed.custom_name
+                    .as_deref()
+                    .unwrap_or(&feed.title)
+                    .to_string()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut proposals: HashMap<String, (i64, i64)> = HashMap::new();
+    println!("{}", "Possible duplicate feeds:".bold());
+    for overlap in &overlaps {
+        let (target, source) = if overlap.other_total > overlap.total {
+            (overlap.other_feed_id, overlap.feed_id)
+        } else {
+            (overlap.feed_id, overlap.other_feed_id)
+        };
+        let reason = if overlap.same_url {
+            "same URL".to_string()
+        } else {
+            format!(
+                "{}/{} articles shared, {:.0}%",
+                overlap.shared,
+                overlap.total.max(overlap.other_total),
+                overlap.ratio * 100.0
+            )
+        };
+        println!(
+            "  {} {} {} {} {} {}{}",
+            overlap.pair_id().cyan().bold(),
+            format!("[{}]", source).dimmed(),
+            name(source).bold(),
+            "->".cyan(),
+            format!("[{}]", target).dimmed(),
+            name(target).bold(),
+            format!(" ({})", reason).dimmed()
+        );
+        proposals.insert(overlap.pair_id(), (source, target));
+    }
+
+    if apply.is_empty() {
+        println!(
+            "\n{}",
+            "Run with --apply <PAIR_ID>... to merge the chosen pairs (read/favorite state is kept)."
+                .dimmed()
+        );
+        return Ok(());
+    }
+
+    // 統合する組を先に確かめ、1つのフィードを2回統合しないようにする
+    let mut selected: Vec<(i64, i64)> = Vec::new();
+    for pair_id in apply {
+        let Some(&(source, target)) = proposals.get(pair_id.trim()) else {
+            anyhow::bail!(
+                "Unknown pair ID: {} (choose one from the list above)",
+                pair_id
+            );
+        };
+        if let Some(feed_id) = [source, target]
+            .into_iter()
+            .find(|id| selected.iter().any(|&(s, t)| s == *id || t == *id))
+        {
+            anyhow::bail!(
+                "Feed {} is part of more than one chosen pair; merge them one at a time",
+                feed_id
+            );
+        }
+        selected.push((source, target));
+    }
+
+    if !no_backup {
+        let saved = backup::create_backup(db, &config.dir_path()?, config.keep)?;
+        println!(
+            "{} {}",
+            "Current database saved to".dimmed(),
+            saved.path.display()
+        );
+    }
+    for (source, target) in selected {
+        let report = db.merge_feeds(source, target)?;
+        println!(
+            "{} {} into {}: {} article(s) merged, {} moved.",
+            "Merged feed".green(),
+            source,
+            target,
+            report.articles_merged,
+            report.articles_moved
+        );
+    }
+
+    Ok(())
+}
+
+/// OPML の購読リストからフィードを登録する
+///
+/// フィードごとに、登録した・登録済み・失敗のいずれかを表示します。
+/// フィードの取得は行わないため、記事は次の `rustfeed fetch` で取得されます。
+pub fn import_opml(db: &Database, path: &Path) -> Result<()> {
+    let xml = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let feeds = opml::parse_opml(&xml)?;
+    if feeds.is_empty() {
+        println!("{}", "No feeds found in the OPML file.".yellow());
+        return Ok(());
+    }
+
+    let entries = db.import_opml(&feeds)?;
+    let (mut added, mut skipped, mut failed) = (0, 0, 0);
+    for entry in &entries {
+        let name = entry.name.as_deref().unwrap_or(&entry.url);
+        let category = entry
+            .category
+            .as_deref()
+            .map(|path| format!(" [{}]", path))
+            .unwrap_or_default();
+        match &entry.status {
+            OpmlImportStatus::Added { feed_id } => {
+                added += 1;
+                println!(
+                    "  {} {}{} {}",
+                    "+".green(),
+                    name.bold(),
+                    category.cyan(),
+                    format!("(ID: {})", feed_id).dimmed()
+                );
+            }
+            OpmlImportStatus::AlreadySubscribed { feed_id } => {
+                skipped += 1;
+                println!(
+                    "  {} {}{} {}",
+                    "=".yellow(),
+                    name,
+                    category.cyan(),
+                    format!("(already subscribed as ID {})", feed_id).dimmed()
+                );
+            }
+            OpmlImportStatus::Failed { error } => {
+                failed += 1;
+                println!(
+                    "  {} {}{} {}",
+                    "!".red(),
+                    name,
+                    category.cyan(),
+                    error.red()
+                );
+            }
+        }
+    }
+
+    println!(
+        "\n{} {} added, {} already subscribed, {} failed.",
+        "Imported:".green(),
+        added.to_string().cyan(),
+        skipped,
+        failed
+    );
+    if added > 0 {
+        println!(
+            "{}",
+            "Run `rustfeed fetch` to load their articles.".dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// 購読しているフィードを OPML 2.0 で書き出す
+///
+/// `category` を指定した場合は、そのカテゴリ（サブカテゴリを含む）のフィードだけを書き出します。
+/// `output` を省略した場合は標準出力に書き出します。
+pub fn export_opml(db: &Database, category: Option<&str>, output: Option<&Path>) -> Result<()> {
+    if let Some(path) = category {
+        if db.get_category(path)?.is_none() {
+            anyhow::bail!("Category not found: {}", path);
+        }
+    }
+
+    let feeds = db.get_feeds(category)?;
+    let title = match category {
+        Some(path) => format!("rustfeed subscriptions: {}", path),
+        None => "rustfeed subscriptions".to_string(),
+    };
+    let xml = opml::write_opml(&title, &feeds)?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, xml)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!(
+                "{} {} {}",
+                "Exported to".green(),
+                path.display(),
+                format!("({} feed(s))", feeds.len()).dimmed()
+            );
+        }
+        None => print!("{}", xml),
+    }
+
+    Ok(())
+}
+
 /// フィードの詳細情報を表示する
-pub fn show_feed_info(db: &Database, feed_id: i64) -> Result<()> {
+pub fn show_feed_info(db: &dyn Storage, feed_id: i64) -> Result<()> {
     let feed = db.get_feed(feed_id)?;
 
     if let Some(feed) = feed {
@@ -209,6 +498,10 @@ pub fn show_feed_info(db: &Database, feed_id: i64) -> Result<()> {
             feed.priority.to_string().magenta()
         );
 
+        if feed.paused {
+            println!("  {}: {}", "Status".cyan(), "paused".yellow());
+        }
+
         println!(
             "  {}: {}",
             "Created".cyan(),
@@ -240,33 +533,74 @@ pub fn show_feed_info(db: &Database, feed_id: i64) -> Result<()> {
 // =============================================================================
 
 /// 全フィードから新しい記事を取得する
-pub async fn fetch_feeds(db: &Database) -> Result<()> {
-    let feeds = db.get_feeds(None)?;
+///
+/// 一時停止中のフィードは取得しません。
+/// 他のプロセス（cron の fetch や TUI/GUI）がフェッチ中の場合は何もせずに終了します。
+pub async fn fetch_feeds(db: &dyn Storage) -> Result<()> {
+    let (feeds, paused): (Vec<Feed>, Vec<Feed>) = db
+        .get_feeds(None)?
+        .into_iter()
+        .partition(|feed| !feed.paused);
 
     if feeds.is_empty() {
-        println!("{}", "No feeds registered yet.".yellow());
+        if paused.is_empty() {
+            println!("{}", "No feeds registered yet.".yellow());
+        } else {
+            println!("{}", "All feeds are paused.".yellow());
+        }
+        return Ok(());
+    }
+
+    let Some(mut lease) = db.try_acquire_fetch_lease()? else {
+        println!(
+            "{}",
+            "Another rustfeed process is already fetching. Skipping.".yellow()
+        );
         return Ok(());
+    };
+
+    // エラーで抜けてもリースは必ず解放する
+    let result = fetch_all_with_lease(db, feeds, &mut lease).await;
+    db.release_fetch_lease(&lease)?;
+    result?;
+
+    if !paused.is_empty() {
+        println!(
+            "{}",
+            format!("{} paused feed(s) skipped.", paused.len()).dimmed()
+        );
     }
 
+    Ok(())
+}
+
+/// リースを保持した状態で全フィードを更新する
+async fn fetch_all_with_lease(
+    db: &dyn Storage,
+    feeds: Vec<Feed>,
+    lease: &mut FetchLease,
+) -> Result<()> {
     println!("{}", "Fetching articles from all feeds...".blue());
     println!();
 
     let mut total_new = 0;
 
     for stored_feed in feeds {
+        // フィードが多い場合にリースが期限切れにならないよう延長する
+        // 期限切れで他のプロセスに取られていたら、同時に更新しないようここで止める
+        if !db.renew_fetch_lease(lease)? {
+            println!();
+            anyhow::bail!(
+                "Fetch lease was taken over by another rustfeed process; stopped after {} new articles",
+                total_new
+            );
+        }
+
         print!("  {} {}... ", "Fetching".dimmed(), stored_feed.title);
 
         match feed::fetch_feed(&stored_feed.url).await {
-            Ok((_feed_info, articles)) => {
-                let mut new_count = 0;
-
-                for mut article in articles {
-                    article.feed_id = stored_feed.id;
-
-                    if db.add_article(&article)?.is_some() {
-                        new_count += 1;
-                    }
-                }
+            Ok(feed_data) => {
+                let new_count = feed::save_articles(db, stored_feed.id, &feed_data)?;
 
                 println!("{} ({} new)", "OK".green(), new_count.to_string().cyan());
 
@@ -288,43 +622,35 @@ pub async fn fetch_feeds(db: &Database) -> Result<()> {
     Ok(())
 }
 
-/// 記事を一覧表示する
-#[allow(clippy::too_many_arguments)]
-pub fn show_articles(
-    db: &Database,
-    unread_only: bool,
-    limit: usize,
-    filter: Option<&str>,
-    feed_id: Option<i64>,
-    disabled_feeds: &[i64],
-    after: Option<&str>,
-    before: Option<&str>,
-) -> Result<()> {
-    let mut articles = db.get_articles(unread_only, limit, filter, feed_id)?;
-
-    // disabled_feedsでフィルタリング
-    if feed_id.is_none() && !disabled_feeds.is_empty() {
-        articles.retain(|article| !disabled_feeds.contains(&article.feed_id));
-    }
+/// 日時の範囲（下限, 上限）
+pub type DateRange = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+/// `--after` / `--before` の日付文字列（YYYY-MM-DD形式）を日時の範囲に変換する
+///
+/// # 戻り値
+///
+/// `(after, before)` のタプル。`after` はその日の 00:00 (UTC)、
+/// `before` はその日を含めるため翌日の 00:00 (UTC) になります。
+pub fn parse_date_range(after: Option<&str>, before: Option<&str>) -> Result<DateRange> {
+    let after = after.map(parse_date).transpose()?;
+    let before = before
+        .map(parse_date)
+        .transpose()?
+        .map(|dt| dt + Duration::days(1));
+    Ok((after, before))
+}
 
-    // 日付範囲でフィルタリング
-    if let Some(after_date) = after {
-        articles.retain(|article| {
-            article
-                .published_at
-                .map(|dt| dt.format("%Y-%m-%d").to_string().as_str() >= after_date)
-                .unwrap_or(false)
-        });
-    }
+/// YYYY-MM-DD 形式の日付をその日の 00:00 (UTC) に変換する
+fn parse_date(date: &str) -> Result<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .with_context(|| format!("Invalid date '{}'. Use YYYY-MM-DD format.", date))?;
+    Ok(date.and_time(NaiveTime::MIN).and_utc())
+}
 
-    if let Some(before_date) = before {
-        articles.retain(|article| {
-            article
-                .published_at
-                .map(|dt| dt.format("%Y-%m-%d").to_string().as_str() <= before_date)
-                .unwrap_or(false)
-        });
-    }
+/// 記事を一覧表示する
+pub fn show_articles(db: &dyn Storage, query: &ArticleQuery) -> Result<()> {
+    let articles = db.query_articles(query)?;
+    let unread_only = query.is_read == Some(false);
 
     if articles.is_empty() {
         if unread_only {
@@ -344,6 +670,9 @@ pub fn show_articles(
     println!("{}", header.bold().underline());
     println!();
 
+    let ids: Vec<i64> = articles.iter().map(|a| a.id).collect();
+    let tags = db.get_tags_for_articles(&ids)?;
+
     for article in articles {
         let read_marker = if article.is_read {
             "[x]".dimmed()
@@ -356,12 +685,32 @@ pub fn show_articles(
             .map(|dt| dt.format("%Y-%m-%d").to_string())
             .unwrap_or_else(|| "----------".to_string());
 
+        let tag_labels = tags
+            .get(&article.id)
+            .map(|names| {
+                names
+                    .iter()
+                    .map(|name| format!("#{}", name))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .unwrap_or_default();
+
+        // 重複記事は元の記事を、元の記事は重複記事の数を示す
+        let duplicates = match (article.duplicate_of, article.duplicate_count) {
+            (Some(original), _) => format!("(duplicate of {})", original),
+            (None, 0) => String::new(),
+            (None, count) => format!("(+{} duplicate(s))", count),
+        };
+
         println!(
-            "  {} {} {} {}",
+            "  {} {} {} {} {} {}",
             read_marker,
             format!("[{}]", article.id).dimmed(),
             date.dimmed(),
-            article.title.bold()
+            article.title.bold(),
+            tag_labels.green(),
+            duplicates.dimmed()
         );
 
         if let Some(url) = &article.url {
@@ -373,7 +722,7 @@ pub fn show_articles(
 }
 
 /// 記事を既読としてマークする
-pub fn mark_as_read(db: &Database, id: i64) -> Result<()> {
+pub fn mark_as_read(db: &dyn Storage, id: i64) -> Result<()> {
     if db.mark_as_read(id)? {
         println!("{} {}", "Marked as read:".green(), id);
     } else {
@@ -383,7 +732,7 @@ pub fn mark_as_read(db: &Database, id: i64) -> Result<()> {
 }
 
 /// 記事をお気に入りに追加する
-pub fn add_favorite(db: &Database, id: i64) -> Result<()> {
+pub fn add_favorite(db: &dyn Storage, id: i64) -> Result<()> {
     if db.add_favorite(id)? {
         println!("{} {}", "Added to favorites:".green(), id);
     } else {
@@ -393,7 +742,7 @@ pub fn add_favorite(db: &Database, id: i64) -> Result<()> {
 }
 
 /// 記事をお気に入りから削除する
-pub fn remove_favorite(db: &Database, id: i64) -> Result<()> {
+pub fn remove_favorite(db: &dyn Storage, id: i64) -> Result<()> {
     if db.remove_favorite(id)? {
         println!("{} {}", "Removed from favorites:".green(), id);
     } else {
@@ -403,7 +752,7 @@ pub fn remove_favorite(db: &Database, id: i64) -> Result<()> {
 }
 
 /// お気に入り記事を一覧表示する
-pub fn show_favorites(db: &Database, limit: usize) -> Result<()> {
+pub fn show_favorites(db: &dyn Storage, limit: usize) -> Result<()> {
     let articles = db.get_favorite_articles(limit)?;
 
     if articles.is_empty() {
@@ -443,155 +792,855 @@ pub fn show_favorites(db: &Database, limit: usize) -> Result<()> {
     Ok(())
 }
 
+/// 読書履歴を新しい順に表示する
+///
+/// 通常は既読にした記録のみを表示し、`all` が true の場合は
+/// 開いた・未読に戻した記録も表示します。
+pub fn show_history(
+    db: &Database,
+    limit: usize,
+    after: Option<DateTime<Utc>>,
+    before: Option<DateTime<Utc>>,
+    all: bool,
+) -> Result<()> {
+    let events: &[HistoryEvent] = if all { &[] } else { &[HistoryEvent::Read] };
+    let entries = db.get_history(events, after, before, Some(limit))?;
+
+    if entries.is_empty() {
+        println!("{}", "No reading history.".yellow());
+        return Ok(());
+    }
+
+    println!("{}", "Reading History:".bold().underline());
+    println!();
+
+    for entry in entries {
+        let event = match entry.event {
+            HistoryEvent::Read => "read  ".green(),
+            HistoryEvent::Unread => "unread".yellow(),
+            HistoryEvent::Opened => "opened".cyan(),
+        };
+
+        println!(
+            "  {} {} {} {}",
+            entry
+                .occurred_at
+                .format("%Y-%m-%d %a %H:%M")
+                .to_string()
+                .dimmed(),
+            event,
+            format!("[{}]", entry.article.id).dimmed(),
+            entry.article.title.bold()
+        );
+
+        if let Some(url) = &entry.article.url {
+            println!("      {}", url.dimmed());
+        }
+    }
+
+    Ok(())
+}
+
 /// 記事をエクスポートする
+///
+/// `output` を省略すると標準出力に書き出します（EPUB は出力先の指定が必要）。
 pub fn export_articles(
     db: &Database,
+    query: &ArticleQuery,
     format: &str,
-    favorites: bool,
-    unread: bool,
-    limit: Option<usize>,
+    output: Option<&Path>,
 ) -> Result<()> {
-    let articles = if favorites {
-        let limit_val = limit.unwrap_or(usize::MAX);
-        db.get_favorite_articles(limit_val)?
-    } else {
-        let limit_val = limit.unwrap_or(usize::MAX);
-        db.get_articles(unread, limit_val, None, None)?
-    };
+    let format: ExportFormat = format.parse()?;
+    if format.is_binary() && output.is_none() {
+        anyhow::bail!(
+            "The {} format needs an output file. Use --output <FILE>.{}",
+            format,
+            format.extension()
+        );
+    }
 
-    if articles.is_empty() {
+    if db.count_articles(query)? == 0 {
         eprintln!("{}", "No articles to export.".yellow());
         return Ok(());
     }
 
-    match format.to_lowercase().as_str() {
-        "json" => export_as_json(&articles)?,
-        "markdown" | "md" => export_as_markdown(&articles)?,
-        _ => {
-            anyhow::bail!(
-                "Unsupported format: '{}'. Use 'json' or 'markdown'.",
-                format
-            );
+    // 記事はページ単位で取得しながら書き出すため、全件をメモリに載せない
+    let out: Box<dyn Write> = match output {
+        Some(path) => Box::new(std::io::BufWriter::new(
+            std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?,
+        )),
+        None => Box::new(std::io::BufWriter::new(std::io::stdout())),
+    };
+    let exporter = export::new_exporter(format, out, "Exported Articles");
+    let count = match export::export_query(db, query, exporter) {
+        Ok(count) => count,
+        Err(e) => {
+            // 書きかけのファイルを残さない
+            if let Some(path) = output {
+                let _ = std::fs::remove_file(path);
+            }
+            return Err(e);
         }
+    };
+
+    if let Some(path) = output {
+        eprintln!(
+            "{} {}",
+            format!("Exported {} article(s) to", count).green(),
+            path.display()
+        );
     }
 
     Ok(())
 }
 
-fn export_as_json(articles: &[Article]) -> Result<()> {
-    let json =
-        serde_json::to_string_pretty(articles).context("Failed to serialize articles to JSON")?;
-
-    println!("{}", json);
+/// 記事を Atom・RSS・JSON Feed のフィードとして書き出す
+pub fn publish(
+    db: &Database,
+    query: &ArticleQuery,
+    format: &str,
+    metadata: &FeedMetadata,
+    output: &Path,
+) -> Result<()> {
+    let format: PublishFormat = format.parse()?;
+    let articles = export::annotate(db, db.query_articles(query)?)?;
+    let document = publish::write_feed(format, metadata, &articles)?;
+
+    // 配信中のファイルが途中まで書かれた状態にならないよう、一時ファイルから名前を変更する
+    let mut tmp_name = output.as_os_str().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_name);
+    let written =
+        std::fs::write(&tmp_path, document).and_then(|()| std::fs::rename(&tmp_path, output));
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e).with_context(|| format!("Failed to write {}", output.display()));
+    }
+    eprintln!(
+        "{} {} {}",
+        format!("Published {} article(s) as {} to", articles.len(), format).green(),
+        output.display(),
+        format!("({})", metadata.title).dimmed()
+    );
 
     Ok(())
 }
 
-fn export_as_markdown(articles: &[Article]) -> Result<()> {
-    println!("# Exported Articles\n");
-    println!("Total: {} articles\n", articles.len());
-    println!("---\n");
+/// `rustfeed digest` の出力の指定
+pub struct DigestOptions<'a> {
+    /// 形式（markdown、html、text）
+    pub format: &'a str,
 
-    for (index, article) in articles.iter().enumerate() {
-        println!("## {}. {}\n", index + 1, article.title);
+    /// テンプレートのファイル（None の場合は設定ディレクトリのもの、なければ組み込みのもの）
+    pub template: Option<&'a Path>,
 
-        if let Some(ref url) = article.url {
-            println!("**URL:** {}\n", url);
-        }
+    /// 出力先のファイル（None の場合は標準出力）
+    pub output: Option<&'a Path>,
 
-        if let Some(published_at) = article.published_at {
-            println!(
-                "**Published:** {}\n",
-                published_at.format("%Y-%m-%d %H:%M:%S")
-            );
-        }
+    /// ダイジェストのタイトル
+    pub title: &'a str,
 
-        println!("**Read:** {}\n", if article.is_read { "Yes" } else { "No" });
-        println!(
-            "**Favorite:** {}\n",
-            if article.is_favorite { "Yes" } else { "No" }
-        );
+    /// 日時の書式（chrono 形式）
+    pub date_format: &'a str,
+
+    /// 出力したあと、含めた記事を既読にするか
+    pub mark_read: bool,
+
+    /// 出力する代わりにメールで送る場合の SMTP の設定
+    pub email: Option<&'a EmailConfig>,
+}
 
-        if let Some(ref content) = article.content {
-            println!("### Content\n");
-            println!("{}\n", content);
+/// 期間内の記事をカテゴリ・フィードごとにまとめたダイジェストを出力する
+///
+/// 既読にするのは、ダイジェストを最後まで書き出せた（送れた）場合だけです。
+pub fn digest(db: &Database, query: &ArticleQuery, options: &DigestOptions) -> Result<()> {
+    if let Some(config) = options.email {
+        let digest = digest::build_digest(db, query, options.title, options.date_format)?;
+        if digest.article_count == 0 {
+            eprintln!("{}", "No new articles. Nothing was sent.".yellow());
+            return Ok(());
         }
+        send_digest(&digest, config)?;
+        if options.mark_read {
+            mark_digest_read(db, &digest)?;
+        }
+        return Ok(());
+    }
+
+    let format: DigestFormat = options.format.parse()?;
+    let template = match options.template {
+        Some(path) => {
+            let source = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            Template::parse(&source)
+                .with_context(|| format!("Invalid template {}", path.display()))?
+        }
+        None => digest::load_template(format)?,
+    };
 
-        if index < articles.len() - 1 {
-            println!("---\n");
+    let digest = digest::build_digest(db, query, options.title, options.date_format)?;
+    let document = digest.render(&template, format)?;
+
+    match options.output {
+        Some(path) => {
+            std::fs::write(path, &document)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!(
+                "{} {}",
+                format!(
+                    "Wrote a digest of {} article(s) from {} feed(s) to",
+                    digest.article_count, digest.feed_count
+                )
+                .green(),
+                path.display()
+            );
+        }
+        None => {
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(document.as_bytes())?;
+            stdout.flush()?;
         }
     }
 
+    if options.mark_read {
+        mark_digest_read(db, &digest)?;
+    }
+
     Ok(())
 }
 
-// =============================================================================
-// 既読管理コマンド
-// =============================================================================
+/// ダイジェストをテキストと HTML の両方のテンプレートで描画し、メールで送る
+fn send_digest(digest: &Digest, config: &EmailConfig) -> Result<()> {
+    let text = digest.render(
+        &digest::load_template(DigestFormat::Text)?,
+        DigestFormat::Text,
+    )?;
+    let html = digest.render(
+        &digest::load_template(DigestFormat::Html)?,
+        DigestFormat::Html,
+    )?;
+    let message = Email {
+        from: config.from.clone(),
+        to: config.to.clone(),
+        subject: digest.title.clone(),
+        text,
+        html: Some(html),
+    };
+    email::send_email(config, &message)?;
 
-/// 記事を一括で既読にする
-pub fn mark_all_read(db: &Database, feed_id: Option<i64>, before_date: Option<&str>) -> Result<()> {
-    let count = db.mark_all_read_with_filter(feed_id, before_date)?;
+    eprintln!(
+        "{} {}",
+        format!(
+            "Sent a digest of {} article(s) from {} feed(s) to",
+            digest.article_count, digest.feed_count
+        )
+        .green(),
+        config.to.join(", ")
+    );
+    Ok(())
+}
 
-    if count == 0 {
-        println!("{}", "No articles matched the criteria.".yellow());
-    } else {
+/// ダイジェストに含めた記事を既読にする
+fn mark_digest_read(db: &Database, digest: &Digest) -> Result<()> {
+    let marked = db.mark_articles_read(&digest.article_ids())?;
+    eprintln!(
+        "{}",
+        format!("Marked {} article(s) as read.", marked).green()
+    );
+    Ok(())
+}
+
+/// 組み込みのダイジェストのテンプレートを設定ディレクトリに書き出す
+pub fn init_digest_templates() -> Result<()> {
+    let written = digest::write_default_templates()?;
+    if written.is_empty() {
         println!(
             "{} {}",
-            format!("Marked {} article(s) as read.", count).green(),
-            "✓".green().bold()
+            "Templates already exist in".yellow(),
+            DigestFormat::Markdown
+                .template_path()?
+                .parent()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_default()
         );
+        return Ok(());
+    }
+
+    for path in &written {
+        println!("{} {}", "Wrote".green(), path.display());
     }
+    println!(
+        "{}",
+        "Edit these files to change how `rustfeed digest` looks.".dimmed()
+    );
 
     Ok(())
 }
 
-/// 記事を未読に戻す
-pub fn mark_unread(db: &Database, id: Option<i64>, feed_id: Option<i64>, all: bool) -> Result<()> {
-    let options_count = [id.is_some(), feed_id.is_some(), all]
-        .iter()
-        .filter(|&&x| x)
-        .count();
+// =============================================================================
+// タグ管理コマンド
+// =============================================================================
 
-    if options_count == 0 {
-        anyhow::bail!("Please specify either an article ID, --feed, or --all");
-    }
+/// 記事にタグを付ける
+pub fn tag_article(db: &dyn Storage, id: i64, tags: &[String]) -> Result<()> {
+    let names: Vec<&str> = tags.iter().map(String::as_str).collect();
+    let added = db.tag_article(id, &names)?;
 
-    if options_count > 1 {
-        anyhow::bail!("Please specify only one of: article ID, --feed, or --all");
-    }
+    let current = db.get_article_tags(id)?;
+    println!(
+        "{} {} {}",
+        format!("Added {} tag(s) to article {}.", added, id).green(),
+        "Tags:".dimmed(),
+        current.join(", ").cyan()
+    );
 
-    let count = if let Some(article_id) = id {
-        let success = db.mark_as_unread(article_id)?;
-        if !success {
-            anyhow::bail!("Article not found with ID: {}", article_id);
-        }
-        1
-    } else if let Some(feed) = feed_id {
-        db.mark_all_unread_by_feed(feed)?
-    } else {
-        db.mark_all_unread()?
-    };
+    Ok(())
+}
 
-    if count == 0 {
-        println!("{}", "No articles to mark as unread.".yellow());
+/// 記事からタグを外す
+pub fn untag_article(db: &dyn Storage, id: i64, tags: &[String]) -> Result<()> {
+    let names: Vec<&str> = tags.iter().map(String::as_str).collect();
+    let removed = db.untag_article(id, &names)?;
+
+    if removed == 0 {
+        println!("{}", "No matching tags on this article.".yellow());
     } else {
         println!(
-            "{} {}",
-            format!("Marked {} article(s) as unread.", count).green(),
-            "✓".green().bold()
+            "{}",
+            format!("Removed {} tag(s) from article {}.", removed, id).green()
         );
     }
 
     Ok(())
 }
 
-/// 記事の既読/未読状態を反転する
-pub fn toggle_read(db: &Database, id: i64) -> Result<()> {
-    let success = db.toggle_read_status(id)?;
+/// タグの一覧を表示する
+pub fn list_tags(db: &dyn Storage) -> Result<()> {
+    let tags = db.get_tags()?;
 
-    if !success {
-        anyhow::bail!("Article not found with ID: {}", id);
+    if tags.is_empty() {
+        println!("{}", "No tags yet.".yellow());
+        println!("Use 'rustfeed tag <article_id> <tags...>' to tag articles.");
+        return Ok(());
+    }
+
+    println!("{}", "Tags:".bold().underline());
+    println!();
+
+    for (tag, count) in tags {
+        println!(
+            "  {} {}",
+            format!("#{}", tag.name).green().bold(),
+            format!("({} articles)", count).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// タグの名前を変更する
+pub fn rename_tag(db: &dyn Storage, old: &str, new: &str) -> Result<()> {
+    if db.rename_tag(old, new)? {
+        println!(
+            "{} {} {}",
+            format!("Tag '{}'", old).green(),
+            "renamed to".green().bold(),
+            format!("'{}'", new.trim()).green()
+        );
+    } else {
+        println!("{} {}", "Tag not found:".yellow(), old);
+    }
+    Ok(())
+}
+
+/// タグを統合する
+pub fn merge_tags(db: &dyn Storage, source: &str, target: &str) -> Result<()> {
+    let moved = db.merge_tags(source, target)?;
+
+    println!(
+        "{} {}",
+        format!("Merged tag '{}' into '{}'.", source, target.trim()).green(),
+        format!("({} article(s) retagged)", moved).dimmed()
+    );
+
+    Ok(())
+}
+
+/// タグを削除する
+pub fn delete_tag(db: &dyn Storage, name: &str) -> Result<()> {
+    if db.delete_tag(name)? {
+        println!("{} {}", "Deleted tag:".green(), name);
+    } else {
+        println!("{} {}", "Tag not found:".yellow(), name);
+    }
+    Ok(())
+}
+
+// =============================================================================
+// メモ・ハイライトコマンド
+// =============================================================================
+
+/// 記事のメモを編集する
+///
+/// `message` が指定された場合はそのまま保存し、指定されない場合は `$EDITOR` を開きます。
+/// `show` はメモの表示のみ、`delete` はメモの削除を行います。
+pub fn edit_note(
+    db: &dyn Storage,
+    id: i64,
+    message: Option<&str>,
+    show: bool,
+    delete: bool,
+) -> Result<()> {
+    let article = db
+        .get_article(id)?
+        .with_context(|| format!("Article not found with ID: {}", id))?;
+    let current = db.get_note(id)?;
+
+    if show {
+        match current {
+            Some(note) => {
+                println!("{} {}", format!("[{}]", id).dimmed(), article.title.bold());
+                println!();
+                println!("{}", note.body.trim_end());
+            }
+            None => println!("{} {}", "No note for article".yellow(), id),
+        }
+        return Ok(());
+    }
+
+    if delete {
+        if db.delete_note(id)? {
+            println!("{} {}", "Deleted note for article".green(), id);
+        } else {
+            println!("{} {}", "No note for article".yellow(), id);
+        }
+        return Ok(());
+    }
+
+    let body = match message {
+        Some(message) => message.to_string(),
+        None => {
+            let initial = current.map(|note| note.body).unwrap_or_default();
+            editor::edit_text(&initial, &format!("note-{}", id))?
+        }
+    };
+
+    db.save_note(id, &body)?;
+    if body.trim().is_empty() {
+        println!(
+            "{} {}",
+            "Note is empty; removed note for article".yellow(),
+            id
+        );
+    } else {
+        println!("{} {}", "Saved note for article".green(), id);
+    }
+
+    Ok(())
+}
+
+/// メモの付いた記事を一覧表示する
+pub fn list_notes(db: &dyn Storage) -> Result<()> {
+    let notes = db.get_notes()?;
+
+    if notes.is_empty() {
+        println!("{}", "No notes yet.".yellow());
+        println!("Use 'rustfeed note <article_id>' to write one.");
+        return Ok(());
+    }
+
+    println!("{}", "Notes:".bold().underline());
+    println!();
+
+    for note in notes {
+        let title = db
+            .get_article(note.article_id)?
+            .map(|article| article.title)
+            .unwrap_or_default();
+        let first_line = note.body.lines().next().unwrap_or_default();
+        let summary: String = first_line.chars().take(80).collect();
+
+        println!(
+            "  {} {} {}",
+            format!("[{}]", note.article_id).dimmed(),
+            title.bold(),
+            format!("(updated {})", note.updated_at.format("%Y-%m-%d")).dimmed()
+        );
+        println!("      {}", summary);
+    }
+
+    Ok(())
+}
+
+/// 記事の本文中のテキストをハイライトとして保存する
+pub fn add_highlight(db: &dyn Storage, id: i64, text: &str) -> Result<()> {
+    let highlight = db.add_highlight_for_text(id, text)?;
+    println!(
+        "{} {} {}",
+        "Saved highlight".green(),
+        format!("[{}]", highlight.id).cyan(),
+        format!("({}..{})", highlight.start, highlight.end).dimmed()
+    );
+    Ok(())
+}
+
+/// 記事のハイライトを一覧表示する
+pub fn list_highlights(db: &dyn Storage, id: i64) -> Result<()> {
+    let highlights = db.get_highlights(id)?;
+
+    if highlights.is_empty() {
+        println!("{} {}", "No highlights for article".yellow(), id);
+        println!("Use 'rustfeed highlight <article_id> <text>' to add one.");
+        return Ok(());
+    }
+
+    println!("{}", "Highlights:".bold().underline());
+    println!();
+
+    for highlight in highlights {
+        println!(
+            "  {} {} {}",
+            format!("[{}]", highlight.id).cyan(),
+            format!("\"{}\"", highlight.text).yellow(),
+            format!("({}..{})", highlight.start, highlight.end).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// ハイライトを削除する
+pub fn delete_highlight(db: &dyn Storage, id: i64) -> Result<()> {
+    if db.delete_highlight(id)? {
+        println!("{} {}", "Deleted highlight:".green(), id);
+    } else {
+        println!("{} {}", "Highlight not found:".yellow(), id);
+    }
+    Ok(())
+}
+
+// =============================================================================
+// 「あとで読む」キューコマンド
+// =============================================================================
+
+/// 記事をキューに追加する
+pub fn queue_add(db: &dyn Storage, ids: &[i64]) -> Result<()> {
+    for &id in ids {
+        if db.add_to_queue(id)? {
+            println!("{} {}", "Added to read-later queue:".green(), id);
+        } else {
+            println!("{} {}", "Already in the queue:".yellow(), id);
+        }
+    }
+    Ok(())
+}
+
+/// 記事をキューから外す
+pub fn queue_remove(db: &dyn Storage, id: i64) -> Result<()> {
+    if db.remove_from_queue(id)? {
+        println!("{} {}", "Removed from read-later queue:".green(), id);
+    } else {
+        println!("{} {}", "Article is not in the queue:".yellow(), id);
+    }
+    Ok(())
+}
+
+/// キューの内容を表示する
+pub fn queue_list(db: &dyn Storage) -> Result<()> {
+    let entries = db.get_queue()?;
+
+    if entries.is_empty() {
+        println!("{}", "Read-later queue is empty.".yellow());
+        println!("Use 'rustfeed queue add <article_id>' to queue articles.");
+        return Ok(());
+    }
+
+    println!("{}", "Read Later:".bold().underline());
+    println!();
+
+    for (index, entry) in entries.iter().enumerate() {
+        let article = &entry.article;
+        println!(
+            "  {} {} {} {}",
+            format!("{:>2}.", index).cyan(),
+            format!("[{}]", article.id).dimmed(),
+            article.title.bold(),
+            format!("(added {})", entry.added_at.format("%Y-%m-%d")).dimmed()
+        );
+
+        if let Some(url) = &article.url {
+            println!("      {}", url.dimmed());
+        }
+    }
+
+    Ok(())
+}
+
+/// キューの先頭の記事を表示する
+///
+/// `keep` が false の場合は既読にし、記事はキューから外れます。
+pub fn queue_next(db: &dyn Storage, keep: bool) -> Result<()> {
+    let Some(article) = db.next_in_queue()? else {
+        println!("{}", "Read-later queue is empty.".green());
+        return Ok(());
+    };
+
+    println!(
+        "{} {}",
+        format!("[{}]", article.id).dimmed(),
+        article.title.bold()
+    );
+    if let Some(published_at) = article.published_at {
+        println!(
+            "  {}",
+            published_at.format("%Y-%m-%d %H:%M").to_string().dimmed()
+        );
+    }
+    if let Some(url) = &article.url {
+        println!("  {}", url.cyan());
+    }
+
+    if !keep {
+        db.mark_as_read(article.id)?;
+        println!();
+        println!("{}", "Marked as read and removed from the queue.".green());
+    }
+
+    Ok(())
+}
+
+/// キュー内の記事の順番を変更する
+pub fn queue_move(db: &dyn Storage, id: i64, position: usize) -> Result<()> {
+    if db.move_in_queue(id, position)? {
+        println!(
+            "{} {} {}",
+            "Moved article".green(),
+            id,
+            format!("to position {}", position).green()
+        );
+    } else {
+        println!("{} {}", "Article is not in the queue:".yellow(), id);
+    }
+    Ok(())
+}
+
+/// キューを空にする
+pub fn queue_clear(db: &dyn Storage) -> Result<()> {
+    let count = db.clear_queue()?;
+    println!(
+        "{}",
+        format!("Cleared {} article(s) from the read-later queue.", count).green()
+    );
+    Ok(())
+}
+
+// =============================================================================
+// カテゴリ管理コマンド
+// =============================================================================
+
+/// カテゴリの一覧を木構造で表示する
+pub fn list_categories(db: &Database) -> Result<()> {
+    let categories = db.get_categories()?;
+
+    if categories.is_empty() {
+        println!("{}", "No categories yet.".yellow());
+        println!("Use 'rustfeed add-category <path>' to create one.");
+        return Ok(());
+    }
+
+    // カテゴリごとの（直接属する）フィード数を数える
+    let mut feed_counts: HashMap<i64, usize> = HashMap::new();
+    for feed in db.get_feeds(None)? {
+        if let Some(category_id) = feed.category_id {
+            *feed_counts.entry(category_id).or_default() += 1;
+        }
+    }
+
+    println!("{}", "Categories:".bold().underline());
+    println!();
+
+    for category in categories {
+        let count = feed_counts.get(&category.id).copied().unwrap_or(0);
+        println!(
+            "  {}{} {}",
+            "  ".repeat(category.depth),
+            category.name.green().bold(),
+            format!("({} feeds)", count).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// カテゴリを作成する
+pub fn add_category(db: &Database, path: &str) -> Result<()> {
+    db.create_category(path)?;
+    println!(
+        "{} {}",
+        "Category ready:".green(),
+        format!("[{}]", path).green()
+    );
+    Ok(())
+}
+
+/// カテゴリの名前を変更する
+pub fn rename_category(db: &Database, path: &str, new_name: &str) -> Result<()> {
+    if db.rename_category(path, new_name)? {
+        println!(
+            "{} {} {}",
+            format!("Category '{}'", path).green(),
+            "renamed to".green().bold(),
+            format!("'{}'", new_name.trim()).green()
+        );
+    } else {
+        println!("{} {}", "Category not found:".yellow(), path);
+    }
+    Ok(())
+}
+
+/// カテゴリを統合する
+pub fn merge_categories(db: &Database, source: &str, target: &str) -> Result<()> {
+    let moved = db.merge_categories(source, target)?;
+
+    println!(
+        "{} {}",
+        format!("Merged category '{}' into '{}'.", source, target).green(),
+        format!("({} feed(s) moved)", moved).dimmed()
+    );
+
+    Ok(())
+}
+
+/// カテゴリを削除する（フィードとサブカテゴリは親カテゴリに移動する）
+pub fn delete_category(db: &Database, path: &str) -> Result<()> {
+    if db.delete_category(path)? {
+        println!("{} {}", "Deleted category:".green(), path);
+    } else {
+        println!("{} {}", "Category not found:".yellow(), path);
+    }
+    Ok(())
+}
+
+/// カテゴリを移動・並び替えする
+///
+/// `parent` または `root` が指定された場合は親カテゴリを変更し、
+/// `position` が指定された場合は兄弟カテゴリの中での位置を変更します。
+pub fn move_category(
+    db: &Database,
+    path: &str,
+    parent: Option<&str>,
+    root: bool,
+    position: Option<usize>,
+) -> Result<()> {
+    if parent.is_none() && !root && position.is_none() {
+        anyhow::bail!("Please specify --parent, --root, or --position");
+    }
+
+    let Some(category) = db.get_category(path)? else {
+        println!("{} {}", "Category not found:".yellow(), path);
+        return Ok(());
+    };
+
+    // 親を変更した場合、以降の操作は新しいパスで行う
+    let mut current_path = category.path.clone();
+    if parent.is_some() || root {
+        db.move_category(&current_path, parent)?;
+        current_path = match parent {
+            Some(parent) => format!("{}/{}", parent.trim_end_matches('/'), category.name),
+            None => category.name.clone(),
+        };
+    }
+
+    if let Some(position) = position {
+        db.reorder_category(&current_path, position)?;
+    }
+
+    println!(
+        "{} {}",
+        "Category moved:".green(),
+        format!("[{}]", current_path).green()
+    );
+
+    Ok(())
+}
+
+// =============================================================================
+// 既読管理コマンド
+// =============================================================================
+
+/// 記事を一括で既読にする
+pub fn mark_all_read(
+    db: &dyn Storage,
+    feed_id: Option<i64>,
+    before_date: Option<&str>,
+) -> Result<()> {
+    let count = db.mark_all_read_with_filter(feed_id, before_date)?;
+
+    if count == 0 {
+        println!("{}", "No articles matched the criteria.".yellow());
+    } else {
+        println!(
+            "{} {}",
+            format!("Marked {} article(s) as read.", count).green(),
+            "✓".green().bold()
+        );
+    }
+
+    Ok(())
+}
+
+/// 記事を未読に戻す
+pub fn mark_unread(
+    db: &dyn Storage,
+    id: Option<i64>,
+    feed_id: Option<i64>,
+    all: bool,
+) -> Result<()> {
+    let options_count = [id.is_some(), feed_id.is_some(), all]
+        .iter()
+        .filter(|&&x| x)
+        .count();
+
+    if options_count == 0 {
+        anyhow::bail!("Please specify either an article ID, --feed, or --all");
+    }
+
+    if options_count > 1 {
+        anyhow::bail!("Please specify only one of: article ID, --feed, or --all");
+    }
+
+    let count = if let Some(article_id) = id {
+        let success = db.mark_as_unread(article_id)?;
+        if !success {
+            anyhow::bail!("Article not found with ID: {}", article_id);
+        }
+        1
+    } else if let Some(feed) = feed_id {
+        db.mark_all_unread_by_feed(feed)?
+    } else {
+        db.mark_all_unread()?
+    };
+
+    if count == 0 {
+        println!("{}", "No articles to mark as unread.".yellow());
+    } else {
+        println!(
+            "{} {}",
+            format!("Marked {} article(s) as unread.", count).green(),
+            "✓".green().bold()
+        );
+    }
+
+    Ok(())
+}
+
+/// 記事の既読/未読状態を反転する
+pub fn toggle_read(db: &dyn Storage, id: i64) -> Result<()> {
+    let success = db.toggle_read_status(id)?;
+
+    if !success {
+        anyhow::bail!("Article not found with ID: {}", id);
     }
 
     println!(
@@ -602,3 +1651,515 @@ pub fn toggle_read(db: &Database, id: i64) -> Result<()> {
 
     Ok(())
 }
+
+// =============================================================================
+// 統計コマンド
+// =============================================================================
+
+/// ヒートマップ・グラフの濃さを表す文字（0件 → 最大）
+const HEATMAP_LEVELS: [&str; 5] = ["·", "░", "▒", "▓", "█"];
+
+/// 時間帯グラフの棒の高さを表す文字
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// 購読状況と読書の傾向を表示する
+///
+/// # 引数
+///
+/// * `stale_days` - この日数の間に新着がないフィードを停滞中として表示する
+/// * `weeks` - 読書アクティビティのヒートマップに表示する週数
+/// * `format` - 出力形式（table または json）
+pub fn show_stats(db: &Database, stale_days: u32, weeks: u32, format: &str) -> Result<()> {
+    let options = StatsOptions {
+        stale_days,
+        activity_days: weeks.clamp(1, u32::MAX / 7) * 7,
+        ..StatsOptions::default()
+    };
+
+    match format.to_lowercase().as_str() {
+        "json" => {
+            let stats = db.get_stats(&options)?;
+            let json = serde_json::to_string_pretty(&stats)
+                .context("Failed to serialize stats to JSON")?;
+            println!("{}", json);
+        }
+        "table" => print_stats_table(&db.get_stats(&options)?),
+        _ => {
+            anyhow::bail!("Unsupported format: '{}'. Use 'table' or 'json'.", format);
+        }
+    }
+
+    Ok(())
+}
+
+/// 統計を表形式で表示する
+fn print_stats_table(stats: &Stats) {
+    let totals = &stats.totals;
+    println!("{}", "Overview:".bold().underline());
+    println!(
+        "  {} {}  {} {}  {} {}  {} {} ({})  {} {}",
+        "Feeds".cyan(),
+        totals.feeds,
+        "Articles".cyan(),
+        totals.articles,
+        "Unread".cyan(),
+        totals.unread.to_string().yellow(),
+        "Read".cyan(),
+        totals.read,
+        percent(totals.read_ratio),
+        "Favorites".cyan(),
+        totals.favorites
+    );
+    println!();
+
+    if !stats.feeds.is_empty() {
+        println!("{}", "Feeds:".bold().underline());
+        println!(
+            "  {}",
+            format!(
+                "{:>4}  {:<32} {:>6} {:>6} {:>6} {:>7}  {}",
+                "ID", "Name", "Total", "Unread", "Read%", "/week", "Latest"
+            )
+            .dimmed()
+        );
+        for feed in &stats.feeds {
+            println!(
+                "  {:>4}  {:<32} {:>6} {:>6} {:>6} {:>7.1}  {}",
+                feed.feed_id,
+                truncate(&feed.name, 32),
+                feed.total,
+                feed.unread,
+                percent(feed.read_ratio),
+                feed.articles_per_week,
+                feed.last_article_at
+                    .map(|dt| dt.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| "----------".to_string())
+            );
+        }
+        println!();
+
+        println!("{}", "Categories:".bold().underline());
+        println!(
+            "  {}",
+            format!(
+                "{:<38} {:>6} {:>6} {:>6} {:>6}",
+                "Category", "Feeds", "Total", "Unread", "Read%"
+            )
+            .dimmed()
+        );
+        for category in &stats.categories {
+            println!(
+                "  {:<38} {:>6} {:>6} {:>6} {:>6}",
+                truncate(
+                    category.category.as_deref().unwrap_or("(uncategorized)"),
+                    38
+                ),
+                category.feeds,
+                category.total,
+                category.unread,
+                percent(category.read_ratio)
+            );
+        }
+        println!();
+    }
+
+    println!(
+        "{}",
+        format!(
+            "Stale feeds (no new articles in {} days):",
+            stats.options.stale_days
+        )
+        .bold()
+        .underline()
+    );
+    if stats.stale_feeds.is_empty() {
+        println!("  {}", "None".green());
+    }
+    for feed in &stats.stale_feeds {
+        let last = match (feed.last_article_at, feed.days_since_last_article) {
+            (Some(at), Some(days)) => format!("last {} ({} days ago)", at.format("%Y-%m-%d"), days),
+            _ => "no articles".to_string(),
+        };
+        println!(
+            "  {} {} {}",
+            format!("[{}]", feed.feed_id).dimmed(),
+            feed.name.bold(),
+            last.yellow()
+        );
+    }
+    println!();
+
+    println!("{}", "Busiest hours (UTC):".bold().underline());
+    println!("  {:<11}{}", "", "0     6     12    18    23".dimmed());
+    print_hours("Publishing", &stats.publish_hours);
+    print_hours("Reading", &stats.reading_hours);
+    println!();
+
+    print_heatmap(&stats.reading_activity);
+}
+
+/// 時間帯ごとの件数を1行のグラフと上位の時間帯で表示する
+fn print_hours(label: &str, hours: &[usize; 24]) {
+    let max = hours.iter().copied().max().unwrap_or(0);
+    if max == 0 {
+        println!("  {:<11}{}", label, "(no data)".dimmed());
+        return;
+    }
+
+    let spark: String = hours
+        .iter()
+        .map(|&count| {
+            if count == 0 {
+                ' '
+            } else {
+                SPARK_LEVELS[(count * (SPARK_LEVELS.len() - 1)).div_ceil(max)]
+            }
+        })
+        .collect();
+
+    let mut busiest: Vec<(usize, usize)> = hours
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    busiest.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let top: Vec<String> = busiest
+        .iter()
+        .take(3)
+        .map(|(hour, count)| format!("{:02}:00 ({})", hour, count))
+        .collect();
+
+    println!(
+        "  {:<11}{}  {}",
+        label,
+        spark.cyan(),
+        top.join(", ").dimmed()
+    );
+}
+
+/// 日ごとの既読数をカレンダー形式（縦が曜日、横が週）のヒートマップで表示する
+fn print_heatmap(activity: &[DailyActivity]) {
+    let Some(first) = activity.first() else {
+        return;
+    };
+    let total: usize = activity.iter().map(|day| day.reads).sum();
+    println!(
+        "{}",
+        format!(
+            "Reading activity (last {} days, {} read):",
+            activity.len(),
+            total
+        )
+        .bold()
+        .underline()
+    );
+
+    // 最初の日を含む週の月曜日を基準に、各日を (曜日, 週) のマスに置く
+    let offset = first.date.weekday().num_days_from_monday() as usize;
+    let week_count = (offset + activity.len()).div_ceil(7);
+    let mut grid = vec![vec![None; week_count]; 7];
+    for (i, day) in activity.iter().enumerate() {
+        let cell = offset + i;
+        grid[cell % 7][cell / 7] = Some(day.reads);
+    }
+
+    let max = activity.iter().map(|day| day.reads).max().unwrap_or(0);
+    let weekdays = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    for (weekday, row) in weekdays.iter().zip(&grid) {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| match cell {
+                None => " ".to_string(),
+                Some(0) => HEATMAP_LEVELS[0].dimmed().to_string(),
+                Some(reads) => {
+                    let level = (reads * (HEATMAP_LEVELS.len() - 1)).div_ceil(max);
+                    HEATMAP_LEVELS[level].green().to_string()
+                }
+            })
+            .collect();
+        println!("  {} {}", weekday.dimmed(), cells.join(" "));
+    }
+}
+
+/// 割合（0.0〜1.0）をパーセント表記にする
+fn percent(ratio: f64) -> String {
+    format!("{:.0}%", ratio * 100.0)
+}
+
+/// 表示幅に収まるよう文字列を切り詰める（文字数で数える）
+fn truncate(s: &str, width: usize) -> String {
+    if s.chars().count() <= width {
+        s.to_string()
+    } else {
+        let mut truncated: String = s.chars().take(width.saturating_sub(1)).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+// =============================================================================
+// バックアップコマンド
+// =============================================================================
+
+/// データベースのバックアップを作成する
+///
+/// # 引数
+///
+/// * `config` - バックアップの設定（保存先と残す数の既定値）
+/// * `path` - 保存先。省略時とディレクトリの場合は日時入りのファイルを作って古いものを削除し、
+///   ファイルの場合はそのファイルに書き出す（世代管理はしない）
+/// * `keep` - 残すバックアップの数（省略時は設定ファイルの値、0 の場合は削除しない）
+pub fn backup_database(
+    db: &Database,
+    config: &BackupConfig,
+    path: Option<&Path>,
+    keep: Option<usize>,
+) -> Result<()> {
+    let dir = match path {
+        None => config.dir_path()?,
+        Some(path) if path.is_dir() => path.to_path_buf(),
+        Some(path) => {
+            db.backup_to(path)?;
+            println!("{} {}", "Backup saved to".green(), path.display());
+            return Ok(());
+        }
+    };
+
+    let result = backup::create_backup(db, &dir, keep.unwrap_or(config.keep))?;
+    println!("{} {}", "Backup saved to".green(), result.path.display());
+    for removed in &result.removed {
+        println!("  {} {}", "Removed old backup".dimmed(), removed.display());
+    }
+
+    Ok(())
+}
+
+/// バックアップからデータベースを復元する
+///
+/// バックアップを検証してから、現在のデータベースを置き換えます。
+/// `no_backup` が偽の場合は、置き換える前に現在のデータベースを
+/// 設定ファイルの保存先にバックアップします。
+pub fn restore_database(
+    db: &mut Database,
+    config: &BackupConfig,
+    path: &Path,
+    no_backup: bool,
+) -> Result<()> {
+    // 現在のデータベースを退避する前に検証し、使えないファイルなら何もしない
+    backup::validate_backup(path)?;
+
+    if !no_backup {
+        let saved = backup::create_backup(db, &config.dir_path()?, config.keep)?;
+        println!(
+            "{} {}",
+            "Current database saved to".dimmed(),
+            saved.path.display()
+        );
+    }
+
+    let info = backup::restore_backup(db, path)?;
+    println!(
+        "{} {} {}",
+        "Restored from".green(),
+        path.display(),
+        format!(
+            "({} feed(s), {} article(s), schema v{})",
+            info.feed_count, info.article_count, info.schema_version
+        )
+        .dimmed()
+    );
+
+    Ok(())
+}
+
+// =============================================================================
+// ダンプコマンド
+// =============================================================================
+
+/// データベース全体を JSON で書き出す
+///
+/// `output` を省略した場合は標準出力に書き出します。
+pub fn dump_database(db: &Database, output: Option<&Path>) -> Result<()> {
+    let dump = db.dump()?;
+
+    match output {
+        Some(path) => {
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            let mut writer = std::io::BufWriter::new(file);
+            serde_json::to_writer_pretty(&mut writer, &dump)
+                .context("Failed to serialize dump to JSON")?;
+            writer.flush()?;
+            eprintln!(
+                "{} {} {}",
+                "Dumped to".green(),
+                path.display(),
+                format!(
+                    "({} feed(s), {} article(s))",
+                    dump.feeds.len(),
+                    dump.articles.len()
+                )
+                .dimmed()
+            );
+        }
+        None => {
+            let stdout = std::io::stdout();
+            let mut writer = std::io::BufWriter::new(stdout.lock());
+            serde_json::to_writer_pretty(&mut writer, &dump)
+                .context("Failed to serialize dump to JSON")?;
+            writeln!(writer)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 他のフィードリーダーのデータを読み込む
+///
+/// # 引数
+///
+/// * `source` - データの形式（google-reader、miniflux、newsboat）
+/// * `path` - 読み込むファイル（newsboat では urls ファイル）
+/// * `cache` - newsboat のキャッシュ（cache.db）
+pub fn import_from_reader(
+    db: &Database,
+    source: &str,
+    path: &Path,
+    cache: Option<&Path>,
+) -> Result<()> {
+    let source: ImportSource = source.parse()?;
+    if cache.is_some() && source != ImportSource::Newsboat {
+        anyhow::bail!("--cache is only used with --from newsboat");
+    }
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let imported = match source {
+        ImportSource::GoogleReader => import::parse_google_reader(&content)?,
+        ImportSource::Miniflux => import::parse_miniflux(&content)?,
+        ImportSource::Newsboat => import::parse_newsboat(&content, cache)?,
+    };
+
+    for reason in &imported.skipped {
+        println!("  {} {}", "!".yellow(), reason.dimmed());
+    }
+
+    let favorites = imported
+        .dump
+        .articles
+        .iter()
+        .filter(|entry| entry.article.is_favorite)
+        .count();
+    let read = imported
+        .dump
+        .articles
+        .iter()
+        .filter(|entry| entry.article.is_read)
+        .count();
+    // 購読リストになく一時停止で追加されるフィード（購読中のものに統合されるものは除く）
+    let mut paused_urls = Vec::new();
+    for feed in imported.dump.feeds.iter().filter(|feed| feed.paused) {
+        if db.find_feed_by_url(&feed.url)?.is_none() {
+            paused_urls.push(feed.url.as_str());
+        }
+    }
+
+    let report = db.load_dump(&imported.dump, LoadMode::Merge)?;
+    println!(
+        "{} {} new feed(s), {} already subscribed; {} new article(s), {} merged.",
+        format!("Imported from {}:", source).green(),
+        report.feeds_added.to_string().cyan(),
+        report.feeds_merged,
+        report.articles_added.to_string().cyan(),
+        report.articles_merged,
+    );
+    if !imported.dump.articles.is_empty() {
+        println!(
+            "  {}",
+            format!(
+                "{} starred and {} read article(s) in the data.",
+                favorites, read
+            )
+            .dimmed()
+        );
+    }
+    if !paused_urls.is_empty() {
+        println!(
+            "  {}",
+            format!(
+                "{} feed(s) were not in a subscription list and were added paused \
+                 (resume with `rustfeed resume <id>`):",
+                paused_urls.len()
+            )
+            .yellow()
+        );
+        for url in paused_urls {
+            if let Some(feed) = db.find_feed_by_url(url)? {
+                println!(
+                    "    {} {}",
+                    format!("[{}]", feed.id).dimmed(),
+                    feed.custom_name.as_deref().unwrap_or(&feed.title)
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// `dump` で書き出した JSON を読み込む
+///
+/// # 引数
+///
+/// * `path` - 読み込むファイル（`-` の場合は標準入力）
+/// * `mode` - 読み込み方（merge: 既存のデータに統合、replace: 既存のデータを置き換え）
+/// * `no_backup` - replace のとき、置き換える前に現在のデータベースをバックアップしない
+pub fn load_database(
+    db: &Database,
+    config: &BackupConfig,
+    path: &Path,
+    mode: &str,
+    no_backup: bool,
+) -> Result<()> {
+    let mode = match mode.to_lowercase().as_str() {
+        "merge" => LoadMode::Merge,
+        "replace" => LoadMode::Replace,
+        _ => anyhow::bail!("Unsupported mode: '{}'. Use 'merge' or 'replace'.", mode),
+    };
+
+    let dump = if path == Path::new("-") {
+        dump::read_dump(std::io::stdin().lock())?
+    } else {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        dump::read_dump(std::io::BufReader::new(file))?
+    };
+
+    if mode == LoadMode::Replace && !no_backup {
+        let saved = backup::create_backup(db, &config.dir_path()?, config.keep)?;
+        println!(
+            "{} {}",
+            "Current database saved to".dimmed(),
+            saved.path.display()
+        );
+    }
+
+    let report = db.load_dump(&dump, mode)?;
+    println!(
+        "{} {} new feed(s), {} merged; {} new article(s), {} merged; {} new categor{}.",
+        "Loaded:".green(),
+        report.feeds_added.to_string().cyan(),
+        report.feeds_merged,
+        report.articles_added.to_string().cyan(),
+        report.articles_merged,
+        report.categories_added,
+        if report.categories_added == 1 {
+            "y"
+        } else {
+            "ies"
+        }
+    );
+
+    Ok(())
+}
@@ -27,8 +27,9 @@
 mod commands;
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
-use rustfeed_core::{config::AppConfig, db::Database};
+use clap::{Args, Parser, Subcommand};
+use rustfeed_core::{config::AppConfig, db::Database, digest, publish::FeedMetadata, ArticleQuery};
+use std::path::PathBuf;
 
 // =============================================================================
 // CLI構造体の定義
@@ -67,7 +68,7 @@ enum Commands {
 
     /// 登録済みの全フィードを一覧表示する
     List {
-        /// カテゴリでフィルタリング（オプション）
+        /// カテゴリでフィルタリング（サブカテゴリを含む）
         #[arg(long)]
         category: Option<String>,
     },
@@ -76,38 +77,14 @@ enum Commands {
     Fetch,
 
     /// 記事を一覧表示する
+    #[command(mut_arg("filter", |arg| arg.short('f')))]
     Articles {
-        /// 未読記事のみを表示するフラグ
-        #[arg(short, long)]
-        unread: bool,
-
         /// 表示する記事数の上限（指定しない場合は設定ファイルのデフォルト値）
         #[arg(short, long)]
         limit: Option<usize>,
 
-        /// キーワードフィルタ（カンマ区切りで複数指定可能、OR条件）
-        #[arg(short, long)]
-        filter: Option<String>,
-
-        /// 特定のフィードIDの記事のみを表示
-        #[arg(long)]
-        feed: Option<i64>,
-
-        /// 指定日時以降の記事のみを表示（YYYY-MM-DD形式）
-        #[arg(long)]
-        after: Option<String>,
-
-        /// 指定日時以前の記事のみを表示（YYYY-MM-DD形式）
-        #[arg(long)]
-        before: Option<String>,
-
-        /// 過去N日間の記事のみを表示
-        #[arg(long, conflicts_with_all = ["after", "before"])]
-        last_days: Option<u32>,
-
-        /// 過去N週間の記事のみを表示
-        #[arg(long, conflicts_with_all = ["after", "before", "last_days"])]
-        last_weeks: Option<u32>,
+        #[command(flatten)]
+        filters: ArticleFilterArgs,
     },
 
     /// 記事を全文検索する
@@ -115,25 +92,12 @@ enum Commands {
         /// 検索クエリ
         query: String,
 
-        /// 未読記事のみを検索
-        #[arg(short, long)]
-        unread: bool,
-
         /// 検索結果の上限（デフォルト: 20）
         #[arg(short, long)]
         limit: Option<usize>,
 
-        /// 特定のフィードIDのみ検索
-        #[arg(long)]
-        feed: Option<i64>,
-
-        /// 指定日時以降の記事のみ検索
-        #[arg(long)]
-        after: Option<String>,
-
-        /// 指定日時以前の記事のみ検索
-        #[arg(long)]
-        before: Option<String>,
+        #[command(flatten)]
+        filters: ArticleFilterArgs,
     },
 
     /// 記事を既読としてマークする
@@ -161,23 +125,226 @@ enum Commands {
         limit: usize,
     },
 
+    /// 最近読んだ記事を読書履歴から表示する
+    History {
+        /// 表示する件数の上限（デフォルト: 20）
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+
+        /// 指定日以降の履歴のみを表示（YYYY-MM-DD形式）
+        #[arg(long)]
+        after: Option<String>,
+
+        /// 指定日以前の履歴のみを表示（YYYY-MM-DD形式）
+        #[arg(long)]
+        before: Option<String>,
+
+        /// 既読以外の出来事（開いた・未読に戻した）も表示する
+        #[arg(short, long)]
+        all: bool,
+    },
+
+    /// 購読状況と読書の傾向（件数・既読率・投稿頻度・停滞フィードなど）を表示する
+    Stats {
+        /// この日数の間に新着記事がないフィードを停滞中として表示（デフォルト: 30、最大 36500）
+        #[arg(long, default_value = "30", value_parser = clap::value_parser!(u32).range(0..=36500))]
+        stale_days: u32,
+
+        /// 読書アクティビティのヒートマップに表示する週数（デフォルト: 12、1〜520）
+        #[arg(long, default_value = "12", value_parser = clap::value_parser!(u32).range(1..=520))]
+        weeks: u32,
+
+        /// 出力形式（table または json）
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+
+    /// 記事にタグを付ける
+    Tag {
+        /// タグを付ける記事の ID
+        id: i64,
+
+        /// 付けるタグ（複数指定可能）
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+
+    /// 記事からタグを外す
+    Untag {
+        /// タグを外す記事の ID
+        id: i64,
+
+        /// 外すタグ（複数指定可能）
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+
+    /// タグの一覧を記事数とともに表示する
+    Tags,
+
+    /// タグの名前を変更する
+    RenameTag {
+        /// 現在のタグ名
+        old: String,
+
+        /// 新しいタグ名
+        new: String,
+    },
+
+    /// タグを統合する（source の記事に target を付け、source を削除）
+    MergeTag {
+        /// 統合元のタグ名
+        source: String,
+
+        /// 統合先のタグ名
+        target: String,
+    },
+
+    /// タグを削除する（記事からも外れる）
+    DeleteTag {
+        /// 削除するタグ名
+        name: String,
+    },
+
+    /// 記事のメモを $EDITOR で編集する
+    Note {
+        /// メモを付ける記事の ID
+        id: i64,
+
+        /// エディタを開かずにこのテキストをメモとして保存する
+        #[arg(short, long)]
+        message: Option<String>,
+
+        /// メモを表示するだけで編集しない
+        #[arg(long, conflicts_with_all = ["message", "delete"])]
+        show: bool,
+
+        /// メモを削除する
+        #[arg(long, conflicts_with = "message")]
+        delete: bool,
+    },
+
+    /// メモの付いた記事を一覧表示する
+    Notes,
+
+    /// 記事の本文中のテキストをハイライトとして保存する
+    Highlight {
+        /// ハイライトする記事の ID
+        id: i64,
+
+        /// ハイライトするテキスト（本文中で最初に見つかった箇所）
+        text: String,
+    },
+
+    /// 記事のハイライトを一覧表示する
+    Highlights {
+        /// 記事の ID
+        id: i64,
+    },
+
+    /// ハイライトを削除する
+    Unhighlight {
+        /// 削除するハイライトの ID（`highlights` で表示される ID）
+        id: i64,
+    },
+
+    /// 「あとで読む」キューを操作する
+    Queue {
+        #[command(subcommand)]
+        action: QueueAction,
+    },
+
     /// 記事をエクスポートする
     Export {
-        /// エクスポート形式（json または markdown）
+        /// エクスポート形式（json、markdown、csv、html、epub）
         #[arg(short, long, default_value = "json")]
         format: String,
 
-        /// お気に入り記事のみエクスポートするフラグ
+        /// エクスポートする記事数の上限（オプション）
+        #[arg(short, long)]
+        limit: Option<usize>,
+
+        /// 出力先のファイル（省略時は標準出力。epub では必須）
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        filters: ArticleFilterArgs,
+    },
+
+    /// 記事を Atom・RSS・JSON Feed のフィードとして書き出す（静的ホスティング向け）
+    Publish {
+        /// フィードの形式（atom、rss、jsonfeed）
+        #[arg(short, long, default_value = "atom")]
+        format: String,
+
+        /// 出力先のファイル
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// フィードのタイトル（省略時は絞り込み条件から作る）
         #[arg(long)]
-        favorites: bool,
+        title: Option<String>,
 
-        /// 未読記事のみエクスポートするフラグ
+        /// フィードの説明
         #[arg(long)]
-        unread: bool,
+        description: Option<String>,
 
-        /// エクスポートする記事数の上限（オプション）
+        /// フィードに対応するWebページのURL（rss ではこれか --feed-url が必要）
+        #[arg(long)]
+        link: Option<String>,
+
+        /// フィード自身を配信するURL
+        #[arg(long)]
+        feed_url: Option<String>,
+
+        /// 含める記事数の上限
+        #[arg(short, long, default_value_t = 50)]
+        limit: usize,
+
+        #[command(flatten)]
+        filters: ArticleFilterArgs,
+    },
+
+    /// 期間内の記事をカテゴリ・フィードごとにまとめたダイジェストを出力する
+    ///
+    /// テンプレートは設定ディレクトリの templates/digest.{md,html,txt} で変更できます。
+    Digest {
+        /// 対象の期間（12h、1d、2w のような相対指定、または YYYY-MM-DD）
+        #[arg(long, default_value = "1d")]
+        since: String,
+
+        /// 出力の形式（markdown、html、text）
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+
+        /// 出力先のファイル（省略時は標準出力）
         #[arg(short, long)]
-        limit: Option<usize>,
+        output: Option<PathBuf>,
+
+        /// 使うテンプレートのファイル（省略時は設定ディレクトリのもの、なければ組み込みのもの）
+        #[arg(long)]
+        template: Option<PathBuf>,
+
+        /// ダイジェストのタイトル
+        #[arg(long)]
+        title: Option<String>,
+
+        /// 未読の記事のみを含める
+        #[arg(long)]
+        unread: bool,
+
+        /// 出力したあと、含めた記事を既読にする
+        #[arg(long)]
+        mark_read: bool,
+
+        /// 出力する代わりに、テキストと HTML のメールで送る（設定ファイルの [email] を使う）
+        #[arg(long, conflicts_with_all = ["format", "output", "template"])]
+        send: bool,
+
+        /// 組み込みのテンプレートを設定ディレクトリに書き出して終了する（既存のファイルは上書きしない）
+        #[arg(long, conflicts_with_all = ["output", "template", "mark_read", "send"])]
+        init_templates: bool,
     },
 
     /// 記事を一括で既読にする
@@ -234,8 +401,63 @@ enum Commands {
         /// 設定するフィードのID
         id: i64,
 
-        /// カテゴリ名（空文字列の場合はカテゴリをクリア）
+        /// カテゴリのパス（例: Tech/Rust、空文字列の場合はカテゴリをクリア）
         category: String,
+
+        /// カテゴリが存在しない場合は作成する
+        #[arg(long)]
+        create: bool,
+    },
+
+    /// カテゴリの一覧を木構造で表示する
+    Categories,
+
+    /// カテゴリを作成する（途中の階層も作成される）
+    AddCategory {
+        /// カテゴリのパス（例: Tech/Rust）
+        path: String,
+    },
+
+    /// カテゴリの名前を変更する
+    RenameCategory {
+        /// 変更するカテゴリのパス
+        path: String,
+
+        /// 新しい名前（パスではなく名前のみ）
+        new_name: String,
+    },
+
+    /// カテゴリを統合する（source のフィードとサブカテゴリを target に移し、source を削除）
+    MergeCategory {
+        /// 統合元のカテゴリのパス
+        source: String,
+
+        /// 統合先のカテゴリのパス
+        target: String,
+    },
+
+    /// カテゴリを削除する（フィードとサブカテゴリは親カテゴリに移動）
+    DeleteCategory {
+        /// 削除するカテゴリのパス
+        path: String,
+    },
+
+    /// カテゴリを別の親の下に移動する、または並び順を変更する
+    MoveCategory {
+        /// 移動するカテゴリのパス
+        path: String,
+
+        /// 移動先の親カテゴリのパス
+        #[arg(long, conflicts_with = "root")]
+        parent: Option<String>,
+
+        /// トップレベルに移動する
+        #[arg(long)]
+        root: bool,
+
+        /// 兄弟カテゴリの中での位置（0始まり）
+        #[arg(long)]
+        position: Option<usize>,
     },
 
     /// フィードの優先順位を設定する
@@ -247,11 +469,251 @@ enum Commands {
         priority: i64,
     },
 
+    /// フィードを一時停止する（一括更新で取得せず、記事一覧にも表示しない）
+    Pause {
+        /// 一時停止するフィードのID
+        id: i64,
+    },
+
+    /// 一時停止したフィードを再開する
+    Resume {
+        /// 再開するフィードのID
+        id: i64,
+    },
+
     /// フィードの詳細情報を表示する
     Info {
         /// 表示するフィードのID
         id: i64,
     },
+
+    /// URLが同じ、または記事の大半が重なっているフィードを探し、統合を提案する
+    DedupeFeeds {
+        /// 重複とみなす共通記事の割合（記事の多い方のフィードに対して、0.0〜1.0）
+        #[arg(long, default_value_t = 0.8)]
+        min_overlap: f64,
+
+        /// 指定した組（一覧に表示される ID）の統合を実行する（既読・お気に入りなどの状態は統合先に引き継ぐ）
+        #[arg(long, value_name = "PAIR_ID", num_args = 1..)]
+        apply: Vec<String>,
+
+        /// --apply のとき、統合する前に現在のデータベースをバックアップしない
+        #[arg(long)]
+        no_backup: bool,
+    },
+
+    /// OPML（1.0 / 2.0）の購読リストからフィードを登録する
+    ImportOpml {
+        /// 読み込む OPML ファイル
+        path: PathBuf,
+    },
+
+    /// 他のフィードリーダーのデータ（フィード・記事・既読・スター）を読み込む
+    Import {
+        /// データの形式（google-reader、miniflux、newsboat）
+        #[arg(short, long)]
+        from: String,
+
+        /// 読み込むファイル（newsboat では urls ファイル）
+        path: PathBuf,
+
+        /// newsboat のキャッシュ（cache.db）。指定すると記事と既読・フラグも読み込む
+        #[arg(long)]
+        cache: Option<PathBuf>,
+    },
+
+    /// 購読しているフィードを OPML 2.0 で書き出す
+    ExportOpml {
+        /// このカテゴリ（サブカテゴリを含む）のフィードだけを書き出す
+        #[arg(long)]
+        category: Option<String>,
+
+        /// 出力先のファイル（省略時は標準出力）
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// データベースのバックアップを作成する（TUI/GUI や fetch の実行中でも可）
+    Backup {
+        /// 保存先のディレクトリまたはファイル（省略時は設定ファイルの backup.dir）
+        path: Option<PathBuf>,
+
+        /// 残すバックアップの数（省略時は設定ファイルの backup.keep、0 で全て残す）
+        #[arg(long)]
+        keep: Option<usize>,
+    },
+
+    /// バックアップからデータベースを復元する
+    Restore {
+        /// 復元するバックアップファイル
+        path: PathBuf,
+
+        /// 復元前に現在のデータベースをバックアップしない
+        #[arg(long)]
+        no_backup: bool,
+    },
+
+    /// データベース全体（フィード・カテゴリ・記事とその状態）を JSON で書き出す
+    Dump {
+        /// 書き出し先のファイル（省略時は標準出力）
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// `dump` で書き出した JSON を読み込む
+    Load {
+        /// 読み込むファイル（`-` で標準入力）
+        path: PathBuf,
+
+        /// 読み込み方（merge: 既存のデータに統合、replace: 既存のデータを置き換え）
+        #[arg(short, long, default_value = "merge")]
+        mode: String,
+
+        /// replace のとき、置き換える前に現在のデータベースをバックアップしない
+        #[arg(long)]
+        no_backup: bool,
+    },
+}
+
+/// `queue` サブコマンドの操作
+#[derive(Subcommand)]
+enum QueueAction {
+    /// 記事をキューの末尾に追加する
+    Add {
+        /// 追加する記事の ID（複数指定可能）
+        #[arg(required = true)]
+        ids: Vec<i64>,
+    },
+
+    /// 記事をキューから外す
+    Remove {
+        /// 外す記事の ID
+        id: i64,
+    },
+
+    /// キューの内容を順番通りに表示する
+    List,
+
+    /// キューの先頭の記事を表示し、既読にする（キューから外れる）
+    Next {
+        /// 既読にせずキューに残す
+        #[arg(long)]
+        keep: bool,
+    },
+
+    /// キュー内の記事の順番を変更する
+    Move {
+        /// 移動する記事の ID
+        id: i64,
+
+        /// 新しい位置（0始まり）
+        position: usize,
+    },
+
+    /// キューを空にする
+    Clear,
+}
+
+/// 記事の絞り込みオプション（`articles`・`search`・`export`・`publish` で共通）
+#[derive(Args)]
+struct ArticleFilterArgs {
+    /// 未読記事のみを対象にする
+    #[arg(short, long)]
+    unread: bool,
+
+    /// お気に入り記事のみを対象にする
+    #[arg(long)]
+    favorites: bool,
+
+    /// キーワードフィルタ（カンマ区切りで複数指定可能、OR条件）
+    #[arg(long)]
+    filter: Option<String>,
+
+    /// 特定のフィードIDの記事のみを対象にする
+    #[arg(long)]
+    feed: Option<i64>,
+
+    /// 特定のカテゴリ（とそのサブカテゴリ）の記事のみを対象にする
+    #[arg(long)]
+    category: Option<String>,
+
+    /// 指定したタグが付いた記事のみを対象にする（複数指定可能、OR条件）
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+
+    /// 指定日以降の記事のみを対象にする（YYYY-MM-DD形式）
+    #[arg(long)]
+    after: Option<String>,
+
+    /// 指定日以前の記事のみを対象にする（YYYY-MM-DD形式）
+    #[arg(long)]
+    before: Option<String>,
+
+    /// 過去N日間の記事のみを対象にする
+    #[arg(
+        long,
+        conflicts_with_all = ["after", "before"],
+        value_parser = clap::value_parser!(u32).range(0..=36500)
+    )]
+    last_days: Option<u32>,
+
+    /// 過去N週間の記事のみを対象にする
+    #[arg(
+        long,
+        conflicts_with_all = ["after", "before", "last_days"],
+        value_parser = clap::value_parser!(u32).range(1..=520)
+    )]
+    last_weeks: Option<u32>,
+
+    /// 他のフィードから届いた重複記事もまとめずに含める
+    #[arg(long)]
+    show_duplicates: bool,
+}
+
+impl ArticleFilterArgs {
+    /// 絞り込みオプションを記事の取得条件にする（件数の上限は呼び出し側で設定する）
+    ///
+    /// フィードを指定しない場合は一時停止中のフィードを除外し、
+    /// `--show-duplicates` がなければ重複記事をまとめます。
+    fn to_query(&self) -> Result<ArticleQuery> {
+        use chrono::{Duration, Utc};
+
+        let mut query = ArticleQuery::new();
+        if self.unread {
+            query = query.unread_only();
+        }
+        if self.favorites {
+            query = query.favorites_only();
+        }
+        if let Some(filter) = &self.filter {
+            query = query.keywords_from_str(filter);
+        }
+        if let Some(category) = &self.category {
+            query = query.category(category.as_str());
+        }
+        for tag in &self.tags {
+            query = query.tag(tag.as_str());
+        }
+
+        query = match self.feed {
+            Some(id) => query.feed(id),
+            None if self.show_duplicates => query.exclude_paused(),
+            None => query.exclude_paused().collapse_duplicates(),
+        };
+
+        // 日付範囲を計算
+        let (after_date, before_date) = if let Some(days) = self.last_days {
+            (Some(Utc::now() - Duration::days(days as i64)), None)
+        } else if let Some(weeks) = self.last_weeks {
+            (Some(Utc::now() - Duration::weeks(weeks as i64)), None)
+        } else {
+            commands::parse_date_range(self.after.as_deref(), self.before.as_deref())?
+        };
+        query.published_after = after_date;
+        query.published_before = before_date;
+
+        Ok(query)
+    }
 }
 
 // =============================================================================
@@ -263,12 +725,15 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     // データベース接続を初期化
-    let db = Database::new()?;
+    let mut db = Database::new()?;
     db.init()?;
 
     // 設定ファイルを読み込む
     let config = AppConfig::load()?;
 
+    // 旧設定の disabled_feeds を一時停止として取り込む（初回のみ）
+    db.import_disabled_feeds(&config.general.disabled_feeds)?;
+
     // パターンマッチングでサブコマンドを処理
     match cli.command {
         Commands::Add { url, name } => {
@@ -287,71 +752,23 @@ async fn main() -> Result<()> {
             commands::fetch_feeds(&db).await?;
         }
 
-        Commands::Articles {
-            unread,
-            limit,
-            filter,
-            feed,
-            after,
-            before,
-            last_days,
-            last_weeks,
-        } => {
-            use chrono::{Duration, Utc};
-
-            let limit_val = limit.unwrap_or(config.general.default_limit);
-
-            let final_feed = if feed.is_some() {
-                feed
-            } else if !config.general.disabled_feeds.is_empty() {
-                feed
-            } else {
-                feed
-            };
-
-            // 日付範囲を計算
-            let (after_date, before_date) = if let Some(days) = last_days {
-                let after = Utc::now() - Duration::days(days as i64);
-                (Some(after.format("%Y-%m-%d").to_string()), None)
-            } else if let Some(weeks) = last_weeks {
-                let after = Utc::now() - Duration::weeks(weeks as i64);
-                (Some(after.format("%Y-%m-%d").to_string()), None)
-            } else {
-                (after, before)
-            };
-
-            commands::show_articles(
-                &db,
-                unread,
-                limit_val,
-                filter.as_deref(),
-                final_feed,
-                &config.general.disabled_feeds,
-                after_date.as_deref(),
-                before_date.as_deref(),
-            )?;
+        Commands::Articles { limit, filters } => {
+            let query = filters
+                .to_query()?
+                .limit(limit.unwrap_or(config.general.default_limit));
+            commands::show_articles(&db, &query)?;
         }
 
         Commands::Search {
             query,
-            unread,
             limit,
-            feed,
-            after,
-            before,
+            filters,
         } => {
-            let limit_val = limit.unwrap_or(20);
-
-            commands::show_articles(
-                &db,
-                unread,
-                limit_val,
-                Some(&query),
-                feed,
-                &[],
-                after.as_deref(),
-                before.as_deref(),
-            )?;
+            let article_query = filters
+                .to_query()?
+                .search_from_str(&query)
+                .limit(limit.unwrap_or(20));
+            commands::show_articles(&db, &article_query)?;
         }
 
         Commands::Read { id } => {
@@ -370,13 +787,185 @@ async fn main() -> Result<()> {
             commands::show_favorites(&db, limit)?;
         }
 
+        Commands::History {
+            limit,
+            after,
+            before,
+            all,
+        } => {
+            let (after, before) = commands::parse_date_range(after.as_deref(), before.as_deref())?;
+            commands::show_history(&db, limit, after, before, all)?;
+        }
+
+        Commands::Stats {
+            stale_days,
+            weeks,
+            format,
+        } => {
+            commands::show_stats(&db, stale_days, weeks, &format)?;
+        }
+
+        Commands::Tag { id, tags } => {
+            commands::tag_article(&db, id, &tags)?;
+        }
+
+        Commands::Untag { id, tags } => {
+            commands::untag_article(&db, id, &tags)?;
+        }
+
+        Commands::Tags => {
+            commands::list_tags(&db)?;
+        }
+
+        Commands::RenameTag { old, new } => {
+            commands::rename_tag(&db, &old, &new)?;
+        }
+
+        Commands::MergeTag { source, target } => {
+            commands::merge_tags(&db, &source, &target)?;
+        }
+
+        Commands::DeleteTag { name } => {
+            commands::delete_tag(&db, &name)?;
+        }
+
+        Commands::Note {
+            id,
+            message,
+            show,
+            delete,
+        } => {
+            commands::edit_note(&db, id, message.as_deref(), show, delete)?;
+        }
+
+        Commands::Notes => {
+            commands::list_notes(&db)?;
+        }
+
+        Commands::Highlight { id, text } => {
+            commands::add_highlight(&db, id, &text)?;
+        }
+
+        Commands::Highlights { id } => {
+            commands::list_highlights(&db, id)?;
+        }
+
+        Commands::Unhighlight { id } => {
+            commands::delete_highlight(&db, id)?;
+        }
+
+        Commands::Queue { action } => match action {
+            QueueAction::Add { ids } => commands::queue_add(&db, &ids)?,
+            QueueAction::Remove { id } => commands::queue_remove(&db, id)?,
+            QueueAction::List => commands::queue_list(&db)?,
+            QueueAction::Next { keep } => commands::queue_next(&db, keep)?,
+            QueueAction::Move { id, position } => commands::queue_move(&db, id, position)?,
+            QueueAction::Clear => commands::queue_clear(&db)?,
+        },
+
         Commands::Export {
             format,
-            favorites,
-            unread,
             limit,
+            output,
+            filters,
         } => {
-            commands::export_articles(&db, &format, favorites, unread, limit)?;
+            let mut query = filters.to_query()?;
+            if let Some(limit) = limit {
+                query = query.limit(limit);
+            }
+            commands::export_articles(&db, &query, &format, output.as_deref())?;
+        }
+
+        Commands::Publish {
+            format,
+            output,
+            title,
+            description,
+            link,
+            feed_url,
+            limit,
+            filters,
+        } => {
+            let query = filters.to_query()?.limit(limit);
+
+            // タイトルを省略した場合は、絞り込み条件をタイトルに含める
+            let mut scope = Vec::new();
+            if filters.unread {
+                scope.push("unread".to_string());
+            }
+            if filters.favorites {
+                scope.push("favorites".to_string());
+            }
+            if let Some(category) = &filters.category {
+                scope.push(format!("category {}", category));
+            }
+            for tag in &filters.tags {
+                scope.push(format!("tag {}", tag));
+            }
+            if let Some(filter) = &filters.filter {
+                scope.push(format!("\"{}\"", filter));
+            }
+            if let Some(id) = filters.feed {
+                scope.push(format!("feed {}", id));
+            }
+
+            let metadata = FeedMetadata {
+                title: title.unwrap_or_else(|| {
+                    if scope.is_empty() {
+                        "rustfeed".to_string()
+                    } else {
+                        format!("rustfeed: {}", scope.join(", "))
+                    }
+                }),
+                description,
+                home_page_url: link,
+                feed_url,
+            };
+            commands::publish(&db, &query, &format, &metadata, &output)?;
+        }
+
+        Commands::Digest {
+            since,
+            format,
+            output,
+            template,
+            title,
+            unread,
+            mark_read,
+            send,
+            init_templates,
+        } => {
+            if init_templates {
+                commands::init_digest_templates()?;
+            } else {
+                let since = digest::parse_since(&since, chrono::Utc::now())?;
+
+                // 一時停止中のフィードを除外し、重複記事をまとめる
+                let mut query = ArticleQuery::new()
+                    .published_after(since)
+                    .exclude_paused()
+                    .collapse_duplicates();
+                if unread {
+                    query = query.unread_only();
+                }
+
+                let title = title.unwrap_or_else(|| {
+                    format!(
+                        "rustfeed digest {}",
+                        chrono::Local::now().format(&config.display.date_format)
+                    )
+                });
+                let options = commands::DigestOptions {
+                    format: &format,
+                    template: template.as_deref(),
+                    output: output.as_deref(),
+                    title: &title,
+                    date_format: &config.display.date_format,
+                    mark_read,
+                    email: send.then_some(&config.email),
+                };
+                commands::digest(&db, &query, &options)?;
+            }
         }
 
         Commands::MarkAllRead { feed, before } => {
@@ -399,17 +988,98 @@ async fn main() -> Result<()> {
             commands::update_feed_url(&db, id, &url)?;
         }
 
-        Commands::SetCategory { id, category } => {
-            commands::set_feed_category(&db, id, &category)?;
+        Commands::SetCategory {
+            id,
+            category,
+            create,
+        } => {
+            commands::set_feed_category(&db, id, &category, create)?;
+        }
+
+        Commands::Categories => {
+            commands::list_categories(&db)?;
+        }
+
+        Commands::AddCategory { path } => {
+            commands::add_category(&db, &path)?;
+        }
+
+        Commands::RenameCategory { path, new_name } => {
+            commands::rename_category(&db, &path, &new_name)?;
+        }
+
+        Commands::MergeCategory { source, target } => {
+            commands::merge_categories(&db, &source, &target)?;
+        }
+
+        Commands::DeleteCategory { path } => {
+            commands::delete_category(&db, &path)?;
+        }
+
+        Commands::MoveCategory {
+            path,
+            parent,
+            root,
+            position,
+        } => {
+            commands::move_category(&db, &path, parent.as_deref(), root, position)?;
         }
 
         Commands::SetPriority { id, priority } => {
             commands::set_feed_priority(&db, id, priority)?;
         }
 
+        Commands::Pause { id } => {
+            commands::set_feed_paused(&db, id, true)?;
+        }
+
+        Commands::Resume { id } => {
+            commands::set_feed_paused(&db, id, false)?;
+        }
+
+        Commands::DedupeFeeds {
+            min_overlap,
+            apply,
+            no_backup,
+        } => {
+            commands::dedupe_feeds(&db, &config.backup, min_overlap, &apply, no_backup)?;
+        }
+
+        Commands::ImportOpml { path } => {
+            commands::import_opml(&db, &path)?;
+        }
+
+        Commands::Import { from, path, cache } => {
+            commands::import_from_reader(&db, &from, &path, cache.as_deref())?;
+        }
+
+        Commands::ExportOpml { category, output } => {
+            commands::export_opml(&db, category.as_deref(), output.as_deref())?;
+        }
+
         Commands::Info { id } => {
             commands::show_feed_info(&db, id)?;
         }
+
+        Commands::Backup { path, keep } => {
+            commands::backup_database(&db, &config.backup, path.as_deref(), keep)?;
+        }
+
+        Commands::Restore { path, no_backup } => {
+            commands::restore_database(&mut db, &config.backup, &path, no_backup)?;
+        }
+
+        Commands::Dump { output } => {
+            commands::dump_database(&db, output.as_deref())?;
+        }
+
+        Commands::Load {
+            path,
+            mode,
+            no_backup,
+        } => {
+            commands::load_database(&db, &config.backup, &path, &mode, no_backup)?;
+        }
     }
 
     Ok(())
@@ -0,0 +1,457 @@
+//! # バックアップモジュール
+//!
+//! データベースのバックアップの作成・世代管理・復元を提供します。
+//!
+//! ## 概要
+//!
+//! バックアップは SQLite のオンラインバックアップ API（[`Database::backup_to`]）で作るため、
+//! TUI/GUI や cron の `rustfeed fetch` がデータベースを使っている最中でも作成できます。
+//!
+//! - [`create_backup`] はディレクトリに日時入りのファイル（`rustfeed-YYYYMMDD-HHMMSS.db`）を作り、
+//!   古いものを削除して指定した数だけ残します
+//! - [`validate_backup`] はファイルが壊れていないこと、スキーマのバージョンが
+//!   このバージョンの rustfeed で扱え、現在のスキーマへ移行できることを確認します
+//! - [`restore_backup`] は検証してからデータベースを置き換えます
+//!
+//! 日時は UTC です（ファイル名の並びがそのまま作成順になります）。
+//!
+//! ## 使用例
+//!
+//! ```rust,no_run
+//! use rustfeed_core::{backup, config::AppConfig, db::Database};
+//!
+//! let config = AppConfig::load()?;
+//! let db = Database::open()?;
+//!
+//! let result = backup::create_backup(&db, &config.backup.dir_path()?, config.backup.keep)?;
+//! println!("Saved {:?}", result.path);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
+use std::path::{Path, PathBuf};
+
+use crate::db::{Database, SCHEMA_VERSION};
+use crate::query::ArticleQuery;
+
+/// バックアップファイル名の接頭辞
+const BACKUP_PREFIX: &str = "rustfeed-";
+
+/// バックアップファイル名の拡張子
+const BACKUP_EXTENSION: &str = ".db";
+
+/// バックアップファイル名に含める日時の形式
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+// =============================================================================
+// バックアップの作成と世代管理
+// =============================================================================
+
+/// ディレクトリ内のバックアップファイル
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupFile {
+    /// ファイルのパス
+    pub path: PathBuf,
+
+    /// 作成日時（ファイル名から読み取ったもの、UTC）
+    pub created_at: DateTime<Utc>,
+}
+
+/// [`create_backup`] の結果
+#[derive(Debug, Clone)]
+pub struct BackupResult {
+    /// 作成したバックアップファイル
+    pub path: PathBuf,
+
+    /// 世代管理で削除した古いバックアップファイル
+    pub removed: Vec<PathBuf>,
+}
+
+/// 日時入りのバックアップファイル名を返す（例: `rustfeed-20250114-093000.db`）
+pub fn backup_file_name(at: DateTime<Utc>) -> String {
+    format!(
+        "{}{}{}",
+        BACKUP_PREFIX,
+        at.format(BACKUP_TIMESTAMP_FORMAT),
+        BACKUP_EXTENSION
+    )
+}
+
+/// バックアップファイル名から作成日時を読み取る（バックアップのファイル名でなければ `None`）
+fn parse_backup_file_name(name: &str) -> Option<DateTime<Utc>> {
+    let timestamp = name
+        .strip_prefix(BACKUP_PREFIX)?
+        .strip_suffix(BACKUP_EXTENSION)?;
+    NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT)
+        .ok()
+        .map(|dt| dt.and_utc())
+}
+
+/// ディレクトリに日時入りのバックアップを作成し、古いものを削除する
+///
+/// # 引数
+///
+/// * `db` - バックアップするデータベース
+/// * `dir` - 保存先ディレクトリ（存在しない場合は作成する）
+/// * `keep` - 残すバックアップの数（0 の場合は削除しない）
+pub fn create_backup(db: &Database, dir: &Path, keep: usize) -> Result<BackupResult> {
+    create_backup_at(db, dir, keep, Utc::now())
+}
+
+/// [`create_backup`] の作成日時を指定できる版
+pub fn create_backup_at(
+    db: &Database,
+    dir: &Path,
+    keep: usize,
+    now: DateTime<Utc>,
+) -> Result<BackupResult> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create backup directory {:?}", dir))?;
+
+    let path = dir.join(backup_file_name(now));
+    db.backup_to(&path)?;
+    let removed = rotate_backups(dir, keep)?;
+
+    Ok(BackupResult { path, removed })
+}
+
+/// ディレクトリ内のバックアップファイルを古い順に取得する
+///
+/// `rustfeed-YYYYMMDD-HHMMSS.db` の形式のファイルだけを対象にします。
+pub fn list_backups(dir: &Path) -> Result<Vec<BackupFile>> {
+    let mut backups = Vec::new();
+    if !dir.exists() {
+        return Ok(backups);
+    }
+
+    for entry in std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read backup directory {:?}", dir))?
+    {
+        let entry = entry?;
+        let name = entry.file_name();
+        let Some(created_at) = name.to_str().and_then(parse_backup_file_name) else {
+            continue;
+        };
+        if entry.file_type()?.is_file() {
+            backups.push(BackupFile {
+                path: entry.path(),
+                created_at,
+            });
+        }
+    }
+    backups.sort_by_key(|backup| backup.created_at);
+
+    Ok(backups)
+}
+
+/// 新しい方から `keep` 個を残して古いバックアップを削除する
+///
+/// # 戻り値
+///
+/// 削除したファイルのパス（`keep` が 0 の場合は何も削除しない）
+pub fn rotate_backups(dir: &Path, keep: usize) -> Result<Vec<PathBuf>> {
+    if keep == 0 {
+        return Ok(Vec::new());
+    }
+
+    let backups = list_backups(dir)?;
+    let excess = backups.len().saturating_sub(keep);
+    let mut removed = Vec::new();
+    for backup in backups.into_iter().take(excess) {
+        std::fs::remove_file(&backup.path)
+            .with_context(|| format!("Failed to remove old backup {:?}", backup.path))?;
+        removed.push(backup.path);
+    }
+
+    Ok(removed)
+}
+
+// =============================================================================
+// 検証と復元
+// =============================================================================
+
+/// バックアップファイルの概要（[`validate_backup`] の結果）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackupInfo {
+    /// スキーマのバージョン（0 はバージョン管理導入前のデータベース）
+    pub schema_version: i64,
+
+    /// フィード数
+    pub feed_count: usize,
+
+    /// 記事数
+    pub article_count: usize,
+}
+
+/// バックアップファイルが復元できるか検証する
+///
+/// 以下を確認します（ファイルは読み取り専用で開き、変更しません）。
+///
+/// - SQLite のデータベースとして壊れていないこと（`PRAGMA quick_check`）
+/// - rustfeed のデータベースであること（`feeds` と `articles` テーブルがある）
+/// - スキーマのバージョンが [`SCHEMA_VERSION`] 以下であること
+/// - メモリ上の複製を [`Database::init`] で現在のスキーマへ移行でき、
+///   移行後のフィードと記事を読み込めること（復元後も同じ移行を行う）
+pub fn validate_backup(path: &Path) -> Result<BackupInfo> {
+    if !path.is_file() {
+        anyhow::bail!("Backup file not found: {}", path.display());
+    }
+
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("Failed to open backup file {:?}", path))?;
+
+    let check: String = conn
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .with_context(|| format!("{} is not a valid SQLite database", path.display()))?;
+    if check != "ok" {
+        anyhow::bail!("Backup file is corrupted: {}", check);
+    }
+
+    for table in ["feeds", "articles"] {
+        let exists = conn
+            .query_row(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                [table],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if !exists {
+            anyhow::bail!(
+                "{} is not a rustfeed database (missing table: {})",
+                path.display(),
+                table
+            );
+        }
+    }
+
+    let schema_version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if schema_version > SCHEMA_VERSION {
+        anyhow::bail!(
+            "Backup uses schema version {}, but this version of rustfeed supports up to {}. \
+             Upgrade rustfeed to restore it.",
+            schema_version,
+            SCHEMA_VERSION
+        );
+    }
+
+    let mut migrated = Database::from_connection(Connection::open_in_memory()?)?;
+    migrated.restore_from(path)?;
+    let readable = migrated.init().and_then(|()| {
+        migrated.get_feeds(None)?;
+        migrated.query_articles(&ArticleQuery::new().limit(1))?;
+        Ok(())
+    });
+    if let Err(e) = readable {
+        anyhow::bail!(
+            "Backup (schema version {}) cannot be migrated to schema version {}: {:#}",
+            schema_version,
+            SCHEMA_VERSION,
+            e
+        );
+    }
+
+    let count = |table: &str| -> Result<usize> {
+        let n: i64 = conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+            row.get(0)
+        })?;
+        Ok(n as usize)
+    };
+
+    Ok(BackupInfo {
+        schema_version,
+        feed_count: count("feeds")?,
+        article_count: count("articles")?,
+    })
+}
+
+/// バックアップを検証してからデータベースを置き換える
+///
+/// 検証に失敗した場合、データベースは変更されません。
+/// 古いスキーマのバックアップは、復元後に現在のスキーマへ移行されます。
+///
+/// # 引数
+///
+/// * `db` - 置き換えるデータベース
+/// * `path` - 復元するバックアップファイル
+pub fn restore_backup(db: &mut Database, path: &Path) -> Result<BackupInfo> {
+    let info = validate_backup(path)?;
+    db.restore_from(path)?;
+    db.init()?;
+
+    Ok(info)
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Feed;
+    use chrono::TimeZone;
+
+    /// テスト用の一時ディレクトリ（テストごとに別名）
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rustfeed-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// バックアップの作成・世代管理・復元が一通り動くことを確認するテスト
+    #[test]
+    fn test_backup_rotation_and_restore() {
+        let dir = temp_dir("backup-test");
+        let mut db = Database::new_at(&dir.join("live.db")).unwrap();
+        db.init().unwrap();
+        db.add_feed(&Feed::new(
+            "https://example.com/feed".to_string(),
+            "Example".to_string(),
+            None,
+        ))
+        .unwrap();
+
+        // 3世代作成し、2つだけ残す（関係のないファイルは削除しない）
+        let backups = dir.join("backups");
+        std::fs::create_dir_all(&backups).unwrap();
+        std::fs::write(backups.join("notes.txt"), "keep me").unwrap();
+        let mut results = Vec::new();
+        for hour in 1..=3 {
+            let at = Utc.with_ymd_and_hms(2025, 1, 14, hour, 0, 0).unwrap();
+            results.push(create_backup_at(&db, &backups, 2, at).unwrap());
+        }
+        assert_eq!(results[2].removed, vec![results[0].path.clone()]);
+        let remaining: Vec<PathBuf> = list_backups(&backups)
+            .unwrap()
+            .into_iter()
+            .map(|b| b.path)
+            .collect();
+        assert_eq!(
+            remaining,
+            vec![results[1].path.clone(), results[2].path.clone()]
+        );
+        assert!(backups.join("notes.txt").exists());
+        assert_eq!(
+            results[2].path.file_name().unwrap(),
+            "rustfeed-20250114-030000.db"
+        );
+
+        // 一時ファイルや -wal が残っていない
+        let names: Vec<String> = std::fs::read_dir(&backups)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names.len(), 3, "{:?}", names);
+
+        // バックアップ後の変更は復元で元に戻る
+        db.add_feed_simple("https://example.com/other", "Other")
+            .unwrap();
+        let info = restore_backup(&mut db, &results[2].path).unwrap();
+        assert_eq!(info.schema_version, SCHEMA_VERSION);
+        assert_eq!(info.feed_count, 1);
+        assert_eq!(db.get_feeds(None).unwrap().len(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// 新しすぎるスキーマや rustfeed 以外のファイルは復元せず、データベースも変更しないことを確認するテスト
+    #[test]
+    fn test_restore_rejects_invalid_backups() {
+        let dir = temp_dir("restore-test");
+        let mut db = Database::new_at(&dir.join("live.db")).unwrap();
+        db.init().unwrap();
+        db.add_feed_simple("https://example.com/feed", "Example")
+            .unwrap();
+
+        let newer = dir.join("newer.db");
+        db.backup_to(&newer).unwrap();
+        Connection::open(&newer)
+            .unwrap()
+            .pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+        let err = restore_backup(&mut db, &newer).unwrap_err();
+        assert!(err.to_string().contains("schema version"), "{}", err);
+
+        // 移行しても読み込めないファイル（移行では追加されないカラムがない）も復元しない
+        let missing = dir.join("missing.db");
+        db.backup_to(&missing).unwrap();
+        Connection::open(&missing)
+            .unwrap()
+            .execute_batch("ALTER TABLE feeds DROP COLUMN title")
+            .unwrap();
+        let err = restore_backup(&mut db, &missing).unwrap_err();
+        assert!(err.to_string().contains("cannot be migrated"), "{}", err);
+
+        let other = dir.join("other.db");
+        Connection::open(&other)
+            .unwrap()
+            .execute_batch("CREATE TABLE notes (id INTEGER)")
+            .unwrap();
+        assert!(restore_backup(&mut db, &other).is_err());
+
+        let text = dir.join("text.db");
+        std::fs::write(&text, "not a database").unwrap();
+        assert!(restore_backup(&mut db, &text).is_err());
+
+        assert_eq!(db.get_feeds(None).unwrap().len(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// 古いスキーマ（後から追加したカラムがない）のバックアップは、復元後に移行されることを確認するテスト
+    #[test]
+    fn test_restore_migrates_old_schema() {
+        let dir = temp_dir("restore-old-test");
+        let mut db = Database::new_at(&dir.join("live.db")).unwrap();
+        db.init().unwrap();
+        let feed = db
+            .add_feed_simple("https://example.com/feed", "Example")
+            .unwrap();
+        db.add_article(&crate::models::Article::new(
+            feed.id,
+            "Hello".to_string(),
+            Some("https://example.com/hello".to_string()),
+            None,
+            None,
+        ))
+        .unwrap();
+
+        let old = dir.join("old.db");
+        db.backup_to(&old).unwrap();
+        let conn = Connection::open(&old).unwrap();
+        conn.execute_batch(
+            "DROP TRIGGER trg_articles_read_history;
+             DROP INDEX idx_articles_canonical_url;
+             ALTER TABLE feeds DROP COLUMN site_url;
+             ALTER TABLE feeds DROP COLUMN paused;
+             ALTER TABLE articles DROP COLUMN canonical_url;
+             ALTER TABLE articles DROP COLUMN read_at;
+             ALTER TABLE articles DROP COLUMN first_opened_at;",
+        )
+        .unwrap();
+        conn.pragma_update(None, "user_version", 3).unwrap();
+        drop(conn);
+
+        let info = restore_backup(&mut db, &old).unwrap();
+        assert_eq!(info.schema_version, 3);
+        let feeds = db.get_feeds(None).unwrap();
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(feeds[0].site_url, None);
+        assert!(!feeds[0].paused);
+        let articles = db.query_articles(&ArticleQuery::new()).unwrap();
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].read_at, None);
+        assert_eq!(
+            validate_backup(&dir.join("live.db"))
+                .unwrap()
+                .schema_version,
+            SCHEMA_VERSION
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -14,7 +14,6 @@
 //! [general]
 //! default_limit = 20
 //! show_unread_only = false
-//! disabled_feeds = [2, 3]
 //!
 //! [display]
 //! date_format = "%Y-%m-%d"
@@ -22,6 +21,19 @@
 //!
 //! [database]
 //! path = "~/.rustfeed/rustfeed.db"
+//!
+//! [backup]
+//! dir = "~/.rustfeed/backups"
+//! keep = 7
+//!
+//! [email]
+//! host = "smtp.example.com"
+//! port = 587
+//! starttls = true
+//! username = "me@example.com"
+//! password = "app-password"
+//! from = "rustfeed <me@example.com>"
+//! to = ["team@example.com"]
 //! ```
 
 use anyhow::{Context, Result};
@@ -35,6 +47,8 @@ use std::path::PathBuf;
 /// - `general`: 一般的な設定（デフォルトリミット、未読のみ表示など）
 /// - `display`: 表示に関する設定（日付フォーマット、説明表示など）
 /// - `database`: データベースに関する設定（パスなど）
+/// - `backup`: バックアップに関する設定（保存先、残す数）
+/// - `email`: ダイジェストをメールで送る SMTP サーバーと宛先
 ///
 /// # デフォルト値
 ///
@@ -50,6 +64,12 @@ pub struct AppConfig {
 
     #[serde(default)]
     pub database: DatabaseConfig,
+
+    #[serde(default)]
+    pub backup: BackupConfig,
+
+    #[serde(default)]
+    pub email: EmailConfig,
 }
 
 /// 一般的な設定
@@ -58,7 +78,7 @@ pub struct AppConfig {
 ///
 /// - `default_limit`: デフォルトの記事表示件数
 /// - `show_unread_only`: デフォルトで未読のみ表示するか
-/// - `disabled_feeds`: 無効化するフィードのIDリスト
+/// - `disabled_feeds`: 無効化するフィードのIDリスト（非推奨）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeneralConfig {
     #[serde(default = "default_limit")]
@@ -67,6 +87,10 @@ pub struct GeneralConfig {
     #[serde(default)]
     pub show_unread_only: bool,
 
+    /// 非推奨: フィードの一時停止はデータベースで管理します（`rustfeed pause <id>`）
+    ///
+    /// 古い設定ファイルを読み込めるように残しています。ここに書かれたIDは
+    /// 初回起動時に一度だけ一時停止として取り込まれます（`Database::import_disabled_feeds`）。
     #[serde(default)]
     pub disabled_feeds: Vec<i64>,
 }
@@ -97,6 +121,58 @@ pub struct DatabaseConfig {
     pub path: String,
 }
 
+/// バックアップに関する設定
+///
+/// # フィールド
+///
+/// - `dir`: `rustfeed backup` でパスを省略したときの保存先ディレクトリ
+/// - `keep`: 残すバックアップの数（古いものから削除、0 の場合は削除しない）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupConfig {
+    #[serde(default = "default_backup_dir")]
+    pub dir: String,
+
+    #[serde(default = "default_backup_keep")]
+    pub keep: usize,
+}
+
+/// メール（SMTP）に関する設定
+///
+/// `rustfeed digest --send` で使います。
+///
+/// # フィールド
+///
+/// - `host`: SMTP サーバーのホスト名
+/// - `port`: SMTP サーバーのポート（デフォルトは 587）
+/// - `starttls`: STARTTLS で暗号化するか（デフォルトは true）
+/// - `username`: 認証のユーザー名（省略時は認証しない）
+/// - `password`: 認証のパスワード（省略時は環境変数 `RUSTFEED_SMTP_PASSWORD`）
+/// - `from`: 差出人（`Name <addr@example.com>` 形式も可）
+/// - `to`: 宛先のリスト
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailConfig {
+    #[serde(default)]
+    pub host: String,
+
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+
+    #[serde(default = "default_true")]
+    pub starttls: bool,
+
+    #[serde(default)]
+    pub username: Option<String>,
+
+    #[serde(default)]
+    pub password: Option<String>,
+
+    #[serde(default)]
+    pub from: String,
+
+    #[serde(default)]
+    pub to: Vec<String>,
+}
+
 // =============================================================================
 // デフォルト値関数
 // =============================================================================
@@ -116,6 +192,21 @@ fn default_db_path() -> String {
     "~/.rustfeed/rustfeed.db".to_string()
 }
 
+/// デフォルトのバックアップ保存先
+fn default_backup_dir() -> String {
+    "~/.rustfeed/backups".to_string()
+}
+
+/// デフォルトで残すバックアップの数
+fn default_backup_keep() -> usize {
+    7
+}
+
+/// デフォルトの SMTP ポート（submission）
+fn default_smtp_port() -> u16 {
+    587
+}
+
 /// デフォルトでtrueを返す
 fn default_true() -> bool {
     true
@@ -152,6 +243,47 @@ impl Default for DatabaseConfig {
     }
 }
 
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            dir: default_backup_dir(),
+            keep: default_backup_keep(),
+        }
+    }
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: default_smtp_port(),
+            starttls: true,
+            username: None,
+            password: None,
+            from: String::new(),
+            to: Vec::new(),
+        }
+    }
+}
+
+impl BackupConfig {
+    /// バックアップの保存先ディレクトリを取得する（先頭の `~` はホームディレクトリに展開）
+    pub fn dir_path(&self) -> Result<PathBuf> {
+        expand_home(&self.dir)
+    }
+}
+
+/// パス先頭の `~` をホームディレクトリに展開する
+fn expand_home(path: &str) -> Result<PathBuf> {
+    match path.strip_prefix('~') {
+        Some(rest) => {
+            let home = dirs::home_dir().context("Could not find home directory")?;
+            Ok(home.join(rest.trim_start_matches(['/', '\\'])))
+        }
+        None => Ok(PathBuf::from(path)),
+    }
+}
+
 // =============================================================================
 // 設定ファイルの読み込み
 // =============================================================================
@@ -210,11 +342,20 @@ impl AppConfig {
     ///
     /// ホームディレクトリが取得できない場合はエラーを返します。
     pub fn config_path() -> Result<PathBuf> {
-        let config_dir = dirs::config_dir()
-            .context("Failed to get config directory")?
-            .join("rustfeed");
+        Ok(Self::config_dir()?.join("config.toml"))
+    }
 
-        Ok(config_dir.join("config.toml"))
+    /// 設定ディレクトリのパスを取得する
+    ///
+    /// 設定ファイルのほか、ダイジェストのテンプレート（`templates/`）を置きます。
+    ///
+    /// # エラー
+    ///
+    /// ホームディレクトリが取得できない場合はエラーを返します。
+    pub fn config_dir() -> Result<PathBuf> {
+        Ok(dirs::config_dir()
+            .context("Failed to get config directory")?
+            .join("rustfeed"))
     }
 
     /// 設定ファイルのサンプルを文字列として返す
@@ -239,10 +380,6 @@ default_limit = 20
 # デフォルトで未読のみ表示するか
 show_unread_only = false
 
-# 無効化するフィードのIDリスト（記事を表示したくないフィード）
-# 例: disabled_feeds = [2, 3]
-disabled_feeds = []
-
 [display]
 # 日付のフォーマット（chrono形式）
 # %Y: 年, %m: 月, %d: 日, %H: 時, %M: 分, %S: 秒
@@ -255,6 +392,23 @@ show_description = true
 # データベースファイルのパス
 # ~ はホームディレクトリに展開されます
 path = "~/.rustfeed/rustfeed.db"
+
+[backup]
+# `rustfeed backup` でパスを省略したときの保存先
+dir = "~/.rustfeed/backups"
+
+# 残すバックアップの数（古いものから削除、0 の場合は削除しない）
+keep = 7
+
+# `rustfeed digest --send` で使う SMTP サーバー
+# [email]
+# host = "smtp.example.com"
+# port = 587
+# starttls = true
+# username = "me@example.com"
+# password = "app-password"  # 省略時は環境変数 RUSTFEED_SMTP_PASSWORD
+# from = "rustfeed <me@example.com>"
+# to = ["team@example.com"]
 "#
     }
 }
@@ -13,6 +13,18 @@
 //! - **テーブル**:
 //!   - `feeds`: RSSフィード情報
 //!   - `articles`: 記事情報（feedsへの外部キーを持つ）
+//!   - `tags` / `article_tags`: タグと記事の多対多の関連
+//!   - `categories`: 階層カテゴリ（feeds.category_id から参照される）
+//!   - `read_later`: 「あとで読む」キュー
+//!   - `article_notes` / `article_highlights`: 記事のメモとハイライト
+//!   - `reading_history`: 記事を開いた・既読/未読にした履歴（追記のみ）
+//!   - `fetch_lease`: フェッチ中のプロセスを1つに限るためのリース
+//!
+//! ## 複数プロセスからのアクセス
+//!
+//! 接続は WAL モードで開き、ロック中は一定時間待つ（busy timeout）ため、
+//! cron の `rustfeed fetch` と TUI/GUI を同時に使えます。
+//! フィードの更新自体は [`Database::try_acquire_fetch_lease`] で1プロセスに限ります。
 //!
 //! ## 使用例
 //!
@@ -29,10 +41,112 @@
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection};
-use std::path::PathBuf;
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::dedup::{self, FeedMergeReport, FeedOverlap};
+use crate::dump::{Dump, DumpArticle, DumpHistoryEntry, DumpQueueEntry, LoadMode, LoadReport};
+use crate::html;
+use crate::models::{
+    Article, Category, Feed, Highlight, HistoryEntry, HistoryEvent, Note, QueueEntry, Tag,
+};
+use crate::opml::{OpmlFeed, OpmlImportEntry, OpmlImportStatus};
+use crate::query::{ArticleQuery, SortOrder, SORT_KEY};
+use crate::stats::{
+    aggregate_categories, days_before, read_ratio, DailyActivity, FeedStats, StaleFeed, Stats,
+    StatsOptions, Totals,
+};
+use crate::storage::new_fetch_lease;
+
+/// articles テーブルから取得するカラム（[`row_to_article`] と順序を合わせる）
+///
+/// 最後のカラムは重複記事の数で、`articles` という名前のテーブルから取得する前提です。
+const ARTICLE_COLUMNS: &str = "id, feed_id, title, url, content, published_at, is_read, \
+     is_favorite, created_at, read_at, first_opened_at, duplicate_of, \
+     (SELECT COUNT(*) FROM articles AS d WHERE d.duplicate_of = articles.id)";
+
+/// [`ARTICLE_COLUMNS`] のカラム数（後ろに続くカラムのインデックス計算に使う）
+const ARTICLE_COLUMN_COUNT: usize = 13;
+
+/// 記事を追加するSQL（[`insert_article`] でパラメータを渡す）
+///
+/// `INSERT OR IGNORE` のため、同じフィードに同じURLの記事があれば何もしません。
+const INSERT_ARTICLE_SQL: &str = "INSERT OR IGNORE INTO articles \
+     (feed_id, title, url, content, published_at, is_read, is_favorite, created_at, \
+      canonical_url, duplicate_of) \
+     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)";
+
+/// タイトルで重複を探すときに期間の判定に使う日時の式
+///
+/// インデックス `idx_articles_duplicate_time` も同じ式で作成しています。
+const DUPLICATE_TIME: &str = "COALESCE(published_at, created_at)";
+
+/// 読書履歴に記録する現在時刻（UTC、ミリ秒まで）を返すSQL式
+///
+/// トリガーの中でも使うため、Rust側ではなくSQLiteで時刻を求めます。
+/// 形式は RFC3339 で、文字列の比較がそのまま時刻の比較になります。
+const SQL_NOW: &str = "strftime('%Y-%m-%dT%H:%M:%fZ', 'now')";
+
+/// 他の接続（別プロセスを含む）がロック中のとき、エラーにせず待つ最大時間
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// データベースのスキーマのバージョン（`PRAGMA user_version` に記録する）
+///
+/// テーブルやカラムを追加・変更したら1つ上げます。
+/// これより新しいバージョンのバックアップは復元できません（[`crate::backup::validate_backup`]）。
+pub const SCHEMA_VERSION: i64 = 4;
+
+/// オンラインバックアップで1ステップにコピーするページ数
+///
+/// ステップの間はロックを手放すため、バックアップ中も他の接続が書き込めます。
+const BACKUP_PAGES_PER_STEP: std::os::raw::c_int = 256;
+
+/// オンラインバックアップのステップ間の待ち時間
+const BACKUP_STEP_PAUSE: Duration = Duration::from_millis(10);
+
+/// フェッチのリースの有効期間（秒）
+///
+/// フェッチ中のプロセスが異常終了しても、この時間が過ぎれば他のプロセスが取得できます。
+/// 長いフェッチは [`Database::renew_fetch_lease`] で延長します。
+pub const FETCH_LEASE_TTL_SECS: i64 = 10 * 60;
+
+/// フェッチのリース（同時に1つのプロセスだけがフィードを更新するための権利）
+///
+/// [`Database::try_acquire_fetch_lease`] で取得し、
+/// 更新が終わったら [`Database::release_fetch_lease`] で解放します。
+#[derive(Debug, Clone)]
+pub struct FetchLease {
+    /// リースの保持者を識別する文字列（プロセスIDと取得時刻）
+    pub holder: String,
+
+    /// リースの有効期限（UTC）
+    pub expires_at: DateTime<Utc>,
+}
 
-use crate::models::{Article, Feed};
+/// feeds テーブルから取得するカラム（[`row_to_feed`] と順序を合わせる）
+///
+/// `cp` は [`CATEGORY_PATHS_CTE`] を LEFT JOIN した別名です。
+const FEED_COLUMNS: &str = "f.id, f.url, f.title, f.description, f.created_at, f.updated_at, \
+     f.custom_name, cp.path, f.priority, f.category_id, f.paused, f.site_url";
+
+/// カテゴリIDとパス（"Tech/Rust"）の対応を求める再帰CTE
+///
+/// `WITH RECURSIVE` の後に置いて使います。
+pub(crate) const CATEGORY_PATHS_CTE: &str = "category_paths(id, path) AS (
+    SELECT id, name FROM categories WHERE parent_id IS NULL
+    UNION ALL
+    SELECT c.id, cp.path || '/' || c.name
+    FROM categories c JOIN category_paths cp ON c.parent_id = cp.id
+)";
+
+/// あるカテゴリ（最初のパラメータ）とその全てのサブカテゴリのIDを求める再帰CTE
+const CATEGORY_TREE_CTE: &str = "category_tree(id) AS (
+    SELECT ?1
+    UNION ALL
+    SELECT c.id FROM categories c JOIN category_tree t ON c.parent_id = t.id
+)";
 
 // =============================================================================
 // Database 構造体
@@ -54,8 +168,8 @@ use crate::models::{Article, Feed};
 pub struct Database {
     /// SQLiteデータベース接続
     ///
-    /// `rusqlite::Connection` はスレッドセーフではないため、
-    /// 複数スレッドで使用する場合は `Mutex` で保護する必要があります。
+    /// `rusqlite::Connection` は `Sync` ではないため、
+    /// 複数のタスクで共有する場合は [`crate::pool::DbPool`] を使います。
     conn: Connection,
 }
 
@@ -78,7 +192,14 @@ impl Database {
     pub fn new() -> Result<Self> {
         // データベースファイルのパスを取得
         let db_path = Self::get_db_path()?;
+        Self::new_at(&db_path)
+    }
 
+    /// 指定したパスのデータベースに接続する
+    ///
+    /// ファイルと親ディレクトリが存在しない場合は作成されます。
+    /// テーブルの初期化（[`Database::init`]）は行いません。
+    pub fn new_at(db_path: &Path) -> Result<Self> {
         // 親ディレクトリを作成（存在しない場合）
         // `if let Some(...)` は Option から値を取り出すイディオム
         if let Some(parent) = db_path.parent() {
@@ -88,9 +209,26 @@ impl Database {
 
         // データベース接続を開く
         // `Connection::open` はファイルが存在しなければ新規作成する
-        let conn = Connection::open(&db_path)
+        let conn = Connection::open(db_path)
             .with_context(|| format!("Failed to open database at {:?}", db_path))?;
 
+        Self::from_connection(conn)
+    }
+
+    /// 開いた接続に共通の設定を適用して `Database` を作成する
+    pub(crate) fn from_connection(conn: Connection) -> Result<Self> {
+        // 外部キー制約を有効化する（SQLiteでは接続ごとに設定が必要）
+        // これにより ON DELETE CASCADE が実際に動作する
+        conn.pragma_update(None, "foreign_keys", true)?;
+
+        // cron の fetch と TUI/GUI が同時に書き込んでも "database is locked" にならないよう、
+        // ロックが解放されるまで待つ
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+
+        // WAL モードでは書き込み中も他の接続から読み込める
+        // （ファイル単位の設定だが、インメモリDBでは "memory" のままになる）
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+
         Ok(Self { conn })
     }
 
@@ -113,7 +251,7 @@ impl Database {
     ///
     /// `PathBuf` は所有権を持つパス型で、`join` で安全にパスを連結できます。
     /// これにより、OS間のパス区切り文字の違い（`/` vs `\`）を自動処理します。
-    fn get_db_path() -> Result<PathBuf> {
+    pub(crate) fn get_db_path() -> Result<PathBuf> {
         // `dirs::home_dir()` はホームディレクトリを取得（Noneの可能性あり）
         let home = dirs::home_dir().context("Could not find home directory")?;
 
@@ -138,8 +276,11 @@ impl Database {
     /// | created_at | TEXT | 作成日時（RFC3339） |
     /// | updated_at | TEXT | 更新日時（RFC3339） |
     /// | custom_name | TEXT | カスタム名（NULL時はtitleを使用） |
-    /// | category | TEXT | カテゴリ（NULL可） |
+    /// | category | TEXT | 旧形式のカテゴリ文字列（移行後は常にNULL） |
     /// | priority | INTEGER | 優先順位（デフォルト0、高いほど優先） |
+    /// | category_id | INTEGER | 外部キー（categories.id、NULL可） |
+    /// | paused | INTEGER | 一時停止中なら1 |
+    /// | site_url | TEXT | フィードを配信しているサイトのURL（NULL可） |
     ///
     /// ## articles テーブル
     /// | カラム | 型 | 説明 |
@@ -152,6 +293,68 @@ impl Database {
     /// | published_at | TEXT | 公開日時（NULL可） |
     /// | is_read | INTEGER | 既読フラグ（0/1） |
     /// | created_at | TEXT | 取得日時 |
+    /// | read_at | TEXT | 既読にした日時（未読ならNULL） |
+    /// | first_opened_at | TEXT | 初めて開いた日時（NULL可） |
+    /// | canonical_url | TEXT | 重複判定用に正規化したURL（NULL可） |
+    /// | duplicate_of | INTEGER | 重複記事の場合、元の記事（articles.id） |
+    ///
+    /// 重複記事のどれかが既読になると、トリガーによって元の記事と他の重複記事も既読になります。
+    ///
+    /// ## tags / article_tags テーブル
+    /// | テーブル | カラム | 説明 |
+    /// |----------|--------|------|
+    /// | tags | id, name, created_at | タグ（name は大文字小文字を区別せず一意） |
+    /// | article_tags | article_id, tag_id, created_at | 記事とタグの関連（多対多） |
+    ///
+    /// ## categories テーブル
+    /// | カラム | 型 | 説明 |
+    /// |--------|-----|------|
+    /// | id | INTEGER | 主キー（自動採番） |
+    /// | name | TEXT | カテゴリ名（同じ親の中で大文字小文字を区別せず一意） |
+    /// | parent_id | INTEGER | 親カテゴリ（NULLはトップレベル） |
+    /// | position | INTEGER | 同じ親の中での並び順 |
+    /// | created_at | TEXT | 作成日時 |
+    ///
+    /// ## read_later テーブル
+    /// | カラム | 型 | 説明 |
+    /// |--------|-----|------|
+    /// | article_id | INTEGER | 主キー・外部キー（articles.id） |
+    /// | position | INTEGER | キュー内の順番（小さいほど先） |
+    /// | added_at | TEXT | キューに追加した日時 |
+    ///
+    /// 記事の `is_read` が 1 に更新されると、トリガーによってキューから削除されます。
+    ///
+    /// ## article_notes / article_highlights テーブル
+    /// | テーブル | カラム | 説明 |
+    /// |----------|--------|------|
+    /// | article_notes | article_id, body, created_at, updated_at | 記事ごとのメモ（1記事につき1件） |
+    /// | article_highlights | id, article_id, start_offset, end_offset, text, created_at | 本文中のハイライト範囲 |
+    ///
+    /// ## reading_history テーブル
+    /// | カラム | 型 | 説明 |
+    /// |--------|-----|------|
+    /// | id | INTEGER | 主キー（自動採番） |
+    /// | article_id | INTEGER | 外部キー（articles.id） |
+    /// | event | TEXT | 出来事（opened / read / unread） |
+    /// | occurred_at | TEXT | 出来事が起きた日時 |
+    ///
+    /// `is_read` が変わると、トリガーによって `read_at` の更新と履歴の追記が行われます。
+    ///
+    /// ## fetch_lease テーブル
+    /// | カラム | 型 | 説明 |
+    /// |--------|-----|------|
+    /// | id | INTEGER | 主キー（常に1、行は最大1つ） |
+    /// | holder | TEXT | リースの保持者 |
+    /// | acquired_at | TEXT | 取得日時 |
+    /// | expires_at | TEXT | 有効期限 |
+    ///
+    /// ## migrations テーブル
+    /// | カラム | 型 | 説明 |
+    /// |--------|-----|------|
+    /// | name | TEXT | 主キー（移行の名前） |
+    /// | applied_at | TEXT | 移行を行った日時 |
+    ///
+    /// 設定ファイルの内容を取り込むなど、一度だけ行う移行の記録に使います。
     ///
     /// # SQLについて
     ///
@@ -202,6 +405,31 @@ impl Database {
             [],
         )?;
 
+        // 公開日時での絞り込み用のインデックス
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_articles_published_at ON articles(published_at)",
+            [],
+        )?;
+
+        // キーセットページネーション用のインデックス（(SORT_KEY, id) の行値での走査）
+        // 式は query::SORT_KEY と一致している必要がある
+        self.conn.execute(
+            &format!(
+                "CREATE INDEX IF NOT EXISTS idx_articles_sort_key ON articles({}, id)",
+                SORT_KEY
+            ),
+            [],
+        )?;
+        self.conn.execute(
+            &format!(
+                "CREATE INDEX IF NOT EXISTS idx_articles_feed_sort_key ON articles(feed_id, {}, id)",
+                SORT_KEY
+            ),
+            [],
+        )?;
+        self.conn
+            .execute("DROP INDEX IF EXISTS idx_articles_feed_published_at", [])?;
+
         // マイグレーション: is_favorite カラムの追加
         // 既存のテーブルにカラムが存在しない場合のみ追加
         // SQLiteでは IF NOT EXISTS が使えないため、エラーを無視する
@@ -239,6 +467,334 @@ impl Database {
             [],
         )?;
 
+        // tags テーブルの作成（タグ名は大文字小文字を区別せず一意）
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS tags (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // article_tags テーブルの作成（記事とタグの多対多の関連）
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS article_tags (
+                article_id INTEGER NOT NULL,
+                tag_id INTEGER NOT NULL,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (article_id, tag_id),
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE,
+                FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_article_tags_tag_id ON article_tags(tag_id)",
+            [],
+        )?;
+
+        // categories テーブルの作成（parent_id で木構造を作る）
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS categories (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                parent_id INTEGER,
+                position INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (parent_id) REFERENCES categories(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        // 同じ親の下でカテゴリ名が重複しないようにする
+        // NULL 同士は UNIQUE で区別されないため、トップレベルは 0 として扱う
+        self.conn.execute(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_categories_parent_name
+             ON categories(IFNULL(parent_id, 0), name COLLATE NOCASE)",
+            [],
+        )?;
+
+        // マイグレーション: feeds テーブルにカテゴリIDを追加
+        let _ = self.conn.execute(
+            "ALTER TABLE feeds ADD COLUMN category_id INTEGER
+             REFERENCES categories(id) ON DELETE SET NULL",
+            [],
+        );
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_feeds_category_id ON feeds(category_id)",
+            [],
+        )?;
+
+        // マイグレーション: 旧形式のカテゴリ文字列を categories テーブルに移す
+        self.migrate_legacy_categories()?;
+
+        // マイグレーション: フィードの一時停止フラグ
+        let _ = self.conn.execute(
+            "ALTER TABLE feeds ADD COLUMN paused INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // マイグレーション: サイトのURL（OPML の htmlUrl）
+        let _ = self
+            .conn
+            .execute("ALTER TABLE feeds ADD COLUMN site_url TEXT", []);
+
+        // read_later テーブルの作成（「あとで読む」キュー）
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS read_later (
+                article_id INTEGER PRIMARY KEY,
+                position INTEGER NOT NULL,
+                added_at TEXT NOT NULL,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        // 既読になった記事はキューから外す
+        // 既読にする経路（単体・一括・トグル）が複数あるため、トリガーでまとめて扱う
+        self.conn.execute(
+            "CREATE TRIGGER IF NOT EXISTS trg_read_later_remove_on_read
+             AFTER UPDATE OF is_read ON articles
+             WHEN NEW.is_read = 1
+             BEGIN
+                 DELETE FROM read_later WHERE article_id = NEW.id;
+             END",
+            [],
+        )?;
+
+        // article_notes テーブルの作成（記事ごとのメモ）
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS article_notes (
+                article_id INTEGER PRIMARY KEY,
+                body TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // article_highlights テーブルの作成（本文中のハイライト範囲）
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS article_highlights (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                article_id INTEGER NOT NULL,
+                start_offset INTEGER NOT NULL,
+                end_offset INTEGER NOT NULL,
+                text TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_article_highlights_article_id
+             ON article_highlights(article_id)",
+            [],
+        )?;
+
+        // マイグレーション: 既読日時と初めて開いた日時
+        let _ = self
+            .conn
+            .execute("ALTER TABLE articles ADD COLUMN read_at TEXT", []);
+        let _ = self
+            .conn
+            .execute("ALTER TABLE articles ADD COLUMN first_opened_at TEXT", []);
+
+        // reading_history テーブルの作成（追記のみの読書履歴）
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS reading_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                article_id INTEGER NOT NULL,
+                event TEXT NOT NULL,
+                occurred_at TEXT NOT NULL,
+                FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_reading_history_occurred_at
+             ON reading_history(occurred_at)",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_reading_history_article_id
+             ON reading_history(article_id)",
+            [],
+        )?;
+        // 既読/未読が実際に変わったときに read_at を更新し、履歴に追記する
+        // 既読/未読を変える経路（単体・一括・トグル）が複数あるため、トリガーでまとめて扱う
+        self.conn.execute(
+            &format!(
+                "CREATE TRIGGER IF NOT EXISTS trg_articles_read_history
+                 AFTER UPDATE OF is_read ON articles
+                 WHEN NEW.is_read != OLD.is_read
+                 BEGIN
+                     UPDATE articles
+                     SET read_at = CASE WHEN NEW.is_read = 1 THEN {now} ELSE NULL END
+                     WHERE id = NEW.id;
+                     INSERT INTO reading_history (article_id, event, occurred_at)
+                     VALUES (
+                         NEW.id,
+                         CASE WHEN NEW.is_read = 1 THEN 'read' ELSE 'unread' END,
+                         {now}
+                     );
+                 END",
+                now = SQL_NOW
+            ),
+            [],
+        )?;
+
+        // fetch_lease テーブルの作成（複数プロセスのフェッチが重ならないようにする）
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS fetch_lease (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                holder TEXT NOT NULL,
+                acquired_at TEXT NOT NULL,
+                expires_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // migrations テーブルの作成（一度だけ行うデータ移行の記録）
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS migrations (
+                name TEXT PRIMARY KEY,
+                applied_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // マイグレーション: 重複記事の検出用のカラム
+        let _ = self
+            .conn
+            .execute("ALTER TABLE articles ADD COLUMN canonical_url TEXT", []);
+        let _ = self.conn.execute(
+            "ALTER TABLE articles ADD COLUMN duplicate_of INTEGER
+             REFERENCES articles(id) ON DELETE SET NULL",
+            [],
+        );
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_articles_canonical_url ON articles(canonical_url)",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_articles_duplicate_of ON articles(duplicate_of)",
+            [],
+        )?;
+        // タイトルでの重複判定は元の記事（duplicate_of IS NULL）だけを期間で絞り込む
+        self.conn.execute(
+            &format!(
+                "CREATE INDEX IF NOT EXISTS idx_articles_duplicate_time
+                 ON articles(duplicate_of, {})",
+                DUPLICATE_TIME
+            ),
+            [],
+        )?;
+        self.migrate_canonical_urls()?;
+
+        // 重複記事のどれかが既読になったら、元の記事と他の重複記事も既読にする
+        // （再帰的なトリガーは無効のため、このトリガーが連鎖することはない）
+        self.conn.execute(
+            "CREATE TRIGGER IF NOT EXISTS trg_articles_read_duplicates
+             AFTER UPDATE OF is_read ON articles
+             WHEN NEW.is_read = 1 AND OLD.is_read = 0
+             BEGIN
+                 UPDATE articles SET is_read = 1
+                 WHERE is_read = 0
+                   AND (id = NEW.duplicate_of
+                        OR duplicate_of = NEW.id
+                        OR duplicate_of = NEW.duplicate_of);
+             END",
+            [],
+        )?;
+
+        // 全ての移行が終わったらスキーマのバージョンを記録する
+        self.conn
+            .pragma_update(None, "user_version", SCHEMA_VERSION)?;
+
+        Ok(())
+    }
+
+    /// データベースに記録されたスキーマのバージョンを取得する
+    ///
+    /// [`Database::init`] 前の古いデータベースでは 0 を返します。
+    pub fn schema_version(&self) -> Result<i64> {
+        Ok(self
+            .conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))?)
+    }
+
+    /// 既存の記事に正規化したURL（`canonical_url`）を設定する（初回のみ）
+    ///
+    /// 以降に追加する記事が、既存の記事の重複として検出されるようにします。
+    /// 既存の記事どうしの関連付けは行いません。
+    fn migrate_canonical_urls(&self) -> Result<()> {
+        const MIGRATION: &str = "article_canonical_urls";
+
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        let applied: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM migrations WHERE name = ?1)",
+            params![MIGRATION],
+            |row| row.get(0),
+        )?;
+        if applied {
+            return Ok(());
+        }
+
+        let urls = {
+            let mut stmt = tx.prepare("SELECT id, url FROM articles WHERE url IS NOT NULL")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<Vec<(i64, String)>, _>>()?
+        };
+        {
+            let mut stmt = tx.prepare("UPDATE articles SET canonical_url = ?1 WHERE id = ?2")?;
+            for (id, url) in urls {
+                if let Some(canonical) = dedup::canonical_url(&url) {
+                    stmt.execute(params![canonical, id])?;
+                }
+            }
+        }
+        tx.execute(
+            "INSERT INTO migrations (name, applied_at) VALUES (?1, ?2)",
+            params![MIGRATION, Utc::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// 旧形式の `feeds.category` 文字列を `categories` テーブルに移行する
+    ///
+    /// 文字列中の `/` は階層の区切りとして扱います（"Tech/Rust" → Tech の下の Rust）。
+    /// 移行したフィードの `category` は NULL にするため、2回目以降は何もしません。
+    fn migrate_legacy_categories(&self) -> Result<()> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT category FROM feeds
+             WHERE category IS NOT NULL AND category_id IS NULL",
+        )?;
+        let legacy = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+
+        if legacy.is_empty() {
+            return Ok(());
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        for name in legacy {
+            // 空白だけのカテゴリは「カテゴリなし」として扱う
+            let category_id = if category_path_segments(&name).is_empty() {
+                None
+            } else {
+                Some(self.create_category(&name)?)
+            };
+            tx.execute(
+                "UPDATE feeds SET category_id = ?1, category = NULL WHERE category = ?2",
+                params![category_id, name],
+            )?;
+        }
+        tx.commit()?;
+
         Ok(())
     }
 
@@ -267,19 +823,42 @@ impl Database {
     /// `params![]` マクロを使ったパラメータ化クエリにより、
     /// SQLインジェクション攻撃を防いでいます。
     /// 値はプレースホルダ（?1, ?2...）で指定し、実際の値は別途渡します。
+    ///
+    /// # URLの正規化
+    ///
+    /// URLは [`dedup::normalize_feed_url`] で正規化してから保存します。
+    /// `http`/`https` や末尾の `/` の違いだけで同じフィードが登録済みの場合はエラーになります。
+    ///
+    /// # カテゴリ
+    ///
+    /// `category_id` が設定されていればそれを使います。未設定で `category`（パス）が
+    /// 設定されている場合は、そのパスのカテゴリ（存在しなければ作成）に登録します。
     pub fn add_feed(&self, feed: &Feed) -> Result<i64> {
+        let url = dedup::normalize_feed_url(&feed.url)?;
+        if let Some(existing) = self.find_feed_by_url(&url)? {
+            anyhow::bail!("Feed already exists: {} (ID {})", existing.url, existing.id);
+        }
+
+        let category_id = match (feed.category_id, feed.category.as_deref()) {
+            (Some(id), _) => Some(id),
+            (None, Some(path)) => Some(self.create_category(path)?),
+            (None, None) => None,
+        };
+
         self.conn.execute(
-            "INSERT INTO feeds (url, title, description, created_at, updated_at, custom_name, category, priority)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO feeds (url, title, description, created_at, updated_at, custom_name, priority, category_id, paused, site_url)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
-                feed.url,
+                url,
                 feed.title,
                 feed.description,
                 feed.created_at.to_rfc3339(), // RFC3339形式の文字列に変換
                 feed.updated_at.to_rfc3339(),
                 feed.custom_name,
-                feed.category,
                 feed.priority,
+                category_id,
+                feed.paused,
+                feed.site_url,
             ],
         )?;
 
@@ -313,11 +892,13 @@ impl Database {
     ///
     /// # 引数
     ///
-    /// * `category` - フィルタリングするカテゴリ（Noneの場合は全件取得）
+    /// * `category` - フィルタリングするカテゴリのパス（Noneの場合は全件取得）
     ///
     /// # 戻り値
     ///
-    /// フィードのベクター（優先順位の降順、同じ優先順位ではID順）
+    /// フィードのベクター（優先順位の降順、同じ優先順位ではID順）。
+    /// カテゴリを指定した場合は、そのサブカテゴリに属するフィードも含まれます。
+    /// 存在しないカテゴリを指定した場合は空のベクターを返します。
     ///
     /// # イテレータとクロージャ
     ///
@@ -328,42 +909,47 @@ impl Database {
     /// クロージャは `|| {}` で定義する無名関数で、
     /// 周囲の変数をキャプチャできます。
     pub fn get_feeds(&self, category: Option<&str>) -> Result<Vec<Feed>> {
-        // 全件取得してRustコードでフィルタリング
-        // プリペアドステートメントを作成
-        let mut stmt = self.conn.prepare(
-            "SELECT id, url, title, description, created_at, updated_at, custom_name, category, priority
-             FROM feeds
-             ORDER BY priority DESC, id",
-        )?;
+        let feeds = match category {
+            None => {
+                // プリペアドステートメントを作成
+                let sql = format!(
+                    "WITH RECURSIVE {}
+                     SELECT {} FROM feeds f
+                     LEFT JOIN category_paths cp ON cp.id = f.category_id
+                     ORDER BY f.priority DESC, f.id",
+                    CATEGORY_PATHS_CTE, FEED_COLUMNS
+                );
+                let mut stmt = self.conn.prepare(&sql)?;
 
-        // クエリ実行と結果のマッピング
-        let feeds = stmt
-            .query_map([], |row| {
-                Ok(Feed {
-                    id: row.get(0)?,
-                    url: row.get(1)?,
-                    title: row.get(2)?,
-                    description: row.get(3)?,
-                    created_at: parse_datetime(row.get::<_, String>(4)?),
-                    updated_at: parse_datetime(row.get::<_, String>(5)?),
-                    custom_name: row.get(6)?,
-                    category: row.get(7)?,
-                    priority: row.get(8).unwrap_or(0),
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
+                // クエリ実行と結果のマッピング
+                let feeds = stmt
+                    .query_map([], row_to_feed)?
+                    .collect::<Result<Vec<_>, _>>()?;
+                feeds
+            }
+            Some(path) => {
+                let Some(category_id) = self.find_category_id(path)? else {
+                    return Ok(Vec::new());
+                };
 
-        // カテゴリフィルタを適用
-        let result = if let Some(cat) = category {
-            feeds
-                .into_iter()
-                .filter(|f| f.category.as_deref() == Some(cat))
-                .collect()
-        } else {
-            feeds
+                // 指定カテゴリとそのサブカテゴリに属するフィードを取得
+                let sql = format!(
+                    "WITH RECURSIVE {}, {}
+                     SELECT {} FROM feeds f
+                     LEFT JOIN category_paths cp ON cp.id = f.category_id
+                     WHERE f.category_id IN (SELECT id FROM category_tree)
+                     ORDER BY f.priority DESC, f.id",
+                    CATEGORY_PATHS_CTE, CATEGORY_TREE_CTE, FEED_COLUMNS
+                );
+                let mut stmt = self.conn.prepare(&sql)?;
+                let feeds = stmt
+                    .query_map(params![category_id], row_to_feed)?
+                    .collect::<Result<Vec<_>, _>>()?;
+                feeds
+            }
         };
 
-        Ok(result)
+        Ok(feeds)
     }
 
     /// IDでフィードを取得する
@@ -383,26 +969,20 @@ impl Database {
     /// `Option<T>` は「値が存在するかもしれない」ことを型で表現します。
     /// これにより、nullチェックを忘れるバグを防ぎます。
     pub fn get_feed(&self, id: i64) -> Result<Option<Feed>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, url, title, description, created_at, updated_at, custom_name, category, priority
-             FROM feeds WHERE id = ?1",
-        )?;
+        let sql = format!(
+            "WITH RECURSIVE {}
+             SELECT {} FROM feeds f
+             LEFT JOIN category_paths cp ON cp.id = f.category_id
+             WHERE f.id = ?1",
+            CATEGORY_PATHS_CTE, FEED_COLUMNS
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
 
         let mut rows = stmt.query(params![id])?;
 
         // 最初の行があれば取得
         if let Some(row) = rows.next()? {
-            Ok(Some(Feed {
-                id: row.get(0)?,
-                url: row.get(1)?,
-                title: row.get(2)?,
-                description: row.get(3)?,
-                created_at: parse_datetime(row.get::<_, String>(4)?),
-                updated_at: parse_datetime(row.get::<_, String>(5)?),
-                custom_name: row.get(6)?,
-                category: row.get(7)?,
-                priority: row.get(8).unwrap_or(0),
-            }))
+            Ok(Some(row_to_feed(row)?))
         } else {
             Ok(None)
         }
@@ -429,42 +1009,157 @@ impl Database {
 
     /// フィードのURLを更新する
     ///
+    /// URLは [`Database::add_feed`] と同じく正規化してから保存します。
+    ///
     /// # 引数
     /// * `feed_id` - 更新するフィードのID
     /// * `new_url` - 新しいURL
     ///
     /// # エラー
-    /// 新しいURLが既に他のフィードで使用されている場合、UNIQUE制約違反でエラーになります。
+    /// 新しいURLが既に他のフィードで使用されている場合（正規化して同じになる場合を含む）は
+    /// エラーになります。
     pub fn update_feed_url(&self, feed_id: i64, new_url: &str) -> Result<()> {
+        let url = dedup::normalize_feed_url(new_url)?;
+        if let Some(existing) = self.find_feed_by_url(&url)? {
+            if existing.id != feed_id {
+                anyhow::bail!("Feed already exists: {} (ID {})", existing.url, existing.id);
+            }
+        }
+
         let now = Utc::now().to_rfc3339();
         self.conn.execute(
             "UPDATE feeds SET url = ?1, updated_at = ?2 WHERE id = ?3",
-            params![new_url, now, feed_id],
+            params![url, now, feed_id],
         )?;
         Ok(())
     }
 
+    /// URLが同じとみなせる登録済みのフィードを探す
+    ///
+    /// [`dedup::feed_url_key`] で比較するため、`http`/`https`、`www.`、末尾の `/`、
+    /// FeedBurner の別名ホストの違いは区別しません。
+    pub fn find_feed_by_url(&self, url: &str) -> Result<Option<Feed>> {
+        let key = dedup::feed_url_key(url);
+        let id = {
+            let mut stmt = self.conn.prepare("SELECT id, url FROM feeds ORDER BY id")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            let mut found = None;
+            for row in rows {
+                let (id, existing): (i64, String) = row?;
+                if dedup::feed_url_key(&existing) == key {
+                    found = Some(id);
+                    break;
+                }
+            }
+            found
+        };
+        match id {
+            Some(id) => self.get_feed(id),
+            None => Ok(None),
+        }
+    }
+
     /// フィードのカテゴリを設定する
     ///
     /// # 引数
     /// * `feed_id` - 更新するフィードのID
-    /// * `category` - 設定するカテゴリ（NULLの場合はカテゴリをクリア）
+    /// * `category` - 設定するカテゴリのパス（例: "Tech/Rust"、NULLの場合はカテゴリをクリア）
+    ///
+    /// # エラー
+    /// カテゴリが存在しない場合はエラーになります（タイプミスで新しいカテゴリが
+    /// できないようにするため）。先に [`Database::create_category`] で作成してください。
     pub fn set_feed_category(&self, feed_id: i64, category: Option<&str>) -> Result<()> {
+        let category_id = match category {
+            Some(path) => Some(
+                self.find_category_id(path)?
+                    .with_context(|| format!("Category not found: {}", path))?,
+            ),
+            None => None,
+        };
+
         self.conn.execute(
-            "UPDATE feeds SET category = ?1 WHERE id = ?2",
-            params![category, feed_id],
+            "UPDATE feeds SET category_id = ?1 WHERE id = ?2",
+            params![category_id, feed_id],
         )?;
         Ok(())
     }
 
-    /// フィードの優先順位を設定する
+    /// フィードの一時停止を設定・解除する
     ///
     /// # 引数
     /// * `feed_id` - 更新するフィードのID
-    /// * `priority` - 優先順位（高いほど優先、デフォルト0）
-    pub fn set_feed_priority(&self, feed_id: i64, priority: i64) -> Result<()> {
-        self.conn.execute(
-            "UPDATE feeds SET priority = ?1 WHERE id = ?2",
+    /// * `paused` - true で一時停止、false で再開
+    ///
+    /// # 戻り値
+    /// 該当するフィードが存在しなかった場合は `Ok(false)`
+    pub fn set_feed_paused(&self, feed_id: i64, paused: bool) -> Result<bool> {
+        let affected = self.conn.execute(
+            "UPDATE feeds SET paused = ?1 WHERE id = ?2",
+            params![paused, feed_id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// フィードのサイトのURLが未設定（NULL）の場合だけ設定する
+    ///
+    /// 更新時に取得したフィードのリンクで、登録時や OPML の読み込みで
+    /// サイトのURLが得られなかったフィードを補います。設定済みのURLは変更しません。
+    ///
+    /// # 戻り値
+    /// 設定した場合は `Ok(true)`
+    pub fn fill_feed_site_url(&self, feed_id: i64, site_url: &str) -> Result<bool> {
+        let affected = self.conn.execute(
+            "UPDATE feeds SET site_url = ?1 WHERE id = ?2 AND site_url IS NULL",
+            params![site_url, feed_id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 設定ファイルの `disabled_feeds` を一時停止フラグとして取り込む
+    ///
+    /// 以前は無効化するフィードを設定ファイルの `general.disabled_feeds` で指定していました。
+    /// 初回だけ、存在するフィードを一時停止にして `migrations` テーブルに記録します。
+    /// 2回目以降は何もしないため、取り込んだ後に再開したフィードが再び止まることはありません。
+    ///
+    /// # 戻り値
+    /// 一時停止にしたフィード数
+    pub fn import_disabled_feeds(&self, feed_ids: &[i64]) -> Result<usize> {
+        const MIGRATION: &str = "config_disabled_feeds";
+
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        let applied: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM migrations WHERE name = ?1)",
+            params![MIGRATION],
+            |row| row.get(0),
+        )?;
+        if applied {
+            return Ok(0);
+        }
+
+        let mut paused = 0;
+        for id in feed_ids {
+            paused += tx.execute(
+                "UPDATE feeds SET paused = 1 WHERE id = ?1 AND paused = 0",
+                params![id],
+            )?;
+        }
+        tx.execute(
+            "INSERT INTO migrations (name, applied_at) VALUES (?1, ?2)",
+            params![MIGRATION, Utc::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+
+        Ok(paused)
+    }
+
+    /// フィードの優先順位を設定する
+    ///
+    /// # 引数
+    /// * `feed_id` - 更新するフィードのID
+    /// * `priority` - 優先順位（高いほど優先、デフォルト0）
+    pub fn set_feed_priority(&self, feed_id: i64, priority: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE feeds SET priority = ?1 WHERE id = ?2",
             params![priority, feed_id],
         )?;
         Ok(())
@@ -482,7 +1177,7 @@ impl Database {
         let now = Utc::now();
         let feed = Feed {
             id: 0, // 自動採番
-            url: url.to_string(),
+            url: dedup::normalize_feed_url(url)?,
             title: title.to_string(),
             description: None,
             created_at: now,
@@ -490,6 +1185,9 @@ impl Database {
             custom_name: None,
             category: None,
             priority: 0,
+            category_id: None,
+            paused: false,
+            site_url: None,
         };
         let id = self.add_feed(&feed)?;
         Ok(Feed { id, ..feed })
@@ -529,7 +1227,10 @@ impl Database {
     /// # 戻り値
     /// 検索結果の記事一覧
     pub fn search_articles(&self, query: &str, limit: i64) -> Result<Vec<Article>> {
-        self.get_articles(false, limit as usize, Some(query), None)
+        let query = ArticleQuery::new()
+            .keywords_from_str(query)
+            .limit(limit.max(0) as usize);
+        self.query_articles(&query)
     }
 
     // =========================================================================
@@ -553,146 +1254,98 @@ impl Database {
     /// エラーではなく単に無視します。これにより、
     /// 同じ記事を重複して登録することを防ぎます。
     pub fn add_article(&self, article: &Article) -> Result<Option<i64>> {
-        let result = self.conn.execute(
-            "INSERT OR IGNORE INTO articles (feed_id, title, url, content, published_at, is_read, is_favorite, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            params![
-                article.feed_id,
-                article.title,
-                article.url,
-                article.content,
-                // Option<DateTime> を Option<String> に変換
-                article.published_at.map(|dt| dt.to_rfc3339()),
-                article.is_read as i32, // bool を整数に変換（SQLiteはboolがない）
-                article.is_favorite as i32, // bool を整数に変換
-                article.created_at.to_rfc3339(),
-            ],
-        )?;
-
-        if result > 0 {
-            Ok(Some(self.conn.last_insert_rowid()))
-        } else {
-            Ok(None) // 記事は既に存在していた
-        }
+        let mut stmt = self.conn.prepare_cached(INSERT_ARTICLE_SQL)?;
+        insert_article(&self.conn, &mut stmt, article)
     }
 
-    /// 記事を取得する（フィルタ付き）
+    /// 複数の記事を1つのトランザクションでまとめて追加する（既存の場合は無視）
+    ///
+    /// 記事ごとに自動コミットすると、その都度ディスクへの同期（fsync）が発生します。
+    /// このメソッドは準備済みステートメントを使い回し、最後に1回だけコミットします。
     ///
     /// # 引数
     ///
-    /// * `unread_only` - true なら未読記事のみ取得
-    /// * `limit` - 取得する最大件数
-    /// * `filter` - キーワードフィルタ（カンマ区切りで複数指定可能、OR条件）
-    /// * `feed_id` - 特定のフィードIDでフィルタ（None の場合は全フィード）
+    /// * `articles` - 追加する記事（`feed_id` は設定済みであること）
     ///
     /// # 戻り値
     ///
-    /// 記事のベクター（公開日時の降順）
+    /// 新規挿入された記事のID（`articles` の順、重複して無視された記事は含まない）
     ///
-    /// # キーワードフィルタについて
+    /// # エラー
     ///
-    /// `filter` パラメータにキーワードを指定すると、タイトルまたは本文に
-    /// そのキーワードを含む記事のみを取得します。
-    /// 複数のキーワードをカンマで区切って指定すると、いずれかを含む記事を取得します（OR条件）。
-    pub fn get_articles(
-        &self,
-        unread_only: bool,
-        limit: usize,
-        filter: Option<&str>,
-        feed_id: Option<i64>,
-    ) -> Result<Vec<Article>> {
-        // ベースとなるSQLクエリ
-        let mut sql = String::from(
-            "SELECT id, feed_id, title, url, content, published_at, is_read, is_favorite, created_at
-             FROM articles"
-        );
-
-        // WHERE句の条件を格納するベクター
-        let mut conditions = Vec::new();
-
-        // 未読フィルタ
-        if unread_only {
-            conditions.push("is_read = 0".to_string());
-        }
-
-        // フィードIDフィルタ
-        if feed_id.is_some() {
-            conditions.push("feed_id = ?".to_string());
-        }
-
-        // キーワードフィルタ
-        // filter が Some の場合、キーワードで検索条件を追加
-        if let Some(filter_str) = filter {
-            // カンマで分割して各キーワードに対する条件を作成
-            // 例: "rust,cargo" -> ["rust", "cargo"]
-            let keywords: Vec<&str> = filter_str.split(',').map(|s| s.trim()).collect();
-
-            if !keywords.is_empty() {
-                // 各キーワードに対してOR条件を作成
-                // (title LIKE '%keyword1%' OR content LIKE '%keyword1%') OR (title LIKE '%keyword2%' OR content LIKE '%keyword2%')
-                let keyword_conditions: Vec<String> = keywords
-                    .iter()
-                    .map(|_| "(title LIKE ? OR content LIKE ?)".to_string())
-                    .collect();
-
-                conditions.push(format!("({})", keyword_conditions.join(" OR ")));
+    /// 途中でエラーが発生した場合はロールバックされ、1件も追加されません。
+    pub fn add_articles(&self, articles: &[Article]) -> Result<Vec<i64>> {
+        // コミットせずにドロップされるとロールバックされる
+        let tx = self.conn.unchecked_transaction()?;
+        let mut ids = Vec::new();
+        {
+            let mut stmt = tx.prepare_cached(INSERT_ARTICLE_SQL)?;
+            for article in articles {
+                if let Some(id) = insert_article(&tx, &mut stmt, article)? {
+                    ids.push(id);
+                }
             }
         }
+        tx.commit()?;
 
-        // WHERE句を追加
-        if !conditions.is_empty() {
-            sql.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
-        }
-
-        // ORDER BY と LIMIT を追加
-        sql.push_str(" ORDER BY published_at DESC, created_at DESC LIMIT ?");
-
-        // パラメータを準備
-        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
-
-        // フィードIDパラメータを追加
-        if let Some(id) = feed_id {
-            params.push(Box::new(id));
-        }
-
-        // キーワードフィルタのパラメータを追加
-        if let Some(filter_str) = filter {
-            let keywords: Vec<&str> = filter_str.split(',').map(|s| s.trim()).collect();
-            for keyword in keywords {
-                let pattern = format!("%{}%", keyword);
-                // タイトル用とコンテンツ用で2回追加
-                params.push(Box::new(pattern.clone()));
-                params.push(Box::new(pattern));
-            }
-        }
+        Ok(ids)
+    }
 
-        // limit パラメータを追加
-        params.push(Box::new(limit as i64));
+    /// 条件に一致する記事を取得する
+    ///
+    /// # 引数
+    ///
+    /// * `query` - 取得条件（[`ArticleQuery`]）
+    ///
+    /// # 戻り値
+    ///
+    /// 条件に一致する記事のベクター（`query.sort` の順）
+    ///
+    /// # フィルタと LIMIT について
+    ///
+    /// 全ての条件は1つのSQL文の WHERE 句に変換されるため、
+    /// `LIMIT` はフィルタ適用後の件数に対して効きます。
+    ///
+    /// # 例
+    ///
+    /// ```rust,no_run
+    /// use rustfeed_core::{db::Database, query::ArticleQuery};
+    ///
+    /// let db = Database::open()?;
+    /// let query = ArticleQuery::new().feed(1).unread_only().limit(50);
+    /// let articles = db.query_articles(&query)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn query_articles(&self, query: &ArticleQuery) -> Result<Vec<Article>> {
+        let (sql, params) = query.to_sql(ARTICLE_COLUMNS);
 
-        // プリペアドステートメントを準備
         let mut stmt = self.conn.prepare(&sql)?;
-
-        // パラメータをバインドして実行
         let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|b| b.as_ref()).collect();
         let articles = stmt
-            .query_map(&params_refs[..], |row| {
-                Ok(Article {
-                    id: row.get(0)?,
-                    feed_id: row.get(1)?,
-                    title: row.get(2)?,
-                    url: row.get(3)?,
-                    content: row.get(4)?,
-                    published_at: row.get::<_, Option<String>>(5)?.map(parse_datetime),
-                    is_read: row.get::<_, i32>(6)? != 0,
-                    is_favorite: row.get::<_, i32>(7)? != 0,
-                    created_at: parse_datetime(row.get::<_, String>(8)?),
-                })
-            })?
+            .query_map(&params_refs[..], row_to_article)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(articles)
     }
 
+    /// 条件に一致する記事の数を数える
+    ///
+    /// `query.limit` / `query.offset` を指定した場合は、それを適用した後の件数になります。
+    pub fn count_articles(&self, query: &ArticleQuery) -> Result<usize> {
+        let (conditions, params) = query.conditions();
+        let mut sql = "SELECT COUNT(*) FROM articles".to_string();
+        if !conditions.is_empty() {
+            sql.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+        }
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|b| b.as_ref()).collect();
+        let count: i64 = self
+            .conn
+            .query_row(&sql, &params_refs[..], |row| row.get(0))?;
+        let count = (count as usize).saturating_sub(query.offset);
+        Ok(query.limit.map_or(count, |limit| count.min(limit)))
+    }
+
     /// 記事を既読としてマークする
     ///
     /// # 引数
@@ -710,6 +1363,32 @@ impl Database {
         Ok(affected > 0)
     }
 
+    /// 複数の記事を1つのトランザクションでまとめて既読にする
+    ///
+    /// 記事ごとに [`Database::mark_as_read`] を呼ぶと、その都度コミットが発生します。
+    /// 重複記事の既読の連動や読書履歴は、1件ずつ既読にした場合と同じく記録されます。
+    ///
+    /// # 戻り値
+    ///
+    /// 新たに既読になった記事の件数（既読だった記事や存在しない記事は数えない）
+    pub fn mark_articles_read(&self, ids: &[i64]) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut affected = 0;
+
+        // SQLite のパラメータ数上限を超えないよう分割して更新する
+        for chunk in ids.chunks(500) {
+            let placeholders = vec!["?"; chunk.len()].join(", ");
+            let sql = format!(
+                "UPDATE articles SET is_read = 1 WHERE is_read = 0 AND id IN ({})",
+                placeholders
+            );
+            affected += tx.execute(&sql, rusqlite::params_from_iter(chunk))?;
+        }
+        tx.commit()?;
+
+        Ok(affected)
+    }
+
     /// 記事を一括で既読にする（フィルタ付き）
     ///
     /// # 引数
@@ -864,29 +1543,7 @@ impl Database {
     ///
     /// お気に入り記事のベクター（公開日時の降順）
     pub fn get_favorite_articles(&self, limit: usize) -> Result<Vec<Article>> {
-        let sql = "SELECT id, feed_id, title, url, content, published_at, is_read, is_favorite, created_at
-             FROM articles WHERE is_favorite = 1
-             ORDER BY published_at DESC, created_at DESC LIMIT ?1";
-
-        let mut stmt = self.conn.prepare(sql)?;
-
-        let articles = stmt
-            .query_map(params![limit as i64], |row| {
-                Ok(Article {
-                    id: row.get(0)?,
-                    feed_id: row.get(1)?,
-                    title: row.get(2)?,
-                    url: row.get(3)?,
-                    content: row.get(4)?,
-                    published_at: row.get::<_, Option<String>>(5)?.map(parse_datetime),
-                    is_read: row.get::<_, i32>(6)? != 0,
-                    is_favorite: row.get::<_, i32>(7)? != 0,
-                    created_at: parse_datetime(row.get::<_, String>(8)?),
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok(articles)
+        self.query_articles(&ArticleQuery::new().favorites_only().limit(limit))
     }
 
     /// 記事のIDで記事を取得する
@@ -900,25 +1557,13 @@ impl Database {
     /// - `Ok(Some(article))`: 記事が見つかった
     /// - `Ok(None)`: 記事が見つからなかった
     pub fn get_article(&self, id: i64) -> Result<Option<Article>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, feed_id, title, url, content, published_at, is_read, is_favorite, created_at
-             FROM articles WHERE id = ?1",
-        )?;
+        let sql = format!("SELECT {} FROM articles WHERE id = ?1", ARTICLE_COLUMNS);
+        let mut stmt = self.conn.prepare(&sql)?;
 
         let mut rows = stmt.query(params![id])?;
 
         if let Some(row) = rows.next()? {
-            Ok(Some(Article {
-                id: row.get(0)?,
-                feed_id: row.get(1)?,
-                title: row.get(2)?,
-                url: row.get(3)?,
-                content: row.get(4)?,
-                published_at: row.get::<_, Option<String>>(5)?.map(parse_datetime),
-                is_read: row.get::<_, i32>(6)? != 0,
-                is_favorite: row.get::<_, i32>(7)? != 0,
-                created_at: parse_datetime(row.get::<_, String>(8)?),
-            }))
+            Ok(Some(row_to_article(row)?))
         } else {
             Ok(None)
         }
@@ -948,28 +1593,3518 @@ impl Database {
 
         Ok((total as usize, unread as usize))
     }
-}
+    // =========================================================================
+    // Tag 関連の操作
+    // =========================================================================
 
-// =============================================================================
-// ヘルパー関数
-// =============================================================================
+    /// タグを取得する（存在しない場合は作成する）
+    ///
+    /// # 引数
+    ///
+    /// * `name` - タグ名（前後の空白は除去される）
+    ///
+    /// # 戻り値
+    ///
+    /// タグのID
+    ///
+    /// # エラー
+    ///
+    /// タグ名が空の場合はエラーを返します。
+    pub fn get_or_create_tag(&self, name: &str) -> Result<i64> {
+        let name = normalize_tag_name(name)?;
 
-/// RFC3339形式の文字列を DateTime<Utc> にパースする
-///
-/// # 引数
-///
-/// * `s` - RFC3339形式の日時文字列（例: "2024-01-01T12:00:00Z"）
-///
-/// # 戻り値
-///
-/// パースされた DateTime。パース失敗時は現在時刻を返す。
-///
-/// # エラー処理
-///
-/// `unwrap_or_else` はエラー時にクロージャを実行してデフォルト値を返します。
-/// これにより、不正な日時形式でもプログラムがパニックしません。
-fn parse_datetime(s: String) -> DateTime<Utc> {
-    DateTime::parse_from_rfc3339(&s)
-        .map(|dt| dt.with_timezone(&Utc)) // タイムゾーンをUTCに変換
-        .unwrap_or_else(|_| Utc::now()) // パース失敗時は現在時刻
+        self.conn.execute(
+            "INSERT OR IGNORE INTO tags (name, created_at) VALUES (?1, ?2)",
+            params![name, Utc::now().to_rfc3339()],
+        )?;
+
+        let id = self.conn.query_row(
+            "SELECT id FROM tags WHERE name = ?1",
+            params![name],
+            |row| row.get(0),
+        )?;
+        Ok(id)
+    }
+
+    /// 全てのタグを記事数とともに取得する
+    ///
+    /// # 戻り値
+    ///
+    /// `(タグ, 記事数)` のベクター（タグ名順）
+    pub fn get_tags(&self) -> Result<Vec<(Tag, usize)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.id, t.name, t.created_at, COUNT(at.article_id)
+             FROM tags t
+             LEFT JOIN article_tags at ON at.tag_id = t.id
+             GROUP BY t.id
+             ORDER BY t.name COLLATE NOCASE",
+        )?;
+
+        let tags = stmt
+            .query_map([], |row| {
+                Ok((
+                    Tag {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        created_at: parse_datetime(row.get::<_, String>(2)?),
+                    },
+                    row.get::<_, i64>(3)? as usize,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(tags)
+    }
+
+    /// 記事にタグを付ける
+    ///
+    /// 存在しないタグは自動的に作成されます。既に付いているタグは無視されます。
+    ///
+    /// # 引数
+    ///
+    /// * `article_id` - 対象記事のID
+    /// * `names` - 付けるタグ名のリスト
+    ///
+    /// # 戻り値
+    ///
+    /// 新しく付けたタグの数
+    ///
+    /// # エラー
+    ///
+    /// 記事が存在しない場合、またはタグ名が空の場合はエラーを返します。
+    pub fn tag_article(&self, article_id: i64, names: &[&str]) -> Result<usize> {
+        if self.get_article(article_id)?.is_none() {
+            anyhow::bail!("Article not found with ID: {}", article_id);
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        let now = Utc::now().to_rfc3339();
+        let mut added = 0;
+
+        for name in names {
+            let tag_id = self.get_or_create_tag(name)?;
+            added += tx.execute(
+                "INSERT OR IGNORE INTO article_tags (article_id, tag_id, created_at)
+                 VALUES (?1, ?2, ?3)",
+                params![article_id, tag_id, now],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(added)
+    }
+
+    /// 記事からタグを外す
+    ///
+    /// # 引数
+    ///
+    /// * `article_id` - 対象記事のID
+    /// * `names` - 外すタグ名のリスト
+    ///
+    /// # 戻り値
+    ///
+    /// 外したタグの数
+    pub fn untag_article(&self, article_id: i64, names: &[&str]) -> Result<usize> {
+        let mut removed = 0;
+
+        for name in names {
+            removed += self.conn.execute(
+                "DELETE FROM article_tags
+                 WHERE article_id = ?1
+                   AND tag_id = (SELECT id FROM tags WHERE name = ?2)",
+                params![article_id, name.trim()],
+            )?;
+        }
+
+        Ok(removed)
+    }
+
+    /// 複数の記事に付いているタグ名をまとめて取得する
+    ///
+    /// 記事一覧やエクスポートで記事ごとにクエリを発行しないためのメソッドです。
+    ///
+    /// # 戻り値
+    ///
+    /// 記事IDをキー、タグ名のベクターを値とするマップ（タグのない記事は含まれない）
+    pub fn get_tags_for_articles(&self, article_ids: &[i64]) -> Result<HashMap<i64, Vec<String>>> {
+        let mut result: HashMap<i64, Vec<String>> = HashMap::new();
+
+        // SQLite のパラメータ数上限を超えないよう分割して取得する
+        for chunk in article_ids.chunks(500) {
+            let placeholders = vec!["?"; chunk.len()].join(", ");
+            let sql = format!(
+                "SELECT at.article_id, t.name FROM article_tags at
+                 JOIN tags t ON t.id = at.tag_id
+                 WHERE at.article_id IN ({})
+                 ORDER BY t.name COLLATE NOCASE",
+                placeholders
+            );
+            let mut stmt = self.conn.prepare(&sql)?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(chunk), |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?;
+
+            for row in rows {
+                let (article_id, name) = row?;
+                result.entry(article_id).or_default().push(name);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// タグの名前を変更する
+    ///
+    /// 大文字小文字だけの変更（"rust" → "Rust"）も可能です。
+    ///
+    /// # 戻り値
+    ///
+    /// - `Ok(true)`: 変更成功
+    /// - `Ok(false)`: 該当するタグが存在しなかった
+    ///
+    /// # エラー
+    ///
+    /// 変更後の名前のタグが既に存在する場合はエラーを返します。
+    /// その場合は [`Database::merge_tags`] を使ってください。
+    pub fn rename_tag(&self, old_name: &str, new_name: &str) -> Result<bool> {
+        let new_name = normalize_tag_name(new_name)?;
+
+        let existing: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT id FROM tags WHERE name = ?1",
+                params![new_name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let source: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT id FROM tags WHERE name = ?1",
+                params![old_name.trim()],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        let Some(source_id) = source else {
+            return Ok(false);
+        };
+        if existing.is_some_and(|id| id != source_id) {
+            anyhow::bail!(
+                "Tag '{}' already exists. Merge the tags instead of renaming.",
+                new_name
+            );
+        }
+
+        self.conn.execute(
+            "UPDATE tags SET name = ?1 WHERE id = ?2",
+            params![new_name, source_id],
+        )?;
+        Ok(true)
+    }
+
+    /// タグを統合する
+    ///
+    /// `source` タグが付いた記事に `target` タグを付け、`source` タグを削除します。
+    /// `target` タグが存在しない場合は作成されます。
+    ///
+    /// # 戻り値
+    ///
+    /// 新しく `target` タグが付いた記事の数
+    ///
+    /// # エラー
+    ///
+    /// `source` タグが存在しない場合はエラーを返します。
+    pub fn merge_tags(&self, source: &str, target: &str) -> Result<usize> {
+        let source_id: i64 = self
+            .conn
+            .query_row(
+                "SELECT id FROM tags WHERE name = ?1",
+                params![source.trim()],
+                |row| row.get(0),
+            )
+            .optional()?
+            .with_context(|| format!("Tag not found: {}", source))?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        let target_id = self.get_or_create_tag(target)?;
+        if target_id == source_id {
+            return Ok(0);
+        }
+
+        let moved = tx.execute(
+            "INSERT OR IGNORE INTO article_tags (article_id, tag_id, created_at)
+             SELECT article_id, ?1, created_at FROM article_tags WHERE tag_id = ?2",
+            params![target_id, source_id],
+        )?;
+        tx.execute(
+            "DELETE FROM article_tags WHERE tag_id = ?1",
+            params![source_id],
+        )?;
+        tx.execute("DELETE FROM tags WHERE id = ?1", params![source_id])?;
+
+        tx.commit()?;
+        Ok(moved)
+    }
+
+    /// タグを削除する（記事との関連も削除される）
+    ///
+    /// # 戻り値
+    ///
+    /// - `Ok(true)`: 削除成功
+    /// - `Ok(false)`: 該当するタグが存在しなかった
+    pub fn delete_tag(&self, name: &str) -> Result<bool> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM article_tags WHERE tag_id = (SELECT id FROM tags WHERE name = ?1)",
+            params![name.trim()],
+        )?;
+        let affected = tx.execute("DELETE FROM tags WHERE name = ?1", params![name.trim()])?;
+        tx.commit()?;
+        Ok(affected > 0)
+    }
+
+    // =========================================================================
+    // 読書履歴関連の操作
+    // =========================================================================
+
+    /// 記事を開いたことを記録する
+    ///
+    /// 初めて開いた場合は `first_opened_at` を設定し、毎回履歴に `opened` を追記します。
+    /// 既読にはしません（既読にする場合は [`Database::mark_as_read`] を別途呼びます）。
+    ///
+    /// # 戻り値
+    ///
+    /// - `Ok(true)`: 記録した
+    /// - `Ok(false)`: 該当する記事が存在しなかった
+    pub fn mark_opened(&self, id: i64) -> Result<bool> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        let affected = tx.execute(
+            &format!(
+                "UPDATE articles SET first_opened_at = COALESCE(first_opened_at, {})
+                 WHERE id = ?1",
+                SQL_NOW
+            ),
+            params![id],
+        )?;
+        if affected > 0 {
+            tx.execute(
+                &format!(
+                    "INSERT INTO reading_history (article_id, event, occurred_at)
+                     VALUES (?1, ?2, {})",
+                    SQL_NOW
+                ),
+                params![id, HistoryEvent::Opened.as_str()],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(affected > 0)
+    }
+
+    /// 読書履歴を新しい順に取得する
+    ///
+    /// 記事を未読に戻しても、過去に既読にした記録は残ります。
+    ///
+    /// # 引数
+    ///
+    /// * `events` - 取得する出来事の種類（空の場合は全て）
+    /// * `since` - この日時以降の履歴のみ（None の場合は制限なし）
+    /// * `until` - この日時より前の履歴のみ（None の場合は制限なし）
+    /// * `limit` - 最大件数（None の場合は全件）
+    pub fn get_history(
+        &self,
+        events: &[HistoryEvent],
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        limit: Option<usize>,
+    ) -> Result<Vec<HistoryEntry>> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if !events.is_empty() {
+            conditions.push(format!("event IN ({})", vec!["?"; events.len()].join(", ")));
+            values.extend(
+                events
+                    .iter()
+                    .map(|event| Box::new(event.as_str()) as Box<dyn rusqlite::ToSql>),
+            );
+        }
+        if let Some(since) = since {
+            conditions.push("occurred_at >= ?".to_string());
+            values.push(Box::new(history_timestamp(since)));
+        }
+        if let Some(until) = until {
+            conditions.push("occurred_at < ?".to_string());
+            values.push(Box::new(history_timestamp(until)));
+        }
+
+        // reading_history と articles は id が重複するため、履歴側を別名にしてから結合する
+        let mut sql = format!(
+            "SELECT {}, history_id, event, occurred_at FROM (
+                 SELECT id AS history_id, article_id, event, occurred_at
+                 FROM reading_history",
+            ARTICLE_COLUMNS
+        );
+        if !conditions.is_empty() {
+            sql.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+        }
+        sql.push_str(
+            ") JOIN articles ON articles.id = article_id
+             ORDER BY occurred_at DESC, history_id DESC",
+        );
+        if let Some(limit) = limit {
+            sql.push_str(" LIMIT ?");
+            values.push(Box::new(i64::try_from(limit).unwrap_or(i64::MAX)));
+        }
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let entries = stmt
+            .query_map(rusqlite::params_from_iter(values.iter()), |row| {
+                let event: String = row.get(ARTICLE_COLUMN_COUNT + 1)?;
+                Ok(HistoryEntry {
+                    history_id: row.get(ARTICLE_COLUMN_COUNT)?,
+                    article: row_to_article(row)?,
+                    event: HistoryEvent::parse(&event).unwrap_or(HistoryEvent::Opened),
+                    occurred_at: parse_datetime(row.get::<_, String>(ARTICLE_COLUMN_COUNT + 2)?),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    // =========================================================================
+    // フェッチのリース関連の操作
+    // =========================================================================
+
+    /// フェッチのリースの取得を試みる
+    ///
+    /// 他のプロセス（または同じプロセスの別のタスク）が有効なリースを持っている場合は
+    /// `None` を返します。期限切れのリースは破棄して取得します。
+    ///
+    /// # 戻り値
+    ///
+    /// - `Ok(Some(lease))`: 取得できた（終わったら [`Database::release_fetch_lease`] を呼ぶ）
+    /// - `Ok(None)`: 他のフェッチが実行中
+    pub fn try_acquire_fetch_lease(&self) -> Result<Option<FetchLease>> {
+        let now = Utc::now();
+        let lease = new_fetch_lease();
+
+        // 確認と取得の間に他の接続が割り込まないよう、最初から書き込みロックを取る
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        tx.execute(
+            "DELETE FROM fetch_lease WHERE expires_at < ?1",
+            params![now.to_rfc3339()],
+        )?;
+        let inserted = tx.execute(
+            "INSERT OR IGNORE INTO fetch_lease (id, holder, acquired_at, expires_at)
+             VALUES (1, ?1, ?2, ?3)",
+            params![
+                lease.holder,
+                now.to_rfc3339(),
+                lease.expires_at.to_rfc3339()
+            ],
+        )?;
+        tx.commit()?;
+
+        Ok((inserted > 0).then_some(lease))
+    }
+
+    /// フェッチのリースの有効期限を延長する
+    ///
+    /// # 戻り値
+    ///
+    /// リースをまだ保持していれば `true`（期限切れで他に取られていた場合は `false`）
+    pub fn renew_fetch_lease(&self, lease: &mut FetchLease) -> Result<bool> {
+        let expires_at = Utc::now() + chrono::Duration::seconds(FETCH_LEASE_TTL_SECS);
+        let affected = self.conn.execute(
+            "UPDATE fetch_lease SET expires_at = ?1 WHERE holder = ?2",
+            params![expires_at.to_rfc3339(), lease.holder],
+        )?;
+        if affected > 0 {
+            lease.expires_at = expires_at;
+        }
+        Ok(affected > 0)
+    }
+
+    /// フェッチのリースを解放する（既に期限切れで他に取られていた場合は何もしない）
+    pub fn release_fetch_lease(&self, lease: &FetchLease) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM fetch_lease WHERE holder = ?1",
+            params![lease.holder],
+        )?;
+        Ok(())
+    }
+
+    // =========================================================================
+    // バックアップ関連の操作
+    // =========================================================================
+
+    /// SQLite のオンラインバックアップ API でデータベースをファイルに複製する
+    ///
+    /// 少しずつページをコピーするため、他のプロセスが読み書きしていても実行できます。
+    /// 途中で失敗しても `path` に中途半端なファイルが残らないよう、
+    /// 一時ファイルに書き出してから名前を変更します。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 書き出し先のファイル（既に存在する場合は置き換える）
+    pub fn backup_to(&self, path: &Path) -> Result<()> {
+        let mut tmp_name = path.as_os_str().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_name);
+        let _ = std::fs::remove_file(&tmp_path);
+
+        let result = (|| -> Result<()> {
+            let mut dest = Connection::open(&tmp_path)
+                .with_context(|| format!("Failed to create backup file {:?}", tmp_path))?;
+            rusqlite::backup::Backup::new(&self.conn, &mut dest)?.run_to_completion(
+                BACKUP_PAGES_PER_STEP,
+                BACKUP_STEP_PAUSE,
+                None,
+            )?;
+            // バックアップは1ファイルで完結させる（-wal / -shm を残さない）
+            dest.pragma_update_and_check(None, "journal_mode", "DELETE", |row| {
+                row.get::<_, String>(0)
+            })?;
+            Ok(())
+        })();
+
+        match result {
+            Ok(()) => std::fs::rename(&tmp_path, path)
+                .with_context(|| format!("Failed to write backup file {:?}", path)),
+            Err(e) => {
+                let _ = std::fs::remove_file(&tmp_path);
+                Err(e)
+            }
+        }
+    }
+
+    /// バックアップファイルの内容でデータベースを置き換える
+    ///
+    /// オンラインバックアップ API を逆向きに使うため、他の接続を開いたままでも
+    /// 一貫した状態で置き換わります。内容の検証は呼び出し側で行います
+    /// （[`crate::backup::restore_backup`]）。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 復元するバックアップファイル
+    pub fn restore_from(&mut self, path: &Path) -> Result<()> {
+        let source = Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .with_context(|| format!("Failed to open backup file {:?}", path))?;
+        rusqlite::backup::Backup::new(&source, &mut self.conn)?.run_to_completion(
+            BACKUP_PAGES_PER_STEP,
+            BACKUP_STEP_PAUSE,
+            None,
+        )?;
+
+        Ok(())
+    }
+
+    // =========================================================================
+    // ダンプ関連の操作
+    // =========================================================================
+
+    /// データベース全体を [`Dump`] として取得する
+    ///
+    /// フィード・カテゴリ・記事とその状態・キュー・読書履歴を全て含みます。
+    /// 1つの読み取りトランザクションで取得するため、途中で他のプロセスが
+    /// 書き込んでも一貫した内容になります。
+    pub fn dump(&self) -> Result<Dump> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut dump = Dump::new();
+
+        dump.categories = self
+            .get_categories()?
+            .into_iter()
+            .map(|category| category.path)
+            .collect();
+        dump.feeds = self
+            .get_feeds(None)?
+            .into_iter()
+            .map(|feed| Feed {
+                category_id: None,
+                ..feed
+            })
+            .collect();
+
+        let articles = self.query_articles(&ArticleQuery::new().sort(SortOrder::Oldest))?;
+        let ids: Vec<i64> = articles.iter().map(|article| article.id).collect();
+        let mut tags = self.get_tags_for_articles(&ids)?;
+        let mut notes = self.get_notes_for_articles(&ids)?;
+        let mut highlights = self.get_highlights_for_articles(&ids)?;
+        dump.articles = articles
+            .into_iter()
+            .map(|article| DumpArticle {
+                tags: tags.remove(&article.id).unwrap_or_default(),
+                note: notes.remove(&article.id),
+                highlights: highlights.remove(&article.id).unwrap_or_default(),
+                article,
+            })
+            .collect();
+
+        dump.queue = self
+            .get_queue()?
+            .into_iter()
+            .map(|entry| DumpQueueEntry {
+                article_id: entry.article.id,
+                added_at: entry.added_at,
+            })
+            .collect();
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT article_id, event, occurred_at FROM reading_history ORDER BY id")?;
+        dump.history = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .filter_map(|row| match row {
+                Ok((article_id, event, occurred_at)) => HistoryEvent::parse(&event).map(|event| {
+                    Ok(DumpHistoryEntry {
+                        article_id,
+                        event,
+                        occurred_at: parse_datetime(occurred_at),
+                    })
+                }),
+                Err(e) => Some(Err(e)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        tx.commit()?;
+        Ok(dump)
+    }
+
+    /// [`Dump`] を読み込む
+    ///
+    /// ダンプ内のIDは読み込み先のIDに対応付け直されます。
+    /// 全体を1つのトランザクションで行うため、途中でエラーになった場合は何も変更されません。
+    ///
+    /// # 引数
+    ///
+    /// * `dump` - 読み込むダンプ（[`crate::dump::read_dump`] で読み込んだもの）
+    /// * `mode` - 既存のデータに統合するか、置き換えるか（[`LoadMode`]）
+    ///
+    /// # エラー
+    ///
+    /// ダンプ内に存在しないフィードの記事や、存在しない記事のキュー・履歴がある場合は
+    /// エラーを返します。
+    pub fn load_dump(&self, dump: &Dump, mode: LoadMode) -> Result<LoadReport> {
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        let mut report = LoadReport::default();
+
+        if mode == LoadMode::Replace {
+            // 外部キーの参照元から順に削除する
+            tx.execute_batch(
+                "DELETE FROM reading_history;
+                 DELETE FROM article_highlights;
+                 DELETE FROM article_notes;
+                 DELETE FROM read_later;
+                 DELETE FROM article_tags;
+                 DELETE FROM tags;
+                 DELETE FROM articles;
+                 DELETE FROM feeds;
+                 DELETE FROM categories;",
+            )?;
+        }
+
+        // カテゴリ（パスが同じものは既存のものを使う）
+        let count_categories = || -> Result<usize> {
+            let n: i64 = tx.query_row("SELECT COUNT(*) FROM categories", [], |row| row.get(0))?;
+            Ok(n as usize)
+        };
+        let categories_before = count_categories()?;
+        for path in &dump.categories {
+            if !category_path_segments(path).is_empty() {
+                self.create_category(path)?;
+            }
+        }
+
+        // フィード（URLが同じとみなせるものは既存のものに対応付ける）
+        let mut feed_ids: HashMap<i64, i64> = HashMap::new();
+        for feed in &dump.feeds {
+            let existing = self.find_feed_by_url(&feed.url)?.map(|feed| feed.id);
+            let id = match existing {
+                Some(id) => {
+                    report.feeds_merged += 1;
+                    id
+                }
+                None => {
+                    let category_id = match feed.category.as_deref() {
+                        Some(path) if !category_path_segments(path).is_empty() => {
+                            Some(self.create_category(path)?)
+                        }
+                        _ => None,
+                    };
+                    report.feeds_added += 1;
+                    self.add_feed(&Feed {
+                        category_id,
+                        ..feed.clone()
+                    })?
+                }
+            };
+            feed_ids.insert(feed.id, id);
+        }
+        report.categories_added = count_categories()?.saturating_sub(categories_before);
+
+        // 記事とその状態
+        let mut article_ids: HashMap<i64, i64> = HashMap::new();
+        let mut added_articles: HashSet<i64> = HashSet::new();
+        for entry in &dump.articles {
+            let article = &entry.article;
+            let feed_id = *feed_ids.get(&article.feed_id).with_context(|| {
+                format!(
+                    "Article {} refers to feed {}, which is not in the dump",
+                    article.id, article.feed_id
+                )
+            })?;
+
+            let id = match self.find_loaded_article(feed_id, article)? {
+                Some(id) => {
+                    self.merge_loaded_article_state(id, article)?;
+                    report.articles_merged += 1;
+                    id
+                }
+                None => {
+                    // 通常の追加と同じく、別のフィードの同じ記事は重複記事として関連付ける
+                    let mut stmt = tx.prepare_cached(INSERT_ARTICLE_SQL)?;
+                    let id = insert_article(
+                        &tx,
+                        &mut stmt,
+                        &Article {
+                            feed_id,
+                            ..article.clone()
+                        },
+                    )?
+                    .with_context(|| format!("Article {} could not be added", article.id))?;
+                    tx.execute(
+                        "UPDATE articles SET read_at = ?1, first_opened_at = ?2 WHERE id = ?3",
+                        params![
+                            article.read_at.map(history_timestamp),
+                            article.first_opened_at.map(history_timestamp),
+                            id
+                        ],
+                    )?;
+                    report.articles_added += 1;
+                    added_articles.insert(id);
+                    id
+                }
+            };
+            article_ids.insert(article.id, id);
+
+            for name in &entry.tags {
+                let tag_id = self.get_or_create_tag(name)?;
+                tx.execute(
+                    "INSERT OR IGNORE INTO article_tags (article_id, tag_id, created_at)
+                     VALUES (?1, ?2, ?3)",
+                    params![id, tag_id, Utc::now().to_rfc3339()],
+                )?;
+            }
+            if let Some(note) = &entry.note {
+                // 既存のメモは上書きしない
+                tx.execute(
+                    "INSERT OR IGNORE INTO article_notes (article_id, body, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        id,
+                        note.body,
+                        note.created_at.to_rfc3339(),
+                        note.updated_at.to_rfc3339()
+                    ],
+                )?;
+            }
+            for highlight in &entry.highlights {
+                tx.execute(
+                    "INSERT INTO article_highlights
+                         (article_id, start_offset, end_offset, text, created_at)
+                     SELECT ?1, ?2, ?3, ?4, ?5
+                     WHERE NOT EXISTS (
+                         SELECT 1 FROM article_highlights
+                         WHERE article_id = ?1 AND start_offset = ?2 AND end_offset = ?3
+                     )",
+                    params![
+                        id,
+                        highlight.start as i64,
+                        highlight.end as i64,
+                        highlight.text,
+                        highlight.created_at.to_rfc3339()
+                    ],
+                )?;
+            }
+        }
+
+        let mapped_article = |dump_id: i64| -> Result<i64> {
+            article_ids
+                .get(&dump_id)
+                .copied()
+                .with_context(|| format!("Article {} is not in the dump", dump_id))
+        };
+
+        // キュー（既存のキューの後ろに、ダンプの順番で追加する。既読の記事は入れない）
+        for entry in &dump.queue {
+            tx.execute(
+                "INSERT OR IGNORE INTO read_later (article_id, position, added_at)
+                 SELECT ?1, (SELECT COALESCE(MAX(position) + 1, 0) FROM read_later), ?2
+                 FROM articles WHERE id = ?1 AND is_read = 0",
+                params![
+                    mapped_article(entry.article_id)?,
+                    entry.added_at.to_rfc3339()
+                ],
+            )?;
+        }
+
+        // 読書履歴（統合時に履歴が重複しないよう、新しく追加した記事の分だけ）
+        for entry in &dump.history {
+            let id = mapped_article(entry.article_id)?;
+            if added_articles.contains(&id) {
+                tx.execute(
+                    "INSERT INTO reading_history (article_id, event, occurred_at)
+                     VALUES (?1, ?2, ?3)",
+                    params![
+                        id,
+                        entry.event.as_str(),
+                        history_timestamp(entry.occurred_at)
+                    ],
+                )?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(report)
+    }
+
+    /// ダンプの記事に対応する既存の記事を探す
+    ///
+    /// 同じフィードで URL が同じ記事、URL がない場合はタイトルと公開日時が同じ記事を
+    /// 同一とみなします。
+    fn find_loaded_article(&self, feed_id: i64, article: &Article) -> Result<Option<i64>> {
+        let id = match &article.url {
+            Some(url) => self
+                .conn
+                .query_row(
+                    "SELECT id FROM articles WHERE feed_id = ?1 AND url = ?2",
+                    params![feed_id, url],
+                    |row| row.get(0),
+                )
+                .optional()?,
+            None => self
+                .conn
+                .query_row(
+                    "SELECT id FROM articles
+                     WHERE feed_id = ?1 AND url IS NULL AND title = ?2 AND published_at IS ?3
+                     ORDER BY id LIMIT 1",
+                    params![
+                        feed_id,
+                        article.title,
+                        article.published_at.map(|dt| dt.to_rfc3339())
+                    ],
+                    |row| row.get(0),
+                )
+                .optional()?,
+        };
+        Ok(id)
+    }
+
+    /// ダンプの記事の既読・お気に入り・開いた日時を既存の記事に足し合わせる
+    ///
+    /// どちらかで既読・お気に入りなら既読・お気に入りにします（未読に戻すことはありません）。
+    fn merge_loaded_article_state(&self, id: i64, article: &Article) -> Result<()> {
+        if article.is_read {
+            // 既読になるとトリガーが read_at を現在時刻にするため、その後でダンプの日時に戻す
+            let changed = self.conn.execute(
+                "UPDATE articles SET is_read = 1 WHERE id = ?1 AND is_read = 0",
+                params![id],
+            )?;
+            if changed > 0 {
+                if let Some(read_at) = article.read_at {
+                    self.conn.execute(
+                        "UPDATE articles SET read_at = ?2 WHERE id = ?1",
+                        params![id, history_timestamp(read_at)],
+                    )?;
+                }
+            }
+        }
+        if article.is_favorite {
+            self.conn.execute(
+                "UPDATE articles SET is_favorite = 1 WHERE id = ?1",
+                params![id],
+            )?;
+        }
+        if let Some(opened) = article.first_opened_at {
+            let opened = history_timestamp(opened);
+            self.conn.execute(
+                "UPDATE articles SET first_opened_at = ?2
+                 WHERE id = ?1 AND (first_opened_at IS NULL OR first_opened_at > ?2)",
+                params![id, opened],
+            )?;
+        }
+        Ok(())
+    }
+
+    // =========================================================================
+    // フィードの統合
+    // =========================================================================
+
+    /// 記事が大きく重なっているフィードの組を探す
+    ///
+    /// 記事は重複記事のまとまり（`duplicate_of`）と正規化したURLで同一とみなし、
+    /// 記事の多い方のフィードに対する共通記事の割合が `min_ratio` 以上の組を返します。
+    /// 共通記事が [`dedup::MIN_SHARED_ARTICLES`] 件未満の組は除きますが、
+    /// URLが同じとみなせる組（[`dedup::feed_url_key`]）は常に含めます。
+    ///
+    /// 結果は割合の降順、同じ割合では共通記事の多い順です。
+    pub fn feed_overlaps(&self, min_ratio: f64) -> Result<Vec<FeedOverlap>> {
+        let feeds = {
+            let mut stmt = self.conn.prepare("SELECT id, url FROM feeds ORDER BY id")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<Vec<(i64, String)>, _>>()?
+        };
+
+        // 記事ごとに「同じ記事」を表すキーを決め、フィードごとのキーの集合を作る
+        // （正規化したURLが同じ記事は、最初に見つかった記事のまとまりに寄せる）
+        let mut stories: HashMap<i64, HashSet<i64>> = HashMap::new();
+        {
+            let mut stmt = self.conn.prepare(
+                "SELECT feed_id, COALESCE(duplicate_of, id), canonical_url FROM articles ORDER BY id",
+            )?;
+            let mut rows = stmt.query([])?;
+            let mut by_url: HashMap<String, i64> = HashMap::new();
+            while let Some(row) = rows.next()? {
+                let feed_id: i64 = row.get(0)?;
+                let group: i64 = row.get(1)?;
+                let story = match row.get::<_, Option<String>>(2)? {
+                    Some(canonical) => *by_url.entry(canonical).or_insert(group),
+                    None => group,
+                };
+                stories.entry(feed_id).or_default().insert(story);
+            }
+        }
+
+        // キーからフィードへの逆引きで、共通記事の数をフィードの組ごとに数える
+        let mut feeds_by_story: HashMap<i64, Vec<i64>> = HashMap::new();
+        for (&feed_id, keys) in &stories {
+            for &story in keys {
+                feeds_by_story.entry(story).or_default().push(feed_id);
+            }
+        }
+        let mut shared: HashMap<(i64, i64), usize> = HashMap::new();
+        for feed_ids in feeds_by_story.values_mut() {
+            feed_ids.sort_unstable();
+            for (i, &a) in feed_ids.iter().enumerate() {
+                for &b in &feed_ids[i + 1..] {
+                    *shared.entry((a, b)).or_default() += 1;
+                }
+            }
+        }
+
+        let total = |feed_id: i64| stories.get(&feed_id).map_or(0, HashSet::len);
+        let keys: Vec<(i64, String)> = feeds
+            .iter()
+            .map(|(id, url)| (*id, dedup::feed_url_key(url)))
+            .collect();
+        let mut overlaps = Vec::new();
+        for (i, (a, key_a)) in keys.iter().enumerate() {
+            for (b, key_b) in &keys[i + 1..] {
+                let same_url = key_a == key_b;
+                let count = shared.get(&(*a, *b)).copied().unwrap_or(0);
+                let (total_a, total_b) = (total(*a), total(*b));
+                let ratio = match total_a.max(total_b) {
+                    0 => 0.0,
+                    larger => count as f64 / larger as f64,
+                };
+                if same_url || (count >= dedup::MIN_SHARED_ARTICLES && ratio >= min_ratio) {
+                    overlaps.push(FeedOverlap {
+                        feed_id: *a,
+                        other_feed_id: *b,
+                        shared: count,
+                        total: total_a,
+                        other_total: total_b,
+                        ratio,
+                        same_url,
+                    });
+                }
+            }
+        }
+        overlaps.sort_by(|x, y| {
+            y.ratio
+                .total_cmp(&x.ratio)
+                .then(y.shared.cmp(&x.shared))
+                .then(x.feed_id.cmp(&y.feed_id))
+        });
+        Ok(overlaps)
+    }
+
+    /// フィードを別のフィードに統合する
+    ///
+    /// `source` の記事のうち `target` にも同じ記事（URL・正規化したURL・重複記事のまとまりが同じ）が
+    /// あるものは、既読・お気に入り・タグ・メモ・ハイライト・キュー・読書履歴を `target` の記事にまとめ、
+    /// それ以外の記事は `target` に移します。カスタム名・カテゴリ・サイトのURLは `target` にない場合だけ引き継ぎ、
+    /// 優先順位は高い方にします。最後に `source` を削除します。
+    ///
+    /// 全体を1つのトランザクションで行うため、途中で失敗した場合は何も変わりません。
+    pub fn merge_feeds(&self, source: i64, target: i64) -> Result<FeedMergeReport> {
+        if source == target {
+            anyhow::bail!("Cannot merge feed {} into itself", source);
+        }
+        for id in [source, target] {
+            if self.get_feed(id)?.is_none() {
+                anyhow::bail!("Feed not found: {}", id);
+            }
+        }
+
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        let mut report = FeedMergeReport::default();
+
+        let articles = {
+            let sql = format!(
+                "SELECT {} FROM articles WHERE feed_id = ?1 ORDER BY id",
+                ARTICLE_COLUMNS
+            );
+            let mut stmt = tx.prepare(&sql)?;
+            let rows = stmt.query_map(params![source], row_to_article)?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+
+        for article in &articles {
+            let canonical = article.url.as_deref().and_then(dedup::canonical_url);
+            let group = article.duplicate_of.unwrap_or(article.id);
+            let matched: Option<(i64, Option<i64>)> = tx
+                .query_row(
+                    "SELECT id, duplicate_of FROM articles
+                     WHERE feed_id = ?1
+                       AND (url = ?2 OR canonical_url = ?3 OR COALESCE(duplicate_of, id) = ?4)
+                     ORDER BY url IS ?2 DESC, id LIMIT 1",
+                    params![target, article.url, canonical, group],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+
+            let Some((merged, merged_original)) = matched else {
+                tx.execute(
+                    "UPDATE articles SET feed_id = ?1 WHERE id = ?2",
+                    params![target, article.id],
+                )?;
+                report.articles_moved += 1;
+                continue;
+            };
+
+            self.merge_loaded_article_state(merged, article)?;
+            tx.execute(
+                "INSERT OR IGNORE INTO article_tags (article_id, tag_id, created_at)
+                 SELECT ?1, tag_id, created_at FROM article_tags WHERE article_id = ?2",
+                params![merged, article.id],
+            )?;
+            tx.execute(
+                "INSERT OR IGNORE INTO article_notes (article_id, body, created_at, updated_at)
+                 SELECT ?1, body, created_at, updated_at FROM article_notes WHERE article_id = ?2",
+                params![merged, article.id],
+            )?;
+            tx.execute(
+                "INSERT OR IGNORE INTO read_later (article_id, position, added_at)
+                 SELECT ?1, position, added_at FROM read_later
+                 WHERE article_id = ?2 AND (SELECT is_read FROM articles WHERE id = ?1) = 0",
+                params![merged, article.id],
+            )?;
+            for table in ["article_highlights", "reading_history"] {
+                tx.execute(
+                    &format!("UPDATE {} SET article_id = ?1 WHERE article_id = ?2", table),
+                    params![merged, article.id],
+                )?;
+            }
+
+            // 削除する記事を元とする重複記事は、統合先の記事のまとまりに付け替える
+            let original = match merged_original {
+                Some(original) if original != article.id => original,
+                _ => merged,
+            };
+            tx.execute(
+                "UPDATE articles SET duplicate_of = NULL WHERE id = ?1 AND duplicate_of = ?2",
+                params![merged, article.id],
+            )?;
+            tx.execute(
+                "UPDATE articles SET duplicate_of = ?1 WHERE duplicate_of = ?2 AND id != ?1",
+                params![original, article.id],
+            )?;
+            tx.execute("DELETE FROM articles WHERE id = ?1", params![article.id])?;
+            report.articles_merged += 1;
+        }
+
+        tx.execute(
+            "UPDATE feeds SET
+                 custom_name = COALESCE(custom_name, (SELECT custom_name FROM feeds WHERE id = ?1)),
+                 category_id = COALESCE(category_id, (SELECT category_id FROM feeds WHERE id = ?1)),
+                 site_url = COALESCE(site_url, (SELECT site_url FROM feeds WHERE id = ?1)),
+                 priority = MAX(priority, (SELECT priority FROM feeds WHERE id = ?1))
+             WHERE id = ?2",
+            params![source, target],
+        )?;
+        tx.execute("DELETE FROM feeds WHERE id = ?1", params![source])?;
+        tx.commit()?;
+
+        Ok(report)
+    }
+
+    // =========================================================================
+    // OPML
+    // =========================================================================
+
+    /// OPML から読み込んだフィードを登録する
+    ///
+    /// フィードの取得は行わず、タイトルにはカスタム名（なければURL）を使います。
+    /// カテゴリがなければ作成します。URLが同じとみなせるフィードが登録済みの場合は登録せず、
+    /// URLが不正なフィードは登録に失敗したものとして、残りのフィードの登録を続けます。
+    ///
+    /// # 戻り値
+    ///
+    /// `feeds` と同じ順序の、フィードごとの登録結果
+    pub fn import_opml(&self, feeds: &[OpmlFeed]) -> Result<Vec<OpmlImportEntry>> {
+        let mut entries = Vec::with_capacity(feeds.len());
+        for feed in feeds {
+            let status = match self.find_feed_by_url(&feed.url)? {
+                Some(existing) => OpmlImportStatus::AlreadySubscribed {
+                    feed_id: existing.id,
+                },
+                None => match self.add_opml_feed(feed) {
+                    Ok(feed_id) => OpmlImportStatus::Added { feed_id },
+                    Err(e) => OpmlImportStatus::Failed {
+                        error: e.to_string(),
+                    },
+                },
+            };
+            entries.push(OpmlImportEntry {
+                url: feed.url.clone(),
+                name: feed.name.clone(),
+                category: feed.category.clone(),
+                status,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// OPML のフィードを1件登録する（[`Database::import_opml`] を参照）
+    fn add_opml_feed(&self, feed: &OpmlFeed) -> Result<i64> {
+        let url = dedup::normalize_feed_url(&feed.url)?;
+        let category_id = match feed.category.as_deref() {
+            Some(path) if !category_path_segments(path).is_empty() => {
+                Some(self.create_category(path)?)
+            }
+            _ => None,
+        };
+        let title = feed.name.clone().unwrap_or_else(|| url.clone());
+        self.add_feed(&Feed {
+            custom_name: feed.name.clone(),
+            category_id,
+            site_url: feed.html_url.clone(),
+            ..Feed::new(url, title, None)
+        })
+    }
+
+    // =========================================================================
+    // 統計関連の操作
+    // =========================================================================
+
+    /// 購読状況と読書の傾向を集計する
+    ///
+    /// フィードごとの件数は1つのクエリでまとめて求めます。
+    /// 時間帯と日付は UTC で集計します。
+    ///
+    /// # 引数
+    ///
+    /// * `options` - 停滞とみなす日数などの集計条件
+    pub fn get_stats(&self, options: &StatsOptions) -> Result<Stats> {
+        let now = Utc::now();
+        let frequency_since = days_before(now, options.frequency_days.max(1));
+
+        // フィードごとの記事数・未読数・直近の記事数・最新記事の日時
+        let sql = format!(
+            "WITH RECURSIVE {}
+             SELECT f.id, COALESCE(f.custom_name, f.title), cp.path,
+                    COUNT(a.id),
+                    COALESCE(SUM(CASE WHEN a.is_read = 0 THEN 1 ELSE 0 END), 0),
+                    COALESCE(SUM(CASE WHEN COALESCE(a.published_at, a.created_at) >= ?1
+                                      THEN 1 ELSE 0 END), 0),
+                    MAX(COALESCE(a.published_at, a.created_at))
+             FROM feeds f
+             LEFT JOIN category_paths cp ON cp.id = f.category_id
+             LEFT JOIN articles a ON a.feed_id = f.id
+             GROUP BY f.id
+             ORDER BY f.priority DESC, f.id",
+            CATEGORY_PATHS_CTE
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let weeks = options.frequency_days.max(1) as f64 / 7.0;
+        let feeds = stmt
+            .query_map(params![frequency_since.to_rfc3339()], |row| {
+                let total = row.get::<_, i64>(3)? as usize;
+                let unread = row.get::<_, i64>(4)? as usize;
+                let recent_articles = row.get::<_, i64>(5)? as usize;
+                let read = total - unread;
+                Ok(FeedStats {
+                    feed_id: row.get(0)?,
+                    name: row.get(1)?,
+                    category: row.get(2)?,
+                    total,
+                    unread,
+                    read,
+                    read_ratio: read_ratio(read, total),
+                    recent_articles,
+                    articles_per_week: recent_articles as f64 / weeks,
+                    last_article_at: row.get::<_, Option<String>>(6)?.map(parse_datetime),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let favorites: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM articles WHERE is_favorite = 1",
+            [],
+            |row| row.get(0),
+        )?;
+        let articles: usize = feeds.iter().map(|f| f.total).sum();
+        let unread: usize = feeds.iter().map(|f| f.unread).sum();
+        let totals = Totals {
+            feeds: feeds.len(),
+            articles,
+            unread,
+            read: articles - unread,
+            favorites: favorites as usize,
+            read_ratio: read_ratio(articles - unread, articles),
+        };
+
+        // 新着が途絶えているフィード（記事が1件もないフィードを先頭に、古い順）
+        let stale_since = days_before(now, options.stale_days);
+        let mut stale_feeds: Vec<StaleFeed> = feeds
+            .iter()
+            .filter(|f| f.last_article_at.is_none_or(|at| at < stale_since))
+            .map(|f| StaleFeed {
+                feed_id: f.feed_id,
+                name: f.name.clone(),
+                last_article_at: f.last_article_at,
+                days_since_last_article: f.last_article_at.map(|at| (now - at).num_days()),
+            })
+            .collect();
+        stale_feeds.sort_by_key(|f| f.last_article_at);
+
+        // 時間帯ごとの件数（RFC3339 の 12〜13文字目が時）
+        let publish_hours = self.count_by_hour(
+            "SELECT CAST(substr(published_at, 12, 2) AS INTEGER), COUNT(*)
+             FROM articles WHERE published_at IS NOT NULL
+             GROUP BY 1",
+        )?;
+        let reading_hours = self.count_by_hour(
+            "SELECT CAST(substr(occurred_at, 12, 2) AS INTEGER), COUNT(*)
+             FROM reading_history WHERE event = 'read'
+             GROUP BY 1",
+        )?;
+
+        // 日ごとの既読数（0件の日も含めて連続した日付にする）
+        let today = now.date_naive();
+        let first_day = today
+            .checked_sub_days(chrono::Days::new(u64::from(
+                options.activity_days.max(1) - 1,
+            )))
+            .unwrap_or(chrono::NaiveDate::MIN);
+        let mut stmt = self.conn.prepare(
+            "SELECT substr(occurred_at, 1, 10), COUNT(*)
+             FROM reading_history
+             WHERE event = 'read' AND occurred_at >= ?1
+             GROUP BY 1",
+        )?;
+        let reads_by_day: HashMap<String, i64> = stmt
+            .query_map(params![first_day.format("%Y-%m-%d").to_string()], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<Result<_, _>>()?;
+        let reading_activity = first_day
+            .iter_days()
+            .take_while(|date| *date <= today)
+            .map(|date| DailyActivity {
+                date,
+                reads: reads_by_day
+                    .get(&date.format("%Y-%m-%d").to_string())
+                    .copied()
+                    .unwrap_or(0) as usize,
+            })
+            .collect();
+
+        Ok(Stats {
+            generated_at: now,
+            options: *options,
+            totals,
+            categories: aggregate_categories(&feeds),
+            feeds,
+            stale_feeds,
+            publish_hours,
+            reading_hours,
+            reading_activity,
+        })
+    }
+
+    /// `(時, 件数)` を返す集計クエリの結果を24時間分の配列にする
+    fn count_by_hour(&self, sql: &str) -> Result<[usize; 24]> {
+        let mut hours = [0usize; 24];
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, Option<i64>>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        for row in rows {
+            if let (Some(hour), count) = row? {
+                if let Some(slot) = usize::try_from(hour).ok().and_then(|h| hours.get_mut(h)) {
+                    *slot += count as usize;
+                }
+            }
+        }
+        Ok(hours)
+    }
+
+    // =========================================================================
+    // 「あとで読む」キュー関連の操作
+    // =========================================================================
+
+    /// 記事をキューの末尾に追加する
+    ///
+    /// 既読の記事も追加できます。次に既読になった時点でキューから外れます。
+    ///
+    /// # 戻り値
+    ///
+    /// - `Ok(true)`: 追加成功
+    /// - `Ok(false)`: 既にキューに入っていた
+    ///
+    /// # エラー
+    ///
+    /// 記事が存在しない場合はエラーを返します。
+    pub fn add_to_queue(&self, article_id: i64) -> Result<bool> {
+        if self.get_article(article_id)?.is_none() {
+            anyhow::bail!("Article not found with ID: {}", article_id);
+        }
+
+        let affected = self.conn.execute(
+            "INSERT OR IGNORE INTO read_later (article_id, position, added_at)
+             SELECT ?1, COALESCE(MAX(position) + 1, 0), ?2 FROM read_later",
+            params![article_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 記事をキューから外す
+    ///
+    /// # 戻り値
+    ///
+    /// - `Ok(true)`: 削除成功
+    /// - `Ok(false)`: キューに入っていなかった
+    pub fn remove_from_queue(&self, article_id: i64) -> Result<bool> {
+        let affected = self.conn.execute(
+            "DELETE FROM read_later WHERE article_id = ?1",
+            params![article_id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// キューの内容を順番通りに取得する
+    pub fn get_queue(&self) -> Result<Vec<QueueEntry>> {
+        // read_later と articles にはカラム名の重複がないため、修飾なしで取得できる
+        let sql = format!(
+            "SELECT {}, position, added_at FROM read_later
+             JOIN articles ON articles.id = read_later.article_id
+             ORDER BY position, added_at",
+            ARTICLE_COLUMNS
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+
+        let entries = stmt
+            .query_map([], |row| {
+                Ok(QueueEntry {
+                    article: row_to_article(row)?,
+                    position: row.get(ARTICLE_COLUMN_COUNT)?,
+                    added_at: parse_datetime(row.get::<_, String>(ARTICLE_COLUMN_COUNT + 1)?),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// キュー内の記事の順番を変更する
+    ///
+    /// # 引数
+    ///
+    /// * `article_id` - 移動する記事のID
+    /// * `position` - 新しい位置（0始まり、キューの長さを超える場合は末尾）
+    ///
+    /// # 戻り値
+    ///
+    /// - `Ok(true)`: 変更成功
+    /// - `Ok(false)`: キューに入っていなかった
+    pub fn move_in_queue(&self, article_id: i64, position: usize) -> Result<bool> {
+        let mut ids: Vec<i64> = self
+            .get_queue()?
+            .into_iter()
+            .map(|entry| entry.article.id)
+            .collect();
+
+        let Some(current) = ids.iter().position(|&id| id == article_id) else {
+            return Ok(false);
+        };
+        ids.remove(current);
+        ids.insert(position.min(ids.len()), article_id);
+
+        // キュー全体の position を 0 から振り直す
+        let tx = self.conn.unchecked_transaction()?;
+        for (index, id) in ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE read_later SET position = ?1 WHERE article_id = ?2",
+                params![index as i64, id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// キューを空にする
+    ///
+    /// # 戻り値
+    ///
+    /// キューから外した記事の数
+    pub fn clear_queue(&self) -> Result<usize> {
+        let affected = self.conn.execute("DELETE FROM read_later", [])?;
+        Ok(affected)
+    }
+
+    // =========================================================================
+    // Note / Highlight 関連の操作
+    // =========================================================================
+
+    /// 記事のメモを取得する
+    ///
+    /// # 戻り値
+    ///
+    /// - `Ok(Some(note))`: メモが見つかった
+    /// - `Ok(None)`: メモが付いていない
+    pub fn get_note(&self, article_id: i64) -> Result<Option<Note>> {
+        let note = self
+            .conn
+            .query_row(
+                "SELECT article_id, body, created_at, updated_at
+                 FROM article_notes WHERE article_id = ?1",
+                params![article_id],
+                row_to_note,
+            )
+            .optional()?;
+        Ok(note)
+    }
+
+    /// 記事のメモを保存する（既にある場合は上書きする）
+    ///
+    /// 本文が空白だけの場合はメモを削除します。
+    ///
+    /// # エラー
+    ///
+    /// 記事が存在しない場合はエラーを返します。
+    pub fn save_note(&self, article_id: i64, body: &str) -> Result<()> {
+        if self.get_article(article_id)?.is_none() {
+            anyhow::bail!("Article not found with ID: {}", article_id);
+        }
+
+        if body.trim().is_empty() {
+            self.delete_note(article_id)?;
+            return Ok(());
+        }
+
+        let now = Utc::now().to_rfc3339();
+        self.conn.execute(
+            "INSERT INTO article_notes (article_id, body, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?3)
+             ON CONFLICT(article_id) DO UPDATE SET
+                 body = excluded.body,
+                 updated_at = excluded.updated_at",
+            params![article_id, body, now],
+        )?;
+        Ok(())
+    }
+
+    /// 記事のメモを削除する
+    ///
+    /// # 戻り値
+    ///
+    /// - `Ok(true)`: 削除成功
+    /// - `Ok(false)`: メモが付いていなかった
+    pub fn delete_note(&self, article_id: i64) -> Result<bool> {
+        let affected = self.conn.execute(
+            "DELETE FROM article_notes WHERE article_id = ?1",
+            params![article_id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 全てのメモを更新日時の新しい順に取得する
+    pub fn get_notes(&self) -> Result<Vec<Note>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT article_id, body, created_at, updated_at
+             FROM article_notes ORDER BY updated_at DESC",
+        )?;
+        let notes = stmt
+            .query_map([], row_to_note)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(notes)
+    }
+
+    /// 複数の記事のメモをまとめて取得する
+    ///
+    /// # 戻り値
+    ///
+    /// 記事IDをキーとするマップ（メモのない記事は含まれない）
+    pub fn get_notes_for_articles(&self, article_ids: &[i64]) -> Result<HashMap<i64, Note>> {
+        let mut result = HashMap::new();
+
+        // SQLite のパラメータ数上限を超えないよう分割して取得する
+        for chunk in article_ids.chunks(500) {
+            let sql = format!(
+                "SELECT article_id, body, created_at, updated_at
+                 FROM article_notes WHERE article_id IN ({})",
+                vec!["?"; chunk.len()].join(", ")
+            );
+            let mut stmt = self.conn.prepare(&sql)?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(chunk), row_to_note)?;
+            for row in rows {
+                let note = row?;
+                result.insert(note.article_id, note);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// 記事の本文中の範囲をハイライトとして保存する
+    ///
+    /// # 引数
+    ///
+    /// * `article_id` - 対象記事のID
+    /// * `start` / `end` - 表示用のテキスト（[`html::render_text`] で本文を変換したもの）中の
+    ///   文字オフセット（`start` を含み `end` を含まない）
+    ///
+    /// # エラー
+    ///
+    /// 記事が存在しない場合、または範囲が本文の外にある場合はエラーを返します。
+    pub fn add_highlight(&self, article_id: i64, start: usize, end: usize) -> Result<Highlight> {
+        let article = self
+            .get_article(article_id)?
+            .with_context(|| format!("Article not found with ID: {}", article_id))?;
+        let content = html::render_text(&article.content.unwrap_or_default());
+
+        let length = content.chars().count();
+        if start >= end || end > length {
+            anyhow::bail!(
+                "Invalid highlight range {}..{} (article content has {} characters)",
+                start,
+                end,
+                length
+            );
+        }
+        let text: String = content.chars().skip(start).take(end - start).collect();
+
+        self.insert_highlight(article_id, start, end, &text)
+    }
+
+    /// 記事のハイライトを本文中の位置順に取得する
+    pub fn get_highlights(&self, article_id: i64) -> Result<Vec<Highlight>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, article_id, start_offset, end_offset, text, created_at
+             FROM article_highlights WHERE article_id = ?1
+             ORDER BY start_offset, id",
+        )?;
+        let highlights = stmt
+            .query_map(params![article_id], row_to_highlight)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(highlights)
+    }
+
+    /// 複数の記事のハイライトをまとめて取得する
+    ///
+    /// # 戻り値
+    ///
+    /// 記事IDをキーとするマップ（ハイライトのない記事は含まれない）
+    pub fn get_highlights_for_articles(
+        &self,
+        article_ids: &[i64],
+    ) -> Result<HashMap<i64, Vec<Highlight>>> {
+        let mut result: HashMap<i64, Vec<Highlight>> = HashMap::new();
+
+        // SQLite のパラメータ数上限を超えないよう分割して取得する
+        for chunk in article_ids.chunks(500) {
+            let sql = format!(
+                "SELECT id, article_id, start_offset, end_offset, text, created_at
+                 FROM article_highlights WHERE article_id IN ({})
+                 ORDER BY start_offset, id",
+                vec!["?"; chunk.len()].join(", ")
+            );
+            let mut stmt = self.conn.prepare(&sql)?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(chunk), row_to_highlight)?;
+            for row in rows {
+                let highlight = row?;
+                result
+                    .entry(highlight.article_id)
+                    .or_default()
+                    .push(highlight);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// ハイライトを削除する
+    ///
+    /// # 戻り値
+    ///
+    /// - `Ok(true)`: 削除成功
+    /// - `Ok(false)`: 該当するハイライトが存在しなかった
+    pub fn delete_highlight(&self, id: i64) -> Result<bool> {
+        let affected = self
+            .conn
+            .execute("DELETE FROM article_highlights WHERE id = ?1", params![id])?;
+        Ok(affected > 0)
+    }
+
+    /// ハイライトを挿入して、保存した内容を返す
+    fn insert_highlight(
+        &self,
+        article_id: i64,
+        start: usize,
+        end: usize,
+        text: &str,
+    ) -> Result<Highlight> {
+        let created_at = Utc::now();
+        self.conn.execute(
+            "INSERT INTO article_highlights (article_id, start_offset, end_offset, text, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                article_id,
+                start as i64,
+                end as i64,
+                text,
+                created_at.to_rfc3339()
+            ],
+        )?;
+
+        Ok(Highlight {
+            id: self.conn.last_insert_rowid(),
+            article_id,
+            start,
+            end,
+            text: text.to_string(),
+            created_at,
+        })
+    }
+
+    // =========================================================================
+    // Category 関連の操作
+    // =========================================================================
+
+    /// カテゴリを作成する（途中の階層も含めて、存在しないものだけ作成する）
+    ///
+    /// # 引数
+    ///
+    /// * `path` - `/` 区切りのカテゴリパス（例: "Tech/Rust"）
+    ///
+    /// # 戻り値
+    ///
+    /// パスの末尾のカテゴリのID（既に存在していた場合はそのID）
+    ///
+    /// # エラー
+    ///
+    /// パスが空の場合はエラーを返します。
+    pub fn create_category(&self, path: &str) -> Result<i64> {
+        let segments = category_path_segments(path);
+        if segments.is_empty() {
+            anyhow::bail!("Category name must not be empty");
+        }
+
+        let mut parent_id: Option<i64> = None;
+        for name in segments {
+            let id = match self.find_child_category(parent_id, name)? {
+                Some(id) => id,
+                None => {
+                    let position = self.next_category_position(parent_id)?;
+                    self.conn.execute(
+                        "INSERT INTO categories (name, parent_id, position, created_at)
+                         VALUES (?1, ?2, ?3, ?4)",
+                        params![name, parent_id, position, Utc::now().to_rfc3339()],
+                    )?;
+                    self.conn.last_insert_rowid()
+                }
+            };
+            parent_id = Some(id);
+        }
+
+        // segments が空でないため、必ず Some になる
+        Ok(parent_id.unwrap_or_default())
+    }
+
+    /// 全てのカテゴリを木構造の順（深さ優先、兄弟は position 順）で取得する
+    pub fn get_categories(&self) -> Result<Vec<Category>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, parent_id, position FROM categories
+             ORDER BY position, name COLLATE NOCASE",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                    row.get::<_, i64>(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // 親IDごとに子カテゴリをまとめる（並び順は SQL の ORDER BY を保つ）
+        let mut children: HashMap<Option<i64>, Vec<(i64, String, i64)>> = HashMap::new();
+        for (id, name, parent_id, position) in rows {
+            children
+                .entry(parent_id)
+                .or_default()
+                .push((id, name, position));
+        }
+
+        let mut categories = Vec::new();
+        push_category_subtree(&children, None, None, 0, &mut categories);
+        Ok(categories)
+    }
+
+    /// パスでカテゴリを取得する
+    ///
+    /// # 戻り値
+    ///
+    /// - `Ok(Some(category))`: カテゴリが見つかった
+    /// - `Ok(None)`: カテゴリが見つからなかった
+    pub fn get_category(&self, path: &str) -> Result<Option<Category>> {
+        let Some(id) = self.find_category_id(path)? else {
+            return Ok(None);
+        };
+        Ok(self.get_categories()?.into_iter().find(|c| c.id == id))
+    }
+
+    /// カテゴリの名前を変更する
+    ///
+    /// 親は変わりません。配下のフィードとサブカテゴリはそのまま付いてきます。
+    ///
+    /// # 戻り値
+    ///
+    /// - `Ok(true)`: 変更成功
+    /// - `Ok(false)`: 該当するカテゴリが存在しなかった
+    ///
+    /// # エラー
+    ///
+    /// 同じ親の下に変更後の名前のカテゴリが既に存在する場合はエラーを返します。
+    /// その場合は [`Database::merge_categories`] を使ってください。
+    pub fn rename_category(&self, path: &str, new_name: &str) -> Result<bool> {
+        let new_name = normalize_category_name(new_name)?;
+        let Some(id) = self.find_category_id(path)? else {
+            return Ok(false);
+        };
+
+        let parent_id = self.category_parent_id(id)?;
+        if self
+            .find_child_category(parent_id, new_name)?
+            .is_some_and(|existing| existing != id)
+        {
+            anyhow::bail!(
+                "Category '{}' already exists. Merge the categories instead of renaming.",
+                new_name
+            );
+        }
+
+        self.conn.execute(
+            "UPDATE categories SET name = ?1 WHERE id = ?2",
+            params![new_name, id],
+        )?;
+        Ok(true)
+    }
+
+    /// カテゴリを統合する
+    ///
+    /// `source` のフィードとサブカテゴリを `target` に移し、`source` を削除します。
+    /// 同じ名前のサブカテゴリがある場合は、それらも再帰的に統合されます。
+    ///
+    /// # 戻り値
+    ///
+    /// `target` 側に移動したフィードの数
+    ///
+    /// # エラー
+    ///
+    /// どちらかのカテゴリが存在しない場合、または `target` が `source` 自身か
+    /// そのサブカテゴリである場合はエラーを返します。
+    pub fn merge_categories(&self, source: &str, target: &str) -> Result<usize> {
+        let source_id = self
+            .find_category_id(source)?
+            .with_context(|| format!("Category not found: {}", source))?;
+        let target_id = self
+            .find_category_id(target)?
+            .with_context(|| format!("Category not found: {}", target))?;
+
+        if self.category_subtree_ids(source_id)?.contains(&target_id) {
+            anyhow::bail!(
+                "Cannot merge category '{}' into itself or one of its subcategories",
+                source
+            );
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        let moved = self.merge_category_ids(source_id, target_id)?;
+        tx.commit()?;
+        Ok(moved)
+    }
+
+    /// カテゴリを削除する
+    ///
+    /// 削除したカテゴリのフィードとサブカテゴリは、親カテゴリ
+    /// （トップレベルの場合はカテゴリなし/トップレベル）に移動します。
+    ///
+    /// # 戻り値
+    ///
+    /// - `Ok(true)`: 削除成功
+    /// - `Ok(false)`: 該当するカテゴリが存在しなかった
+    pub fn delete_category(&self, path: &str) -> Result<bool> {
+        let Some(id) = self.find_category_id(path)? else {
+            return Ok(false);
+        };
+        let parent_id = self.category_parent_id(id)?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE feeds SET category_id = ?1 WHERE category_id = ?2",
+            params![parent_id, id],
+        )?;
+        for child_id in self.child_category_ids(Some(id))? {
+            self.reparent_category(child_id, parent_id)?;
+        }
+        tx.execute("DELETE FROM categories WHERE id = ?1", params![id])?;
+        tx.commit()?;
+
+        Ok(true)
+    }
+
+    /// カテゴリを別の親カテゴリの下に移動する
+    ///
+    /// 移動先に同じ名前のカテゴリがある場合は、そのカテゴリに統合されます。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 移動するカテゴリのパス
+    /// * `new_parent` - 移動先の親カテゴリのパス（None の場合はトップレベル）
+    ///
+    /// # 戻り値
+    ///
+    /// - `Ok(true)`: 移動成功
+    /// - `Ok(false)`: 該当するカテゴリが存在しなかった
+    ///
+    /// # エラー
+    ///
+    /// 移動先が存在しない場合、または移動先が自身かそのサブカテゴリである場合はエラーを返します。
+    pub fn move_category(&self, path: &str, new_parent: Option<&str>) -> Result<bool> {
+        let Some(id) = self.find_category_id(path)? else {
+            return Ok(false);
+        };
+        let parent_id = match new_parent {
+            Some(parent) => Some(
+                self.find_category_id(parent)?
+                    .with_context(|| format!("Category not found: {}", parent))?,
+            ),
+            None => None,
+        };
+
+        if let Some(parent_id) = parent_id {
+            if self.category_subtree_ids(id)?.contains(&parent_id) {
+                anyhow::bail!(
+                    "Cannot move category '{}' into itself or one of its subcategories",
+                    path
+                );
+            }
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        self.reparent_category(id, parent_id)?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// 兄弟カテゴリの中での並び順を変更する
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 並び替えるカテゴリのパス
+    /// * `position` - 新しい位置（0始まり、兄弟の数を超える場合は末尾）
+    ///
+    /// # 戻り値
+    ///
+    /// - `Ok(true)`: 変更成功
+    /// - `Ok(false)`: 該当するカテゴリが存在しなかった
+    pub fn reorder_category(&self, path: &str, position: usize) -> Result<bool> {
+        let Some(id) = self.find_category_id(path)? else {
+            return Ok(false);
+        };
+        let parent_id = self.category_parent_id(id)?;
+
+        let mut siblings = self.child_category_ids(parent_id)?;
+        siblings.retain(|&sibling| sibling != id);
+        siblings.insert(position.min(siblings.len()), id);
+
+        // 兄弟全体の position を 0 から振り直す
+        let tx = self.conn.unchecked_transaction()?;
+        for (index, sibling) in siblings.iter().enumerate() {
+            tx.execute(
+                "UPDATE categories SET position = ?1 WHERE id = ?2",
+                params![index as i64, sibling],
+            )?;
+        }
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// パスからカテゴリIDを求める（大文字小文字は区別しない）
+    fn find_category_id(&self, path: &str) -> Result<Option<i64>> {
+        let segments = category_path_segments(path);
+        if segments.is_empty() {
+            return Ok(None);
+        }
+
+        let mut parent_id: Option<i64> = None;
+        for name in segments {
+            match self.find_child_category(parent_id, name)? {
+                Some(id) => parent_id = Some(id),
+                None => return Ok(None),
+            }
+        }
+        Ok(parent_id)
+    }
+
+    /// 指定した親の下にある、指定した名前のカテゴリIDを取得する
+    fn find_child_category(&self, parent_id: Option<i64>, name: &str) -> Result<Option<i64>> {
+        // `IS` は NULL 同士の比較でも真になる（`=` では NULL = NULL は偽）
+        let id = self
+            .conn
+            .query_row(
+                "SELECT id FROM categories WHERE parent_id IS ?1 AND name = ?2 COLLATE NOCASE",
+                params![parent_id, name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(id)
+    }
+
+    /// カテゴリの親IDを取得する
+    fn category_parent_id(&self, id: i64) -> Result<Option<i64>> {
+        let parent_id = self.conn.query_row(
+            "SELECT parent_id FROM categories WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        Ok(parent_id)
+    }
+
+    /// 指定した親の直下にあるカテゴリIDを並び順で取得する
+    fn child_category_ids(&self, parent_id: Option<i64>) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id FROM categories WHERE parent_id IS ?1
+             ORDER BY position, name COLLATE NOCASE",
+        )?;
+        let ids = stmt
+            .query_map(params![parent_id], |row| row.get(0))?
+            .collect::<Result<Vec<i64>, _>>()?;
+        Ok(ids)
+    }
+
+    /// カテゴリ自身とその全てのサブカテゴリのIDを取得する
+    fn category_subtree_ids(&self, id: i64) -> Result<Vec<i64>> {
+        let sql = format!(
+            "WITH RECURSIVE {} SELECT id FROM category_tree",
+            CATEGORY_TREE_CTE
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let ids = stmt
+            .query_map(params![id], |row| row.get(0))?
+            .collect::<Result<Vec<i64>, _>>()?;
+        Ok(ids)
+    }
+
+    /// 指定した親の下で次に使う position を求める（末尾に追加するため）
+    fn next_category_position(&self, parent_id: Option<i64>) -> Result<i64> {
+        let position = self.conn.query_row(
+            "SELECT COALESCE(MAX(position) + 1, 0) FROM categories WHERE parent_id IS ?1",
+            params![parent_id],
+            |row| row.get(0),
+        )?;
+        Ok(position)
+    }
+
+    /// カテゴリを別の親の下に移す（同名のカテゴリがあれば統合する）
+    ///
+    /// 呼び出し元でトランザクションを開始しておくこと。
+    ///
+    /// # 戻り値
+    ///
+    /// 統合によって移動したフィードの数
+    fn reparent_category(&self, id: i64, parent_id: Option<i64>) -> Result<usize> {
+        if self.category_parent_id(id)? == parent_id {
+            return Ok(0);
+        }
+
+        let name: String = self.conn.query_row(
+            "SELECT name FROM categories WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        if let Some(existing) = self.find_child_category(parent_id, &name)? {
+            return self.merge_category_ids(id, existing);
+        }
+
+        let position = self.next_category_position(parent_id)?;
+        self.conn.execute(
+            "UPDATE categories SET parent_id = ?1, position = ?2 WHERE id = ?3",
+            params![parent_id, position, id],
+        )?;
+        Ok(0)
+    }
+
+    /// `source` カテゴリを `target` カテゴリに統合する
+    ///
+    /// 呼び出し元でトランザクションを開始しておくこと。
+    fn merge_category_ids(&self, source_id: i64, target_id: i64) -> Result<usize> {
+        let mut moved = self.conn.execute(
+            "UPDATE feeds SET category_id = ?1 WHERE category_id = ?2",
+            params![target_id, source_id],
+        )?;
+        for child_id in self.child_category_ids(Some(source_id))? {
+            moved += self.reparent_category(child_id, Some(target_id))?;
+        }
+        self.conn
+            .execute("DELETE FROM categories WHERE id = ?1", params![source_id])?;
+        Ok(moved)
+    }
+}
+
+// =============================================================================
+// ヘルパー関数
+// =============================================================================
+
+/// タグ名を正規化する（前後の空白を除去し、空の場合はエラー）
+pub(crate) fn normalize_tag_name(name: &str) -> Result<&str> {
+    let name = name.trim();
+    if name.is_empty() {
+        anyhow::bail!("Tag name must not be empty");
+    }
+    Ok(name)
+}
+
+/// カテゴリ名を正規化する（前後の空白を除去し、空または `/` を含む場合はエラー）
+fn normalize_category_name(name: &str) -> Result<&str> {
+    let name = name.trim();
+    if name.is_empty() {
+        anyhow::bail!("Category name must not be empty");
+    }
+    if name.contains('/') {
+        anyhow::bail!("Category name must not contain '/': {}", name);
+    }
+    Ok(name)
+}
+
+/// カテゴリパスを階層ごとの名前に分割する（空の階層は無視する）
+///
+/// 例: `" Tech / Rust "` → `["Tech", "Rust"]`
+pub(crate) fn category_path_segments(path: &str) -> Vec<&str> {
+    path.split('/')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+/// 親IDごとにまとめた子カテゴリから、深さ優先でカテゴリのリストを組み立てる
+fn push_category_subtree(
+    children: &HashMap<Option<i64>, Vec<(i64, String, i64)>>,
+    parent_id: Option<i64>,
+    parent_path: Option<&str>,
+    depth: usize,
+    out: &mut Vec<Category>,
+) {
+    let Some(siblings) = children.get(&parent_id) else {
+        return;
+    };
+
+    for (id, name, position) in siblings {
+        let path = match parent_path {
+            Some(parent_path) => format!("{}/{}", parent_path, name),
+            None => name.clone(),
+        };
+        out.push(Category {
+            id: *id,
+            name: name.clone(),
+            parent_id,
+            position: *position,
+            path: path.clone(),
+            depth,
+        });
+        push_category_subtree(children, Some(*id), Some(&path), depth + 1, out);
+    }
+}
+
+/// [`FEED_COLUMNS`] の順で取得した行を [`Feed`] に変換する
+fn row_to_feed(row: &rusqlite::Row) -> rusqlite::Result<Feed> {
+    Ok(Feed {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        title: row.get(2)?,
+        description: row.get(3)?,
+        created_at: parse_datetime(row.get::<_, String>(4)?),
+        updated_at: parse_datetime(row.get::<_, String>(5)?),
+        custom_name: row.get(6)?,
+        category: row.get(7)?,
+        priority: row.get(8).unwrap_or(0),
+        category_id: row.get(9)?,
+        paused: row.get::<_, i64>(10)? != 0,
+        site_url: row.get(11)?,
+    })
+}
+
+/// `article_id, body, created_at, updated_at` の順で取得した行を [`Note`] に変換する
+fn row_to_note(row: &rusqlite::Row) -> rusqlite::Result<Note> {
+    Ok(Note {
+        article_id: row.get(0)?,
+        body: row.get(1)?,
+        created_at: parse_datetime(row.get::<_, String>(2)?),
+        updated_at: parse_datetime(row.get::<_, String>(3)?),
+    })
+}
+
+/// `id, article_id, start_offset, end_offset, text, created_at` の順で取得した行を
+/// [`Highlight`] に変換する
+fn row_to_highlight(row: &rusqlite::Row) -> rusqlite::Result<Highlight> {
+    Ok(Highlight {
+        id: row.get(0)?,
+        article_id: row.get(1)?,
+        start: row.get::<_, i64>(2)? as usize,
+        end: row.get::<_, i64>(3)? as usize,
+        text: row.get(4)?,
+        created_at: parse_datetime(row.get::<_, String>(5)?),
+    })
+}
+
+/// [`ARTICLE_COLUMNS`] の順で取得した行を [`Article`] に変換する
+fn row_to_article(row: &rusqlite::Row) -> rusqlite::Result<Article> {
+    Ok(Article {
+        id: row.get(0)?,
+        feed_id: row.get(1)?,
+        title: row.get(2)?,
+        url: row.get(3)?,
+        content: row.get(4)?,
+        published_at: row.get::<_, Option<String>>(5)?.map(parse_datetime),
+        is_read: row.get::<_, i32>(6)? != 0,
+        is_favorite: row.get::<_, i32>(7)? != 0,
+        created_at: parse_datetime(row.get::<_, String>(8)?),
+        read_at: row.get::<_, Option<String>>(9)?.map(parse_datetime),
+        first_opened_at: row.get::<_, Option<String>>(10)?.map(parse_datetime),
+        duplicate_of: row.get(11)?,
+        duplicate_count: row.get::<_, i64>(12)? as usize,
+    })
+}
+
+/// 準備済みの [`INSERT_ARTICLE_SQL`] で記事を1件追加する
+///
+/// 新規挿入された場合はそのIDを、既に存在していた場合（重複URL）は `None` を返します。
+/// 別のフィードから届いた同じ記事（[`crate::dedup`]）は元の記事に関連付け、
+/// 元の記事が既読なら既読として追加します。`article.duplicate_of` は使いません。
+fn insert_article(
+    conn: &Connection,
+    stmt: &mut rusqlite::CachedStatement<'_>,
+    article: &Article,
+) -> Result<Option<i64>> {
+    // フィードの更新ではほとんどの記事が登録済みのため、重複記事を探す前に無視する
+    if let Some(url) = &article.url {
+        let exists = conn
+            .prepare_cached("SELECT 1 FROM articles WHERE feed_id = ?1 AND url = ?2")?
+            .exists(params![article.feed_id, url])?;
+        if exists {
+            return Ok(None);
+        }
+    }
+
+    let canonical = article.url.as_deref().and_then(dedup::canonical_url);
+    let original = find_duplicate_original(conn, article, canonical.as_deref())?;
+    let is_read = article.is_read || original.is_some_and(|(_, read)| read);
+
+    let inserted = stmt.execute(params![
+        article.feed_id,
+        article.title,
+        article.url,
+        article.content,
+        // Option<DateTime> を Option<String> に変換
+        article.published_at.map(|dt| dt.to_rfc3339()),
+        is_read as i32,             // bool を整数に変換（SQLiteはboolがない）
+        article.is_favorite as i32, // bool を整数に変換
+        article.created_at.to_rfc3339(),
+        canonical,
+        original.map(|(id, _)| id),
+    ])?;
+
+    if inserted > 0 {
+        Ok(Some(conn.last_insert_rowid()))
+    } else {
+        Ok(None) // 記事は既に存在していた
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    /// [`find_duplicate_original`] を呼んだ回数（テストで重複の判定を省いたことを確かめる）
+    static DUPLICATE_LOOKUPS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// 追加する記事の重複元（元の記事のIDと既読状態）を探す
+///
+/// 正規化したURLが同じ別のフィードの記事を優先し、なければ公開日時が近い別のフィードの記事から
+/// タイトルが似ているものを、日時の近い順に探します。見つかった記事が重複記事の場合は
+/// その元の記事を返すため、関連付けは常に1段になります。
+fn find_duplicate_original(
+    conn: &Connection,
+    article: &Article,
+    canonical: Option<&str>,
+) -> Result<Option<(i64, bool)>> {
+    #[cfg(test)]
+    DUPLICATE_LOOKUPS.with(|count| count.set(count.get() + 1));
+
+    let mut original: Option<i64> = None;
+
+    if let Some(canonical) = canonical {
+        original = conn
+            .prepare_cached(
+                "SELECT COALESCE(duplicate_of, id) FROM articles
+                 WHERE canonical_url = ?1 AND feed_id != ?2 ORDER BY id LIMIT 1",
+            )?
+            .query_row(params![canonical, article.feed_id], |row| row.get(0))
+            .optional()?;
+    }
+
+    if original.is_none() {
+        let (from, to) = dedup::duplicate_window(article);
+        let time = article.published_at.unwrap_or(article.created_at);
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT id, title FROM articles
+             WHERE duplicate_of IS NULL AND feed_id != ?1
+               AND {time} BETWEEN ?2 AND ?3
+             ORDER BY abs(julianday({time}) - julianday(?4)), id",
+            time = DUPLICATE_TIME
+        ))?;
+        let mut rows = stmt.query(params![
+            article.feed_id,
+            from.to_rfc3339(),
+            to.to_rfc3339(),
+            time.to_rfc3339()
+        ])?;
+        while let Some(row) = rows.next()? {
+            let title: String = row.get(1)?;
+            if dedup::is_similar_title(&article.title, &title) {
+                original = Some(row.get(0)?);
+                break;
+            }
+        }
+    }
+
+    let Some(id) = original else {
+        return Ok(None);
+    };
+    let is_read: bool = conn
+        .prepare_cached("SELECT is_read FROM articles WHERE id = ?1")?
+        .query_row(params![id], |row| row.get(0))?;
+    Ok(Some((id, is_read)))
+}
+
+/// 日時を読書履歴の `occurred_at` と同じ形式（[`SQL_NOW`]）の文字列にする
+fn history_timestamp(dt: DateTime<Utc>) -> String {
+    dt.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+/// RFC3339形式の文字列を DateTime<Utc> にパースする
+///
+/// # 引数
+///
+/// * `s` - RFC3339形式の日時文字列（例: "2024-01-01T12:00:00Z"）
+///
+/// # 戻り値
+///
+/// パースされた DateTime。パース失敗時は現在時刻を返す。
+///
+/// # エラー処理
+///
+/// `unwrap_or_else` はエラー時にクロージャを実行してデフォルト値を返します。
+/// これにより、不正な日時形式でもプログラムがパニックしません。
+fn parse_datetime(s: String) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(&s)
+        .map(|dt| dt.with_timezone(&Utc)) // タイムゾーンをUTCに変換
+        .unwrap_or_else(|_| Utc::now()) // パース失敗時は現在時刻
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::ArticleCursor;
+    use crate::stats::StatsOptions;
+    use crate::storage::Storage;
+    use chrono::TimeZone;
+
+    /// テスト用のインメモリデータベースを作成する
+    fn test_db() -> Database {
+        let db = Database::from_connection(Connection::open_in_memory().unwrap()).unwrap();
+        db.init().unwrap();
+        db
+    }
+
+    /// テスト用のフィードを追加してIDを返す
+    fn add_test_feed(db: &Database, url: &str) -> i64 {
+        let feed = Feed::new(url.to_string(), "Test Feed".to_string(), None);
+        db.add_feed(&feed).unwrap()
+    }
+
+    /// LIMIT がフィルタ適用後の結果に対して効くことを確認するテスト
+    #[test]
+    fn test_query_articles_limit_applies_after_filters() {
+        let db = test_db();
+        let feed_a = add_test_feed(&db, "https://a.example.com/feed");
+        let feed_b = add_test_feed(&db, "https://b.example.com/feed");
+
+        // 新しい記事は全て除外対象のフィードBに入れる
+        for day in 1..=5 {
+            let published = Utc.with_ymd_and_hms(2025, 1, day, 0, 0, 0).unwrap();
+            let old = Article::new(
+                feed_a,
+                format!("A{}", day),
+                Some(format!("https://a.example.com/{}", day)),
+                None,
+                Some(published),
+            );
+            let new = Article::new(
+                feed_b,
+                format!("B{}", day),
+                Some(format!("https://b.example.com/{}", day)),
+                None,
+                Some(published + chrono::Duration::days(30)),
+            );
+            db.add_article(&old).unwrap();
+            db.add_article(&new).unwrap();
+        }
+
+        let query = ArticleQuery::new().exclude_feeds(&[feed_b]).limit(3);
+        let articles = db.query_articles(&query).unwrap();
+
+        assert_eq!(articles.len(), 3);
+        assert!(articles.iter().all(|a| a.feed_id == feed_a));
+        assert_eq!(articles[0].title, "A5");
+    }
+
+    /// 日付範囲が日時として比較されることを確認するテスト
+    #[test]
+    fn test_query_articles_date_range() {
+        let db = test_db();
+        let feed_id = add_test_feed(&db, "https://example.com/feed");
+
+        for day in 1..=10 {
+            let published = Utc.with_ymd_and_hms(2025, 1, day, 12, 0, 0).unwrap();
+            let article = Article::new(
+                feed_id,
+                format!("Day {}", day),
+                Some(format!("https://example.com/{}", day)),
+                None,
+                Some(published),
+            );
+            db.add_article(&article).unwrap();
+        }
+
+        let query = ArticleQuery::new()
+            .published_after(Utc.with_ymd_and_hms(2025, 1, 3, 0, 0, 0).unwrap())
+            .published_before(Utc.with_ymd_and_hms(2025, 1, 6, 0, 0, 0).unwrap())
+            .sort(crate::query::SortOrder::Oldest);
+        let titles: Vec<String> = db
+            .query_articles(&query)
+            .unwrap()
+            .into_iter()
+            .map(|a| a.title)
+            .collect();
+
+        assert_eq!(titles, vec!["Day 3", "Day 4", "Day 5"]);
+    }
+
+    /// まとめて追加すると新規分のIDだけが返り、エラー時は1件も追加されないことを確認するテスト
+    #[test]
+    fn test_add_articles_batch_and_rollback() {
+        let db = test_db();
+        let feed_id = add_test_feed(&db, "https://example.com/feed");
+        let article = |n: i64, feed_id: i64| {
+            Article::new(
+                feed_id,
+                format!("Article {}", n),
+                Some(format!("https://example.com/{}", n)),
+                None,
+                None,
+            )
+        };
+
+        let existing = db.add_article(&article(1, feed_id)).unwrap().unwrap();
+        let batch: Vec<Article> = (1..=3).map(|n| article(n, feed_id)).collect();
+        let ids = db.add_articles(&batch).unwrap();
+
+        // 既存の1件目は無視され、残り2件のIDが順に返る
+        assert_eq!(ids.len(), 2);
+        assert!(!ids.contains(&existing));
+        let titles: Vec<String> = ids
+            .iter()
+            .map(|id| db.get_article(*id).unwrap().unwrap().title)
+            .collect();
+        assert_eq!(titles, vec!["Article 2", "Article 3"]);
+
+        // 存在しないフィードの記事が混ざると外部キー制約でエラーになり、全件ロールバックされる
+        let broken = vec![article(4, feed_id), article(5, feed_id + 100)];
+        assert!(db.add_articles(&broken).is_err());
+        assert_eq!(db.query_articles(&ArticleQuery::new()).unwrap().len(), 3);
+
+        // ロールバック後も接続は使える
+        assert_eq!(db.add_articles(&[article(4, feed_id)]).unwrap().len(), 1);
+    }
+
+    /// カーソルで全ページを辿ると、重複・欠落なく全記事を取得できることを確認するテスト
+    #[test]
+    fn test_query_articles_page_walks_all_articles() {
+        let db = test_db();
+        let feed_id = add_test_feed(&db, "https://example.com/feed");
+
+        // 同じ公開日時の記事と公開日時のない記事を混ぜる
+        for i in 0..7 {
+            let published = if i < 5 {
+                Some(Utc.with_ymd_and_hms(2025, 1, 1 + i / 2, 0, 0, 0).unwrap())
+            } else {
+                None
+            };
+            let article = Article::new(
+                feed_id,
+                format!("Article {}", i),
+                Some(format!("https://example.com/{}", i)),
+                None,
+                published,
+            );
+            db.add_article(&article).unwrap();
+        }
+
+        let walk = |query: &ArticleQuery| {
+            let mut ids = Vec::new();
+            let mut cursor: Option<String> = None;
+            loop {
+                let page = db.query_articles_page(query, cursor.as_deref(), 2).unwrap();
+                ids.extend(page.articles.iter().map(|a| a.id));
+                match page.next_cursor {
+                    Some(next) => cursor = Some(next),
+                    None => break,
+                }
+            }
+            ids
+        };
+
+        let query = ArticleQuery::new().feed(feed_id);
+        let expected: Vec<i64> = db
+            .query_articles(&query)
+            .unwrap()
+            .iter()
+            .map(|a| a.id)
+            .collect();
+        let ids = walk(&query);
+        assert_eq!(ids, expected);
+        assert_eq!(ids.len(), 7);
+
+        // 古い順でも同じ（公開日時のない記事が先頭に来る）
+        let oldest = query.clone().sort(SortOrder::Oldest);
+        let mut reversed = expected.clone();
+        reversed.reverse();
+        assert_eq!(walk(&oldest), reversed);
+        assert_eq!(db.count_articles(&query).unwrap(), 7);
+        assert_eq!(db.count_articles(&query.clone().limit(3)).unwrap(), 3);
+
+        // カーソル位置の条件は並び替え用のインデックスで絞り込まれる
+        let cursor = ArticleCursor::from_article(&db.get_article(ids[3]).unwrap().unwrap());
+        let (sql, params) = ArticleQuery::new()
+            .after_cursor(cursor)
+            .limit(2)
+            .to_sql("id");
+        let plan: Vec<String> = db
+            .conn
+            .prepare(&format!("EXPLAIN QUERY PLAN {}", sql))
+            .unwrap()
+            .query_map(rusqlite::params_from_iter(params.iter()), |row| {
+                row.get::<_, String>(3)
+            })
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert!(
+            plan.iter()
+                .any(|detail| detail.contains("idx_articles_sort_key")),
+            "{:?}",
+            plan
+        );
+    }
+
+    /// タグの付与・統合・名前変更と、タグによる絞り込みを確認するテスト
+    #[test]
+    fn test_tags_merge_and_query() {
+        let db = test_db();
+        let feed_id = add_test_feed(&db, "https://example.com/feed");
+        let first = Article::new(
+            feed_id,
+            "First".to_string(),
+            Some("https://example.com/1".to_string()),
+            None,
+            None,
+        );
+        let second = Article::new(
+            feed_id,
+            "Second".to_string(),
+            Some("https://example.com/2".to_string()),
+            None,
+            None,
+        );
+        let first_id = db.add_article(&first).unwrap().unwrap();
+        let second_id = db.add_article(&second).unwrap().unwrap();
+
+        assert_eq!(db.tag_article(first_id, &["security", "rust"]).unwrap(), 2);
+        // 大文字小文字違いは同じタグとして扱われる
+        assert_eq!(db.tag_article(first_id, &["Rust"]).unwrap(), 0);
+        db.tag_article(second_id, &["sec"]).unwrap();
+
+        // "sec" を "security" に統合する
+        assert_eq!(db.merge_tags("sec", "security").unwrap(), 1);
+        let tagged = db
+            .query_articles(&ArticleQuery::new().tag("security"))
+            .unwrap();
+        assert_eq!(tagged.len(), 2);
+
+        // 既存のタグ名への変更はエラーになる
+        assert!(db.rename_tag("rust", "security").is_err());
+        assert!(db.rename_tag("rust", "Rust").unwrap());
+        assert_eq!(
+            db.get_article_tags(first_id).unwrap(),
+            vec!["Rust".to_string(), "security".to_string()]
+        );
+
+        let tags = db.get_tags().unwrap();
+        assert_eq!(tags.len(), 2);
+    }
+
+    /// キューの順番変更と、既読になった記事が自動的にキューから外れることを確認するテスト
+    #[test]
+    fn test_read_later_queue() {
+        let db = test_db();
+        let feed_id = add_test_feed(&db, "https://example.com/feed");
+        let ids: Vec<i64> = (0..3)
+            .map(|i| {
+                let article = Article::new(
+                    feed_id,
+                    format!("Article {}", i),
+                    Some(format!("https://example.com/{}", i)),
+                    None,
+                    None,
+                );
+                db.add_article(&article).unwrap().unwrap()
+            })
+            .collect();
+
+        for id in &ids {
+            assert!(db.add_to_queue(*id).unwrap());
+        }
+        assert!(!db.add_to_queue(ids[0]).unwrap());
+
+        // 末尾の記事を先頭に移動する
+        assert!(db.move_in_queue(ids[2], 0).unwrap());
+        assert_eq!(db.next_in_queue().unwrap().unwrap().id, ids[2]);
+
+        // 既読にするとキューから外れる（一括既読でも同じ）
+        db.mark_as_read(ids[2]).unwrap();
+        db.mark_all_read_with_filter(Some(feed_id), None).unwrap();
+        assert!(db.get_queue().unwrap().is_empty());
+
+        // お気に入りとは独立している
+        db.add_to_queue(ids[1]).unwrap();
+        db.add_favorite(ids[1]).unwrap();
+        db.remove_favorite(ids[1]).unwrap();
+        assert_eq!(db.get_queue().unwrap().len(), 1);
+    }
+
+    /// メモの上書き・削除と、ハイライトの範囲が文字単位で保存されることを確認するテスト
+    #[test]
+    fn test_notes_and_highlights() {
+        let db = test_db();
+        let feed_id = add_test_feed(&db, "https://example.com/feed");
+        let article = Article::new(
+            feed_id,
+            "Article".to_string(),
+            Some("https://example.com/1".to_string()),
+            Some("日本語の本文と Rust の話".to_string()),
+            None,
+        );
+        let article_id = db.add_article(&article).unwrap().unwrap();
+
+        db.save_note(article_id, "first").unwrap();
+        db.save_note(article_id, "second").unwrap();
+        assert_eq!(db.get_note(article_id).unwrap().unwrap().body, "second");
+        // 空のメモを保存すると削除される
+        db.save_note(article_id, "  \n").unwrap();
+        assert!(db.get_note(article_id).unwrap().is_none());
+        assert!(db.save_note(9999, "note").is_err());
+
+        let highlight = db.add_highlight_for_text(article_id, "Rust").unwrap();
+        assert_eq!((highlight.start, highlight.end), (8, 12));
+        let by_range = db.add_highlight(article_id, 0, 3).unwrap();
+        assert_eq!(by_range.text, "日本語");
+        assert!(db.add_highlight(article_id, 5, 100).is_err());
+        assert!(db.add_highlight_for_text(article_id, "Go").is_err());
+
+        let texts: Vec<String> = db
+            .get_highlights(article_id)
+            .unwrap()
+            .into_iter()
+            .map(|h| h.text)
+            .collect();
+        assert_eq!(texts, vec!["日本語", "Rust"]);
+
+        // HTML の本文では、プレビューに表示されるテキスト上の位置で数える
+        let article = Article::new(
+            feed_id,
+            "HTML".to_string(),
+            Some("https://example.com/2".to_string()),
+            Some("<p>Intro &amp; more</p><p>About <em>Rust</em></p>".to_string()),
+            None,
+        );
+        let article_id = db.add_article(&article).unwrap().unwrap();
+        let highlight = db.add_highlight_for_text(article_id, "Rust").unwrap();
+        assert_eq!((highlight.start, highlight.end), (21, 25));
+        let by_range = db.add_highlight(article_id, 0, 12).unwrap();
+        assert_eq!(by_range.text, "Intro & more");
+        assert!(db.add_highlight_for_text(article_id, "<em>").is_err());
+    }
+
+    /// ダンプを別のデータベースに読み込むと、IDが変わっても状態が全て引き継がれることを確認するテスト
+    #[test]
+    fn test_dump_and_load() {
+        let source = test_db();
+        source.create_category("Tech/Rust").unwrap();
+        source.create_category("News").unwrap();
+        let feed_id = add_test_feed(&source, "https://example.com/feed");
+        source.rename_feed(feed_id, Some("My Feed")).unwrap();
+        source
+            .set_feed_category(feed_id, Some("Tech/Rust"))
+            .unwrap();
+        source.set_feed_priority(feed_id, 3).unwrap();
+
+        let ids: Vec<i64> = (1..=3)
+            .map(|n| {
+                let article = Article::new(
+                    feed_id,
+                    format!("Article {}", n),
+                    Some(format!("https://example.com/{}", n)),
+                    Some("Rust の本文".to_string()),
+                    None,
+                );
+                source.add_article(&article).unwrap().unwrap()
+            })
+            .collect();
+        source.mark_as_read(ids[0]).unwrap();
+        source.add_favorite(ids[1]).unwrap();
+        source.tag_article(ids[1], &["rust"]).unwrap();
+        source.save_note(ids[1], "memo").unwrap();
+        source.add_highlight_for_text(ids[1], "Rust").unwrap();
+        source.add_to_queue(ids[2]).unwrap();
+
+        // JSON を経由して読み込む
+        let json = serde_json::to_string(&source.dump().unwrap()).unwrap();
+        let dump = crate::dump::read_dump(json.as_bytes()).unwrap();
+
+        let target = test_db();
+        // 置き換え前のデータがあるため、読み込み先ではIDがずれる
+        target.create_category("Old").unwrap();
+        add_test_feed(&target, "https://old.example.com/feed");
+        let report = target.load_dump(&dump, LoadMode::Replace).unwrap();
+        assert_eq!(report.feeds_added, 1);
+        assert_eq!(report.articles_added, 3);
+
+        let paths: Vec<String> = target
+            .get_categories()
+            .unwrap()
+            .into_iter()
+            .map(|c| c.path)
+            .collect();
+        assert_eq!(paths, vec!["Tech", "Tech/Rust", "News"]);
+        let feeds = target.get_feeds(None).unwrap();
+        assert_eq!(feeds.len(), 1);
+        let feed = &feeds[0];
+        assert_ne!(feed.id, feed_id);
+        assert_eq!(feed.custom_name.as_deref(), Some("My Feed"));
+        assert_eq!(feed.category.as_deref(), Some("Tech/Rust"));
+        assert_eq!(feed.priority, 3);
+
+        let articles = target
+            .query_articles(&ArticleQuery::new().sort(crate::query::SortOrder::Oldest))
+            .unwrap();
+        assert!(articles[0].is_read && articles[0].read_at.is_some());
+        assert!(articles[1].is_favorite);
+        assert_eq!(
+            target.get_article_tags(articles[1].id).unwrap(),
+            vec!["rust"]
+        );
+        assert_eq!(
+            target.get_note(articles[1].id).unwrap().unwrap().body,
+            "memo"
+        );
+        assert_eq!(
+            target.get_highlights(articles[1].id).unwrap()[0].text,
+            "Rust"
+        );
+        assert_eq!(target.get_queue().unwrap()[0].article.id, articles[2].id);
+        assert_eq!(
+            target
+                .get_history(&[HistoryEvent::Read], None, None, None)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // 同じダンプを統合しても重複せず、どちらかで既読の記事は既読になる
+        target.mark_as_unread(articles[0].id).unwrap();
+        target.mark_as_read(articles[2].id).unwrap();
+        let report = target.load_dump(&dump, LoadMode::Merge).unwrap();
+        assert_eq!(report.feeds_merged, 1);
+        assert_eq!((report.articles_added, report.articles_merged), (0, 3));
+        let articles = target
+            .query_articles(&ArticleQuery::new().sort(crate::query::SortOrder::Oldest))
+            .unwrap();
+        let read: Vec<bool> = articles.iter().map(|a| a.is_read).collect();
+        assert_eq!(read, vec![true, false, true]);
+        assert_eq!(target.get_highlights(articles[1].id).unwrap().len(), 1);
+
+        // 読み込んだ記事も、別のフィードの同じ記事の重複記事として関連付けられる
+        let mirror = test_db();
+        let planet = add_test_feed(&mirror, "https://planet.example.com/feed");
+        let original = mirror
+            .add_article(&Article::new(
+                planet,
+                "Planet: Article 2".to_string(),
+                Some("https://example.com/2?utm_source=planet".to_string()),
+                None,
+                None,
+            ))
+            .unwrap()
+            .unwrap();
+        let report = mirror.load_dump(&dump, LoadMode::Merge).unwrap();
+        assert_eq!(report.articles_added, 3);
+        let loaded = |url: &str| {
+            mirror
+                .query_articles(&ArticleQuery::new())
+                .unwrap()
+                .into_iter()
+                .find(|a| a.url.as_deref() == Some(url))
+                .unwrap()
+        };
+        assert_eq!(loaded("https://example.com/2").duplicate_of, Some(original));
+        assert_eq!(loaded("https://example.com/3").duplicate_of, None);
+        assert!(loaded("https://example.com/1").read_at.is_some());
+        assert_eq!(
+            mirror
+                .get_article(original)
+                .unwrap()
+                .unwrap()
+                .duplicate_count,
+            1
+        );
+
+        // 存在しないフィードを参照する記事があると、何も変更されない
+        let mut broken = dump.clone();
+        broken.articles[0].article.feed_id = 9999;
+        assert!(target.load_dump(&broken, LoadMode::Replace).is_err());
+        assert_eq!(target.get_feeds(None).unwrap().len(), 1);
+    }
+
+    /// 旧形式のカテゴリ文字列の移行と、サブカテゴリを含む絞り込み・統合・削除を確認するテスト
+    #[test]
+    fn test_categories_migration_and_hierarchy() {
+        let db = test_db();
+        let rust = add_test_feed(&db, "https://rust.example.com/feed");
+        let go = add_test_feed(&db, "https://go.example.com/feed");
+        let news = add_test_feed(&db, "https://news.example.com/feed");
+
+        // 旧形式の文字列カテゴリを設定して再初期化すると移行される
+        for (id, category) in [(rust, "Tech/Rust"), (go, "tech/Go"), (news, "News")] {
+            db.conn
+                .execute(
+                    "UPDATE feeds SET category = ?1 WHERE id = ?2",
+                    params![category, id],
+                )
+                .unwrap();
+        }
+        db.init().unwrap();
+
+        let paths: Vec<String> = db
+            .get_categories()
+            .unwrap()
+            .into_iter()
+            .map(|c| c.path)
+            .collect();
+        assert_eq!(paths, vec!["Tech", "Tech/Rust", "Tech/Go", "News"]);
+        assert_eq!(
+            db.get_feed(rust).unwrap().unwrap().category.as_deref(),
+            Some("Tech/Rust")
+        );
+
+        // 親カテゴリの指定でサブカテゴリのフィードも含まれる
+        assert_eq!(db.get_feeds(Some("tech")).unwrap().len(), 2);
+        let article = Article::new(
+            go,
+            "Go".to_string(),
+            Some("https://go.example.com/1".to_string()),
+            None,
+            None,
+        );
+        db.add_article(&article).unwrap();
+        let articles = db
+            .query_articles(&ArticleQuery::new().category("Tech"))
+            .unwrap();
+        assert_eq!(articles.len(), 1);
+
+        // 存在しないカテゴリは設定できない
+        assert!(db.set_feed_category(news, Some("Tehc")).is_err());
+
+        // 自身のサブカテゴリへの統合はエラー、それ以外はフィードが移動する
+        assert!(db.merge_categories("Tech", "Tech/Rust").is_err());
+        assert_eq!(db.merge_categories("Tech/Go", "Tech/Rust").unwrap(), 1);
+        assert_eq!(db.get_feeds(Some("Tech/Rust")).unwrap().len(), 2);
+
+        // 削除すると配下のフィードは親カテゴリに移る
+        assert!(db.delete_category("Tech/Rust").unwrap());
+        let feed = db.get_feed(go).unwrap().unwrap();
+        assert_eq!(feed.category.as_deref(), Some("Tech"));
+
+        // 追加時のカテゴリはパスでも指定でき、存在しなければ作成される
+        let mut feed = Feed::new(
+            "https://lobsters.example.com/feed".to_string(),
+            "Lobsters".to_string(),
+            None,
+        );
+        feed.category = Some("News/Links".to_string());
+        let id = db.add_feed(&feed).unwrap();
+        let feed = db.get_feed(id).unwrap().unwrap();
+        assert_eq!(feed.category.as_deref(), Some("News/Links"));
+        assert!(db.get_category("News/Links").unwrap().is_some());
+    }
+
+    /// 一時停止と、旧設定の disabled_feeds の取り込みが一度だけ行われることを確認するテスト
+    #[test]
+    fn test_pause_feeds_and_import_disabled_feeds() {
+        let db = test_db();
+        let active = add_test_feed(&db, "https://active.example.com/feed");
+        let paused = add_test_feed(&db, "https://paused.example.com/feed");
+        for (feed_id, title) in [(active, "Active"), (paused, "Paused")] {
+            let article = Article::new(feed_id, title.to_string(), None, None, None);
+            db.add_article(&article).unwrap();
+        }
+
+        // 存在しないIDは無視して取り込む
+        assert_eq!(db.import_disabled_feeds(&[paused, 999]).unwrap(), 1);
+        assert!(db.get_feed(paused).unwrap().unwrap().paused);
+        assert!(!db.get_feed(active).unwrap().unwrap().paused);
+
+        // フィード指定なしでは除外され、フィードを指定すれば取得できる
+        let titles = |query: &ArticleQuery| -> Vec<String> {
+            db.query_articles(query)
+                .unwrap()
+                .into_iter()
+                .map(|a| a.title)
+                .collect()
+        };
+        assert_eq!(
+            titles(&ArticleQuery::new().exclude_paused()),
+            vec!["Active"]
+        );
+        assert_eq!(
+            titles(&ArticleQuery::new().feed(paused).exclude_paused()),
+            Vec::<String>::new()
+        );
+        assert_eq!(titles(&ArticleQuery::new().feed(paused)), vec!["Paused"]);
+
+        // 再開した後に再び取り込んでも一時停止には戻らない
+        assert!(db.set_feed_paused(paused, false).unwrap());
+        db.init().unwrap();
+        assert_eq!(db.import_disabled_feeds(&[paused]).unwrap(), 0);
+        assert!(!db.get_feed(paused).unwrap().unwrap().paused);
+        assert!(!db.set_feed_paused(999, true).unwrap());
+    }
+
+    /// 別のフィードから届いた同じ記事が元の記事に関連付けられ、既読が連動することを確認するテスト
+    #[test]
+    fn test_duplicate_articles() {
+        let db = test_db();
+        let blog = add_test_feed(&db, "https://blog.example.com/feed");
+        let planet = add_test_feed(&db, "https://planet.example.com/feed");
+        let news = add_test_feed(&db, "https://news.example.com/feed");
+        let published = Utc.with_ymd_and_hms(2025, 1, 10, 9, 0, 0).unwrap();
+        let add = |feed_id: i64, title: &str, url: &str, hours: i64| {
+            let article = Article::new(
+                feed_id,
+                title.to_string(),
+                Some(url.to_string()),
+                None,
+                Some(published + chrono::Duration::hours(hours)),
+            );
+            db.add_article(&article).unwrap().unwrap()
+        };
+
+        let original = add(
+            blog,
+            "The state of async Rust in 2025",
+            "https://blog.example.com/async",
+            0,
+        );
+        // URL が同じ（トラッキング用パラメータだけが違う）
+        let same_url = add(
+            planet,
+            "Blog: async Rust",
+            "http://www.blog.example.com/async?utm_source=planet",
+            2,
+        );
+        // URL は違うがタイトルが似ている
+        let similar = add(
+            news,
+            "The State of Async Rust in 2025!",
+            "https://news.example.com/item/42",
+            5,
+        );
+        // 数字が違うタイトルや、期間外の記事は別の記事
+        let other = add(
+            news,
+            "The state of async Rust in 2024",
+            "https://news.example.com/item/43",
+            5,
+        );
+        let late = add(
+            planet,
+            "The state of async Rust in 2025",
+            "https://planet.example.com/late",
+            24 * 10,
+        );
+
+        let article = |id: i64| db.get_article(id).unwrap().unwrap();
+        assert_eq!(article(original).duplicate_of, None);
+        assert_eq!(article(original).duplicate_count, 2);
+        assert_eq!(article(same_url).duplicate_of, Some(original));
+        assert_eq!(article(similar).duplicate_of, Some(original));
+        assert_eq!(article(other).duplicate_of, None);
+        assert_eq!(article(late).duplicate_of, None);
+
+        let collapsed: Vec<i64> = db
+            .query_articles(&ArticleQuery::new().collapse_duplicates())
+            .unwrap()
+            .into_iter()
+            .map(|a| a.id)
+            .collect();
+        assert_eq!(collapsed, vec![late, other, original]);
+
+        // 元の記事が他の条件で除外される場合は、重複記事が代わりに含まれる
+        let collapsed = |query: ArticleQuery| -> Vec<i64> {
+            db.query_articles(&query.collapse_duplicates())
+                .unwrap()
+                .into_iter()
+                .map(|a| a.id)
+                .collect()
+        };
+        assert_eq!(
+            collapsed(ArticleQuery::new().feed(news)),
+            vec![other, similar]
+        );
+        db.set_feed_paused(blog, true).unwrap();
+        assert_eq!(
+            collapsed(ArticleQuery::new().exclude_paused()),
+            vec![late, other, similar, same_url]
+        );
+        db.set_feed_paused(blog, false).unwrap();
+
+        // 重複記事を既読にすると、元の記事と他の重複記事も既読になる
+        db.mark_as_read(similar).unwrap();
+        assert!(article(original).is_read);
+        assert!(article(same_url).is_read);
+        assert!(!article(other).is_read);
+
+        // 既読の記事の重複は既読として追加される
+        let later = add(
+            news,
+            "Async Rust 2025",
+            "https://blog.example.com/async/#comments",
+            3,
+        );
+        assert_eq!(article(later).duplicate_of, Some(original));
+        assert!(article(later).is_read);
+
+        // 元の記事のフィードを削除すると、関連付けは外れる
+        db.remove_feed(blog).unwrap();
+        assert_eq!(article(same_url).duplicate_of, None);
+    }
+
+    /// 同じフィード内では重複を関連付けず、タイトルの候補は日時の近い記事が優先されることを確認するテスト
+    #[test]
+    fn test_duplicate_candidates() {
+        let db = test_db();
+        let blog = add_test_feed(&db, "https://blog.example.com/feed");
+        let planet = add_test_feed(&db, "https://planet.example.com/feed");
+        let news = add_test_feed(&db, "https://news.example.com/feed");
+        let published = Utc.with_ymd_and_hms(2025, 1, 10, 9, 0, 0).unwrap();
+        let add = |feed_id: i64, title: &str, url: &str, hours: i64| {
+            let article = Article::new(
+                feed_id,
+                title.to_string(),
+                Some(url.to_string()),
+                None,
+                Some(published + chrono::Duration::hours(hours)),
+            );
+            db.add_article(&article).unwrap().unwrap()
+        };
+
+        // 同じフィードに URL の正規化結果が同じ記事が届いても、重複として扱わない
+        add(blog, "Release notes", "https://blog.example.com/notes", 0);
+        let again = add(
+            blog,
+            "Release notes (updated)",
+            "https://blog.example.com/notes?utm_source=rss",
+            1,
+        );
+        assert_eq!(db.get_article(again).unwrap().unwrap().duplicate_of, None);
+
+        // 期間内に似たタイトルの元の記事が2つあれば、日時が近い方（IDの新しい方）に関連付ける
+        let older = add(
+            planet,
+            "Weekly links about systems programming",
+            "https://planet.example.com/a",
+            -48,
+        );
+        let newer = add(
+            blog,
+            "Weekly links about systems programming",
+            "https://blog.example.com/b",
+            48,
+        );
+        assert_eq!(db.get_article(newer).unwrap().unwrap().duplicate_of, None);
+        let linked = add(
+            news,
+            "Weekly links about systems programming",
+            "https://news.example.com/c",
+            24,
+        );
+        assert_eq!(
+            db.get_article(linked).unwrap().unwrap().duplicate_of,
+            Some(newer)
+        );
+        assert_eq!(db.get_article(older).unwrap().unwrap().duplicate_count, 0);
+
+        // 元の記事の期間での絞り込みはインデックスを使う
+        let plan: Vec<String> = db
+            .conn
+            .prepare(&format!(
+                "EXPLAIN QUERY PLAN SELECT id FROM articles
+                 WHERE duplicate_of IS NULL AND feed_id != 1 AND {} BETWEEN 'a' AND 'b'",
+                DUPLICATE_TIME
+            ))
+            .unwrap()
+            .query_map([], |row| row.get::<_, String>(3))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert!(
+            plan.iter()
+                .any(|detail| detail.contains("idx_articles_duplicate_time")),
+            "{:?}",
+            plan
+        );
+    }
+
+    /// 登録済みの記事を再び追加したときは、重複記事を探さずに無視することを確認するテスト
+    #[test]
+    fn test_readd_article_skips_duplicate_lookup() {
+        let db = test_db();
+        let feed_id = add_test_feed(&db, "https://blog.example.com/feed");
+        let article = Article::new(
+            feed_id,
+            "Release notes".to_string(),
+            Some("https://blog.example.com/notes".to_string()),
+            None,
+            None,
+        );
+
+        let lookups = DUPLICATE_LOOKUPS.with(|count| count.get());
+        assert!(db.add_article(&article).unwrap().is_some());
+        assert_eq!(DUPLICATE_LOOKUPS.with(|count| count.get()), lookups + 1);
+
+        assert_eq!(db.add_article(&article).unwrap(), None);
+        assert_eq!(DUPLICATE_LOOKUPS.with(|count| count.get()), lookups + 1);
+    }
+
+    /// OPML のフィードがカテゴリ付きで登録され、登録済みのURLや不正なURLは登録されないことを確認するテスト
+    #[test]
+    fn test_import_opml() {
+        let db = test_db();
+        let existing = add_test_feed(&db, "https://blog.rust-lang.org/feed.xml");
+        let feeds = crate::opml::parse_opml(
+            r#"<opml version="2.0"><body>
+                 <outline text="Tech">
+                   <outline text="Rust">
+                     <outline text="Rust Blog" xmlUrl="http://blog.rust-lang.org/feed.xml"/>
+                     <outline text="This Week in Rust" xmlUrl="https://this-week-in-rust.org/rss.xml"
+                              htmlUrl="https://this-week-in-rust.org/"/>
+                   </outline>
+                 </outline>
+                 <outline text="Broken" xmlUrl="not a url"/>
+                 <outline xmlUrl="https://example.com/feed"/>
+               </body></opml>"#,
+        )
+        .unwrap();
+
+        let entries = db.import_opml(&feeds).unwrap();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(
+            entries[0].status,
+            OpmlImportStatus::AlreadySubscribed { feed_id: existing }
+        );
+        let OpmlImportStatus::Added { feed_id } = entries[1].status else {
+            panic!("not added: {:?}", entries[1]);
+        };
+        let feed = db.get_feed(feed_id).unwrap().unwrap();
+        assert_eq!(feed.custom_name.as_deref(), Some("This Week in Rust"));
+        assert_eq!(feed.category.as_deref(), Some("Tech/Rust"));
+        assert_eq!(
+            feed.site_url.as_deref(),
+            Some("https://this-week-in-rust.org/")
+        );
+        assert!(matches!(entries[2].status, OpmlImportStatus::Failed { .. }));
+        let OpmlImportStatus::Added { feed_id } = entries[3].status else {
+            panic!("not added: {:?}", entries[3]);
+        };
+        let feed = db.get_feed(feed_id).unwrap().unwrap();
+        assert_eq!(feed.title, "https://example.com/feed");
+        assert_eq!(feed.custom_name, None);
+
+        // 2回目は全て登録済みになる
+        let again = db.import_opml(&feeds).unwrap();
+        assert!(matches!(
+            again[3].status,
+            OpmlImportStatus::AlreadySubscribed { .. }
+        ));
+        assert_eq!(db.get_feeds(None).unwrap().len(), 3);
+    }
+
+    /// フィードのURLの正規化と、記事が重なるフィードの検出・統合を確認するテスト
+    #[test]
+    fn test_feed_urls_and_merge_feeds() {
+        let db = test_db();
+        let blog = add_test_feed(&db, "https://blog.example.com/feed?utm_source=x");
+        assert_eq!(
+            db.get_feed(blog).unwrap().unwrap().url,
+            "https://blog.example.com/feed"
+        );
+
+        // 表記が違うだけの同じURLは登録できない
+        assert!(db
+            .add_feed(&Feed::new(
+                "http://www.blog.example.com/feed/".to_string(),
+                "Blog".to_string(),
+                None,
+            ))
+            .is_err());
+        assert_eq!(
+            db.find_feed_by_url("http://blog.example.com/feed/")
+                .unwrap()
+                .map(|feed| feed.id),
+            Some(blog)
+        );
+        let mirror = add_test_feed(&db, "https://mirror.example.com/rss");
+        assert!(db
+            .update_feed_url(mirror, "https://www.blog.example.com/feed")
+            .is_err());
+
+        // ミラーは元のブログの記事をほぼそのまま配信している
+        let add = |feed_id: i64, url: String| {
+            let article = Article::new(feed_id, url.clone(), Some(url), None, None);
+            db.add_article(&article).unwrap().unwrap()
+        };
+        let originals: Vec<i64> = (1..=4)
+            .map(|n| add(blog, format!("https://blog.example.com/{}", n)))
+            .collect();
+        let mirrored: Vec<i64> = (1..=3)
+            .map(|n| {
+                add(
+                    mirror,
+                    format!("https://blog.example.com/{}?utm_source=mirror", n),
+                )
+            })
+            .collect();
+        let unique = add(mirror, "https://mirror.example.com/only-here".to_string());
+        db.add_favorite(mirrored[0]).unwrap();
+        db.tag_article(mirrored[1], &["rust"]).unwrap();
+        db.add_to_queue(mirrored[2]).unwrap();
+        db.fill_feed_site_url(mirror, "https://blog.example.com/")
+            .unwrap();
+
+        let overlaps = db.feed_overlaps(0.7).unwrap();
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(
+            (overlaps[0].feed_id, overlaps[0].other_feed_id),
+            (blog, mirror)
+        );
+        assert_eq!(overlaps[0].shared, 3);
+        assert_eq!((overlaps[0].total, overlaps[0].other_total), (4, 4));
+        assert!(!overlaps[0].same_url);
+        assert!(db.feed_overlaps(0.8).unwrap().is_empty());
+
+        // 統合すると状態は元の記事にまとまり、ミラーにしかない記事は移される
+        let report = db.merge_feeds(mirror, blog).unwrap();
+        assert_eq!(report.articles_merged, 3);
+        assert_eq!(report.articles_moved, 1);
+        assert!(db.get_feed(mirror).unwrap().is_none());
+        assert!(db.get_article(mirrored[0]).unwrap().is_none());
+        // サイトのURLは統合先にない場合だけ引き継ぐ
+        assert_eq!(
+            db.get_feed(blog).unwrap().unwrap().site_url.as_deref(),
+            Some("https://blog.example.com/")
+        );
+
+        let article = |id: i64| db.get_article(id).unwrap().unwrap();
+        assert!(article(originals[0]).is_favorite);
+        assert_eq!(db.get_article_tags(originals[1]).unwrap(), vec!["rust"]);
+        assert_eq!(
+            db.get_queue()
+                .unwrap()
+                .iter()
+                .map(|entry| entry.article.id)
+                .collect::<Vec<_>>(),
+            vec![originals[2]]
+        );
+        assert_eq!(article(unique).feed_id, blog);
+        assert_eq!(article(originals[0]).duplicate_count, 0);
+        assert!(db.feed_overlaps(0.0).unwrap().is_empty());
+        assert!(db.merge_feeds(blog, blog).is_err());
+
+        // 大きなフィードの一部だけを配信するフィードは、共通記事の割合が低くなる
+        let picks = add_test_feed(&db, "https://picks.example.com/feed");
+        for n in 1..=3 {
+            add(picks, format!("https://blog.example.com/{}", n));
+        }
+        assert!(db.feed_overlaps(0.7).unwrap().is_empty());
+        let overlaps = db.feed_overlaps(0.6).unwrap();
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[0].ratio, 0.6);
+        assert_eq!(overlaps[0].pair_id(), format!("{}-{}", blog, picks));
+    }
+
+    /// 複数の記事をまとめて既読にしても、重複記事の連動と読書履歴が1件ずつの場合と同じになることを確認するテスト
+    #[test]
+    fn test_mark_articles_read() {
+        let db = test_db();
+        let blog = add_test_feed(&db, "https://blog.example.com/feed");
+        let planet = add_test_feed(&db, "https://planet.example.com/feed");
+        let add = |feed_id: i64, url: &str| {
+            let article = Article::new(feed_id, url.to_string(), Some(url.to_string()), None, None);
+            db.add_article(&article).unwrap().unwrap()
+        };
+        let ids: Vec<i64> = (1..=3)
+            .map(|n| add(blog, &format!("https://blog.example.com/{}", n)))
+            .collect();
+        let copy = add(planet, "https://blog.example.com/1?utm_source=planet");
+
+        assert_eq!(db.mark_articles_read(&[ids[0], ids[1], 9999]).unwrap(), 2);
+        let is_read = |id: i64| db.get_article(id).unwrap().unwrap().is_read;
+        assert!(is_read(ids[0]) && is_read(ids[1]) && is_read(copy));
+        assert!(!is_read(ids[2]));
+        let history: Vec<i64> = db
+            .get_history(&[HistoryEvent::Read], None, None, None)
+            .unwrap()
+            .iter()
+            .map(|entry| entry.article.id)
+            .collect();
+        assert!(history.contains(&ids[0]) && history.contains(&ids[1]));
+
+        // 既読の記事は数えない
+        assert_eq!(db.mark_articles_read(&ids).unwrap(), 1);
+        assert_eq!(db.mark_articles_read(&[]).unwrap(), 0);
+    }
+
+    /// 既読日時・初回オープン日時の記録と、未読に戻しても履歴が残ることを確認するテスト
+    #[test]
+    fn test_reading_history() {
+        let db = test_db();
+        let feed_id = add_test_feed(&db, "https://example.com/feed");
+        let article = Article::new(
+            feed_id,
+            "Article".to_string(),
+            Some("https://example.com/1".to_string()),
+            None,
+            None,
+        );
+        let article_id = db.add_article(&article).unwrap().unwrap();
+
+        // 開いた日時は最初の1回だけ記録される
+        assert!(db.mark_opened(article_id).unwrap());
+        let opened_at = db
+            .get_article(article_id)
+            .unwrap()
+            .unwrap()
+            .first_opened_at
+            .unwrap();
+        db.mark_opened(article_id).unwrap();
+        let article = db.get_article(article_id).unwrap().unwrap();
+        assert_eq!(article.first_opened_at, Some(opened_at));
+        assert!(article.read_at.is_none());
+
+        // 既読にすると read_at が設定され、既読のままの再設定は履歴に残らない
+        db.mark_as_read(article_id).unwrap();
+        db.mark_all_read_with_filter(None, None).unwrap();
+        assert!(db
+            .get_article(article_id)
+            .unwrap()
+            .unwrap()
+            .read_at
+            .is_some());
+
+        // 未読に戻すと read_at はクリアされるが、既読の履歴は残る
+        db.mark_as_unread(article_id).unwrap();
+        assert!(db
+            .get_article(article_id)
+            .unwrap()
+            .unwrap()
+            .read_at
+            .is_none());
+
+        let reads = db
+            .get_history(&[HistoryEvent::Read], None, None, None)
+            .unwrap();
+        assert_eq!(reads.len(), 1);
+        assert_eq!(reads[0].article.id, article_id);
+
+        let events: Vec<HistoryEvent> = db
+            .get_history(&[], None, None, None)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.event)
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                HistoryEvent::Unread,
+                HistoryEvent::Read,
+                HistoryEvent::Opened,
+                HistoryEvent::Opened
+            ]
+        );
+
+        // 期間で絞り込める
+        let future = Utc::now() + chrono::Duration::days(1);
+        assert!(db
+            .get_history(&[], Some(future), None, None)
+            .unwrap()
+            .is_empty());
+        assert_eq!(db.get_history(&[], None, None, Some(2)).unwrap().len(), 2);
+    }
+
+    /// フィード・カテゴリごとの集計、停滞フィード、読書アクティビティを確認するテスト
+    #[test]
+    fn test_stats() {
+        let db = test_db();
+        let active = add_test_feed(&db, "https://example.com/active");
+        let stale = add_test_feed(&db, "https://example.com/stale");
+        let empty = add_test_feed(&db, "https://example.com/empty");
+        db.create_category("Tech").unwrap();
+        db.set_feed_category(active, Some("Tech")).unwrap();
+
+        let now = Utc::now();
+        let published = [
+            (active, now - chrono::Duration::days(1)),
+            (active, now - chrono::Duration::days(2)),
+            (stale, now - chrono::Duration::days(100)),
+        ];
+        let ids: Vec<i64> = published
+            .iter()
+            .enumerate()
+            .map(|(i, (feed_id, at))| {
+                let article = Article::new(
+                    *feed_id,
+                    format!("Article {}", i),
+                    Some(format!("https://example.com/{}", i)),
+                    None,
+                    Some(*at),
+                );
+                db.add_article(&article).unwrap().unwrap()
+            })
+            .collect();
+        db.mark_as_read(ids[0]).unwrap();
+        db.add_favorite(ids[1]).unwrap();
+
+        let stats = db.get_stats(&StatsOptions::default()).unwrap();
+
+        assert_eq!(stats.totals.feeds, 3);
+        assert_eq!(stats.totals.articles, 3);
+        assert_eq!(stats.totals.unread, 2);
+        assert_eq!(stats.totals.favorites, 1);
+
+        let active_stats = stats.feeds.iter().find(|f| f.feed_id == active).unwrap();
+        assert_eq!(active_stats.category.as_deref(), Some("Tech"));
+        assert_eq!((active_stats.total, active_stats.read), (2, 1));
+        assert_eq!(active_stats.recent_articles, 2);
+        assert!((active_stats.read_ratio - 0.5).abs() < f64::EPSILON);
+
+        // カテゴリは Tech → 未分類の順
+        assert_eq!(stats.categories.len(), 2);
+        assert_eq!(stats.categories[0].category.as_deref(), Some("Tech"));
+        assert_eq!(stats.categories[1].feeds, 2);
+
+        // 記事のないフィードが先頭、次に最後の記事が古いフィード
+        let stale_ids: Vec<i64> = stats.stale_feeds.iter().map(|f| f.feed_id).collect();
+        assert_eq!(stale_ids, vec![empty, stale]);
+        assert_eq!(stats.stale_feeds[1].days_since_last_article, Some(100));
+
+        // 今日の既読が1件記録されている
+        assert_eq!(stats.reading_activity.len(), 84);
+        let today = stats.reading_activity.last().unwrap();
+        assert_eq!((today.date, today.reads), (now.date_naive(), 1));
+        assert_eq!(stats.reading_hours.iter().sum::<usize>(), 1);
+        assert_eq!(stats.publish_hours.iter().sum::<usize>(), 3);
+
+        // 日付の範囲を超える日数でもパニックしない（全ての記事が期間内になる）
+        let stats = db
+            .get_stats(&StatsOptions {
+                stale_days: u32::MAX,
+                frequency_days: u32::MAX,
+                ..StatsOptions::default()
+            })
+            .unwrap();
+        assert_eq!(stats.stale_feeds.len(), 1);
+        let stale_stats = stats.feeds.iter().find(|f| f.feed_id == stale).unwrap();
+        assert_eq!(stale_stats.recent_articles, 1);
+    }
+
+    /// テスト用の一時データベースファイルのパス（テストごとに別名）
+    fn temp_db_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rustfeed-{}-{}.db", name, std::process::id()))
+    }
+
+    /// 一時データベースファイルと WAL 関連のファイルを削除する
+    fn remove_db_files(path: &Path) {
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    /// リースは同時に1つの接続しか取得できず、解放・期限切れ後は取得できることを確認するテスト
+    #[test]
+    fn test_fetch_lease_is_exclusive() {
+        let path = temp_db_path("lease-test");
+        remove_db_files(&path);
+        let first = Database::new_at(&path).unwrap();
+        first.init().unwrap();
+        let second = Database::new_at(&path).unwrap();
+
+        let mut lease = first.try_acquire_fetch_lease().unwrap().unwrap();
+        assert!(second.try_acquire_fetch_lease().unwrap().is_none());
+        assert!(first.renew_fetch_lease(&mut lease).unwrap());
+
+        first.release_fetch_lease(&lease).unwrap();
+        let taken = second.try_acquire_fetch_lease().unwrap().unwrap();
+
+        // 期限切れのリースは別の接続が引き継げる（異常終了したプロセスを想定）
+        second
+            .conn
+            .execute(
+                "UPDATE fetch_lease SET expires_at = ?1",
+                params![(Utc::now() - chrono::Duration::seconds(1)).to_rfc3339()],
+            )
+            .unwrap();
+        assert!(first.try_acquire_fetch_lease().unwrap().is_some());
+        let mut taken = taken;
+        assert!(!second.renew_fetch_lease(&mut taken).unwrap());
+
+        drop((first, second));
+        remove_db_files(&path);
+    }
+
+    /// 子プロセスとして同じデータベースに記事を書き込む（親テストから起動される）
+    ///
+    /// 環境変数がない通常のテスト実行では何もしません。
+    #[test]
+    fn concurrent_writer_process() {
+        let (Ok(path), Ok(feed_id)) = (
+            std::env::var("RUSTFEED_TEST_DB"),
+            std::env::var("RUSTFEED_TEST_FEED_ID"),
+        ) else {
+            return;
+        };
+        let db = Database::new_at(Path::new(&path)).unwrap();
+        let feed_id: i64 = feed_id.parse().unwrap();
+
+        for i in 0..CONCURRENT_WRITES {
+            let article = Article::new(
+                feed_id,
+                format!("Article {}", i),
+                Some(format!("https://example.com/{}/{}", feed_id, i)),
+                None,
+                None,
+            );
+            let id = db.add_article(&article).unwrap().unwrap();
+            db.mark_as_read(id).unwrap();
+        }
+    }
+
+    /// 各子プロセスが書き込む記事数
+    const CONCURRENT_WRITES: usize = 200;
+
+    /// 2つのプロセスが同時に書き込んでも "database is locked" にならないことを確認するテスト
+    #[test]
+    fn test_two_processes_write_concurrently() {
+        let path = temp_db_path("multiprocess-test");
+        remove_db_files(&path);
+        let db = Database::new_at(&path).unwrap();
+        db.init().unwrap();
+        let feeds = [
+            db.add_feed_simple("https://example.com/a", "A").unwrap().id,
+            db.add_feed_simple("https://example.com/b", "B").unwrap().id,
+        ];
+
+        // このテストバイナリ自身を子プロセスとして2つ起動し、同時に書き込ませる
+        let exe = std::env::current_exe().unwrap();
+        let children: Vec<_> = feeds
+            .iter()
+            .map(|feed_id| {
+                std::process::Command::new(&exe)
+                    .args(["db::tests::concurrent_writer_process", "--exact", "--quiet"])
+                    .env("RUSTFEED_TEST_DB", &path)
+                    .env("RUSTFEED_TEST_FEED_ID", feed_id.to_string())
+                    .stdout(std::process::Stdio::null())
+                    .spawn()
+                    .unwrap()
+            })
+            .collect();
+        for mut child in children {
+            assert!(child.wait().unwrap().success());
+        }
+
+        let articles = db.query_articles(&ArticleQuery::new()).unwrap();
+        assert_eq!(articles.len(), CONCURRENT_WRITES * 2);
+        assert!(articles.iter().all(|article| article.is_read));
+
+        drop(db);
+        remove_db_files(&path);
+    }
 }
@@ -0,0 +1,437 @@
+//! # 重複記事の検出
+//!
+//! 同じ記事が複数のフィード（アグリゲーターなど）から届いたときに、
+//! 同一の記事かどうかを判定する関数を提供します。
+//!
+//! ## 判定方法
+//!
+//! 1. **正規化したURL**（[`canonical_url`]）が同じ記事は重複とみなします。
+//!    `utm_*` などのトラッキング用パラメータ、フラグメント、`www.`、末尾の `/` を除き、
+//!    `http` は `https` として扱います。
+//! 2. URLが異なっても、**公開日時が近く**（[`DUPLICATE_WINDOW_DAYS`] 日以内）、
+//!    別のフィードの記事で**タイトルが十分に似ている**（[`is_similar_title`]）場合は重複とみなします。
+//!
+//! 判定は記事の追加時に行われ、重複記事は `Article::duplicate_of` で元の記事に関連付けられます
+//! （`Database::add_article` を参照）。
+//!
+//! ## フィードのURL
+//!
+//! フィードのURLは登録時に [`normalize_feed_url`] で正規化し、[`feed_url_key`] が同じURLは
+//! 同じフィードとみなします（`http` と `https`、`www.` や末尾の `/` の有無、
+//! FeedBurner の別名ホストの違いを区別しません）。
+//! URLが異なっても記事の大半が同じフィード（[`FeedOverlap`]）は `Database::feed_overlaps` で検出し、
+//! `Database::merge_feeds` で1つにまとめられます。
+//!
+//! ## 使用例
+//!
+//! ```rust
+//! use rustfeed_core::dedup::{canonical_url, is_similar_title};
+//!
+//! assert_eq!(
+//!     canonical_url("http://www.example.com/post/?utm_source=rss#top").as_deref(),
+//!     Some("https://example.com/post")
+//! );
+//! assert!(is_similar_title(
+//!     "Rust 1.80 has been released",
+//!     "Rust 1.80 has been released!",
+//! ));
+//! assert!(!is_similar_title(
+//!     "Rust 1.80 has been released",
+//!     "Rust 1.81 has been released",
+//! ));
+//! ```
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Url;
+use serde::Serialize;
+
+use crate::models::Article;
+
+/// タイトルで重複を判定するとき、公開日時の差として許容する日数
+pub const DUPLICATE_WINDOW_DAYS: i64 = 3;
+
+/// タイトルが「似ている」とみなす類似度（0.0〜1.0、[`title_similarity`]）
+pub const TITLE_SIMILARITY_THRESHOLD: f64 = 0.85;
+
+/// 類似度で判定するタイトルの最小の長さ（正規化後の文字数）
+///
+/// 短いタイトル（"Update" など）は偶然一致しやすいため、タイトルでは判定しません。
+const MIN_TITLE_CHARS: usize = 12;
+
+/// `utm_*` 以外に取り除くトラッキング用のクエリパラメータ
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "mc_cid", "mc_eid"];
+
+/// FeedBurner のフィードを配信するホスト（どれも同じフィードを返す）
+const FEEDBURNER_HOSTS: &[&str] = &[
+    "feeds.feedburner.com",
+    "feeds2.feedburner.com",
+    "feedproxy.google.com",
+];
+
+/// FeedBurner のフィードを登録するときに使うホスト
+const FEEDBURNER_HOST: &str = "feeds.feedburner.com";
+
+/// 記事の重なりでフィードの重複を判定するとき、必要な共通記事の最小数
+///
+/// 記事が1〜2件しかないフィードどうしが偶然重なっただけで候補にならないようにします。
+pub const MIN_SHARED_ARTICLES: usize = 3;
+
+// =============================================================================
+// データ型
+// =============================================================================
+
+/// 記事が重なっている2つのフィード
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeedOverlap {
+    /// 一方のフィードのID（`other_feed_id` より小さい）
+    pub feed_id: i64,
+
+    /// もう一方のフィードのID
+    pub other_feed_id: i64,
+
+    /// 両方のフィードにある記事の数（重複記事は1件として数える）
+    pub shared: usize,
+
+    /// `feed_id` のフィードの記事数
+    pub total: usize,
+
+    /// `other_feed_id` のフィードの記事数
+    pub other_total: usize,
+
+    /// 記事の多い方のフィードに対する共通記事の割合（0.0〜1.0）
+    ///
+    /// どちらのフィードから見ても同じ値になるため、小さなフィードが大きなフィードに
+    /// 含まれているだけの組は重複になりません。
+    pub ratio: f64,
+
+    /// URLが同じフィードとみなせるか（[`feed_url_key`] が一致する）
+    pub same_url: bool,
+}
+
+impl FeedOverlap {
+    /// 組を指定するためのID（`"<feed_id>-<other_feed_id>"`）
+    pub fn pair_id(&self) -> String {
+        format!("{}-{}", self.feed_id, self.other_feed_id)
+    }
+}
+
+/// フィードの統合結果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FeedMergeReport {
+    /// 統合先の記事に状態（既読・お気に入り・タグなど）をまとめた記事数
+    pub articles_merged: usize,
+
+    /// 統合先のフィードに移した記事数
+    pub articles_moved: usize,
+}
+
+// =============================================================================
+// URL の正規化
+// =============================================================================
+
+/// 記事のURLを重複判定用に正規化する
+///
+/// - `http` は `https` に揃える
+/// - ホスト名の `www.` を除く（ホスト名は小文字になる）
+/// - フラグメント（`#...`）を除く
+/// - `utm_*` などのトラッキング用パラメータを除き、残りのパラメータを名前順に並べる
+/// - パスの末尾の `/` を除く
+///
+/// http(s) 以外のURLや解釈できないURLの場合は `None` を返します。
+pub fn canonical_url(url: &str) -> Option<String> {
+    let mut url = Url::parse(url.trim()).ok()?;
+    match url.scheme() {
+        "https" => {}
+        "http" => url.set_scheme("https").ok()?,
+        _ => return None,
+    }
+
+    let host = url.host_str()?.to_string();
+    if let Some(stripped) = host.strip_prefix("www.") {
+        url.set_host(Some(stripped)).ok()?;
+    }
+    url.set_fragment(None);
+
+    let mut pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !is_tracking_param(key))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    pairs.sort();
+    if pairs.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+
+    let path = url.path().trim_end_matches('/').to_string();
+    url.set_path(&path);
+
+    // ルートのパスは `set_path("")` でも "/" になるため、文字列にしてから除く
+    let canonical = url.to_string();
+    if url.path() == "/" && url.query().is_none() {
+        return Some(canonical.trim_end_matches('/').to_string());
+    }
+    Some(canonical)
+}
+
+/// フィードのURLを登録用に正規化する
+///
+/// - 前後の空白を除く（ホスト名は小文字になる）
+/// - フラグメント（`#...`）と `utm_*` などのトラッキング用パラメータを除く
+/// - FeedBurner の別名ホストを `feeds.feedburner.com` に揃える
+///
+/// スキームや末尾の `/` はサーバーによって意味が変わりうるため、そのまま残します
+/// （取得に使うURLとして有効なままにするため）。同じフィードかどうかの比較には
+/// [`feed_url_key`] を使ってください。
+///
+/// # エラー
+///
+/// URLとして解釈できない場合や、http(s) 以外のURLの場合はエラーになります。
+pub fn normalize_feed_url(url: &str) -> Result<String> {
+    let mut parsed =
+        Url::parse(url.trim()).with_context(|| format!("Invalid feed URL: {}", url))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        bail!("Feed URL must use http or https: {}", url);
+    }
+
+    parsed.set_fragment(None);
+    if parsed.query().is_some() {
+        let pairs: Vec<(String, String)> = parsed
+            .query_pairs()
+            .filter(|(key, _)| !is_tracking_param(key))
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+        if pairs.is_empty() {
+            parsed.set_query(None);
+        } else {
+            parsed.query_pairs_mut().clear().extend_pairs(pairs);
+        }
+    }
+
+    if parsed
+        .host_str()
+        .is_some_and(|host| FEEDBURNER_HOSTS.contains(&host))
+    {
+        parsed
+            .set_host(Some(FEEDBURNER_HOST))
+            .with_context(|| format!("Invalid feed URL: {}", url))?;
+    }
+    Ok(parsed.to_string())
+}
+
+/// 同じフィードかどうかを比較するためのキー
+///
+/// [`normalize_feed_url`] で正規化したうえで、スキーム、ホスト名の `www.`、
+/// パスの末尾の `/` を除いた文字列です（FeedBurner のフィード名は大文字と小文字を区別しない）。
+/// 解釈できないURLは前後の空白を除いた文字列をそのまま返します。
+pub fn feed_url_key(url: &str) -> String {
+    let Some(parsed) = normalize_feed_url(url)
+        .ok()
+        .and_then(|normalized| Url::parse(&normalized).ok())
+    else {
+        return url.trim().to_string();
+    };
+
+    let host = parsed.host_str().unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    let mut path = parsed.path().trim_end_matches('/').to_string();
+    if host == FEEDBURNER_HOST {
+        path = path.to_lowercase();
+    }
+
+    let mut key = host.to_string();
+    if let Some(port) = parsed.port() {
+        key.push_str(&format!(":{}", port));
+    }
+    key.push_str(&path);
+    if let Some(query) = parsed.query() {
+        key.push('?');
+        key.push_str(query);
+    }
+    key
+}
+
+/// トラッキング用のクエリパラメータかどうか
+fn is_tracking_param(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key.starts_with("utm_") || TRACKING_PARAMS.contains(&key.as_str())
+}
+
+// =============================================================================
+// タイトルの類似度
+// =============================================================================
+
+/// 2つのタイトルの類似度（0.0〜1.0）を求める
+///
+/// 小文字にして英数字（日本語などの文字を含む）以外を除いた文字列の、
+/// 2文字ずつの組（バイグラム）の Dice 係数です。単語の区切りがない言語でも使えます。
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    let a = bigrams(&normalize_title(a));
+    let b = bigrams(&normalize_title(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    // 同じバイグラムが複数回現れる場合も、対応する分だけ数える
+    let mut rest = b.clone();
+    let mut shared = 0;
+    for pair in &a {
+        if let Some(index) = rest.iter().position(|other| other == pair) {
+            rest.swap_remove(index);
+            shared += 1;
+        }
+    }
+    (2 * shared) as f64 / (a.len() + b.len()) as f64
+}
+
+/// 2つのタイトルが同じ記事のものとみなせるほど似ているか
+///
+/// 類似度が [`TITLE_SIMILARITY_THRESHOLD`] 以上で、タイトル中の数字（バージョンや回数）が
+/// 全て一致する場合に true を返します。短いタイトルは常に false です。
+pub fn is_similar_title(a: &str, b: &str) -> bool {
+    let (normalized_a, normalized_b) = (normalize_title(a), normalize_title(b));
+    if normalized_a.chars().count() < MIN_TITLE_CHARS
+        || normalized_b.chars().count() < MIN_TITLE_CHARS
+    {
+        return false;
+    }
+    // "Rust 1.80" と "Rust 1.81" のように数字だけが違うタイトルは別の記事
+    if numbers(a) != numbers(b) {
+        return false;
+    }
+    title_similarity(a, b) >= TITLE_SIMILARITY_THRESHOLD
+}
+
+/// タイトルを比較用に正規化する（小文字の英数字だけを残す）
+fn normalize_title(title: &str) -> String {
+    title
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 文字列のバイグラム（隣り合う2文字の組）
+fn bigrams(text: &str) -> Vec<(char, char)> {
+    let chars: Vec<char> = text.chars().collect();
+    chars.windows(2).map(|pair| (pair[0], pair[1])).collect()
+}
+
+/// タイトルに含まれる数字の並び
+fn numbers(title: &str) -> Vec<String> {
+    title
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// タイトルで重複を探す範囲（公開日時、なければ取得日時の前後 [`DUPLICATE_WINDOW_DAYS`] 日）
+pub fn duplicate_window(article: &Article) -> (DateTime<Utc>, DateTime<Utc>) {
+    let time = article.published_at.unwrap_or(article.created_at);
+    let window = Duration::days(DUPLICATE_WINDOW_DAYS);
+    (time - window, time + window)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// トラッキング用パラメータなどの違いが正規化で吸収されることを確認するテスト
+    #[test]
+    fn test_canonical_url() {
+        let canonical = Some("https://example.com/2025/01/post".to_string());
+        for url in [
+            "https://example.com/2025/01/post",
+            "http://www.example.com/2025/01/post/",
+            "https://EXAMPLE.com/2025/01/post?utm_source=rss&utm_medium=feed",
+            "https://example.com/2025/01/post#comments",
+            "https://example.com/2025/01/post?fbclid=abc",
+        ] {
+            assert_eq!(canonical_url(url), canonical, "{}", url);
+        }
+
+        // 意味のあるパラメータは残し、順序を揃える
+        assert_eq!(
+            canonical_url("https://example.com/item?utm_campaign=x&id=2&page=1").as_deref(),
+            Some("https://example.com/item?id=2&page=1")
+        );
+        assert_eq!(
+            canonical_url("https://example.com/item?page=1&id=2"),
+            canonical_url("https://example.com/item?id=2&page=1")
+        );
+        assert_eq!(
+            canonical_url("https://www.example.com/").as_deref(),
+            Some("https://example.com")
+        );
+
+        assert_eq!(canonical_url("mailto:someone@example.com"), None);
+        assert_eq!(canonical_url("not a url"), None);
+    }
+
+    /// 登録するフィードのURLの正規化と、同じフィードとみなすURLを確認するテスト
+    #[test]
+    fn test_feed_urls() {
+        assert_eq!(
+            normalize_feed_url(" https://Example.com/feed.xml?utm_source=x#top ").unwrap(),
+            "https://example.com/feed.xml"
+        );
+        assert_eq!(
+            normalize_feed_url("http://feeds2.feedburner.com/Example").unwrap(),
+            "http://feeds.feedburner.com/Example"
+        );
+        // 取得に使うため、スキームと末尾の `/` は残す
+        assert_eq!(
+            normalize_feed_url("http://example.com/feed/").unwrap(),
+            "http://example.com/feed/"
+        );
+        assert!(normalize_feed_url("ftp://example.com/feed").is_err());
+        assert!(normalize_feed_url("not a url").is_err());
+
+        let key = feed_url_key("https://example.com/feed");
+        for url in [
+            "http://example.com/feed",
+            "https://www.example.com/feed/",
+            "https://example.com/feed?utm_medium=rss",
+        ] {
+            assert_eq!(feed_url_key(url), key, "{}", url);
+        }
+        assert_eq!(
+            feed_url_key("https://feedproxy.google.com/example"),
+            feed_url_key("http://feeds.feedburner.com/Example")
+        );
+        assert_ne!(
+            feed_url_key("https://example.com/feed?lang=en"),
+            feed_url_key("https://example.com/feed?lang=ja")
+        );
+        assert_ne!(
+            feed_url_key("https://example.com/Feed"),
+            feed_url_key("https://example.com/feed")
+        );
+    }
+
+    /// 言い回しの小さな違いは似ているとみなし、数字の違いや短いタイトルは区別することを確認するテスト
+    #[test]
+    fn test_similar_titles() {
+        assert!(is_similar_title(
+            "The state of async Rust in 2025",
+            "The State of Async Rust in 2025!"
+        ));
+        assert!(is_similar_title(
+            "新しいプログラミング言語の設計について",
+            "新しいプログラミング言語の設計について。"
+        ));
+
+        assert!(!is_similar_title(
+            "Rust 1.80 has been released",
+            "Rust 1.81 has been released"
+        ));
+        assert!(!is_similar_title("Weekly news", "Weekly news"));
+        assert!(!is_similar_title(
+            "The state of async Rust in 2025",
+            "A history of garbage collection"
+        ));
+    }
+}
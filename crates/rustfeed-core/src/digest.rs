@@ -0,0 +1,948 @@
+//! # ダイジェストモジュール
+//!
+//! 指定した期間に届いた記事を、カテゴリ → フィードの順にまとめたダイジェスト
+//! （週刊のニュースレターなどにそのまま貼り付けられる要約）を作る機能を提供します。
+//!
+//! ## テンプレート
+//!
+//! ダイジェストは Markdown・HTML・プレーンテキストのテンプレートで描画します。
+//! 形式ごとに組み込みのテンプレートがあり、設定ディレクトリの `templates/` に
+//! `digest.md`・`digest.html`・`digest.txt` を置くとそちらが使われます
+//! （[`write_default_templates`] で組み込みのものを書き出して編集できます）。
+//!
+//! 書式は Mustache のサブセットです。
+//!
+//! | タグ | 意味 |
+//! |------|------|
+//! | `{{name}}` | 値を出力する（HTML ではエスケープする） |
+//! | `{{{name}}}` / `{{& name}}` | 値をそのまま出力する |
+//! | `{{#name}}…{{/name}}` | 配列なら要素ごとに繰り返し、それ以外は値があるときだけ出力する |
+//! | `{{^name}}…{{/name}}` | 値がない（null・false・空文字列・空の配列）ときだけ出力する |
+//! | `{{! comment}}` | コメント（何も出力しない） |
+//!
+//! 名前は `feed.title` のようにドットでたどれ、`{{.}}` は現在の値を表します。
+//! セクションの中で見つからない名前は外側から探します。セクションやコメントのタグだけの行は
+//! 行ごと取り除かれます。
+//!
+//! テンプレートに渡す値は [`Digest`] を JSON にしたものです。
+//!
+//! | 名前 | 内容 |
+//! |------|------|
+//! | `title` / `since` / `until` / `generated_at` | タイトルと期間（`since` は省略されることがある） |
+//! | `article_count` / `feed_count` | 記事とフィードの数 |
+//! | `categories[]` | `name`・`article_count`・`feeds[]` |
+//! | `feeds[]` | `id`・`title`・`site_url`・`article_count`・`articles[]` |
+//! | `articles[]` | `id`・`title`・`url`・`published_at`・`excerpt`・`tags[]`・`is_favorite` |
+//!
+//! ## 使用例
+//!
+//! ```rust
+//! use rustfeed_core::digest::Template;
+//!
+//! let template = Template::parse("{{#items}}\n- {{name}}\n{{/items}}\n")?;
+//! let data = serde_json::json!({ "items": [{ "name": "a" }, { "name": "<b>" }] });
+//! assert_eq!(template.render(&data, false), "- a\n- <b>\n");
+//! assert_eq!(template.render(&data, true), "- a\n- &lt;b&gt;\n");
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::config::AppConfig;
+use crate::db::Database;
+use crate::export::annotate;
+use crate::html::{escape_html, html_to_text, is_safe_url};
+use crate::query::ArticleQuery;
+
+/// 抜粋の最大文字数
+pub const EXCERPT_CHARS: usize = 280;
+
+/// カテゴリのないフィードをまとめる見出し（常に最後に並ぶ）
+const UNCATEGORIZED: &str = "Uncategorized";
+
+// =============================================================================
+// 形式
+// =============================================================================
+
+/// ダイジェストの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestFormat {
+    /// Markdown
+    Markdown,
+    /// HTML（メール本文にも使えるよう、スタイルは要素に直接書く）
+    Html,
+    /// プレーンテキスト
+    Text,
+}
+
+impl DigestFormat {
+    /// すべての形式
+    pub const ALL: [DigestFormat; 3] = [
+        DigestFormat::Markdown,
+        DigestFormat::Html,
+        DigestFormat::Text,
+    ];
+
+    /// テンプレートと出力ファイルの拡張子
+    pub fn extension(self) -> &'static str {
+        match self {
+            DigestFormat::Markdown => "md",
+            DigestFormat::Html => "html",
+            DigestFormat::Text => "txt",
+        }
+    }
+
+    /// 組み込みのテンプレート
+    pub fn default_template(self) -> &'static str {
+        match self {
+            DigestFormat::Markdown => DEFAULT_MARKDOWN_TEMPLATE,
+            DigestFormat::Html => DEFAULT_HTML_TEMPLATE,
+            DigestFormat::Text => DEFAULT_TEXT_TEMPLATE,
+        }
+    }
+
+    /// 設定ディレクトリのテンプレートのパス（`templates/digest.<拡張子>`）
+    pub fn template_path(self) -> Result<PathBuf> {
+        Ok(AppConfig::config_dir()?
+            .join("templates")
+            .join(format!("digest.{}", self.extension())))
+    }
+
+    /// `{{name}}` の値を HTML としてエスケープするか
+    fn escapes_html(self) -> bool {
+        self == DigestFormat::Html
+    }
+}
+
+impl FromStr for DigestFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "markdown" | "md" => Ok(DigestFormat::Markdown),
+            "html" => Ok(DigestFormat::Html),
+            "text" | "txt" | "plain" => Ok(DigestFormat::Text),
+            _ => bail!("Unsupported format: '{}'. Use markdown, html or text.", s),
+        }
+    }
+}
+
+impl fmt::Display for DigestFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DigestFormat::Markdown => "markdown",
+            DigestFormat::Html => "html",
+            DigestFormat::Text => "text",
+        };
+        f.write_str(name)
+    }
+}
+
+// =============================================================================
+// ダイジェストのデータ
+// =============================================================================
+
+/// ダイジェスト（テンプレートに渡す値）
+#[derive(Debug, Clone, Serialize)]
+pub struct Digest {
+    /// タイトル
+    pub title: String,
+
+    /// 期間の始まり（日付の書式で整形済み）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+
+    /// 期間の終わり（日付の書式で整形済み）
+    pub until: String,
+
+    /// 作成日時（RFC 3339）
+    pub generated_at: String,
+
+    /// 記事の数
+    pub article_count: usize,
+
+    /// 記事のあるフィードの数
+    pub feed_count: usize,
+
+    /// カテゴリごとの記事（名前順、カテゴリのないフィードは最後）
+    pub categories: Vec<DigestCategory>,
+}
+
+/// ダイジェストのカテゴリ
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestCategory {
+    /// カテゴリのパス（例: "Tech/Rust"）
+    pub name: String,
+
+    /// カテゴリ内の記事の数
+    pub article_count: usize,
+
+    /// フィードごとの記事（名前順）
+    pub feeds: Vec<DigestFeed>,
+}
+
+/// ダイジェストのフィード
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestFeed {
+    /// フィードID
+    pub id: i64,
+
+    /// 表示名（カスタム名、なければタイトル）
+    pub title: String,
+
+    /// サイトのURL（http(s) のみ）
+    pub site_url: Option<String>,
+
+    /// フィード内の記事の数
+    pub article_count: usize,
+
+    /// 記事（条件の並び順）
+    pub articles: Vec<DigestArticle>,
+}
+
+/// ダイジェストの記事
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestArticle {
+    /// 記事ID
+    pub id: i64,
+
+    /// タイトル
+    pub title: String,
+
+    /// 記事のURL（http(s) と mailto のみ）
+    pub url: Option<String>,
+
+    /// 公開日時（日付の書式で整形済み）
+    pub published_at: Option<String>,
+
+    /// 本文の先頭 [`EXCERPT_CHARS`] 文字までのプレーンテキスト
+    pub excerpt: String,
+
+    /// タグ
+    pub tags: Vec<String>,
+
+    /// お気に入りかどうか
+    pub is_favorite: bool,
+}
+
+impl Digest {
+    /// ダイジェストに含まれる記事のID
+    pub fn article_ids(&self) -> Vec<i64> {
+        self.categories
+            .iter()
+            .flat_map(|category| &category.feeds)
+            .flat_map(|feed| &feed.articles)
+            .map(|article| article.id)
+            .collect()
+    }
+
+    /// テンプレートで描画する
+    pub fn render(&self, template: &Template, format: DigestFormat) -> Result<String> {
+        let data = serde_json::to_value(self).context("Failed to serialize digest")?;
+        Ok(template.render(&data, format.escapes_html()))
+    }
+}
+
+/// 条件に一致する記事からダイジェストを作る
+///
+/// 期間は `query.published_after` から `query.published_before`（なければ現在）までです。
+/// 公開日時のない記事は、取得日時（`created_at`）が期間内なら含めます。
+/// 日時は `date_format`（chrono の書式）で整形します。
+pub fn build_digest(
+    db: &Database,
+    query: &ArticleQuery,
+    title: &str,
+    date_format: &str,
+) -> Result<Digest> {
+    let now = Utc::now();
+    let feeds: HashMap<i64, _> = db
+        .get_feeds(None)?
+        .into_iter()
+        .map(|feed| (feed.id, feed))
+        .collect();
+    let query = query.clone().undated_by_created_at();
+    let articles = annotate(db, db.query_articles(&query)?)?;
+
+    // (カテゴリなしか, カテゴリ名) → (表示名の小文字, フィードID) → フィード
+    let mut groups: BTreeMap<(bool, String), BTreeMap<(String, i64), DigestFeed>> = BTreeMap::new();
+    for entry in &articles {
+        let article = &entry.article;
+        let feed = feeds.get(&article.feed_id);
+        let category = feed.and_then(|feed| feed.category.clone());
+        let feed_title = entry
+            .feed_title
+            .clone()
+            .unwrap_or_else(|| format!("Feed {}", article.feed_id));
+
+        let digest_feed = groups
+            .entry((
+                category.is_none(),
+                category.unwrap_or_else(|| UNCATEGORIZED.to_string()),
+            ))
+            .or_default()
+            .entry((feed_title.to_lowercase(), article.feed_id))
+            .or_insert_with(|| DigestFeed {
+                id: article.feed_id,
+                title: feed_title,
+                site_url: feed.and_then(|feed| feed.site_url.clone()).filter(|url| {
+                    is_safe_url(url) && !url.to_ascii_lowercase().starts_with("mailto:")
+                }),
+                article_count: 0,
+                articles: Vec::new(),
+            });
+        digest_feed.article_count += 1;
+        digest_feed.articles.push(DigestArticle {
+            id: article.id,
+            title: article.title.clone(),
+            url: article.url.clone().filter(|url| is_safe_url(url)),
+            published_at: article
+                .published_at
+                .map(|date| date.format(date_format).to_string()),
+            excerpt: excerpt(article.content.as_deref().unwrap_or("")),
+            tags: entry.tags.clone(),
+            is_favorite: article.is_favorite,
+        });
+    }
+
+    let categories: Vec<DigestCategory> = groups
+        .into_iter()
+        .map(|((_, name), feeds)| {
+            let feeds: Vec<DigestFeed> = feeds.into_values().collect();
+            DigestCategory {
+                name,
+                article_count: feeds.iter().map(|feed| feed.article_count).sum(),
+                feeds,
+            }
+        })
+        .collect();
+
+    Ok(Digest {
+        title: title.to_string(),
+        since: query
+            .published_after
+            .map(|date| date.format(date_format).to_string()),
+        until: query
+            .published_before
+            .unwrap_or(now)
+            .format(date_format)
+            .to_string(),
+        generated_at: now.to_rfc3339(),
+        article_count: articles.len(),
+        feed_count: categories.iter().map(|category| category.feeds.len()).sum(),
+        categories,
+    })
+}
+
+/// 本文（HTML）から抜粋を作る
+///
+/// 長い場合は [`EXCERPT_CHARS`] 文字以内の単語の切れ目で切り、`…` を付けます。
+fn excerpt(content: &str) -> String {
+    let text = html_to_text(content);
+    if text.chars().count() <= EXCERPT_CHARS {
+        return text;
+    }
+
+    let cut = text
+        .char_indices()
+        .nth(EXCERPT_CHARS)
+        .map_or(text.len(), |(index, _)| index);
+    let head = &text[..cut];
+    // 単語の途中で切らない（空白のない言語では文字数で切る）
+    let head = match head.rfind(' ') {
+        Some(space) if space > cut / 2 => &head[..space],
+        _ => head,
+    };
+    format!("{}…", head.trim_end())
+}
+
+/// `--since` の値を日時にする
+///
+/// `30m`・`12h`・`1d`・`2w` のような `now` からの相対指定か、
+/// YYYY-MM-DD（その日の 00:00 UTC）を受け付けます。
+pub fn parse_since(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(NaiveTime::MIN).and_utc());
+    }
+
+    let invalid = || {
+        anyhow::anyhow!(
+            "Invalid period '{}'. Use a duration like 12h, 1d or 2w, or a date (YYYY-MM-DD).",
+            value
+        )
+    };
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(split);
+    let amount: u32 = amount.parse().map_err(|_| invalid())?;
+    let period = match unit {
+        "m" | "min" => Duration::minutes(amount.into()),
+        "h" => Duration::hours(amount.into()),
+        "d" => Duration::days(amount.into()),
+        "w" => Duration::weeks(amount.into()),
+        _ => return Err(invalid()),
+    };
+    now.checked_sub_signed(period).ok_or_else(invalid)
+}
+
+// =============================================================================
+// テンプレートの読み込み
+// =============================================================================
+
+/// 形式のテンプレートを読み込む（設定ディレクトリになければ組み込みのもの）
+pub fn load_template(format: DigestFormat) -> Result<Template> {
+    let path = format.template_path()?;
+    if !path.exists() {
+        return Template::parse(format.default_template());
+    }
+
+    let source = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Template::parse(&source).with_context(|| format!("Invalid template {}", path.display()))
+}
+
+/// 組み込みのテンプレートを設定ディレクトリに書き出す
+///
+/// すでにあるファイルは上書きしません。
+///
+/// # 戻り値
+///
+/// 書き出したファイルのパス
+pub fn write_default_templates() -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    for format in DigestFormat::ALL {
+        let path = format.template_path()?;
+        if path.exists() {
+            continue;
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(&path, format.default_template())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+// =============================================================================
+// テンプレート
+// =============================================================================
+
+/// 解釈済みのテンプレート
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+/// テンプレートの要素
+#[derive(Debug, Clone)]
+enum Node {
+    /// そのまま出力する文字列
+    Text(String),
+    /// `{{name}}`（`escape`）と `{{{name}}}`
+    Value { name: String, escape: bool },
+    /// `{{#name}}…{{/name}}`（`inverted` なら `{{^name}}`）
+    Section {
+        name: String,
+        inverted: bool,
+        children: Vec<Node>,
+    },
+}
+
+/// 解釈中のセクション（名前, 反転か, 中の要素）
+type OpenSection = (String, bool, Vec<Node>);
+
+impl Template {
+    /// テンプレートを解釈する
+    ///
+    /// # エラー
+    ///
+    /// タグが閉じられていない場合や、セクションの開始と終了が対応しない場合はエラーを返します。
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut stack: Vec<OpenSection> = vec![(String::new(), false, Vec::new())];
+        let mut pos = 0;
+
+        while let Some(offset) = source[pos..].find("{{") {
+            let start = pos + offset;
+            let line = source[..start].matches('\n').count() + 1;
+            let (inner, end) = if source[start..].starts_with("{{{") {
+                let close = source[start + 3..]
+                    .find("}}}")
+                    .with_context(|| format!("Unclosed tag on line {}", line))?;
+                (
+                    format!("&{}", &source[start + 3..start + 3 + close]),
+                    start + 3 + close + 3,
+                )
+            } else {
+                let close = source[start + 2..]
+                    .find("}}")
+                    .with_context(|| format!("Unclosed tag on line {}", line))?;
+                (
+                    source[start + 2..start + 2 + close].to_string(),
+                    start + 2 + close + 2,
+                )
+            };
+            let inner = inner.trim();
+            let (sigil, name) = match inner.chars().next() {
+                Some(c @ ('#' | '^' | '/' | '!' | '&')) => (Some(c), inner[1..].trim()),
+                _ => (None, inner),
+            };
+
+            // セクションとコメントのタグだけの行は、行ごと取り除く
+            let (mut text_end, mut next) = (start, end);
+            if matches!(sigil, Some('#' | '^' | '/' | '!')) {
+                let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+                let line_end = source[end..]
+                    .find('\n')
+                    .map_or(source.len(), |i| end + i + 1);
+                if line_start >= pos
+                    && is_blank(&source[line_start..start])
+                    && is_blank(&source[end..line_end])
+                {
+                    text_end = line_start;
+                    next = line_end;
+                }
+            }
+            push_text(&mut stack, &source[pos..text_end]);
+            pos = next;
+
+            if sigil != Some('!') && name.is_empty() {
+                bail!("Empty tag on line {}", line);
+            }
+            match sigil {
+                Some('!') => {}
+                Some('#') | Some('^') => {
+                    stack.push((name.to_string(), sigil == Some('^'), Vec::new()));
+                }
+                Some('/') => {
+                    if stack.len() == 1 {
+                        bail!("Unexpected {{{{/{}}}}} on line {}", name, line);
+                    }
+                    let (open, inverted, children) = stack.pop().unwrap_or_default();
+                    if open != name {
+                        bail!(
+                            "Expected {{{{/{}}}}} but found {{{{/{}}}}} on line {}",
+                            open,
+                            name,
+                            line
+                        );
+                    }
+                    current(&mut stack).push(Node::Section {
+                        name: open,
+                        inverted,
+                        children,
+                    });
+                }
+                _ => current(&mut stack).push(Node::Value {
+                    name: name.to_string(),
+                    escape: sigil.is_none(),
+                }),
+            }
+        }
+        push_text(&mut stack, &source[pos..]);
+
+        if stack.len() > 1 {
+            let (name, inverted, _) = &stack[stack.len() - 1];
+            bail!(
+                "Unclosed section {{{{{}{}}}}}",
+                if *inverted { '^' } else { '#' },
+                name
+            );
+        }
+        let (_, _, nodes) = stack.pop().unwrap_or_default();
+        Ok(Template { nodes })
+    }
+
+    /// 値を埋め込んで描画する
+    ///
+    /// `escape_html` が true なら `{{name}}` の値を HTML としてエスケープします。
+    pub fn render(&self, data: &Value, escape_html: bool) -> String {
+        let mut out = String::new();
+        render_nodes(&self.nodes, &mut vec![data], escape_html, &mut out);
+        out
+    }
+}
+
+/// 解釈中のいちばん内側のセクションの要素
+fn current(stack: &mut [OpenSection]) -> &mut Vec<Node> {
+    let last = stack.len() - 1;
+    &mut stack[last].2
+}
+
+/// 文字列の要素を加える（空なら何もしない）
+fn push_text(stack: &mut [OpenSection], text: &str) {
+    if !text.is_empty() {
+        current(stack).push(Node::Text(text.to_string()));
+    }
+}
+
+/// 空白と改行だけか
+fn is_blank(text: &str) -> bool {
+    text.chars().all(|c| matches!(c, ' ' | '\t' | '\r' | '\n'))
+}
+
+/// 要素を描画する（`context` は内側ほど後ろにある値の積み重ね）
+fn render_nodes(nodes: &[Node], context: &mut Vec<&Value>, escape: bool, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Value {
+                name,
+                escape: escape_value,
+            } => {
+                let text = lookup(context, name).map(value_to_text).unwrap_or_default();
+                if escape && *escape_value {
+                    out.push_str(&escape_html(&text));
+                } else {
+                    out.push_str(&text);
+                }
+            }
+            Node::Section {
+                name,
+                inverted,
+                children,
+            } => {
+                let value = lookup(context, name);
+                let truthy = value.is_some_and(is_truthy);
+                if *inverted {
+                    if !truthy {
+                        render_nodes(children, context, escape, out);
+                    }
+                    continue;
+                }
+                match value {
+                    Some(Value::Array(items)) => {
+                        for item in items {
+                            context.push(item);
+                            render_nodes(children, context, escape, out);
+                            context.pop();
+                        }
+                    }
+                    Some(value) if truthy => {
+                        context.push(value);
+                        render_nodes(children, context, escape, out);
+                        context.pop();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// 名前（`a.b` のようにドットでたどれる、`.` は現在の値）の値を探す
+fn lookup<'v>(context: &[&'v Value], name: &str) -> Option<&'v Value> {
+    if name == "." {
+        return context.last().copied();
+    }
+    let mut parts = name.split('.');
+    let first = parts.next()?;
+    let mut value = context.iter().rev().find_map(|value| value.get(first))?;
+    for part in parts {
+        value = value.get(part)?;
+    }
+    Some(value)
+}
+
+/// セクションを出力する値か（null・false・空文字列・空の配列以外）
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => true,
+    }
+}
+
+/// 値を出力する文字列にする（配列とオブジェクトは空）
+fn value_to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => String::new(),
+    }
+}
+
+// =============================================================================
+// 組み込みのテンプレート
+// =============================================================================
+
+/// 組み込みの Markdown テンプレート
+const DEFAULT_MARKDOWN_TEMPLATE: &str = "\
+# {{title}}
+
+{{article_count}} new article(s) from {{feed_count}} feed(s){{#since}} since {{since}}{{/since}}.
+{{#categories}}
+
+## {{name}}
+{{#feeds}}
+
+### {{#site_url}}[{{title}}]({{site_url}}){{/site_url}}{{^site_url}}{{title}}{{/site_url}}
+
+{{#articles}}
+- {{#url}}[{{title}}]({{url}}){{/url}}{{^url}}{{title}}{{/url}}{{#published_at}} ({{published_at}}){{/published_at}}
+{{#excerpt}}
+  {{excerpt}}
+{{/excerpt}}
+{{/articles}}
+{{/feeds}}
+{{/categories}}
+{{^categories}}
+
+No new articles.
+{{/categories}}
+";
+
+/// 組み込みの HTML テンプレート
+const DEFAULT_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8" />
+<title>{{title}}</title>
+</head>
+<body style="font-family: -apple-system, 'Segoe UI', sans-serif; max-width: 40em; margin: 0 auto; padding: 1em; line-height: 1.5; color: #222;">
+<h1 style="margin-bottom: 0.2em;">{{title}}</h1>
+<p style="color: #666; margin-top: 0;">{{article_count}} new article(s) from {{feed_count}} feed(s){{#since}} since {{since}}{{/since}}.</p>
+{{#categories}}
+<h2 style="border-bottom: 1px solid #ddd; padding-bottom: 0.2em;">{{name}}</h2>
+{{#feeds}}
+<h3>{{#site_url}}<a href="{{site_url}}" style="color: #222;">{{title}}</a>{{/site_url}}{{^site_url}}{{title}}{{/site_url}}</h3>
+<ul style="padding-left: 1.2em;">
+{{#articles}}
+<li style="margin-bottom: 0.8em;">
+{{#url}}<a href="{{url}}" style="font-weight: bold; color: #0b57d0;">{{title}}</a>{{/url}}{{^url}}<strong>{{title}}</strong>{{/url}}{{#published_at}} <span style="color: #666;">({{published_at}})</span>{{/published_at}}
+{{#excerpt}}
+<br /><span style="color: #444;">{{excerpt}}</span>
+{{/excerpt}}
+</li>
+{{/articles}}
+</ul>
+{{/feeds}}
+{{/categories}}
+{{^categories}}
+<p>No new articles.</p>
+{{/categories}}
+</body>
+</html>
+"#;
+
+/// 組み込みのプレーンテキストテンプレート
+const DEFAULT_TEXT_TEMPLATE: &str = "\
+{{title}}
+
+{{article_count}} new article(s) from {{feed_count}} feed(s){{#since}} since {{since}}{{/since}}.
+{{#categories}}
+
+== {{name}} ==
+{{#feeds}}
+
+{{title}}
+{{#articles}}
+  * {{title}}{{#published_at}} ({{published_at}}){{/published_at}}
+{{#url}}
+    {{url}}
+{{/url}}
+{{#excerpt}}
+    {{excerpt}}
+{{/excerpt}}
+{{/articles}}
+{{/feeds}}
+{{/categories}}
+{{^categories}}
+
+No new articles.
+{{/categories}}
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Article, Feed};
+    use rusqlite::Connection;
+    use serde_json::json;
+
+    /// セクション・反転セクション・ドット付きの名前・エスケープ・タグだけの行を確認するテスト
+    #[test]
+    fn test_template() {
+        let data = json!({
+            "title": "A & B",
+            "feed": { "title": "Example" },
+            "items": [{ "name": "one", "url": "https://example.com/1" }, { "name": "two" }],
+            "empty": [],
+        });
+
+        let template = Template::parse(
+            "{{! header }}\n# {{title}} / {{{title}}} / {{& title}}\n{{feed.title}}\n\
+             {{#items}}\n  - {{name}}{{#url}} <{{url}}>{{/url}} in {{feed.title}}\n{{/items}}\n\
+             {{^empty}}\nnothing\n{{/empty}}\n{{#missing}}hidden{{/missing}}end\n",
+        )
+        .unwrap();
+        assert_eq!(
+            template.render(&data, false),
+            "# A & B / A & B / A & B\nExample\n  - one <https://example.com/1> in Example\n  \
+             - two in Example\nnothing\nend\n"
+        );
+        assert!(template
+            .render(&data, true)
+            .starts_with("# A &amp; B / A & B / A & B\n"));
+
+        // `{{.}}` と、文字列・数値の値
+        let template = Template::parse("{{#tags}}[{{.}}]{{/tags}} {{count}}").unwrap();
+        assert_eq!(
+            template.render(&json!({ "tags": ["x", "y"], "count": 2 }), false),
+            "[x][y] 2"
+        );
+
+        // 対応しないタグ
+        assert!(Template::parse("{{#a}}x").is_err());
+        assert!(Template::parse("{{#a}}x{{/b}}").is_err());
+        assert!(Template::parse("x{{/a}}").is_err());
+        assert!(Template::parse("{{title").is_err());
+    }
+
+    /// 相対指定と日付の `--since` を解釈できることを確認するテスト
+    #[test]
+    fn test_parse_since() {
+        let now = Utc::now();
+        assert_eq!(parse_since("1d", now).unwrap(), now - Duration::days(1));
+        assert_eq!(parse_since("12h", now).unwrap(), now - Duration::hours(12));
+        assert_eq!(parse_since("2w", now).unwrap(), now - Duration::weeks(2));
+        assert_eq!(
+            parse_since("30m", now).unwrap(),
+            now - Duration::minutes(30)
+        );
+        assert_eq!(
+            parse_since("2024-03-01", now).unwrap().to_rfc3339(),
+            "2024-03-01T00:00:00+00:00"
+        );
+        assert!(parse_since("1y", now).is_err());
+        assert!(parse_since("d", now).is_err());
+        assert!(parse_since("yesterday", now).is_err());
+    }
+
+    /// 記事がカテゴリ → フィードの順にまとめられ、抜粋が作られることを確認するテスト
+    #[test]
+    fn test_build_digest() {
+        let db = Database::from_connection(Connection::open_in_memory().unwrap()).unwrap();
+        db.init().unwrap();
+        db.create_category("Tech/Rust").unwrap();
+
+        let mut feed_ids = Vec::new();
+        for (url, title, category) in [
+            ("https://a.example.com/feed", "Zeta Blog", Some("Tech/Rust")),
+            (
+                "https://b.example.com/feed",
+                "alpha news",
+                Some("Tech/Rust"),
+            ),
+            ("https://c.example.com/feed", "Misc", None),
+        ] {
+            let id = db
+                .add_feed(&Feed::new(url.to_string(), title.to_string(), None))
+                .unwrap();
+            db.set_feed_category(id, category).unwrap();
+            feed_ids.push(id);
+        }
+
+        let now = Utc::now();
+        let long_body = format!("<p>{}</p>", "word ".repeat(100));
+        let articles = vec![
+            Article::new(
+                feed_ids[0],
+                "Zeta post".to_string(),
+                Some("https://a.example.com/1".to_string()),
+                Some("<p>Hello <b>world</b></p>".to_string()),
+                Some(now - Duration::hours(1)),
+            ),
+            Article::new(
+                feed_ids[1],
+                "Alpha post".to_string(),
+                Some("javascript:alert(1)".to_string()),
+                Some(long_body),
+                Some(now - Duration::hours(2)),
+            ),
+            Article::new(feed_ids[2], "Misc post".to_string(), None, None, Some(now)),
+            Article::new(
+                feed_ids[0],
+                "Old post".to_string(),
+                None,
+                None,
+                Some(now - Duration::days(3)),
+            ),
+        ];
+        db.add_articles(&articles).unwrap();
+
+        let query = ArticleQuery::new().published_after(now - Duration::days(1));
+        let digest = build_digest(&db, &query, "Weekly", "%Y-%m-%d").unwrap();
+        assert_eq!(digest.article_count, 3);
+        assert_eq!(digest.feed_count, 3);
+        assert_eq!(digest.article_ids().len(), 3);
+
+        let names: Vec<&str> = digest.categories.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Tech/Rust", "Uncategorized"]);
+        let tech = &digest.categories[0];
+        assert_eq!(tech.article_count, 2);
+        assert_eq!(tech.feeds[0].title, "alpha news");
+        assert_eq!(tech.feeds[1].title, "Zeta Blog");
+
+        let alpha = &tech.feeds[0].articles[0];
+        assert_eq!(alpha.url, None);
+        assert!(alpha.excerpt.ends_with("word…"));
+        assert!(alpha.excerpt.chars().count() <= EXCERPT_CHARS + 1);
+        assert_eq!(tech.feeds[1].articles[0].excerpt, "Hello world");
+
+        let markdown = digest
+            .render(
+                &load_default(DigestFormat::Markdown),
+                DigestFormat::Markdown,
+            )
+            .unwrap();
+        assert!(markdown.starts_with("# Weekly\n\n3 new article(s) from 3 feed(s) since "));
+        assert!(markdown.contains("\n## Tech/Rust\n\n### alpha news\n\n- Alpha post ("));
+        assert!(markdown.contains("- [Zeta post](https://a.example.com/1) ("));
+        assert!(markdown.contains("\n  Hello world\n"));
+        assert!(!markdown.contains("No new articles."));
+
+        let html = digest
+            .render(&load_default(DigestFormat::Html), DigestFormat::Html)
+            .unwrap();
+        assert!(html.contains("<a href=\"https://a.example.com/1\""));
+        assert!(!html.contains("javascript:"));
+
+        // 公開日時のない記事は、取得日時が期間内なら含まれる
+        let undated = Article::new(
+            feed_ids[2],
+            "Undated post".to_string(),
+            Some("https://c.example.com/undated".to_string()),
+            None,
+            None,
+        );
+        let undated_id = db.add_article(&undated).unwrap().unwrap();
+        let digest = build_digest(&db, &query, "Weekly", "%Y-%m-%d").unwrap();
+        assert_eq!(digest.article_count, 4);
+        assert!(digest.article_ids().contains(&undated_id));
+
+        let empty = build_digest(&db, &query.clone().keyword("nothing"), "Weekly", "%Y-%m-%d")
+            .unwrap()
+            .render(&load_default(DigestFormat::Text), DigestFormat::Text)
+            .unwrap();
+        assert!(empty.ends_with("\n\nNo new articles.\n"));
+    }
+
+    fn load_default(format: DigestFormat) -> Template {
+        Template::parse(format.default_template()).unwrap()
+    }
+}
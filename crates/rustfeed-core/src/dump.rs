@@ -0,0 +1,231 @@
+//! # ダンプモジュール
+//!
+//! データベース全体を JSON で書き出し・読み込みするための [`Dump`] を提供します。
+//!
+//! ## 概要
+//!
+//! `rustfeed export` は記事だけを書き出しますが、ダンプにはフィード（カスタム名・カテゴリ・
+//! 優先順位）、カテゴリ、記事とその状態（既読・お気に入り・タグ・メモ・ハイライト）、
+//! 「あとで読む」キュー、読書履歴が全て含まれます。別のマシンへの移行や、
+//! データベースが壊れたときの復旧に使います。
+//!
+//! `Database::dump` で書き出し、`Database::load_dump` で読み込みます。
+//! ダンプ内のIDは書き出し元のデータベースのもので、読み込み時に
+//! 読み込み先のIDに対応付け直されます（[`LoadMode`] を参照）。
+//!
+//! ## 形式のバージョン
+//!
+//! ダンプには [`DUMP_FORMAT_VERSION`] が記録されます。フィールドを追加するだけなら
+//! バージョンは変えず、意味が変わる変更をしたときだけ上げます。
+//! 新しいバージョンのダンプは読み込めません（[`read_dump`] がエラーを返します）。
+//!
+//! ## 使用例
+//!
+//! ```rust,no_run
+//! use rustfeed_core::{db::Database, dump::{self, LoadMode}};
+//!
+//! let db = Database::open()?;
+//!
+//! // 書き出す
+//! let file = std::fs::File::create("rustfeed.json")?;
+//! serde_json::to_writer_pretty(file, &db.dump()?)?;
+//!
+//! // 別のデータベースに統合する
+//! let other = Database::new_at("other.db".as_ref())?;
+//! other.init()?;
+//! let dump = dump::read_dump(std::fs::File::open("rustfeed.json")?)?;
+//! let report = other.load_dump(&dump, LoadMode::Merge)?;
+//! println!("{} new article(s)", report.articles_added);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+
+use crate::models::{Article, Feed, Highlight, HistoryEvent, Note};
+
+/// ダンプであることを示す `format` フィールドの値
+pub const DUMP_FORMAT: &str = "rustfeed-dump";
+
+/// ダンプの形式のバージョン
+pub const DUMP_FORMAT_VERSION: u32 = 1;
+
+// =============================================================================
+// ダンプの構造
+// =============================================================================
+
+/// データベース全体のダンプ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dump {
+    /// 常に [`DUMP_FORMAT`]
+    pub format: String,
+
+    /// 形式のバージョン（[`DUMP_FORMAT_VERSION`]）
+    pub version: u32,
+
+    /// 書き出した日時（UTC）
+    pub exported_at: DateTime<Utc>,
+
+    /// カテゴリのパス（木構造の順、例: "Tech/Rust"）
+    #[serde(default)]
+    pub categories: Vec<String>,
+
+    /// フィード（`category` はカテゴリのパス、`category_id` は使わない）
+    #[serde(default)]
+    pub feeds: Vec<Feed>,
+
+    /// 記事とその状態
+    #[serde(default)]
+    pub articles: Vec<DumpArticle>,
+
+    /// 「あとで読む」キュー（先頭から順に）
+    #[serde(default)]
+    pub queue: Vec<DumpQueueEntry>,
+
+    /// 読書履歴（古い順）
+    #[serde(default)]
+    pub history: Vec<DumpHistoryEntry>,
+}
+
+/// ダンプ内の記事
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DumpArticle {
+    /// 記事本体（`id` と `feed_id` は書き出し元のID）
+    #[serde(flatten)]
+    pub article: Article,
+
+    /// 付いているタグの名前
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// メモ
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<Note>,
+
+    /// ハイライト
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<Highlight>,
+}
+
+/// ダンプ内の「あとで読む」キューの項目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DumpQueueEntry {
+    /// 記事のID（書き出し元のID）
+    pub article_id: i64,
+
+    /// キューに追加した日時
+    pub added_at: DateTime<Utc>,
+}
+
+/// ダンプ内の読書履歴の項目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DumpHistoryEntry {
+    /// 記事のID（書き出し元のID）
+    pub article_id: i64,
+
+    /// 出来事の種類
+    pub event: HistoryEvent,
+
+    /// 出来事が起きた日時
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl Dump {
+    /// 空のダンプを作成する（`exported_at` は現在時刻）
+    pub fn new() -> Self {
+        Self {
+            format: DUMP_FORMAT.to_string(),
+            version: DUMP_FORMAT_VERSION,
+            exported_at: Utc::now(),
+            categories: Vec::new(),
+            feeds: Vec::new(),
+            articles: Vec::new(),
+            queue: Vec::new(),
+            history: Vec::new(),
+        }
+    }
+}
+
+impl Default for Dump {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ダンプの先頭部分（形式とバージョンだけを先に確認するため）
+#[derive(Deserialize)]
+struct DumpHeader {
+    format: Option<String>,
+    version: Option<u32>,
+}
+
+/// JSON のダンプを読み込む
+///
+/// 本体を解釈する前に `format` と `version` を確認するため、
+/// 新しい形式のダンプや rustfeed 以外の JSON は分かりやすいエラーになります。
+pub fn read_dump(mut reader: impl Read) -> Result<Dump> {
+    let mut json = String::new();
+    reader
+        .read_to_string(&mut json)
+        .context("Failed to read dump")?;
+
+    let header: DumpHeader = serde_json::from_str(&json).context("Dump is not valid JSON")?;
+    if header.format.as_deref() != Some(DUMP_FORMAT) {
+        anyhow::bail!(
+            "Not a rustfeed dump (expected \"format\": \"{}\")",
+            DUMP_FORMAT
+        );
+    }
+    match header.version {
+        Some(version) if version <= DUMP_FORMAT_VERSION => {}
+        Some(version) => anyhow::bail!(
+            "Dump format version {} is newer than supported ({}). Upgrade rustfeed to load it.",
+            version,
+            DUMP_FORMAT_VERSION
+        ),
+        None => anyhow::bail!("Dump has no format version"),
+    }
+
+    serde_json::from_str(&json).context("Failed to parse dump")
+}
+
+// =============================================================================
+// 読み込み
+// =============================================================================
+
+/// ダンプの読み込み方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadMode {
+    /// 既存のデータに統合する
+    ///
+    /// - カテゴリはパス、フィードはURLが同じものを同一とみなす
+    ///   （既存のフィードのカスタム名・カテゴリ・優先順位はそのまま）
+    /// - 記事は同じフィードで URL が同じもの（URL がない場合はタイトルと公開日時が同じもの）を
+    ///   同一とみなし、既読・お気に入り・タグ・ハイライトを足し合わせる
+    ///   （既存のメモは上書きしない）
+    Merge,
+
+    /// 既存のデータを全て削除してから読み込む
+    Replace,
+}
+
+/// ダンプの読み込み結果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LoadReport {
+    /// 新しく作成したカテゴリ数
+    pub categories_added: usize,
+
+    /// 新しく追加したフィード数
+    pub feeds_added: usize,
+
+    /// 既存のフィードに対応付けたフィード数
+    pub feeds_merged: usize,
+
+    /// 新しく追加した記事数
+    pub articles_added: usize,
+
+    /// 既存の記事に状態を統合した記事数
+    pub articles_merged: usize,
+}
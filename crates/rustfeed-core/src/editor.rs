@@ -0,0 +1,127 @@
+//! # 外部エディタ連携
+//!
+//! `$VISUAL` または `$EDITOR` で指定されたエディタでテキストを編集します。
+//! CLI（`rustfeed note`）と TUI（プレビュー画面）の両方から使用されます。
+//!
+//! ## 使用例
+//!
+//! ```rust,no_run
+//! use rustfeed_core::editor::edit_text;
+//!
+//! let edited = edit_text("既存のメモ", "note-1")?;
+//! println!("{}", edited);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use anyhow::{Context, Result};
+use std::collections::hash_map::RandomState;
+use std::fs::{File, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::SystemTime;
+
+/// 環境変数でエディタが指定されていない場合に使うエディタ
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+
+/// 一時ファイル名が既存のファイルと衝突した場合に作り直す回数
+const TEMP_FILE_ATTEMPTS: usize = 16;
+
+/// エディタでテキストを編集し、保存された内容を返す
+///
+/// 一時ファイルに `initial` を書き出してエディタで開き、
+/// エディタの終了後にファイルの内容を読み込みます。一時ファイルは削除されます。
+///
+/// 一時ファイルは推測されにくい名前で新規に作成し（既存のファイルやシンボリックリンクは開かない）、
+/// Unix では所有者だけが読み書きできる権限にします。
+///
+/// # 引数
+///
+/// * `initial` - エディタに表示する初期テキスト
+/// * `name` - 一時ファイル名に使う識別子（例: "note-42"）
+///
+/// # エラー
+///
+/// エディタを起動できなかった場合、またはエディタが失敗を返した場合はエラーを返します。
+pub fn edit_text(initial: &str, name: &str) -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| DEFAULT_EDITOR.to_string());
+
+    let (path, mut file) = create_temp_file(name)?;
+    let written = file
+        .write_all(initial.as_bytes())
+        .and_then(|()| file.sync_all())
+        .with_context(|| format!("Failed to write temporary file {:?}", path));
+    drop(file);
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&path);
+        return Err(e);
+    }
+
+    // エディタの指定には引数が含まれることがある（例: "code --wait"）
+    let mut parts = editor.split_whitespace();
+    let result = match parts.next() {
+        Some(program) => Command::new(program)
+            .args(parts)
+            .arg(&path)
+            .status()
+            .with_context(|| format!("Failed to launch editor '{}'", editor))
+            .and_then(|status| {
+                if !status.success() {
+                    anyhow::bail!("Editor '{}' exited with {}", editor, status);
+                }
+                std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read temporary file {:?}", path))
+            }),
+        None => Err(anyhow::anyhow!("Editor command is empty")),
+    };
+
+    // 成功・失敗にかかわらず一時ファイルを削除する
+    let _ = std::fs::remove_file(&path);
+
+    result
+}
+
+/// 一時ディレクトリにランダムな名前のファイルを新規作成する
+fn create_temp_file(name: &str) -> Result<(PathBuf, File)> {
+    for _ in 0..TEMP_FILE_ATTEMPTS {
+        let path =
+            std::env::temp_dir().join(format!("rustfeed-{}-{:016x}.md", name, random_suffix()));
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        match options.open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to create temporary file {:?}", path))
+            }
+        }
+    }
+    anyhow::bail!("Failed to create a unique temporary file for '{}'", name)
+}
+
+/// 一時ファイル名に使う乱数
+///
+/// `RandomState` はプロセスごとにランダムな鍵で初期化されるため、外部クレートなしで
+/// 推測されにくい値が得られる。呼び出すたびに異なる値になるよう時刻も混ぜる。
+fn random_suffix() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    if let Ok(elapsed) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+        hasher.write_u128(elapsed.as_nanos());
+    }
+    hasher.write_u32(std::process::id());
+    hasher.finish()
+}
@@ -0,0 +1,741 @@
+//! # メール送信モジュール
+//!
+//! ダイジェストをメールで送るための、最小限の SMTP クライアントを提供します。
+//!
+//! ## 概要
+//!
+//! - メッセージはテキストと HTML の `multipart/alternative` で組み立てる（本文は base64）
+//! - 接続は平文の SMTP で始め、設定（`starttls`）により STARTTLS で暗号化してから送る
+//! - 認証は AUTH PLAIN（なければ AUTH LOGIN）。暗号化していない接続でパスワードを送るのは
+//!   ローカルホストへの接続のみ許可する
+//!
+//! 送る前に、ローカルの SMTP シンク（Mailpit・MailHog など）に `starttls = false` で
+//! 送って確認できます。
+//!
+//! ## 使用例
+//!
+//! ```rust,no_run
+//! use rustfeed_core::config::AppConfig;
+//! use rustfeed_core::email::{send_email, Email};
+//!
+//! let config = AppConfig::load()?;
+//! let email = Email {
+//!     from: config.email.from.clone(),
+//!     to: config.email.to.clone(),
+//!     subject: "Weekly digest".to_string(),
+//!     text: "Hello".to_string(),
+//!     html: Some("<p>Hello</p>".to_string()),
+//! };
+//! send_email(&config.email, &email)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use native_tls::TlsConnector;
+
+use crate::config::EmailConfig;
+
+/// `[email]` に `password` がないときにパスワードを読む環境変数
+pub const PASSWORD_ENV: &str = "RUSTFEED_SMTP_PASSWORD";
+
+/// 接続と応答待ちのタイムアウト
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// base64 の本文の1行の長さ（RFC 2045 の上限は76文字）
+const BASE64_LINE_LEN: usize = 76;
+
+// =============================================================================
+// メッセージ
+// =============================================================================
+
+/// 送信するメール
+#[derive(Debug, Clone)]
+pub struct Email {
+    /// 差出人（`Name <addr@example.com>` 形式も可）
+    pub from: String,
+
+    /// 宛先
+    pub to: Vec<String>,
+
+    /// 件名
+    pub subject: String,
+
+    /// テキストの本文
+    pub text: String,
+
+    /// HTML の本文（ある場合はテキストと合わせて `multipart/alternative` にする）
+    pub html: Option<String>,
+}
+
+impl Email {
+    /// RFC 5322 のメッセージにする（改行は CRLF）
+    ///
+    /// # エラー
+    ///
+    /// 宛先がない場合、アドレスが不正な場合、ヘッダーに改行が含まれる場合はエラーを返します。
+    pub fn to_message(&self, date: DateTime<Utc>) -> Result<String> {
+        if self.to.is_empty() {
+            bail!("The email has no recipients");
+        }
+        for header in std::iter::once(&self.from)
+            .chain(&self.to)
+            .chain(std::iter::once(&self.subject))
+        {
+            if header.contains(['\r', '\n']) {
+                bail!("Email headers must not contain line breaks: {:?}", header);
+            }
+        }
+        let from = address(&self.from)?;
+        for to in &self.to {
+            address(to)?;
+        }
+
+        let id = format!(
+            "{:x}.{:x}",
+            date.timestamp_nanos_opt().unwrap_or_default(),
+            std::process::id()
+        );
+        let domain = from.rsplit('@').next().unwrap_or("localhost");
+
+        let mut message = String::new();
+        push_header(&mut message, "Date", &date.to_rfc2822());
+        push_header(&mut message, "From", &encode_mailbox(&self.from));
+        let to: Vec<String> = self.to.iter().map(|to| encode_mailbox(to)).collect();
+        push_header(&mut message, "To", &to.join(", "));
+        push_header(&mut message, "Subject", &encode_word(&self.subject));
+        push_header(&mut message, "Message-ID", &format!("<{}@{}>", id, domain));
+        push_header(&mut message, "MIME-Version", "1.0");
+
+        match &self.html {
+            Some(html) => {
+                // base64 の行には `-` が現れないため、境界が本文と衝突することはない
+                let boundary = format!("rustfeed-{}", id);
+                push_header(
+                    &mut message,
+                    "Content-Type",
+                    &format!("multipart/alternative; boundary=\"{}\"", boundary),
+                );
+                message.push_str("\r\n");
+                for (subtype, body) in [("plain", &self.text), ("html", html)] {
+                    message.push_str(&format!("--{}\r\n", boundary));
+                    push_part(&mut message, subtype, body);
+                }
+                message.push_str(&format!("--{}--\r\n", boundary));
+            }
+            None => push_part(&mut message, "plain", &self.text),
+        }
+
+        Ok(message)
+    }
+}
+
+/// ヘッダーを1行加える
+fn push_header(message: &mut String, name: &str, value: &str) {
+    message.push_str(name);
+    message.push_str(": ");
+    message.push_str(value);
+    message.push_str("\r\n");
+}
+
+/// `text/<subtype>` の本文（ヘッダーと base64 の本文）を加える
+fn push_part(message: &mut String, subtype: &str, body: &str) {
+    push_header(
+        message,
+        "Content-Type",
+        &format!("text/{}; charset=utf-8", subtype),
+    );
+    push_header(message, "Content-Transfer-Encoding", "base64");
+    message.push_str("\r\n");
+
+    // テキストの改行は CRLF にしてから符号化する（RFC 2046 の正規形）
+    let body = body.replace("\r\n", "\n").replace('\n', "\r\n");
+    let encoded = BASE64.encode(body);
+    for chunk in encoded.as_bytes().chunks(BASE64_LINE_LEN) {
+        // base64 は ASCII のみなので、どこで区切っても文字列として正しい
+        message.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        message.push_str("\r\n");
+    }
+}
+
+/// `Name <addr@example.com>` または `addr@example.com` からアドレスを取り出す
+fn address(mailbox: &str) -> Result<&str> {
+    let mailbox = mailbox.trim();
+    let address = match (mailbox.rfind('<'), mailbox.strip_suffix('>')) {
+        (Some(start), Some(rest)) => &rest[start + 1..],
+        _ => mailbox,
+    };
+    let valid = address
+        .split_once('@')
+        .is_some_and(|(local, domain)| !local.is_empty() && !domain.is_empty())
+        && !address.contains(|c: char| c.is_whitespace() || c == '<' || c == '>');
+    if !valid {
+        bail!("Invalid email address: '{}'", mailbox);
+    }
+    Ok(address)
+}
+
+/// 表示名が ASCII 以外を含む場合は RFC 2047 で符号化する
+///
+/// ASCII の表示名が RFC 5322 の特殊文字（`,` や `@` など）を含む場合は、
+/// 引用符で囲んで1つの表示名として扱われるようにします。
+fn encode_mailbox(mailbox: &str) -> String {
+    let mailbox = mailbox.trim();
+    match mailbox.rfind('<') {
+        Some(start) if start > 0 => {
+            let name = mailbox[..start].trim();
+            let name = match name
+                .strip_prefix('"')
+                .and_then(|name| name.strip_suffix('"'))
+            {
+                // 引用符で囲まれた表示名は `\` によるエスケープを戻す
+                Some(quoted) => {
+                    let mut unescaped = String::new();
+                    let mut chars = quoted.chars();
+                    while let Some(c) = chars.next() {
+                        unescaped.push(if c == '\\' {
+                            chars.next().unwrap_or(c)
+                        } else {
+                            c
+                        });
+                    }
+                    unescaped
+                }
+                None => name.to_string(),
+            };
+            format!("{} {}", encode_display_name(&name), &mailbox[start..])
+        }
+        _ => mailbox.to_string(),
+    }
+}
+
+/// 表示名をヘッダーに書ける形にする
+fn encode_display_name(name: &str) -> String {
+    const SPECIALS: &[char] = &[
+        '(', ')', '<', '>', '[', ']', ':', ';', '@', '\\', ',', '.', '"',
+    ];
+
+    if name.is_ascii() && name.contains(SPECIALS) {
+        format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        encode_word(name)
+    }
+}
+
+/// ASCII 以外を含むヘッダーの値を RFC 2047 の encoded-word（UTF-8, B）にする
+///
+/// 1つの encoded-word は75文字までのため、長い値は複数に分けて折り返します。
+fn encode_word(text: &str) -> String {
+    if text.is_ascii() {
+        return text.to_string();
+    }
+
+    // 45バイトを base64 にすると60文字で、`=?UTF-8?B?` と `?=` を加えて72文字になる
+    let mut words = Vec::new();
+    let mut chunk = String::new();
+    for c in text.chars() {
+        if chunk.len() + c.len_utf8() > 45 {
+            words.push(format!("=?UTF-8?B?{}?=", BASE64.encode(&chunk)));
+            chunk.clear();
+        }
+        chunk.push(c);
+    }
+    if !chunk.is_empty() {
+        words.push(format!("=?UTF-8?B?{}?=", BASE64.encode(&chunk)));
+    }
+    words.join("\r\n ")
+}
+
+// =============================================================================
+// SMTP
+// =============================================================================
+
+/// メールを送る
+///
+/// `config.starttls` が true ならサーバーが STARTTLS に対応していることを要求し、
+/// 暗号化してから認証・送信します。
+///
+/// # エラー
+///
+/// サーバーが設定されていない場合、接続・認証に失敗した場合、
+/// サーバーが差出人や宛先を拒否した場合はエラーを返します。
+pub fn send_email(config: &EmailConfig, email: &Email) -> Result<()> {
+    if config.host.trim().is_empty() {
+        bail!("No SMTP server is configured. Set host in the [email] section of config.toml.");
+    }
+    let message = email.to_message(Utc::now())?;
+    let credentials = match &config.username {
+        Some(username) => {
+            let password = config
+                .password
+                .clone()
+                .or_else(|| std::env::var(PASSWORD_ENV).ok())
+                .with_context(|| {
+                    format!(
+                        "Set password in the [email] section or the {} environment variable",
+                        PASSWORD_ENV
+                    )
+                })?;
+            Some((username.clone(), password))
+        }
+        None => None,
+    };
+
+    let stream = connect(&config.host, config.port)?;
+    let peer = stream.peer_addr()?;
+    let helo = match stream.local_addr()?.ip() {
+        IpAddr::V4(ip) => format!("[{}]", ip),
+        IpAddr::V6(ip) => format!("[IPv6:{}]", ip),
+    };
+    let mut smtp = SmtpConnection::new(stream);
+    smtp.expect("greeting", &[220])?;
+    let capabilities = smtp.ehlo(&helo)?;
+
+    if config.starttls {
+        if !has_capability(&capabilities, "STARTTLS") {
+            bail!(
+                "{} does not support STARTTLS. Set starttls = false to send without encryption.",
+                config.host
+            );
+        }
+        smtp.command("STARTTLS", "STARTTLS", &[220])?;
+        let tls = TlsConnector::new()?
+            .connect(&config.host, smtp.into_inner())
+            .map_err(|e| anyhow!("TLS handshake with {} failed: {}", config.host, e))?;
+
+        // 暗号化した後は、改めて EHLO で対応している機能を確かめる
+        let mut smtp = SmtpConnection::new(tls);
+        let capabilities = smtp.ehlo(&helo)?;
+        deliver(
+            &mut smtp,
+            &capabilities,
+            credentials.as_ref(),
+            email,
+            &message,
+        )
+    } else {
+        if credentials.is_some() && !peer.ip().is_loopback() {
+            bail!(
+                "Refusing to send the SMTP password to {} without encryption. Set starttls = true.",
+                config.host
+            );
+        }
+        deliver(
+            &mut smtp,
+            &capabilities,
+            credentials.as_ref(),
+            email,
+            &message,
+        )
+    }
+}
+
+/// サーバーに接続する（名前解決で得たアドレスを順に試す）
+fn connect(host: &str, port: u16) -> Result<TcpStream> {
+    let addresses: Vec<SocketAddr> = (host, port)
+        .to_socket_addrs()
+        .with_context(|| format!("Failed to resolve {}", host))?
+        .collect();
+
+    let mut last_error = None;
+    for address in addresses {
+        match TcpStream::connect_timeout(&address, SMTP_TIMEOUT) {
+            Ok(stream) => {
+                stream.set_read_timeout(Some(SMTP_TIMEOUT))?;
+                stream.set_write_timeout(Some(SMTP_TIMEOUT))?;
+                return Ok(stream);
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+    match last_error {
+        Some(e) => Err(e).with_context(|| format!("Failed to connect to {}:{}", host, port)),
+        None => bail!("No address found for {}", host),
+    }
+}
+
+/// 認証してからメッセージを送り、接続を閉じる
+fn deliver<S: Read + Write>(
+    smtp: &mut SmtpConnection<S>,
+    capabilities: &[String],
+    credentials: Option<&(String, String)>,
+    email: &Email,
+    message: &str,
+) -> Result<()> {
+    if let Some((username, password)) = credentials {
+        authenticate(smtp, capabilities, username, password)?;
+    }
+
+    smtp.command(
+        "MAIL FROM",
+        &format!("MAIL FROM:<{}>", address(&email.from)?),
+        &[250],
+    )?;
+    for to in &email.to {
+        smtp.command(
+            "RCPT TO",
+            &format!("RCPT TO:<{}>", address(to)?),
+            &[250, 251],
+        )?;
+    }
+    smtp.command("DATA", "DATA", &[354])?;
+    smtp.send_data(message)?;
+    smtp.expect("DATA", &[250])?;
+
+    // メッセージは受け付けられているため、QUIT の失敗は無視する
+    let _ = smtp.command("QUIT", "QUIT", &[221]);
+    Ok(())
+}
+
+/// AUTH PLAIN（なければ AUTH LOGIN）で認証する
+fn authenticate<S: Read + Write>(
+    smtp: &mut SmtpConnection<S>,
+    capabilities: &[String],
+    username: &str,
+    password: &str,
+) -> Result<()> {
+    let mechanisms: Vec<String> = capabilities
+        .iter()
+        .filter_map(|line| {
+            let (keyword, rest) = line.split_once(' ')?;
+            keyword
+                .eq_ignore_ascii_case("AUTH")
+                .then(|| rest.to_ascii_uppercase())
+        })
+        .flat_map(|rest| {
+            rest.split_whitespace()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    if mechanisms.iter().any(|m| m == "PLAIN") {
+        let token = BASE64.encode(format!("\0{}\0{}", username, password));
+        smtp.command("AUTH PLAIN", &format!("AUTH PLAIN {}", token), &[235])?;
+    } else if mechanisms.iter().any(|m| m == "LOGIN") {
+        smtp.command("AUTH LOGIN", "AUTH LOGIN", &[334])?;
+        smtp.command("AUTH LOGIN", &BASE64.encode(username), &[334])?;
+        smtp.command("AUTH LOGIN", &BASE64.encode(password), &[235])?;
+    } else {
+        bail!("The SMTP server does not offer AUTH PLAIN or AUTH LOGIN");
+    }
+    Ok(())
+}
+
+/// EHLO の応答に機能があるか
+fn has_capability(capabilities: &[String], name: &str) -> bool {
+    capabilities.iter().any(|line| {
+        line.split_whitespace()
+            .next()
+            .is_some_and(|keyword| keyword.eq_ignore_ascii_case(name))
+    })
+}
+
+/// SMTP サーバーの応答
+struct Reply {
+    /// 応答コード
+    code: u16,
+
+    /// 応答の文（複数行の応答は行ごと）
+    lines: Vec<String>,
+}
+
+/// SMTP の接続（平文の TCP か、STARTTLS 後の TLS）
+struct SmtpConnection<S: Read + Write> {
+    stream: BufReader<S>,
+}
+
+impl<S: Read + Write> SmtpConnection<S> {
+    fn new(stream: S) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+        }
+    }
+
+    /// 下の接続を取り出す（STARTTLS の応答の後なので、読み残しはない）
+    fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+
+    /// 1行送って応答を確かめる
+    ///
+    /// `label` はエラーメッセージに使う名前です（認証情報を含む行そのものは出さない）。
+    fn command(&mut self, label: &str, line: &str, expected: &[u16]) -> Result<Reply> {
+        let stream = self.stream.get_mut();
+        stream.write_all(line.as_bytes())?;
+        stream.write_all(b"\r\n")?;
+        stream.flush()?;
+        self.expect(label, expected)
+    }
+
+    /// EHLO を送り、対応している機能（応答の2行目以降）を返す
+    fn ehlo(&mut self, name: &str) -> Result<Vec<String>> {
+        let reply = self.command("EHLO", &format!("EHLO {}", name), &[250])?;
+        Ok(reply.lines.into_iter().skip(1).collect())
+    }
+
+    /// メッセージを送る（`.` で始まる行は `..` にし、`.` だけの行で終える）
+    fn send_data(&mut self, message: &str) -> Result<()> {
+        let stream = self.stream.get_mut();
+        for line in message.lines() {
+            if line.starts_with('.') {
+                stream.write_all(b".")?;
+            }
+            stream.write_all(line.as_bytes())?;
+            stream.write_all(b"\r\n")?;
+        }
+        stream.write_all(b".\r\n")?;
+        stream.flush()?;
+        Ok(())
+    }
+
+    /// 応答を読み、コードが期待したものか確かめる
+    fn expect(&mut self, label: &str, expected: &[u16]) -> Result<Reply> {
+        let reply = self.read_reply()?;
+        if !expected.contains(&reply.code) {
+            bail!(
+                "SMTP {} failed: {} {}",
+                label,
+                reply.code,
+                reply.lines.join(" ")
+            );
+        }
+        Ok(reply)
+    }
+
+    /// 応答を読む（`250-...` のような複数行の応答は最後の行までまとめる）
+    fn read_reply(&mut self) -> Result<Reply> {
+        let mut lines = Vec::new();
+        loop {
+            let mut line = String::new();
+            if self
+                .stream
+                .read_line(&mut line)
+                .context("Failed to read from the SMTP server")?
+                == 0
+            {
+                bail!("The SMTP server closed the connection");
+            }
+            let line = line.trim_end_matches(['\r', '\n']);
+            let code = line
+                .get(..3)
+                .and_then(|code| code.parse::<u16>().ok())
+                .with_context(|| format!("Unexpected SMTP reply: {}", line))?;
+            lines.push(line.get(4..).unwrap_or_default().to_string());
+            if line.as_bytes().get(3) != Some(&b'-') {
+                return Ok(Reply { code, lines });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// テスト用の SMTP シンク
+    ///
+    /// 1つの接続を受け付け、受け取ったコマンド（本文は `DATA ` を付けた行）を返します。
+    /// `reject` を含む RCPT TO は 550 で拒否します。
+    fn spawn_sink(reject: Option<&'static str>) -> (u16, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+            writer.write_all(b"220 sink ESMTP\r\n").unwrap();
+
+            let mut log = Vec::new();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                    break;
+                }
+                let line = line.trim_end_matches(['\r', '\n']).to_string();
+                if in_data {
+                    if line == "." {
+                        in_data = false;
+                        writer.write_all(b"250 2.0.0 queued\r\n").unwrap();
+                    } else {
+                        log.push(format!("DATA {}", line));
+                    }
+                    continue;
+                }
+
+                log.push(line.clone());
+                let reply: &[u8] = if line.starts_with("EHLO ") {
+                    b"250-sink greets you\r\n250-AUTH LOGIN PLAIN\r\n250 8BITMIME\r\n"
+                } else if line.starts_with("AUTH ") {
+                    b"235 2.7.0 accepted\r\n"
+                } else if line.starts_with("RCPT ") && reject.is_some_and(|r| line.contains(r)) {
+                    b"550 5.1.1 no such user\r\n"
+                } else if line == "DATA" {
+                    in_data = true;
+                    b"354 end with .\r\n"
+                } else if line == "QUIT" {
+                    writer.write_all(b"221 bye\r\n").unwrap();
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                writer.write_all(reply).unwrap();
+            }
+            log
+        });
+        (port, handle)
+    }
+
+    fn sink_config(port: u16) -> EmailConfig {
+        EmailConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            starttls: false,
+            username: Some("me".to_string()),
+            password: Some("secret".to_string()),
+            from: "rustfeed <me@example.com>".to_string(),
+            to: vec![
+                "a@example.com".to_string(),
+                "Bob <b@example.com>".to_string(),
+            ],
+        }
+    }
+
+    fn sample_email(config: &EmailConfig) -> Email {
+        Email {
+            from: config.from.clone(),
+            to: config.to.clone(),
+            subject: "Weekly digest – 今週の記事".to_string(),
+            text: "Hello\n.leading dot\n".to_string(),
+            html: Some("<p>Hello</p>".to_string()),
+        }
+    }
+
+    /// ヘッダーの符号化と、テキスト・HTML の multipart/alternative を確認するテスト
+    #[test]
+    fn test_to_message() {
+        let config = sink_config(25);
+        let message = sample_email(&config).to_message(Utc::now()).unwrap();
+
+        assert!(message.contains("\r\nFrom: rustfeed <me@example.com>\r\n"));
+        assert!(message.contains("\r\nTo: a@example.com, Bob <b@example.com>\r\n"));
+        assert!(message.contains("\r\nSubject: =?UTF-8?B?"));
+        assert!(message.contains("@example.com>\r\nMIME-Version: 1.0\r\n"));
+        assert!(message.contains("Content-Type: multipart/alternative; boundary=\"rustfeed-"));
+        assert!(!message.replace("\r\n", "").contains('\n'));
+
+        // 本文を取り出して復号する
+        let bodies: Vec<String> = message
+            .split("Content-Transfer-Encoding: base64\r\n\r\n")
+            .skip(1)
+            .map(|part| {
+                let encoded: String = part
+                    .lines()
+                    .take_while(|line| !line.starts_with("--"))
+                    .collect();
+                String::from_utf8(BASE64.decode(encoded).unwrap()).unwrap()
+            })
+            .collect();
+        assert_eq!(bodies, ["Hello\r\n.leading dot\r\n", "<p>Hello</p>"]);
+
+        // 件名の encoded-word を戻すと元の件名になる
+        let subject = message
+            .lines()
+            .skip_while(|line| !line.starts_with("Subject: "))
+            .take_while(|line| line.starts_with("Subject: ") || line.starts_with(' '))
+            .map(|line| {
+                let word = line.trim_start_matches("Subject: ").trim();
+                let encoded = word.trim_start_matches("=?UTF-8?B?").trim_end_matches("?=");
+                String::from_utf8(BASE64.decode(encoded).unwrap()).unwrap()
+            })
+            .collect::<String>();
+        assert_eq!(subject, "Weekly digest – 今週の記事");
+
+        // 特殊文字を含む表示名は引用符で囲む（引用符で囲まれた表示名もそのまま保つ）
+        let mut email = sample_email(&config);
+        email.to = vec![
+            "\"Doe, John\" <j@example.com>".to_string(),
+            "Jane Q. Public <jane@example.com>".to_string(),
+            "\"Say \\\"hi\\\"\" <s@example.com>".to_string(),
+        ];
+        let message = email.to_message(Utc::now()).unwrap();
+        assert!(
+            message.contains(
+                "\r\nTo: \"Doe, John\" <j@example.com>, \"Jane Q. Public\" <jane@example.com>, \
+                 \"Say \\\"hi\\\"\" <s@example.com>\r\n"
+            ),
+            "{}",
+            message
+        );
+
+        // ヘッダーへの改行の混入と不正なアドレスは拒否する
+        let mut email = sample_email(&config);
+        email.subject = "Hi\r\nBcc: x@example.com".to_string();
+        assert!(email.to_message(Utc::now()).is_err());
+        let mut email = sample_email(&config);
+        email.to = vec!["not an address".to_string()];
+        assert!(email.to_message(Utc::now()).is_err());
+    }
+
+    /// ローカルの SMTP シンクに、認証してから送れることを確認するテスト
+    #[test]
+    fn test_send_email_to_sink() {
+        let (port, sink) = spawn_sink(None);
+        let config = sink_config(port);
+        send_email(&config, &sample_email(&config)).unwrap();
+        let log = sink.join().unwrap();
+
+        assert!(log[0].starts_with("EHLO [127.0.0.1]"));
+        let token = log[1].strip_prefix("AUTH PLAIN ").unwrap();
+        assert_eq!(BASE64.decode(token).unwrap(), b"\0me\0secret");
+        assert_eq!(log[2], "MAIL FROM:<me@example.com>");
+        assert_eq!(log[3], "RCPT TO:<a@example.com>");
+        assert_eq!(log[4], "RCPT TO:<b@example.com>");
+        assert_eq!(log[5], "DATA");
+        assert!(log.contains(&"DATA MIME-Version: 1.0".to_string()));
+        assert_eq!(log.last().unwrap(), "QUIT");
+    }
+
+    /// 宛先の拒否や、暗号化されていない接続での STARTTLS 要求がエラーになることを確認するテスト
+    #[test]
+    fn test_send_email_errors() {
+        let (port, sink) = spawn_sink(Some("b@example.com"));
+        let config = sink_config(port);
+        let error = send_email(&config, &sample_email(&config)).unwrap_err();
+        assert!(
+            error.to_string().contains("RCPT TO failed: 550"),
+            "{}",
+            error
+        );
+        sink.join().unwrap();
+
+        // シンクは STARTTLS に対応していない
+        let (port, sink) = spawn_sink(None);
+        let config = EmailConfig {
+            starttls: true,
+            ..sink_config(port)
+        };
+        let error = send_email(&config, &sample_email(&config)).unwrap_err();
+        assert!(
+            error.to_string().contains("does not support STARTTLS"),
+            "{}",
+            error
+        );
+        sink.join().unwrap();
+
+        let config = EmailConfig {
+            host: String::new(),
+            ..sink_config(port)
+        };
+        assert!(send_email(&config, &sample_email(&config)).is_err());
+    }
+}
@@ -0,0 +1,1075 @@
+//! # エクスポートモジュール
+//!
+//! 記事を JSON・Markdown・CSV・HTML・EPUB で書き出す機能を提供します。
+//!
+//! ## 概要
+//!
+//! 書き出しは [`Exporter`] トレイトで抽象化されています。記事を1件ずつ
+//! [`Exporter::write_article`] に渡し、最後に [`Exporter::finish`] を呼ぶと
+//! 書き出しが完了します。記事を全件メモリに載せなくても書き出せるため、
+//! 大量の記事も扱えます。
+//!
+//! | 形式 | 内容 |
+//! |------|------|
+//! | `json` | 記事の配列（記事のフィールドにタグ・メモ・ハイライトを加えたもの） |
+//! | `markdown` | 人が読むための Markdown |
+//! | `csv` | 表計算ソフト向けの CSV（RFC 4180） |
+//! | `html` | 本文を無害化した、単体で開ける HTML |
+//! | `epub` | 記事ごとに1章、目次付きの EPUB 3 |
+//!
+//! 新しい形式を追加するときは、[`Exporter`] を実装して [`ExportFormat`] と
+//! [`new_exporter`] に加えます。
+//!
+//! [`export_query`] は [`ArticleQuery`] に一致する記事を [`EXPORT_PAGE_SIZE`] 件ずつ
+//! 取得しながら書き出すため、記事が何万件あってもメモリ使用量は一定です。
+//!
+//! ## 使用例
+//!
+//! ```rust,no_run
+//! use rustfeed_core::db::Database;
+//! use rustfeed_core::export::{export_query, new_exporter, ExportFormat};
+//! use rustfeed_core::query::ArticleQuery;
+//!
+//! let db = Database::new()?;
+//! let file = std::fs::File::create("articles.epub")?;
+//! let exporter = new_exporter(ExportFormat::Epub, Box::new(file), "My articles");
+//! let count = export_query(&db, &ArticleQuery::new().favorites_only(), exporter)?;
+//! println!("Exported {} articles", count);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::Serialize;
+
+use crate::db::Database;
+use crate::html::{escape_html, is_safe_url, sanitize_html};
+use crate::models::{Article, Highlight};
+use crate::query::ArticleQuery;
+use crate::storage::Storage;
+
+/// [`export_query`] が1度に取得する記事の数
+pub const EXPORT_PAGE_SIZE: usize = 500;
+
+// =============================================================================
+// 書き出す記事
+// =============================================================================
+
+/// 書き出す記事（記事のフィールドにフィード名・タグ・メモ・ハイライトを加えたもの）
+///
+/// JSON では記事のフィールドと同じ階層に `feed_title`・`tags`・`note`・`highlights` が並びます。
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportArticle {
+    /// 記事
+    #[serde(flatten)]
+    pub article: Article,
+
+    /// 記事が属するフィードの表示名（カスタム名、なければタイトル）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed_title: Option<String>,
+
+    /// 記事に付けられたタグ
+    pub tags: Vec<String>,
+
+    /// 記事のメモ
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+
+    /// 記事のハイライト
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<Highlight>,
+}
+
+/// 記事にフィード名・タグ・メモ・ハイライトを加える
+///
+/// タグ・メモ・ハイライトは記事IDでまとめて取得します。
+pub fn annotate(db: &Database, articles: Vec<Article>) -> Result<Vec<ExportArticle>> {
+    annotate_with(db, articles, &feed_titles(db)?)
+}
+
+/// 条件に一致する記事を取得しながら書き出す
+///
+/// 記事は [`EXPORT_PAGE_SIZE`] 件ずつキーセットページネーションで取得するため、
+/// 全件をメモリに載せることはありません。`query.limit` があればその件数で止めます
+/// （`query.offset` は使いません）。
+///
+/// # 戻り値
+///
+/// 書き出した記事の数
+pub fn export_query(
+    db: &Database,
+    query: &ArticleQuery,
+    mut exporter: Box<dyn Exporter>,
+) -> Result<usize> {
+    let query = ArticleQuery {
+        offset: 0,
+        ..query.clone()
+    };
+    let limit = query.limit.unwrap_or(usize::MAX);
+    let feed_titles = feed_titles(db)?;
+
+    exporter.begin(Some(db.count_articles(&query)?))?;
+    let mut written = 0;
+    let mut cursor: Option<String> = None;
+    while written < limit {
+        let page_size = EXPORT_PAGE_SIZE.min(limit - written);
+        let page = db.query_articles_page(&query, cursor.as_deref(), page_size)?;
+        for article in annotate_with(db, page.articles, &feed_titles)? {
+            exporter.write_article(&article)?;
+            written += 1;
+        }
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    exporter.finish()?;
+
+    Ok(written)
+}
+
+/// フィードIDから表示名（カスタム名、なければタイトル）へのマップ
+fn feed_titles(db: &Database) -> Result<HashMap<i64, String>> {
+    Ok(db
+        .get_feeds(None)?
+        .into_iter()
+        .map(|feed| (feed.id, feed.custom_name.unwrap_or(feed.title)))
+        .collect())
+}
+
+/// 記事にフィード名・タグ・メモ・ハイライトを加える（フィード名のマップを使い回す）
+fn annotate_with(
+    db: &Database,
+    articles: Vec<Article>,
+    feed_titles: &HashMap<i64, String>,
+) -> Result<Vec<ExportArticle>> {
+    let ids: Vec<i64> = articles.iter().map(|a| a.id).collect();
+    let mut tags = db.get_tags_for_articles(&ids)?;
+    let mut notes = db.get_notes_for_articles(&ids)?;
+    let mut highlights = db.get_highlights_for_articles(&ids)?;
+
+    Ok(articles
+        .into_iter()
+        .map(|article| ExportArticle {
+            feed_title: feed_titles.get(&article.feed_id).cloned(),
+            tags: tags.remove(&article.id).unwrap_or_default(),
+            note: notes.remove(&article.id).map(|note| note.body),
+            highlights: highlights.remove(&article.id).unwrap_or_default(),
+            article,
+        })
+        .collect())
+}
+
+// =============================================================================
+// 形式とトレイト
+// =============================================================================
+
+/// 書き出しの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// JSON（記事の配列）
+    Json,
+    /// Markdown
+    Markdown,
+    /// CSV（RFC 4180）
+    Csv,
+    /// 単体で開ける HTML
+    Html,
+    /// EPUB 3
+    Epub,
+}
+
+impl ExportFormat {
+    /// 出力がバイナリ（端末に出力すべきでない）かどうか
+    pub fn is_binary(self) -> bool {
+        matches!(self, ExportFormat::Epub)
+    }
+
+    /// 出力ファイルの一般的な拡張子
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Html => "html",
+            ExportFormat::Epub => "epub",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(ExportFormat::Json),
+            "markdown" | "md" => Ok(ExportFormat::Markdown),
+            "csv" => Ok(ExportFormat::Csv),
+            "html" => Ok(ExportFormat::Html),
+            "epub" => Ok(ExportFormat::Epub),
+            _ => bail!(
+                "Unsupported format: '{}'. Use json, markdown, csv, html or epub.",
+                s
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "markdown",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Html => "html",
+            ExportFormat::Epub => "epub",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 記事を1件ずつ書き出すトレイト
+///
+/// `begin` → `write_article`（記事の数だけ）→ `finish` の順に呼びます。
+/// `finish` を呼ばないと出力が不完全になる形式があります（JSON の `]`、EPUB の目次など）。
+pub trait Exporter {
+    /// 書き出しを始める
+    ///
+    /// `count` は書き出す記事の数です（分からなければ None）。
+    fn begin(&mut self, count: Option<usize>) -> Result<()>;
+
+    /// 記事を1件書き出す
+    fn write_article(&mut self, article: &ExportArticle) -> Result<()>;
+
+    /// 書き出しを終える（出力をフラッシュする）
+    fn finish(self: Box<Self>) -> Result<()>;
+}
+
+/// 形式に応じた [`Exporter`] を作る
+///
+/// # 引数
+///
+/// * `format` - 書き出しの形式
+/// * `out` - 出力先
+/// * `title` - 文書のタイトル（HTML・EPUB・Markdown で使用）
+pub fn new_exporter(format: ExportFormat, out: Box<dyn Write>, title: &str) -> Box<dyn Exporter> {
+    match format {
+        ExportFormat::Json => Box::new(JsonExporter { out, count: 0 }),
+        ExportFormat::Markdown => Box::new(MarkdownExporter {
+            out,
+            title: title.to_string(),
+            count: 0,
+        }),
+        ExportFormat::Csv => Box::new(CsvExporter { out }),
+        ExportFormat::Html => Box::new(HtmlExporter {
+            out,
+            title: title.to_string(),
+        }),
+        ExportFormat::Epub => Box::new(EpubExporter::new(out, title)),
+    }
+}
+
+// =============================================================================
+// JSON
+// =============================================================================
+
+/// JSON（記事の配列）で書き出す
+struct JsonExporter {
+    out: Box<dyn Write>,
+    count: usize,
+}
+
+impl Exporter for JsonExporter {
+    fn begin(&mut self, _count: Option<usize>) -> Result<()> {
+        self.out.write_all(b"[")?;
+        Ok(())
+    }
+
+    fn write_article(&mut self, article: &ExportArticle) -> Result<()> {
+        let json =
+            serde_json::to_string_pretty(article).context("Failed to serialize article to JSON")?;
+        self.out
+            .write_all(if self.count == 0 { b"\n" } else { b",\n" })?;
+        // 配列の要素として2文字字下げする
+        for (index, line) in json.lines().enumerate() {
+            if index > 0 {
+                self.out.write_all(b"\n")?;
+            }
+            write!(self.out, "  {}", line)?;
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.out
+            .write_all(if self.count == 0 { b"]\n" } else { b"\n]\n" })?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Markdown
+// =============================================================================
+
+/// Markdown で書き出す
+struct MarkdownExporter {
+    out: Box<dyn Write>,
+    title: String,
+    count: usize,
+}
+
+impl Exporter for MarkdownExporter {
+    fn begin(&mut self, count: Option<usize>) -> Result<()> {
+        writeln!(self.out, "# {}\n", self.title)?;
+        if let Some(count) = count {
+            writeln!(self.out, "Total: {} articles\n", count)?;
+        }
+        writeln!(self.out, "---\n")?;
+        Ok(())
+    }
+
+    fn write_article(&mut self, entry: &ExportArticle) -> Result<()> {
+        let article = &entry.article;
+        if self.count > 0 {
+            writeln!(self.out, "---\n")?;
+        }
+        self.count += 1;
+
+        writeln!(self.out, "## {}. {}\n", self.count, article.title)?;
+        if let Some(ref url) = article.url {
+            writeln!(self.out, "**URL:** {}\n", url)?;
+        }
+        if let Some(published_at) = article.published_at {
+            writeln!(
+                self.out,
+                "**Published:** {}\n",
+                published_at.format("%Y-%m-%d %H:%M:%S")
+            )?;
+        }
+        writeln!(
+            self.out,
+            "**Read:** {}\n",
+            if article.is_read { "Yes" } else { "No" }
+        )?;
+        writeln!(
+            self.out,
+            "**Favorite:** {}\n",
+            if article.is_favorite { "Yes" } else { "No" }
+        )?;
+        if !entry.tags.is_empty() {
+            writeln!(self.out, "**Tags:** {}\n", entry.tags.join(", "))?;
+        }
+        if let Some(ref note) = entry.note {
+            writeln!(self.out, "### Note\n")?;
+            writeln!(self.out, "{}\n", note.trim_end())?;
+        }
+        if !entry.highlights.is_empty() {
+            writeln!(self.out, "### Highlights\n")?;
+            for highlight in &entry.highlights {
+                writeln!(self.out, "> {}\n", highlight.text)?;
+            }
+        }
+        if let Some(ref content) = article.content {
+            writeln!(self.out, "### Content\n")?;
+            writeln!(self.out, "{}\n", content)?;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+// =============================================================================
+// CSV
+// =============================================================================
+
+/// CSV の列
+const CSV_COLUMNS: &[&str] = &[
+    "id",
+    "feed",
+    "title",
+    "url",
+    "published_at",
+    "is_read",
+    "is_favorite",
+    "tags",
+    "note",
+];
+
+/// CSV（RFC 4180）で書き出す
+///
+/// 本文は含めません。タグは `;` 区切りで1列にまとめます。
+struct CsvExporter {
+    out: Box<dyn Write>,
+}
+
+impl Exporter for CsvExporter {
+    fn begin(&mut self, _count: Option<usize>) -> Result<()> {
+        write_csv_record(&mut self.out, CSV_COLUMNS)
+    }
+
+    fn write_article(&mut self, entry: &ExportArticle) -> Result<()> {
+        let article = &entry.article;
+        let fields = [
+            article.id.to_string(),
+            entry.feed_title.clone().unwrap_or_default(),
+            article.title.clone(),
+            article.url.clone().unwrap_or_default(),
+            article
+                .published_at
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default(),
+            article.is_read.to_string(),
+            article.is_favorite.to_string(),
+            entry.tags.join(";"),
+            entry.note.clone().unwrap_or_default(),
+        ];
+        write_csv_record(&mut self.out, &fields)
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// CSV の1行を書き出す（行末は CRLF）
+fn write_csv_record<S: AsRef<str>>(out: &mut Box<dyn Write>, fields: &[S]) -> Result<()> {
+    let line: Vec<String> = fields.iter().map(|f| csv_field(f.as_ref())).collect();
+    write!(out, "{}\r\n", line.join(","))?;
+    Ok(())
+}
+
+/// CSV のフィールドをエスケープする
+///
+/// 表計算ソフトで数式として解釈されないよう、`=`・`+`・`-`・`@` で始まる値の先頭には
+/// `'` を付けます。
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+// =============================================================================
+// HTML
+// =============================================================================
+
+/// HTML と EPUB で共通のスタイル
+const STYLESHEET: &str = "body { font-family: sans-serif; line-height: 1.6; max-width: 46em; margin: 0 auto; padding: 1em; }
+article { border-bottom: 1px solid #ccc; padding-bottom: 1em; margin-bottom: 2em; }
+.meta { color: #666; font-size: 0.9em; }
+.tags span { background: #eef; border-radius: 3px; padding: 0 0.4em; margin-right: 0.3em; }
+.note { background: #ffd; padding: 0.5em 1em; }
+blockquote { border-left: 3px solid #fc6; margin-left: 0; padding-left: 1em; }
+img { max-width: 100%; height: auto; }
+pre { overflow-x: auto; }
+";
+
+/// 単体で開ける HTML で書き出す
+///
+/// スタイルは埋め込み、本文は [`sanitize_html`] で無害化します。
+/// 画像などの外部リソースは元のURLを参照したままです。
+struct HtmlExporter {
+    out: Box<dyn Write>,
+    title: String,
+}
+
+impl Exporter for HtmlExporter {
+    fn begin(&mut self, count: Option<usize>) -> Result<()> {
+        let title = escape_html(&self.title);
+        write!(
+            self.out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\" />\n\
+             <meta name=\"generator\" content=\"rustfeed\" />\n<title>{}</title>\n\
+             <style>\n{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+            title, STYLESHEET, title
+        )?;
+        if let Some(count) = count {
+            writeln!(self.out, "<p class=\"meta\">{} articles</p>", count)?;
+        }
+        Ok(())
+    }
+
+    fn write_article(&mut self, entry: &ExportArticle) -> Result<()> {
+        writeln!(
+            self.out,
+            "<article id=\"article-{}\">\n{}</article>",
+            entry.article.id,
+            render_article(entry, "h2")
+        )?;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.out.write_all(b"</body>\n</html>\n")?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// 記事1件分の本文（見出し・メタ情報・タグ・メモ・ハイライト・本文）を整形式の HTML にする
+///
+/// HTML と EPUB の章の両方で使います。
+fn render_article(entry: &ExportArticle, heading: &str) -> String {
+    let article = &entry.article;
+    let title = escape_html(&article.title);
+    let mut html = match article.url.as_deref().filter(|url| is_safe_url(url)) {
+        Some(url) => format!(
+            "<{h}><a href=\"{}\">{}</a></{h}>\n",
+            escape_html(url),
+            title,
+            h = heading
+        ),
+        None => format!("<{h}>{}</{h}>\n", title, h = heading),
+    };
+
+    let mut meta = Vec::new();
+    if let Some(ref feed) = entry.feed_title {
+        meta.push(escape_html(feed));
+    }
+    if let Some(published_at) = article.published_at {
+        meta.push(format!(
+            "<time datetime=\"{}\">{}</time>",
+            published_at.to_rfc3339(),
+            published_at.format("%Y-%m-%d %H:%M")
+        ));
+    }
+    if article.is_favorite {
+        meta.push("★".to_string());
+    }
+    if !meta.is_empty() {
+        html.push_str(&format!("<p class=\"meta\">{}</p>\n", meta.join(" · ")));
+    }
+
+    if !entry.tags.is_empty() {
+        let tags: Vec<String> = entry
+            .tags
+            .iter()
+            .map(|tag| format!("<span>{}</span>", escape_html(tag)))
+            .collect();
+        html.push_str(&format!("<p class=\"tags\">{}</p>\n", tags.join("")));
+    }
+
+    if let Some(ref note) = entry.note {
+        html.push_str("<div class=\"note\">\n");
+        for paragraph in note.trim_end().split("\n\n") {
+            html.push_str(&format!(
+                "<p>{}</p>\n",
+                escape_html(paragraph).replace('\n', "<br />")
+            ));
+        }
+        html.push_str("</div>\n");
+    }
+
+    for highlight in &entry.highlights {
+        html.push_str(&format!(
+            "<blockquote class=\"highlight\"><p>{}</p></blockquote>\n",
+            escape_html(&highlight.text)
+        ));
+    }
+
+    if let Some(ref content) = article.content {
+        html.push_str(&format!(
+            "<div class=\"content\">\n{}\n</div>\n",
+            sanitize_html(content)
+        ));
+    }
+    html
+}
+
+// =============================================================================
+// EPUB
+// =============================================================================
+
+/// EPUB に書き込める章の数（ZIP のエントリ数の上限から、章以外の6ファイルを除いたもの）
+pub const MAX_EPUB_CHAPTERS: usize = u16::MAX as usize - 6;
+
+/// EPUB 3 で書き出す
+///
+/// 記事を1件ずつ章（XHTML）として ZIP に書き込み、目次（`nav.xhtml` と `toc.ncx`）と
+/// パッケージ文書（`content.opf`）は最後に書き込みます。
+///
+/// ZIP64 には対応していないため、章は [`MAX_EPUB_CHAPTERS`] までです。
+struct EpubExporter {
+    zip: ZipWriter,
+    title: String,
+    identifier: String,
+    /// 書き込んだ章（ファイル名とタイトル）
+    chapters: Vec<(String, String)>,
+}
+
+impl EpubExporter {
+    fn new(out: Box<dyn Write>, title: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            zip: ZipWriter::new(out, now),
+            title: title.to_string(),
+            identifier: format!("urn:rustfeed:export:{}", now.timestamp_millis()),
+            chapters: Vec::new(),
+        }
+    }
+
+    /// 目次（EPUB 3 のナビゲーション文書）
+    fn nav(&self) -> String {
+        let items: String = self
+            .chapters
+            .iter()
+            .map(|(file, title)| {
+                format!(
+                    "      <li><a href=\"{}\">{}</a></li>\n",
+                    file,
+                    escape_html(title)
+                )
+            })
+            .collect();
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html>\n\
+             <html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\">\n\
+             <head>\n  <title>{title}</title>\n  <link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\" />\n</head>\n\
+             <body>\n  <nav epub:type=\"toc\" id=\"toc\">\n    <h1>{title}</h1>\n    <ol>\n{items}    </ol>\n  </nav>\n</body>\n</html>\n",
+            title = escape_html(&self.title),
+            items = items
+        )
+    }
+
+    /// 目次（EPUB 2 のリーダー向けの NCX）
+    fn ncx(&self) -> String {
+        let points: String = self
+            .chapters
+            .iter()
+            .enumerate()
+            .map(|(index, (file, title))| {
+                format!(
+                    "    <navPoint id=\"nav-{n}\" playOrder=\"{n}\">\n      <navLabel><text>{}</text></navLabel>\n      <content src=\"{}\" />\n    </navPoint>\n",
+                    escape_html(title),
+                    file,
+                    n = index + 1
+                )
+            })
+            .collect();
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <ncx xmlns=\"http://www.daisy.org/z3986/2005/ncx/\" version=\"2005-1\">\n\
+             <head>\n  <meta name=\"dtb:uid\" content=\"{}\" />\n</head>\n\
+             <docTitle><text>{}</text></docTitle>\n<navMap>\n{}</navMap>\n</ncx>\n",
+            escape_html(&self.identifier),
+            escape_html(&self.title),
+            points
+        )
+    }
+
+    /// パッケージ文書（メタデータ・ファイル一覧・読む順序）
+    fn opf(&self) -> String {
+        let mut manifest = String::from(
+            "    <item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\" />\n\
+             \x20   <item id=\"ncx\" href=\"toc.ncx\" media-type=\"application/x-dtbncx+xml\" />\n\
+             \x20   <item id=\"style\" href=\"style.css\" media-type=\"text/css\" />\n",
+        );
+        let mut spine = String::from("    <itemref idref=\"nav\" />\n");
+        for (index, (file, _)) in self.chapters.iter().enumerate() {
+            manifest.push_str(&format!(
+                "    <item id=\"chapter-{}\" href=\"{}\" media-type=\"application/xhtml+xml\" />\n",
+                index + 1,
+                file
+            ));
+            spine.push_str(&format!(
+                "    <itemref idref=\"chapter-{}\" />\n",
+                index + 1
+            ));
+        }
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"book-id\">\n\
+             \x20 <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n\
+             \x20   <dc:identifier id=\"book-id\">{}</dc:identifier>\n\
+             \x20   <dc:title>{}</dc:title>\n\
+             \x20   <dc:language>und</dc:language>\n\
+             \x20   <dc:creator>rustfeed</dc:creator>\n\
+             \x20   <meta property=\"dcterms:modified\">{}</meta>\n\
+             \x20 </metadata>\n  <manifest>\n{}  </manifest>\n  <spine toc=\"ncx\">\n{}  </spine>\n</package>\n",
+            escape_html(&self.identifier),
+            escape_html(&self.title),
+            self.zip.modified.format("%Y-%m-%dT%H:%M:%SZ"),
+            manifest,
+            spine
+        )
+    }
+}
+
+impl Exporter for EpubExporter {
+    fn begin(&mut self, count: Option<usize>) -> Result<()> {
+        if let Some(count) = count.filter(|&count| count > MAX_EPUB_CHAPTERS) {
+            bail!(
+                "EPUB can hold at most {} articles ({} requested). Narrow the export down with filters or a limit.",
+                MAX_EPUB_CHAPTERS,
+                count
+            );
+        }
+        // mimetype は無圧縮で先頭に置く必要がある
+        self.zip.add("mimetype", b"application/epub+zip", false)?;
+        self.zip.add(
+            "META-INF/container.xml",
+            b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+              <container version=\"1.0\" xmlns=\"urn:oasis:names:tc:opendocument:xmlns:container\">\n\
+              \x20 <rootfiles>\n\
+              \x20   <rootfile full-path=\"OEBPS/content.opf\" media-type=\"application/oebps-package+xml\" />\n\
+              \x20 </rootfiles>\n</container>\n",
+            true,
+        )?;
+        self.zip
+            .add("OEBPS/style.css", STYLESHEET.as_bytes(), true)?;
+        Ok(())
+    }
+
+    fn write_article(&mut self, entry: &ExportArticle) -> Result<()> {
+        if self.chapters.len() >= MAX_EPUB_CHAPTERS {
+            bail!("EPUB can hold at most {} articles", MAX_EPUB_CHAPTERS);
+        }
+        let file = format!("chapter-{:05}.xhtml", self.chapters.len() + 1);
+        let chapter = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html>\n\
+             <html xmlns=\"http://www.w3.org/1999/xhtml\">\n\
+             <head>\n  <title>{}</title>\n  <link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\" />\n</head>\n\
+             <body>\n{}</body>\n</html>\n",
+            escape_html(&entry.article.title),
+            render_article(entry, "h1")
+        );
+        self.zip
+            .add(&format!("OEBPS/{}", file), chapter.as_bytes(), true)?;
+        self.chapters.push((file, entry.article.title.clone()));
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        let nav = self.nav();
+        let ncx = self.ncx();
+        let opf = self.opf();
+        self.zip.add("OEBPS/nav.xhtml", nav.as_bytes(), true)?;
+        self.zip.add("OEBPS/toc.ncx", ncx.as_bytes(), true)?;
+        self.zip.add("OEBPS/content.opf", opf.as_bytes(), true)?;
+        self.zip.finish()
+    }
+}
+
+// =============================================================================
+// ZIP
+// =============================================================================
+
+/// ZIP のエントリ1件分（セントラルディレクトリに書く情報）
+struct ZipEntry {
+    name: String,
+    method: u16,
+    crc: u32,
+    compressed_size: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// EPUB の書き出しに必要な分だけの ZIP ライター
+///
+/// エントリを1件ずつ（無圧縮または deflate で）書き込み、`finish` でセントラルディレクトリを
+/// 書き込みます。ZIP64 には対応していないため、4GiB を超える出力はエラーになります。
+struct ZipWriter {
+    out: Box<dyn Write>,
+    entries: Vec<ZipEntry>,
+    offset: u64,
+    modified: DateTime<Utc>,
+    dos_time: u16,
+    dos_date: u16,
+}
+
+/// UTF-8 のファイル名を示すフラグ
+const ZIP_FLAG_UTF8: u16 = 0x0800;
+
+impl ZipWriter {
+    fn new(out: Box<dyn Write>, modified: DateTime<Utc>) -> Self {
+        let dos_time =
+            ((modified.hour() << 11) | (modified.minute() << 5) | (modified.second() / 2)) as u16;
+        let dos_date = (((modified.year().max(1980) - 1980) as u32) << 9
+            | (modified.month() << 5)
+            | modified.day()) as u16;
+        Self {
+            out,
+            entries: Vec::new(),
+            offset: 0,
+            modified,
+            dos_time,
+            dos_date,
+        }
+    }
+
+    /// エントリを1件書き込む
+    fn add(&mut self, name: &str, data: &[u8], compress: bool) -> Result<()> {
+        let crc = crc32fast::hash(data);
+        let (method, body) = if compress {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data)?;
+            (8, encoder.finish()?)
+        } else {
+            (0, data.to_vec())
+        };
+
+        let entry = ZipEntry {
+            name: name.to_string(),
+            method,
+            crc,
+            compressed_size: to_u32(body.len() as u64)?,
+            size: to_u32(data.len() as u64)?,
+            offset: to_u32(self.offset)?,
+        };
+
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        header.extend_from_slice(&20u16.to_le_bytes()); // 展開に必要なバージョン
+        header.extend_from_slice(&ZIP_FLAG_UTF8.to_le_bytes());
+        header.extend_from_slice(&entry.method.to_le_bytes());
+        header.extend_from_slice(&self.dos_time.to_le_bytes());
+        header.extend_from_slice(&self.dos_date.to_le_bytes());
+        header.extend_from_slice(&entry.crc.to_le_bytes());
+        header.extend_from_slice(&entry.compressed_size.to_le_bytes());
+        header.extend_from_slice(&entry.size.to_le_bytes());
+        header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // 拡張フィールドの長さ
+        header.extend_from_slice(name.as_bytes());
+
+        self.out.write_all(&header)?;
+        self.out.write_all(&body)?;
+        self.offset += (header.len() + body.len()) as u64;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// セントラルディレクトリと終端レコードを書き込む
+    fn finish(mut self) -> Result<()> {
+        let directory_offset = to_u32(self.offset)?;
+        let mut directory = Vec::new();
+        for entry in &self.entries {
+            directory.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+            directory.extend_from_slice(&20u16.to_le_bytes()); // 作成したバージョン
+            directory.extend_from_slice(&20u16.to_le_bytes()); // 展開に必要なバージョン
+            directory.extend_from_slice(&ZIP_FLAG_UTF8.to_le_bytes());
+            directory.extend_from_slice(&entry.method.to_le_bytes());
+            directory.extend_from_slice(&self.dos_time.to_le_bytes());
+            directory.extend_from_slice(&self.dos_date.to_le_bytes());
+            directory.extend_from_slice(&entry.crc.to_le_bytes());
+            directory.extend_from_slice(&entry.compressed_size.to_le_bytes());
+            directory.extend_from_slice(&entry.size.to_le_bytes());
+            directory.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+            directory.extend_from_slice(&[0; 12]); // 拡張フィールド・コメント・ディスク番号・属性
+            directory.extend_from_slice(&entry.offset.to_le_bytes());
+            directory.extend_from_slice(entry.name.as_bytes());
+        }
+
+        let count = u16::try_from(self.entries.len()).context("Too many entries for EPUB")?;
+        let mut end = Vec::with_capacity(22);
+        end.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        end.extend_from_slice(&[0; 4]); // ディスク番号
+        end.extend_from_slice(&count.to_le_bytes());
+        end.extend_from_slice(&count.to_le_bytes());
+        end.extend_from_slice(&to_u32(directory.len() as u64)?.to_le_bytes());
+        end.extend_from_slice(&directory_offset.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes()); // コメントの長さ
+
+        self.out.write_all(&directory)?;
+        self.out.write_all(&end)?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// ZIP のサイズ・オフセットを32ビットに収める
+fn to_u32(value: u64) -> Result<u32> {
+    u32::try_from(value).context("EPUB is too large (over 4 GiB)")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// テスト用の、書き出した内容を後から読める出力先
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn sample(id: i64, title: &str) -> ExportArticle {
+        ExportArticle {
+            article: Article {
+                id,
+                feed_id: 1,
+                title: title.to_string(),
+                url: Some(format!("https://example.com/{}", id)),
+                content: Some("<p>Hello<script>alert(1)</script></p>".to_string()),
+                published_at: None,
+                is_read: false,
+                is_favorite: true,
+                created_at: Utc::now(),
+                read_at: None,
+                first_opened_at: None,
+                duplicate_of: None,
+                duplicate_count: 0,
+            },
+            feed_title: Some("Example".to_string()),
+            tags: vec!["rust".to_string(), "=cmd".to_string()],
+            note: Some("line, \"quoted\"".to_string()),
+            highlights: Vec::new(),
+        }
+    }
+
+    fn export(format: ExportFormat, articles: &[ExportArticle]) -> Vec<u8> {
+        let buffer = SharedBuffer::default();
+        let mut exporter = new_exporter(format, Box::new(buffer.clone()), "Test <export>");
+        exporter.begin(Some(articles.len())).unwrap();
+        for article in articles {
+            exporter.write_article(article).unwrap();
+        }
+        exporter.finish().unwrap();
+        let bytes = buffer.0.borrow().clone();
+        bytes
+    }
+
+    /// JSON・CSV・HTML の出力を確認するテスト
+    #[test]
+    fn test_text_exporters() {
+        let articles = vec![sample(1, "First"), sample(2, "=SUM(A1)")];
+
+        let json: serde_json::Value =
+            serde_json::from_slice(&export(ExportFormat::Json, &articles)).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 2);
+        assert_eq!(json[1]["title"], "=SUM(A1)");
+        assert_eq!(json[0]["tags"][0], "rust");
+        let empty: serde_json::Value =
+            serde_json::from_slice(&export(ExportFormat::Json, &[])).unwrap();
+        assert_eq!(empty, serde_json::json!([]));
+
+        let csv = String::from_utf8(export(ExportFormat::Csv, &articles)).unwrap();
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(
+            lines[0],
+            "id,feed,title,url,published_at,is_read,is_favorite,tags,note"
+        );
+        assert_eq!(
+            lines[2],
+            "2,Example,'=SUM(A1),https://example.com/2,,false,true,rust;=cmd,\"line, \"\"quoted\"\"\""
+        );
+
+        let html = String::from_utf8(export(ExportFormat::Html, &articles)).unwrap();
+        assert!(html.contains("<title>Test &lt;export&gt;</title>"));
+        assert!(html.contains("<p>Hello</p>"));
+        assert!(!html.contains("<script>"));
+        assert!(html.ends_with("</html>\n"));
+
+        let markdown = String::from_utf8(export(ExportFormat::Markdown, &articles)).unwrap();
+        assert!(markdown.contains("Total: 2 articles"));
+        assert!(markdown.contains("## 2. =SUM(A1)"));
+
+        assert!("EPUB".parse::<ExportFormat>().unwrap().is_binary());
+        assert!("pdf".parse::<ExportFormat>().is_err());
+    }
+
+    /// 複数ページにまたがる記事を、条件と上限を守って順に書き出せることを確認するテスト
+    #[test]
+    fn test_export_query_pages() {
+        use crate::models::Feed;
+        use rusqlite::Connection;
+
+        let db = Database::from_connection(Connection::open_in_memory().unwrap()).unwrap();
+        db.init().unwrap();
+        let feed_id = db
+            .add_feed(&Feed::new(
+                "https://example.com/feed".to_string(),
+                "Example".to_string(),
+                None,
+            ))
+            .unwrap();
+        let articles: Vec<Article> = (0..EXPORT_PAGE_SIZE + 20)
+            .map(|i| {
+                let title = if i % 2 == 0 { "Rust news" } else { "Other" };
+                Article::new(
+                    feed_id,
+                    format!("{} {}", title, i),
+                    Some(format!("https://example.com/{}", i)),
+                    None,
+                    Some(Utc::now() - chrono::Duration::minutes(i as i64)),
+                )
+            })
+            .collect();
+        db.add_articles(&articles).unwrap();
+
+        let run = |query: &ArticleQuery| {
+            let buffer = SharedBuffer::default();
+            let exporter = new_exporter(ExportFormat::Json, Box::new(buffer.clone()), "Test");
+            let count = export_query(&db, query, exporter).unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&buffer.0.borrow()).unwrap();
+            (count, json)
+        };
+
+        let (count, json) = run(&ArticleQuery::new().keyword("Rust"));
+        assert_eq!(count, EXPORT_PAGE_SIZE / 2 + 10);
+        let items = json.as_array().unwrap();
+        assert_eq!(items.len(), count);
+        assert_eq!(items[0]["title"], "Rust news 0");
+        assert_eq!(items[count - 1]["feed_title"], "Example");
+
+        let (count, json) = run(&ArticleQuery::new().limit(EXPORT_PAGE_SIZE + 5));
+        assert_eq!(count, EXPORT_PAGE_SIZE + 5);
+        assert_eq!(json.as_array().unwrap().len(), EXPORT_PAGE_SIZE + 5);
+    }
+
+    /// EPUB が mimetype を先頭に持つ ZIP で、章と目次を含むことを確認するテスト
+    #[test]
+    fn test_epub_exporter() {
+        let articles = vec![sample(1, "First"), sample(2, "Second & more")];
+        let epub = export(ExportFormat::Epub, &articles);
+
+        assert_eq!(&epub[..4], b"PK\x03\x04");
+        assert_eq!(&epub[30..38], b"mimetype");
+        assert_eq!(&epub[38..58], b"application/epub+zip");
+
+        // 終端レコードのエントリ数: mimetype・container・style・章2つ・nav・ncx・opf
+        let end = &epub[epub.len() - 22..];
+        assert_eq!(&end[..4], &0x0605_4b50u32.to_le_bytes());
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 8);
+
+        let text = String::from_utf8_lossy(&epub);
+        for name in [
+            "META-INF/container.xml",
+            "OEBPS/chapter-00001.xhtml",
+            "OEBPS/chapter-00002.xhtml",
+            "OEBPS/nav.xhtml",
+            "OEBPS/content.opf",
+        ] {
+            assert!(text.contains(name), "missing {}", name);
+        }
+    }
+}
@@ -31,8 +31,8 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use feed_rs::parser;
 
-use crate::db::Database;
 use crate::models::{Article, Feed};
+use crate::storage::Storage;
 
 // =============================================================================
 // 公開関数
@@ -125,8 +125,18 @@ pub async fn fetch_feed(url: &str) -> Result<(Feed, Vec<Article>)> {
     // 説明を取得（オプショナル）
     let description = parsed.description.map(|d| d.content);
 
+    // サイトのURL（rel が alternate または指定なしの最初のリンク）
+    let site_url = parsed
+        .links
+        .iter()
+        .find(|link| matches!(link.rel.as_deref(), None | Some("alternate")))
+        .map(|link| link.href.clone());
+
     // Feed 構造体を作成
-    let feed = Feed::new(url.to_string(), title, description);
+    let feed = Feed {
+        site_url,
+        ..Feed::new(url.to_string(), title, description)
+    };
 
     // -------------------------------------------------------------------------
     // Step 4: Article 構造体のリストを作成
@@ -186,7 +196,7 @@ pub async fn fetch_feed(url: &str) -> Result<(Feed, Vec<Article>)> {
 ///
 /// # 引数
 ///
-/// * `db` - データベース接続への参照
+/// * `db` - 保存先のストレージ（[`Database`](crate::db::Database) など）
 /// * `feed_id` - 記事を関連付けるフィードのID
 /// * `feed_data` - `fetch_feed`から返されたタプル (Feed, Vec<Article>)
 ///
@@ -194,6 +204,10 @@ pub async fn fetch_feed(url: &str) -> Result<(Feed, Vec<Article>)> {
 ///
 /// 新規追加された記事の数
 ///
+/// 記事は [`Storage::add_articles`] で1つのトランザクションにまとめて保存され、
+/// エラー時はこのフィードの記事は1件も保存されません。
+/// フィードのサイトのURLが未設定なら、取得したフィードのリンクで補います。
+///
 /// # 使用例
 ///
 /// ```rust,no_run
@@ -206,27 +220,29 @@ pub async fn fetch_feed(url: &str) -> Result<(Feed, Vec<Article>)> {
 /// }
 /// ```
 pub fn save_articles(
-    db: &Database,
+    db: &dyn Storage,
     feed_id: i64,
     feed_data: &(Feed, Vec<Article>),
 ) -> Result<usize> {
-    let (_, articles) = feed_data;
-    let mut count = 0;
+    let (feed, articles) = feed_data;
 
-    for article in articles {
-        // feed_id を設定して記事を作成
-        let article_with_feed_id = Article {
+    // feed_id を設定した記事を作成
+    let articles: Vec<Article> = articles
+        .iter()
+        .map(|article| Article {
             feed_id,
             ..article.clone()
-        };
+        })
+        .collect();
 
-        // 記事をデータベースに追加（重複は無視される）
-        if let Some(_id) = db.add_article(&article_with_feed_id)? {
-            count += 1;
-        }
+    // 1つのトランザクションでまとめて追加（重複は無視される）
+    let ids = db.add_articles(&articles)?;
+
+    if let Some(site_url) = &feed.site_url {
+        db.fill_feed_site_url(feed_id, site_url)?;
     }
 
-    Ok(count)
+    Ok(ids.len())
 }
 
 // =============================================================================
@@ -271,4 +287,48 @@ mod tests {
             assert!(!articles.is_empty(), "Feed should have articles");
         }
     }
+
+    /// 記事の保存時に、未設定のサイトのURLだけが補われることを確認するテスト
+    #[test]
+    fn test_save_articles_fills_site_url() {
+        let db =
+            crate::db::Database::from_connection(rusqlite::Connection::open_in_memory().unwrap())
+                .unwrap();
+        db.init().unwrap();
+        let storages: Vec<Box<dyn Storage>> =
+            vec![Box::new(db), Box::new(crate::memory::MemoryStorage::new())];
+
+        for storage in storages {
+            let feed = storage
+                .add_feed_simple("https://example.com/feed", "Example")
+                .unwrap();
+            let fetched = |site_url: &str| {
+                let feed = Feed {
+                    site_url: Some(site_url.to_string()),
+                    ..Feed::new(feed.url.clone(), "Example".to_string(), None)
+                };
+                let article = Article::new(
+                    0,
+                    "Article".to_string(),
+                    Some("https://example.com/1".to_string()),
+                    None,
+                    None,
+                );
+                (feed, vec![article])
+            };
+
+            assert_eq!(
+                save_articles(storage.as_ref(), feed.id, &fetched("https://example.com/")).unwrap(),
+                1
+            );
+            save_articles(
+                storage.as_ref(),
+                feed.id,
+                &fetched("https://other.example.com/"),
+            )
+            .unwrap();
+            let stored = storage.get_feed(feed.id).unwrap().unwrap();
+            assert_eq!(stored.site_url.as_deref(), Some("https://example.com/"));
+        }
+    }
 }
@@ -0,0 +1,544 @@
+//! # HTML の無害化
+//!
+//! フィードから取得した記事の本文（HTML）を、書き出したファイルに埋め込んでも
+//! 安全な HTML に変換する関数を提供します。
+//!
+//! ## 方針
+//!
+//! - 許可リストにあるタグだけを残し、それ以外のタグは取り除く（中のテキストは残す）
+//! - `<script>` や `<style>` などは中身ごと取り除く
+//! - 属性は `<a href>` と `<img src alt>` など一部だけを残し、URLは http(s) と mailto のみ許可する
+//! - 出力は常に整形式（XHTML としても読める）にする。閉じられていないタグは閉じ、
+//!   `&nbsp;` などの名前付き文字参照は数値文字参照に置き換える
+//!
+//! EPUB の章は XHTML でなければならないため、整形式であることも重要です。
+//!
+//! ## 使用例
+//!
+//! ```rust
+//! use rustfeed_core::html::sanitize_html;
+//!
+//! assert_eq!(
+//!     sanitize_html(r#"<p onclick="x()">Hi<script>alert(1)</script><br>there&nbsp;!"#),
+//!     "<p>Hi<br />there&#160;!</p>"
+//! );
+//! ```
+
+/// 残すタグ（小文字）
+const ALLOWED_TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "article",
+    "aside",
+    "b",
+    "blockquote",
+    "br",
+    "caption",
+    "cite",
+    "code",
+    "dd",
+    "del",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "i",
+    "img",
+    "ins",
+    "kbd",
+    "li",
+    "mark",
+    "ol",
+    "p",
+    "pre",
+    "q",
+    "s",
+    "samp",
+    "section",
+    "small",
+    "span",
+    "strong",
+    "sub",
+    "summary",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "time",
+    "tr",
+    "u",
+    "ul",
+];
+
+/// 中身ごと取り除くタグ
+const DROPPED_TAGS: &[&str] = &[
+    "script", "style", "iframe", "object", "embed", "noscript", "template", "svg", "math", "form",
+    "head", "title",
+];
+
+/// 終了タグを持たないタグ
+const VOID_TAGS: &[&str] = &["br", "hr", "img"];
+
+/// XHTML で使えない名前付き文字参照のうち、数値文字参照に置き換えるもの
+const NAMED_ENTITIES: &[(&str, char)] = &[
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", '\u{a0}'),
+    ("copy", '©'),
+    ("reg", '®'),
+    ("trade", '™'),
+    ("hellip", '…'),
+    ("mdash", '—'),
+    ("ndash", '–'),
+    ("lsquo", '‘'),
+    ("rsquo", '’'),
+    ("ldquo", '“'),
+    ("rdquo", '”'),
+    ("laquo", '«'),
+    ("raquo", '»'),
+    ("middot", '·'),
+    ("bull", '•'),
+    ("times", '×'),
+    ("deg", '°'),
+    ("euro", '€'),
+    ("yen", '¥'),
+    ("pound", '£'),
+];
+
+// =============================================================================
+// 無害化
+// =============================================================================
+
+/// 記事の本文（HTML）を無害化する
+///
+/// 結果は整形式の HTML 断片で、そのまま HTML・XHTML の本文に埋め込めます。
+pub fn sanitize_html(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut open: Vec<String> = Vec::new();
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        push_text(&mut out, &rest[..start]);
+        rest = &rest[start..];
+
+        // コメント・DOCTYPE・処理命令は取り除く
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        }
+
+        let Some(tag) = parse_tag(rest) else {
+            // タグとして解釈できない `<` は文字として扱う
+            out.push_str("&lt;");
+            rest = &rest[1..];
+            continue;
+        };
+        rest = &rest[tag.len..];
+
+        if tag.closing {
+            if let Some(index) = open.iter().rposition(|name| *name == tag.name) {
+                for name in open.drain(index..).rev() {
+                    push_end_tag(&mut out, &name);
+                }
+            }
+            continue;
+        }
+
+        if DROPPED_TAGS.contains(&tag.name.as_str()) {
+            if !tag.self_closing {
+                rest = skip_element(rest, &tag.name);
+            }
+            continue;
+        }
+        if !ALLOWED_TAGS.contains(&tag.name.as_str()) {
+            continue;
+        }
+
+        let attributes = allowed_attributes(&tag.name, &tag.attributes);
+        if tag.name == "img" && !attributes.iter().any(|(name, _)| *name == "src") {
+            continue;
+        }
+        out.push('<');
+        out.push_str(&tag.name);
+        for (name, value) in &attributes {
+            out.push_str(&format!(" {}=\"{}\"", name, escape_html(value)));
+        }
+        if VOID_TAGS.contains(&tag.name.as_str()) {
+            out.push_str(" />");
+        } else {
+            out.push('>');
+            open.push(tag.name);
+        }
+    }
+    push_text(&mut out, rest);
+
+    for name in open.iter().rev() {
+        push_end_tag(&mut out, name);
+    }
+    out
+}
+
+/// 本文を表示用のテキストにするときの折り返し幅（文字数）
+pub const RENDER_WIDTH: usize = 80;
+
+/// 記事の本文（HTML）を、TUI のプレビューと同じ表示用のテキストにする
+///
+/// [`RENDER_WIDTH`] 文字で折り返します。ハイライトの位置はこのテキスト上の文字数で数えます。
+pub fn render_text(html: &str) -> String {
+    html2text::from_read(html.as_bytes(), RENDER_WIDTH)
+}
+
+/// 記事の本文（HTML）をプレーンテキストにする
+///
+/// タグを取り除いて文字参照を展開し、連続する空白（改行を含む）を1つの空白にまとめます。
+/// `<script>` や `<style>` などは中身ごと取り除きます。ダイジェストの抜粋などに使います。
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&decode_entities(&rest[..start]));
+        rest = &rest[start..];
+
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        }
+
+        let Some(tag) = parse_tag(rest) else {
+            text.push('<');
+            rest = &rest[1..];
+            continue;
+        };
+        rest = &rest[tag.len..];
+        if !tag.closing && !tag.self_closing && DROPPED_TAGS.contains(&tag.name.as_str()) {
+            rest = skip_element(rest, &tag.name);
+        }
+        // `<p>a</p><p>b</p>` が "ab" にならないよう、タグは空白として扱う
+        text.push(' ');
+    }
+    text.push_str(&decode_entities(rest));
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// テキストをエスケープする（`&`、`<`、`>`、`"`、`'`）
+///
+/// 要素の内容と属性値のどちらにも使えます。
+pub fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// 文字参照を展開する（不明な名前付き文字参照はそのまま残す）
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 32)
+            .and_then(|end| decode_entity(&rest[1..end + 1]).map(|c| (c, end + 2)));
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 文字参照1つ（`&` と `;` を除いた部分）を文字にする
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(number) = entity.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code).filter(|c| *c != '\0');
+    }
+    NAMED_ENTITIES
+        .iter()
+        .find(|(name, _)| *name == entity)
+        .map(|(_, c)| *c)
+}
+
+/// テキストを出力に加える（文字参照を展開してからエスケープし直す）
+fn push_text(out: &mut String, text: &str) {
+    for c in decode_entities(text).chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\u{a0}' => out.push_str("&#160;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// 終了タグを出力に加える
+fn push_end_tag(out: &mut String, name: &str) {
+    out.push_str("</");
+    out.push_str(name);
+    out.push('>');
+}
+
+/// タグの属性のうち、残すものを選ぶ（値は文字参照を展開したもの）
+fn allowed_attributes(tag: &str, attributes: &[(String, String)]) -> Vec<(&'static str, String)> {
+    let allowed: &[&'static str] = match tag {
+        "a" => &["href", "title"],
+        "img" => &["src", "alt", "title"],
+        "td" | "th" => &["colspan", "rowspan"],
+        "abbr" => &["title"],
+        "time" => &["datetime"],
+        _ => &[],
+    };
+
+    let mut kept = Vec::new();
+    for name in allowed {
+        let Some((_, value)) = attributes.iter().find(|(key, _)| key == name) else {
+            continue;
+        };
+        let value = decode_entities(value);
+        let ok = match *name {
+            "href" | "src" => is_safe_url(&value),
+            "colspan" | "rowspan" => !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()),
+            _ => true,
+        };
+        if ok {
+            kept.push((*name, value));
+        }
+    }
+    kept
+}
+
+/// 書き出したファイルから参照してよいURLか（http(s) と mailto の絶対URLのみ）
+pub fn is_safe_url(url: &str) -> bool {
+    let url = url.trim().to_ascii_lowercase();
+    url.starts_with("http://") || url.starts_with("https://") || url.starts_with("mailto:")
+}
+
+/// 要素の終了タグの後ろまで読み飛ばす（見つからなければ最後まで）
+fn skip_element<'a>(html: &'a str, name: &str) -> &'a str {
+    let lower = html.to_ascii_lowercase();
+    let end_tag = format!("</{}", name);
+    match lower.find(&end_tag) {
+        Some(start) => {
+            let after = &html[start..];
+            after.find('>').map_or("", |end| &after[end + 1..])
+        }
+        None => "",
+    }
+}
+
+// =============================================================================
+// タグの解釈
+// =============================================================================
+
+/// 解釈したタグ
+struct Tag {
+    /// タグ名（小文字）
+    name: String,
+
+    /// 終了タグ（`</p>`）かどうか
+    closing: bool,
+
+    /// `<br/>` のように `/>` で閉じているか
+    self_closing: bool,
+
+    /// 属性（名前は小文字、値は元の文字列）
+    attributes: Vec<(String, String)>,
+
+    /// タグ全体の長さ（バイト数）
+    len: usize,
+}
+
+/// `<` で始まる文字列の先頭のタグを解釈する
+fn parse_tag(html: &str) -> Option<Tag> {
+    let bytes = html.as_bytes();
+    let mut pos = 1;
+    let closing = bytes.get(pos) == Some(&b'/');
+    if closing {
+        pos += 1;
+    }
+
+    let name_start = pos;
+    while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'-') {
+        pos += 1;
+    }
+    if pos == name_start || !bytes[name_start].is_ascii_alphabetic() {
+        return None;
+    }
+    let name = html[name_start..pos].to_ascii_lowercase();
+
+    let mut attributes = Vec::new();
+    let mut self_closing = false;
+    loop {
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        match bytes.get(pos) {
+            None => return None,
+            Some(b'>') => {
+                pos += 1;
+                break;
+            }
+            Some(b'/') => {
+                self_closing = bytes.get(pos + 1) == Some(&b'>');
+                pos += 1;
+                continue;
+            }
+            Some(_) => {}
+        }
+
+        let key_start = pos;
+        while pos < bytes.len()
+            && !bytes[pos].is_ascii_whitespace()
+            && !matches!(bytes[pos], b'=' | b'>' | b'/')
+        {
+            pos += 1;
+        }
+        let key = html[key_start..pos].to_ascii_lowercase();
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+
+        let mut value = String::new();
+        if bytes.get(pos) == Some(&b'=') {
+            pos += 1;
+            while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            match bytes.get(pos) {
+                Some(&quote @ (b'"' | b'\'')) => {
+                    let end = html[pos + 1..].find(quote as char)? + pos + 1;
+                    value = html[pos + 1..end].to_string();
+                    pos = end + 1;
+                }
+                _ => {
+                    let value_start = pos;
+                    while pos < bytes.len()
+                        && !bytes[pos].is_ascii_whitespace()
+                        && bytes[pos] != b'>'
+                    {
+                        pos += 1;
+                    }
+                    value = html[value_start..pos].to_string();
+                }
+            }
+        }
+        if !key.is_empty() {
+            attributes.push((key, value));
+        }
+    }
+
+    Some(Tag {
+        name,
+        closing,
+        self_closing,
+        attributes,
+        len: pos,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 危険な要素・属性が取り除かれ、出力が整形式になることを確認するテスト
+    #[test]
+    fn test_sanitize_html() {
+        assert_eq!(
+            sanitize_html(
+                r#"<div class="post"><h2 id="x">Title</h2><p>One <b>two<i>three</p>
+                   <img src="javascript:alert(1)"><img src="https://example.com/a.png" alt="A &amp; B" onerror="x()">
+                   <a href="JAVASCRIPT:bad()">bad</a> <a href='https://example.com/?a=1&amp;b=2' target=_blank>ok</a>"#
+            ),
+            "<div><h2>Title</h2><p>One <b>two<i>three</i></b></p>\n                   \
+             <img src=\"https://example.com/a.png\" alt=\"A &amp; B\" />\n                   \
+             <a>bad</a> <a href=\"https://example.com/?a=1&amp;b=2\">ok</a></div>"
+        );
+
+        // 中身ごと取り除く要素、コメント、許可していないタグ
+        assert_eq!(
+            sanitize_html(
+                "a<script>x<y</script>b<!-- c --><STYLE>p{}</STYLE><font color=red>d</font>"
+            ),
+            "abd"
+        );
+
+        // 文字参照と、タグとして解釈できない `<`
+        assert_eq!(
+            sanitize_html("1 < 2 &amp;&amp; 3 > 2&nbsp;&hellip; &unknown; &#x41;"),
+            "1 &lt; 2 &amp;&amp; 3 &gt; 2&#160;… &amp;unknown; A"
+        );
+
+        // 対応しない終了タグは無視し、閉じられていないタグは閉じる
+        assert_eq!(
+            sanitize_html("</p><ul><li>x</ul></li>"),
+            "<ul><li>x</li></ul>"
+        );
+        assert_eq!(
+            sanitize_html("<p>unterminated <a href="),
+            "<p>unterminated &lt;a href=</p>"
+        );
+    }
+
+    /// タグと中身ごと取り除く要素が消え、文字参照と空白が整えられることを確認するテスト
+    #[test]
+    fn test_html_to_text() {
+        assert_eq!(
+            html_to_text("<p>Hello,<br>world&nbsp;&amp; <b>friends</b></p><p>Bye</p>"),
+            "Hello, world & friends Bye"
+        );
+        assert_eq!(
+            html_to_text("a<script>var x = '<p>';</script>\n\n<!-- c -->b < c"),
+            "a b < c"
+        );
+        assert_eq!(html_to_text("plain text"), "plain text");
+    }
+}
@@ -0,0 +1,838 @@
+//! # 他のフィードリーダーからの移行モジュール
+//!
+//! 他のフィードリーダーが書き出したデータから、フィードと記事（既読・スターの状態を含む）を
+//! 読み込む機能を提供します。
+//!
+//! ## 対応している形式
+//!
+//! | 形式 | 読み込むもの |
+//! |------|--------------|
+//! | Google Reader 形式の JSON（FreshRSS・Inoreader・The Old Reader のスター付き記事の書き出しなど） | 記事・フィード・ラベル（タグ）・既読・スター |
+//! | Miniflux の JSON（`/v1/entries` や `/v1/feeds` の応答） | 記事・フィード・カテゴリ・タグ・既読・スター |
+//! | newsboat の `urls` ファイルとキャッシュ（`cache.db`） | フィード・タグ（最初のタグをカテゴリ）・記事・既読・フラグ |
+//!
+//! どの形式も [`Dump`] に変換するため、読み込みは `Database::load_dump` の統合（[`LoadMode::Merge`]）
+//! と同じ規則で行われます。登録済みのフィード（URLが同じとみなせるもの）や記事はそのまま使い、
+//! 既読・お気に入りは足し合わせます（未読に戻すことはありません）。
+//!
+//! 状態の対応は次のとおりです。
+//!
+//! - スター（`starred`）→ お気に入り（`is_favorite`）
+//! - 既読（`read`）→ 既読（`is_read`）
+//! - newsboat のフラグ（`flags`）が1つでも付いた記事 → お気に入り
+//!
+//! 購読リストに含まれず、記事の配信元（Google Reader 形式の `origin`）としてだけ現れるフィードは、
+//! 購読をやめたフィードの可能性があるため一時停止（`paused`）の状態で追加します。
+//!
+//! [`LoadMode::Merge`]: crate::dump::LoadMode::Merge
+//!
+//! ## 使用例
+//!
+//! ```rust
+//! use rustfeed_core::import::parse_google_reader;
+//!
+//! let import = parse_google_reader(
+//!     r#"{"id": "user/-/state/com.google/starred", "items": [{
+//!          "title": "Hello",
+//!          "alternate": [{"href": "https://example.com/hello"}],
+//!          "origin": {"streamId": "feed/https://example.com/feed", "title": "Example"}
+//!        }]}"#,
+//! )?;
+//! assert_eq!(import.dump.feeds.len(), 1);
+//! assert!(import.dump.articles[0].article.is_favorite);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use rusqlite::{Connection, OpenFlags};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::dedup::feed_url_key;
+use crate::dump::{Dump, DumpArticle};
+use crate::models::{Article, Feed};
+
+// =============================================================================
+// 形式と結果
+// =============================================================================
+
+/// 読み込むデータの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportSource {
+    /// Google Reader 形式の JSON（FreshRSS・Inoreader・The Old Reader）
+    GoogleReader,
+    /// Miniflux の JSON
+    Miniflux,
+    /// newsboat の `urls` ファイル（とキャッシュ）
+    Newsboat,
+}
+
+impl FromStr for ImportSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "google-reader" | "greader" | "freshrss" | "inoreader" | "oldreader" => {
+                Ok(ImportSource::GoogleReader)
+            }
+            "miniflux" => Ok(ImportSource::Miniflux),
+            "newsboat" => Ok(ImportSource::Newsboat),
+            _ => bail!(
+                "Unsupported source: '{}'. Use google-reader, miniflux or newsboat.",
+                s
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ImportSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ImportSource::GoogleReader => "google-reader",
+            ImportSource::Miniflux => "miniflux",
+            ImportSource::Newsboat => "newsboat",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 読み込んだデータ
+#[derive(Debug, Clone, Default)]
+pub struct ReaderImport {
+    /// フィードと記事（`Database::load_dump` で読み込む）
+    pub dump: Dump,
+
+    /// 読み込めなかった項目の説明（URLが http(s) でないフィードなど）
+    pub skipped: Vec<String>,
+}
+
+/// [`ReaderImport`] を組み立てる（フィードはURLが同じとみなせるものを1つにまとめる）
+#[derive(Default)]
+struct ImportBuilder {
+    import: ReaderImport,
+    feed_ids: HashMap<String, i64>,
+}
+
+impl ImportBuilder {
+    /// フィードを追加してダンプ内のIDを返す（追加済みならそのID、URLが不正なら None）
+    ///
+    /// `subscribed` が偽（購読リストになく、記事の配信元としてだけ現れる）のフィードは
+    /// 一時停止の状態で追加します。一度でも購読リストに現れたフィードは一時停止にしません。
+    fn feed(
+        &mut self,
+        url: &str,
+        title: Option<&str>,
+        site_url: Option<&str>,
+        category: Option<&str>,
+        subscribed: bool,
+    ) -> Option<i64> {
+        let url = url.trim();
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            self.import
+                .skipped
+                .push(format!("Feed with unsupported URL: {}", url));
+            return None;
+        }
+        let key = feed_url_key(url);
+        if let Some(&id) = self.feed_ids.get(&key) {
+            if subscribed {
+                if let Some(feed) = self.import.dump.feeds.iter_mut().find(|f| f.id == id) {
+                    feed.paused = false;
+                }
+            }
+            return Some(id);
+        }
+
+        let id = self.import.dump.feeds.len() as i64 + 1;
+        let title = title
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .unwrap_or(url);
+        let category = category
+            .map(str::trim)
+            .filter(|category| !category.is_empty());
+        if let Some(category) = category {
+            if !self.import.dump.categories.iter().any(|c| c == category) {
+                self.import.dump.categories.push(category.to_string());
+            }
+        }
+        self.import.dump.feeds.push(Feed {
+            id,
+            category: category.map(str::to_string),
+            site_url: site_url
+                .filter(|url| !url.trim().is_empty())
+                .map(str::to_string),
+            paused: !subscribed,
+            ..Feed::new(url.to_string(), title.to_string(), None)
+        });
+        self.feed_ids.insert(key, id);
+        Some(id)
+    }
+
+    /// 記事を追加する
+    fn article(&mut self, mut article: Article, tags: Vec<String>) {
+        article.id = self.import.dump.articles.len() as i64 + 1;
+        if article.title.trim().is_empty() {
+            article.title = article
+                .url
+                .clone()
+                .unwrap_or_else(|| "(untitled)".to_string());
+        }
+        let mut tags: Vec<String> = tags
+            .into_iter()
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        tags.dedup();
+        self.import.dump.articles.push(DumpArticle {
+            article,
+            tags,
+            note: None,
+            highlights: Vec::new(),
+        });
+    }
+}
+
+/// Unix時刻（秒、数値または数値の文字列）を日時にする
+fn unix_time(value: &Value) -> Option<DateTime<Utc>> {
+    let seconds = match value {
+        Value::Number(n) => n.as_i64()?,
+        Value::String(s) => s.trim().parse().ok()?,
+        _ => return None,
+    };
+    Utc.timestamp_opt(seconds, 0)
+        .single()
+        .filter(|_| seconds > 0)
+}
+
+// =============================================================================
+// Google Reader 形式
+// =============================================================================
+
+/// Google Reader 形式のストリーム（`items` を持つ JSON）
+#[derive(Deserialize)]
+struct GReaderStream {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    items: Vec<GReaderItem>,
+}
+
+#[derive(Deserialize)]
+struct GReaderItem {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    published: Option<Value>,
+    #[serde(default)]
+    canonical: Vec<GReaderLink>,
+    #[serde(default)]
+    alternate: Vec<GReaderLink>,
+    #[serde(default)]
+    categories: Vec<String>,
+    #[serde(default)]
+    origin: Option<GReaderOrigin>,
+    #[serde(default)]
+    summary: Option<GReaderContent>,
+    #[serde(default)]
+    content: Option<GReaderContent>,
+}
+
+#[derive(Deserialize)]
+struct GReaderLink {
+    href: String,
+}
+
+#[derive(Deserialize)]
+struct GReaderOrigin {
+    #[serde(default, rename = "streamId")]
+    stream_id: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default, rename = "htmlUrl")]
+    html_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GReaderContent {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// Google Reader 形式の JSON を読み込む
+///
+/// 記事の `categories` のうち、`.../state/com.google/starred` はスター、
+/// `.../state/com.google/read` は既読、`.../label/<名前>` はタグとして扱います。
+/// ストリーム自体がスター付き記事の一覧（`id` が `.../state/com.google/starred`）の場合は、
+/// 全ての記事をお気に入りにします。
+///
+/// この形式には購読リストが含まれないため、記事の `origin` から作るフィードは
+/// 一時停止の状態で追加します（購読中のフィードと同じとみなせるものは、読み込み時に既存のものを使います）。
+pub fn parse_google_reader(json: &str) -> Result<ReaderImport> {
+    let stream: GReaderStream =
+        serde_json::from_str(json).context("Failed to parse Google Reader JSON")?;
+    let all_starred = stream
+        .id
+        .as_deref()
+        .is_some_and(|id| id.ends_with("/state/com.google/starred"));
+
+    let mut builder = ImportBuilder::default();
+    for item in stream.items {
+        let title = item.title.unwrap_or_default();
+        let Some(origin) = item.origin else {
+            builder
+                .import
+                .skipped
+                .push(format!("Item without a feed: {}", title));
+            continue;
+        };
+        let Some(feed_url) = origin
+            .stream_id
+            .as_deref()
+            .and_then(|id| id.strip_prefix("feed/"))
+        else {
+            builder
+                .import
+                .skipped
+                .push(format!("Item without a feed URL: {}", title));
+            continue;
+        };
+        let Some(feed_id) = builder.feed(
+            feed_url,
+            origin.title.as_deref(),
+            origin.html_url.as_deref(),
+            None,
+            false,
+        ) else {
+            continue;
+        };
+
+        let mut is_read = false;
+        let mut is_favorite = all_starred;
+        let mut tags = Vec::new();
+        for category in &item.categories {
+            if category.ends_with("/state/com.google/starred") {
+                is_favorite = true;
+            } else if category.ends_with("/state/com.google/read") {
+                is_read = true;
+            } else if let Some((_, label)) = category.split_once("/label/") {
+                tags.push(label.to_string());
+            }
+        }
+
+        let url = item
+            .canonical
+            .into_iter()
+            .chain(item.alternate)
+            .map(|link| link.href)
+            .next();
+        let content = item
+            .content
+            .or(item.summary)
+            .and_then(|content| content.content);
+        let published_at = item.published.as_ref().and_then(unix_time);
+
+        let mut article = Article::new(feed_id, title, url, content, published_at);
+        article.is_read = is_read;
+        article.is_favorite = is_favorite;
+        builder.article(article, tags);
+    }
+    Ok(builder.import)
+}
+
+// =============================================================================
+// Miniflux
+// =============================================================================
+
+/// Miniflux の記事（`/v1/entries` の `entries` の要素）
+#[derive(Deserialize)]
+struct MinifluxEntry {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    published_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    starred: bool,
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+    #[serde(default)]
+    feed_id: Option<i64>,
+    #[serde(default)]
+    feed: Option<MinifluxFeed>,
+}
+
+/// Miniflux のフィード（`/v1/feeds` の要素、記事の `feed`）
+#[derive(Clone, Deserialize)]
+struct MinifluxFeed {
+    #[serde(default)]
+    id: Option<i64>,
+    feed_url: String,
+    #[serde(default)]
+    site_url: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    category: Option<MinifluxCategory>,
+}
+
+#[derive(Clone, Deserialize)]
+struct MinifluxCategory {
+    title: String,
+}
+
+/// Miniflux の JSON を読み込む
+///
+/// 次のどれでも読み込めます。
+///
+/// - `/v1/entries` の応答（`{"total": ..., "entries": [...]}`）または記事の配列
+/// - `/v1/feeds` の応答（フィードの配列）
+/// - `{"feeds": [...], "entries": [...]}`（記事が `feed` を持たず `feed_id` だけの場合）
+///
+/// 記事の `status` が `read` なら既読、`removed` の記事は読み込みません。
+pub fn parse_miniflux(json: &str) -> Result<ReaderImport> {
+    let value: Value = serde_json::from_str(json).context("Failed to parse Miniflux JSON")?;
+
+    let (feeds, entries) = match value {
+        Value::Array(items) => {
+            // フィードの配列か記事の配列かは、要素が feed_url を持つかで判断する
+            if items.iter().all(|item| item.get("feed_url").is_some()) {
+                (items, Vec::new())
+            } else {
+                (Vec::new(), items)
+            }
+        }
+        Value::Object(mut object) => {
+            let mut take = |key: &str| match object.remove(key) {
+                Some(Value::Array(items)) => items,
+                _ => Vec::new(),
+            };
+            let feeds = take("feeds");
+            let entries = take("entries");
+            if feeds.is_empty() && entries.is_empty() {
+                bail!("Miniflux JSON has no \"entries\" or \"feeds\"");
+            }
+            (feeds, entries)
+        }
+        _ => bail!("Miniflux JSON must be an object or an array"),
+    };
+
+    let mut builder = ImportBuilder::default();
+    let mut feeds_by_id: HashMap<i64, MinifluxFeed> = HashMap::new();
+    for feed in feeds {
+        let feed: MinifluxFeed =
+            serde_json::from_value(feed).context("Failed to parse Miniflux feed")?;
+        builder.feed(
+            &feed.feed_url,
+            feed.title.as_deref(),
+            feed.site_url.as_deref(),
+            feed.category.as_ref().map(|c| c.title.as_str()),
+            true,
+        );
+        if let Some(id) = feed.id {
+            feeds_by_id.insert(id, feed);
+        }
+    }
+
+    for entry in entries {
+        let entry: MinifluxEntry =
+            serde_json::from_value(entry).context("Failed to parse Miniflux entry")?;
+        if entry.status.as_deref() == Some("removed") {
+            continue;
+        }
+        let Some(feed) = entry
+            .feed
+            .or_else(|| entry.feed_id.and_then(|id| feeds_by_id.get(&id).cloned()))
+        else {
+            builder
+                .import
+                .skipped
+                .push(format!("Entry without a feed: {}", entry.title));
+            continue;
+        };
+        let Some(feed_id) = builder.feed(
+            &feed.feed_url,
+            feed.title.as_deref(),
+            feed.site_url.as_deref(),
+            feed.category.as_ref().map(|c| c.title.as_str()),
+            true,
+        ) else {
+            continue;
+        };
+
+        // Miniflux は公開日時が不明な記事に 0001-01-01 を入れる
+        let published_at = entry
+            .published_at
+            .filter(|published| published.timestamp() > 0);
+        let mut article = Article::new(
+            feed_id,
+            entry.title,
+            entry.url.filter(|url| !url.is_empty()),
+            entry.content.filter(|content| !content.is_empty()),
+            published_at,
+        );
+        article.is_read = entry.status.as_deref() == Some("read");
+        article.is_favorite = entry.starred;
+        builder.article(article, entry.tags.unwrap_or_default());
+    }
+    Ok(builder.import)
+}
+
+// =============================================================================
+// newsboat
+// =============================================================================
+
+/// newsboat の `urls` ファイルとキャッシュを読み込む
+///
+/// `urls` の各行は `URL "タグ" "~表示名" ...` の形式です。最初のタグ（`~` や `!` で始まらないもの）を
+/// カテゴリにし、`~` で始まるものをフィードの表示名にします。`query:`・`exec:`・`filter:` の
+/// フィードは読み込みません。
+///
+/// `cache` を指定すると、`urls` にあるフィードの記事を読み込みます（削除済みの記事は除く）。
+/// `unread` が0の記事は既読、フラグ（`flags`）が付いた記事はお気に入りになります。
+pub fn parse_newsboat(urls: &str, cache: Option<&Path>) -> Result<ReaderImport> {
+    let mut builder = ImportBuilder::default();
+    for line in urls.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut tokens = newsboat_tokens(line).into_iter();
+        let Some(url) = tokens.next() else {
+            continue;
+        };
+        let mut name = None;
+        let mut category = None;
+        for token in tokens {
+            if let Some(title) = token.strip_prefix('~') {
+                name = Some(title.to_string());
+            } else if !token.starts_with('!') && category.is_none() {
+                category = Some(token);
+            }
+        }
+        if let Some(id) = builder.feed(&url, None, None, category.as_deref(), true) {
+            if let Some(name) = name {
+                builder.import.dump.feeds[id as usize - 1].custom_name = Some(name);
+            }
+        }
+    }
+
+    if let Some(path) = cache {
+        read_newsboat_cache(&mut builder, path)
+            .with_context(|| format!("Failed to read newsboat cache {}", path.display()))?;
+    }
+    Ok(builder.import)
+}
+
+/// `urls` の1行を、空白区切り・`"` で囲まれた単位に分ける
+fn newsboat_tokens(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '#' {
+            break; // 行末のコメント
+        }
+        let mut token = String::new();
+        if c == '"' {
+            chars.next();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => token.extend(chars.next()),
+                    '"' => break,
+                    _ => token.push(c),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+        }
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// newsboat のキャッシュ（SQLite）から記事を読み込む
+fn read_newsboat_cache(builder: &mut ImportBuilder, path: &Path) -> Result<()> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+    // urls にあるフィードに、キャッシュのタイトルとサイトのURLを補う
+    let mut stmt = conn.prepare("SELECT rssurl, url, title FROM rss_feed")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+        ))
+    })?;
+    for row in rows {
+        let (rss_url, site_url, title) = row?;
+        let Some(&id) = builder.feed_ids.get(&feed_url_key(&rss_url)) else {
+            continue;
+        };
+        let feed = &mut builder.import.dump.feeds[id as usize - 1];
+        if let Some(title) = title.filter(|title| !title.trim().is_empty()) {
+            feed.title = title;
+        }
+        if feed.site_url.is_none() {
+            feed.site_url = site_url.filter(|url| !url.is_empty());
+        }
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT feedurl, title, url, content, pubDate, unread, flags FROM rss_item
+         WHERE deleted = 0 ORDER BY id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Option<i64>>(4)?,
+            row.get::<_, Option<i64>>(5)?,
+            row.get::<_, Option<String>>(6)?,
+        ))
+    })?;
+
+    let mut unsubscribed = 0;
+    for row in rows {
+        let (feed_url, title, url, content, published, unread, flags) = row?;
+        let Some(&feed_id) = builder.feed_ids.get(&feed_url_key(&feed_url)) else {
+            unsubscribed += 1;
+            continue;
+        };
+        let published_at = published
+            .filter(|&seconds| seconds > 0)
+            .and_then(|seconds| Utc.timestamp_opt(seconds, 0).single());
+        let mut article = Article::new(
+            feed_id,
+            title.unwrap_or_default(),
+            url.filter(|url| !url.is_empty()),
+            content.filter(|content| !content.is_empty()),
+            published_at,
+        );
+        article.is_read = unread == Some(0);
+        article.is_favorite = flags.is_some_and(|flags| !flags.trim().is_empty());
+        builder.article(article, Vec::new());
+    }
+    if unsubscribed > 0 {
+        builder.import.skipped.push(format!(
+            "{} cached item(s) of feeds that are not in the urls file",
+            unsubscribed
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use crate::dump::LoadMode;
+    use crate::query::ArticleQuery;
+    use crate::storage::Storage;
+
+    fn test_db() -> Database {
+        let db = Database::from_connection(Connection::open_in_memory().unwrap()).unwrap();
+        db.init().unwrap();
+        db
+    }
+
+    /// Google Reader 形式の JSON から、フィード・ラベル・既読・スターを読み込めることを確認するテスト
+    #[test]
+    fn test_parse_google_reader() {
+        let import = parse_google_reader(
+            r#"{
+              "id": "user/-/state/com.google/reading-list",
+              "items": [
+                {
+                  "title": "Starred and read",
+                  "published": 1700000000,
+                  "canonical": [{"href": "https://a.example.com/1"}],
+                  "categories": [
+                    "user/-/state/com.google/reading-list",
+                    "user/-/state/com.google/starred",
+                    "user/-/state/com.google/read",
+                    "user/1005/label/Security"
+                  ],
+                  "origin": {"streamId": "feed/https://a.example.com/feed",
+                             "title": "A", "htmlUrl": "https://a.example.com/"},
+                  "summary": {"content": "<p>Summary</p>"}
+                },
+                {
+                  "title": "Unread",
+                  "published": "1700000100",
+                  "alternate": [{"href": "https://a.example.com/2", "type": "text/html"}],
+                  "categories": [],
+                  "origin": {"streamId": "feed/http://a.example.com/feed", "title": "A"}
+                },
+                {"title": "No origin"}
+              ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(import.dump.feeds.len(), 1);
+        assert_eq!(import.skipped.len(), 1);
+        let feed = &import.dump.feeds[0];
+        assert_eq!(feed.title, "A");
+        assert_eq!(feed.site_url.as_deref(), Some("https://a.example.com/"));
+        // 購読リストがないため、配信元のフィードは一時停止で追加される
+        assert!(feed.paused);
+
+        let first = &import.dump.articles[0];
+        assert!(first.article.is_favorite && first.article.is_read);
+        assert_eq!(first.tags, vec!["Security"]);
+        assert_eq!(first.article.content.as_deref(), Some("<p>Summary</p>"));
+        assert_eq!(first.article.published_at.unwrap().timestamp(), 1700000000);
+        let second = &import.dump.articles[1];
+        assert!(!second.article.is_favorite && !second.article.is_read);
+        assert_eq!(
+            second.article.url.as_deref(),
+            Some("https://a.example.com/2")
+        );
+
+        // 読み込むと既存の記事に状態が足し合わされる
+        let db = test_db();
+        db.load_dump(&import.dump, LoadMode::Merge).unwrap();
+        let report = db.load_dump(&import.dump, LoadMode::Merge).unwrap();
+        assert_eq!((report.feeds_merged, report.articles_merged), (1, 2));
+        assert!(db.get_feeds(None).unwrap()[0].paused);
+        let favorites = db
+            .query_articles(&ArticleQuery::new().favorites_only())
+            .unwrap();
+        assert_eq!(favorites.len(), 1);
+        assert_eq!(
+            db.get_article_tags(favorites[0].id).unwrap(),
+            vec!["Security"]
+        );
+
+        // 購読中のフィードの記事は、そのフィードに読み込まれ一時停止にならない
+        let db = test_db();
+        db.add_feed_simple("https://a.example.com/feed", "A")
+            .unwrap();
+        db.load_dump(&import.dump, LoadMode::Merge).unwrap();
+        let feeds = db.get_feeds(None).unwrap();
+        assert_eq!(feeds.len(), 1);
+        assert!(!feeds[0].paused);
+    }
+
+    /// Miniflux の記事・フィードの JSON を読み込めることを確認するテスト
+    #[test]
+    fn test_parse_miniflux() {
+        let import = parse_miniflux(
+            r#"{"total": 3, "entries": [
+              {"id": 1, "title": "Read", "url": "https://m.example.com/1", "status": "read",
+               "starred": true, "tags": ["rust"], "published_at": "2024-05-01T10:00:00+02:00",
+               "feed": {"id": 7, "feed_url": "https://m.example.com/feed", "site_url": "https://m.example.com/",
+                        "title": "M", "category": {"id": 1, "title": "Tech"}}},
+              {"id": 2, "title": "Unread", "url": "https://m.example.com/2", "status": "unread",
+               "starred": false, "published_at": "0001-01-01T00:00:00Z",
+               "feed": {"id": 7, "feed_url": "https://m.example.com/feed", "title": "M"}},
+              {"id": 3, "title": "Removed", "status": "removed",
+               "feed": {"id": 7, "feed_url": "https://m.example.com/feed"}}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(import.dump.feeds.len(), 1);
+        assert_eq!(import.dump.feeds[0].category.as_deref(), Some("Tech"));
+        assert_eq!(import.dump.categories, vec!["Tech"]);
+        assert_eq!(import.dump.articles.len(), 2);
+        let first = &import.dump.articles[0];
+        assert!(first.article.is_read && first.article.is_favorite);
+        assert_eq!(first.tags, vec!["rust"]);
+        assert_eq!(
+            first.article.published_at.unwrap().to_rfc3339(),
+            "2024-05-01T08:00:00+00:00"
+        );
+        assert_eq!(import.dump.articles[1].article.published_at, None);
+
+        // フィードの配列
+        let feeds = parse_miniflux(
+            r#"[{"id": 1, "feed_url": "https://x.example.com/feed", "title": "X",
+                 "category": {"title": "News"}}]"#,
+        )
+        .unwrap();
+        assert_eq!(feeds.dump.feeds.len(), 1);
+        assert!(feeds.dump.articles.is_empty());
+        assert!(parse_miniflux(r#"{"total": 0}"#).is_err());
+    }
+
+    /// newsboat の urls ファイルとキャッシュを読み込めることを確認するテスト
+    #[test]
+    fn test_parse_newsboat() {
+        let urls = r#"
+# comment
+https://n.example.com/feed "tech" "~Newsy" "rust"
+"query:Unread:unread = \"yes\""
+https://other.example.com/rss  # no tags
+exec:~/bin/feed.sh "scripts"
+"#;
+        let path =
+            std::env::temp_dir().join(format!("rustfeed-newsboat-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE rss_feed (rssurl VARCHAR(1024) PRIMARY KEY, url VARCHAR(1024), title VARCHAR(1024));
+                 CREATE TABLE rss_item (id INTEGER PRIMARY KEY AUTOINCREMENT, guid VARCHAR(64), title VARCHAR(1024),
+                     author VARCHAR(1024), url VARCHAR(1024), feedurl VARCHAR(1024), pubDate INTEGER,
+                     content VARCHAR(65535), unread INTEGER, enclosure_url VARCHAR(1024),
+                     enclosure_type VARCHAR(1024), enqueued INTEGER DEFAULT 0, flags VARCHAR(52),
+                     deleted INTEGER DEFAULT 0, base VARCHAR(128));
+                 INSERT INTO rss_feed VALUES ('https://n.example.com/feed', 'https://n.example.com/', 'News Site');
+                 INSERT INTO rss_item (title, url, feedurl, pubDate, content, unread, flags) VALUES
+                     ('Read', 'https://n.example.com/1', 'https://n.example.com/feed', 1700000000, 'x', 0, ''),
+                     ('Flagged', 'https://n.example.com/2', 'https://n.example.com/feed', 1700000100, 'y', 1, 's'),
+                     ('Gone', 'https://gone.example.com/1', 'https://gone.example.com/feed', 0, '', 1, NULL);
+                 INSERT INTO rss_item (title, url, feedurl, unread, deleted) VALUES
+                     ('Deleted', 'https://n.example.com/3', 'https://n.example.com/feed', 1, 1);",
+            )
+            .unwrap();
+        }
+
+        let import = parse_newsboat(urls, Some(&path)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(import.dump.feeds.len(), 2);
+        let feed = &import.dump.feeds[0];
+        assert_eq!(feed.title, "News Site");
+        assert_eq!(feed.custom_name.as_deref(), Some("Newsy"));
+        assert_eq!(feed.category.as_deref(), Some("tech"));
+        assert_eq!(feed.site_url.as_deref(), Some("https://n.example.com/"));
+        assert_eq!(import.dump.feeds[1].category, None);
+
+        // query: と exec: のフィード、購読していないフィードの記事は読み込まない
+        assert_eq!(import.skipped.len(), 3);
+        assert_eq!(import.dump.articles.len(), 2);
+        let (read, flagged) = (&import.dump.articles[0], &import.dump.articles[1]);
+        assert!(read.article.is_read && !read.article.is_favorite);
+        assert!(!flagged.article.is_read && flagged.article.is_favorite);
+
+        assert_eq!(
+            newsboat_tokens(r#"https://x "a b" "esc\"aped" bare # tail"#),
+            vec!["https://x", "a b", "esc\"aped", "bare"]
+        );
+    }
+}
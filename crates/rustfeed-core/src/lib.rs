@@ -5,10 +5,26 @@
 //! このクレートは、CLI と TUI の両方から使用される
 //! コア機能を提供します:
 //!
-//! - **models**: データモデル（Feed, Article）
+//! - **models**: データモデル（Feed, Article, Tag, Category, QueueEntry, Note, Highlight）
 //! - **db**: データベース操作
+//! - **digest**: 期間内の記事をカテゴリ・フィードごとにまとめたダイジェスト（テンプレートで描画）
+//! - **dedup**: 複数のフィードに届いた同じ記事（重複記事）の検出
+//! - **backup**: データベースのバックアップ・世代管理・復元
+//! - **dump**: データベース全体の JSON での書き出し・読み込み
+//! - **email**: ダイジェストをメールで送る SMTP クライアント（STARTTLS・認証対応）
+//! - **export**: 記事の書き出し（JSON・Markdown・CSV・HTML・EPUB）
+//! - **import**: 他のフィードリーダー（Google Reader 形式・Miniflux・newsboat）からの移行
+//! - **opml**: 購読リスト（OPML）の読み込み・書き出し
+//! - **storage**: フィード・記事の保存先を抽象化する Storage トレイト
+//! - **memory**: メモリ上だけにデータを保持するストレージ（テスト・組み込み用）
+//! - **publish**: 記事の Atom・RSS・JSON Feed での配信
+//! - **pool**: タスク間で共有できるデータベース接続のプール（DbPool）
 //! - **feed**: RSS/Atom フィード取得・パース
+//! - **html**: 記事の本文（HTML）の無害化
+//! - **query**: 記事の取得条件（ArticleQuery）
+//! - **stats**: 購読状況・読書傾向の統計
 //! - **config**: 設定ファイル管理
+//! - **editor**: 外部エディタ（`$EDITOR`）でのテキスト編集
 //!
 //! ## 使用例
 //!
@@ -31,12 +47,35 @@
 //! }
 //! ```
 
+pub mod backup;
 pub mod config;
 pub mod db;
+pub mod dedup;
+pub mod digest;
+pub mod dump;
+pub mod editor;
+pub mod email;
+pub mod export;
 pub mod feed;
+pub mod html;
+pub mod import;
+pub mod memory;
 pub mod models;
+pub mod opml;
+pub mod pool;
+pub mod publish;
+pub mod query;
+pub mod stats;
+pub mod storage;
+mod xml;
 
 // 便利な再エクスポート
 pub use config::AppConfig;
 pub use db::Database;
-pub use models::{Article, Feed};
+pub use memory::MemoryStorage;
+pub use models::{
+    Article, Category, Feed, Highlight, HistoryEntry, HistoryEvent, Note, QueueEntry, Tag,
+};
+pub use pool::{DbPool, PooledDatabase};
+pub use query::{ArticleCursor, ArticlePage, ArticleQuery, SortOrder};
+pub use storage::{SharedStorage, Storage};
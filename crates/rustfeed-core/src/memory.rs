@@ -0,0 +1,1282 @@
+//! # インメモリストレージモジュール
+//!
+//! データをメモリ上だけに保持する [`MemoryStorage`] を提供します。
+//!
+//! ## 概要
+//!
+//! [`Storage`] トレイトの実装で、`~/.rustfeed` に一切触れません。
+//! フロントエンドのテストや、rustfeed を他のアプリケーションに組み込んで
+//! 一時的なデータだけを扱う場合に使います。
+//!
+//! `Clone` は同じデータを共有します（`Arc` の複製）。プロセスが終了するとデータは消えます。
+//!
+//! ## SQLite 版との違い
+//!
+//! - フィードのカテゴリは [`Storage::add_feed`] に渡した `Feed::category` のパスをそのまま使います
+//!   （カテゴリの作成や変更は SQLite 版の [`Database`](crate::db::Database) のみの機能です）
+//! - 読書履歴は記録しません（`read_at` と `first_opened_at` は更新します）
+//! - 重複記事の判定（[`crate::dedup`]）は SQLite 版と同じ規則で行います
+//!
+//! ## 使用例
+//!
+//! ```rust
+//! use rustfeed_core::{memory::MemoryStorage, storage::Storage, Article, Feed};
+//!
+//! let storage = MemoryStorage::new();
+//! let feed_id = storage.add_feed(&Feed::new(
+//!     "https://example.com/feed".to_string(),
+//!     "Example".to_string(),
+//!     None,
+//! ))?;
+//! let article = Article::new(feed_id, "Hello".to_string(), None, None, None);
+//! let id = storage.add_article(&article)?.unwrap();
+//!
+//! storage.mark_as_read(id)?;
+//! assert!(storage.get_article(id)?.unwrap().is_read);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::db::{category_path_segments, normalize_tag_name, FetchLease, FETCH_LEASE_TTL_SECS};
+use crate::dedup;
+use crate::html;
+use crate::models::{Article, Feed, Highlight, Note, QueueEntry, Tag};
+use crate::query::{ArticleQuery, SortOrder};
+use crate::storage::{new_fetch_lease, Storage};
+
+/// メモリ上にデータを保持するストレージ
+///
+/// クローンは全て同じデータを指します。
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    inner: Arc<Mutex<MemoryData>>,
+}
+
+/// メモリ上のデータ本体（SQLite のテーブルに対応する）
+#[derive(Default)]
+struct MemoryData {
+    feeds: Vec<Feed>,
+    articles: Vec<Article>,
+    tags: Vec<Tag>,
+    /// (記事ID, タグID)
+    article_tags: Vec<(i64, i64)>,
+    /// 「あとで読む」キューの (記事ID, 追加日時)（先頭から順に）
+    queue: Vec<(i64, DateTime<Utc>)>,
+    notes: HashMap<i64, Note>,
+    highlights: Vec<Highlight>,
+    fetch_lease: Option<FetchLease>,
+    /// テーブルごとに最後に採番したID（SQLite の AUTOINCREMENT と同じく1から）
+    last_feed_id: i64,
+    last_article_id: i64,
+    last_tag_id: i64,
+    last_highlight_id: i64,
+}
+
+impl MemoryStorage {
+    /// 空のストレージを作成する
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// データをロックする
+    ///
+    /// 他のスレッドがパニックしてもデータ自体は壊れないため、ロックの poison は無視します。
+    fn data(&self) -> MutexGuard<'_, MemoryData> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl MemoryData {
+    fn feed_mut(&mut self, id: i64) -> Option<&mut Feed> {
+        self.feeds.iter_mut().find(|feed| feed.id == id)
+    }
+
+    fn article(&self, id: i64) -> Option<&Article> {
+        self.articles.iter().find(|article| article.id == id)
+    }
+
+    fn article_mut(&mut self, id: i64) -> Option<&mut Article> {
+        self.articles.iter_mut().find(|article| article.id == id)
+    }
+
+    /// 記事が存在しない場合はエラーにする
+    fn ensure_article(&self, id: i64) -> Result<&Article> {
+        self.article(id)
+            .with_context(|| format!("Article not found with ID: {}", id))
+    }
+
+    /// フィードが存在しない場合はエラーにする（SQLite 版の外部キー制約に相当）
+    fn ensure_feed(&self, id: i64) -> Result<()> {
+        if !self.feeds.iter().any(|feed| feed.id == id) {
+            anyhow::bail!("Feed not found with ID: {}", id);
+        }
+        Ok(())
+    }
+
+    /// 記事を追加する（フィードの存在は確認済みであること）
+    fn insert_article(&mut self, article: &Article) -> Option<i64> {
+        // SQLite の UNIQUE(feed_id, url) と同じく、URL のない記事は重複とみなさない
+        let duplicate = article.url.is_some()
+            && self
+                .articles
+                .iter()
+                .any(|existing| existing.feed_id == article.feed_id && existing.url == article.url);
+        if duplicate {
+            return None;
+        }
+
+        let original = self.find_duplicate_original(article);
+        let is_read = article.is_read
+            || original.is_some_and(|id| self.article(id).is_some_and(|a| a.is_read));
+
+        let id = next_id(&mut self.last_article_id);
+        self.articles.push(Article {
+            id,
+            is_read,
+            read_at: None,
+            first_opened_at: None,
+            duplicate_of: original,
+            duplicate_count: 0,
+            ..article.clone()
+        });
+        Some(id)
+    }
+
+    /// 追加する記事の重複元のIDを探す（SQLite 版の `find_duplicate_original` と同じ規則）
+    fn find_duplicate_original(&self, article: &Article) -> Option<i64> {
+        let canonical = article.url.as_deref().and_then(dedup::canonical_url);
+        if let Some(canonical) = canonical {
+            let same_url = self.articles.iter().find(|existing| {
+                existing.feed_id != article.feed_id
+                    && existing
+                        .url
+                        .as_deref()
+                        .and_then(dedup::canonical_url)
+                        .as_ref()
+                        == Some(&canonical)
+            });
+            if let Some(existing) = same_url {
+                return Some(existing.duplicate_of.unwrap_or(existing.id));
+            }
+        }
+
+        let (from, to) = dedup::duplicate_window(article);
+        let time = article.published_at.unwrap_or(article.created_at);
+        let mut candidates: Vec<&Article> = self
+            .articles
+            .iter()
+            .filter(|existing| {
+                existing.duplicate_of.is_none() && existing.feed_id != article.feed_id
+            })
+            .filter(|existing| {
+                let time = existing.published_at.unwrap_or(existing.created_at);
+                from <= time && time <= to
+            })
+            .collect();
+        // 日時の近い順（同じなら古いID順）に比べる
+        candidates.sort_by_key(|existing| {
+            let distance = existing.published_at.unwrap_or(existing.created_at) - time;
+            (distance.abs(), existing.id)
+        });
+        candidates
+            .into_iter()
+            .find(|existing| dedup::is_similar_title(&article.title, &existing.title))
+            .map(|existing| existing.id)
+    }
+
+    /// 重複記事の数を設定した記事のコピー（SQLite 版では読み込み時に数える）
+    fn with_duplicate_count(&self, article: &Article) -> Article {
+        Article {
+            duplicate_count: self
+                .articles
+                .iter()
+                .filter(|other| other.duplicate_of == Some(article.id))
+                .count(),
+            ..article.clone()
+        }
+    }
+
+    /// 記事の既読状態を設定する（SQLite 版のトリガーと同じく `read_at` とキューも更新する）
+    ///
+    /// 既読にした場合は、元の記事と他の重複記事も既読にします。
+    fn set_read(&mut self, id: i64, is_read: bool) -> bool {
+        let Some(article) = self.article_mut(id) else {
+            return false;
+        };
+        let changed = article.is_read != is_read;
+        if changed {
+            article.is_read = is_read;
+            article.read_at = is_read.then(Utc::now);
+        }
+        let original = article.duplicate_of.unwrap_or(id);
+        if is_read {
+            self.queue.retain(|&(queued, _)| queued != id);
+        }
+
+        if is_read && changed {
+            let group: Vec<i64> = self
+                .articles
+                .iter()
+                .filter(|other| other.id == original || other.duplicate_of == Some(original))
+                .filter(|other| other.id != id && !other.is_read)
+                .map(|other| other.id)
+                .collect();
+            for other in group {
+                if let Some(article) = self.article_mut(other) {
+                    article.is_read = true;
+                    article.read_at = Some(Utc::now());
+                }
+                self.queue.retain(|&(queued, _)| queued != other);
+            }
+        }
+        true
+    }
+
+    /// 条件に一致する記事の既読状態をまとめて設定し、対象の件数を返す
+    fn set_read_where(&mut self, is_read: bool, filter: impl Fn(&Article) -> bool) -> usize {
+        let ids: Vec<i64> = self
+            .articles
+            .iter()
+            .filter(|article| filter(article))
+            .map(|article| article.id)
+            .collect();
+        for id in &ids {
+            self.set_read(*id, is_read);
+        }
+        ids.len()
+    }
+
+    /// タグ名からタグのIDを探す
+    ///
+    /// タグ名は大文字小文字を区別しない（SQLite 版の COLLATE NOCASE と同じ）
+    fn tag_id(&self, name: &str) -> Option<i64> {
+        self.tags
+            .iter()
+            .find(|tag| tag.name.eq_ignore_ascii_case(name.trim()))
+            .map(|tag| tag.id)
+    }
+
+    /// タグのIDを取得する（存在しない場合は作成する）
+    fn get_or_create_tag(&mut self, name: &str) -> Result<i64> {
+        let name = normalize_tag_name(name)?;
+        if let Some(id) = self.tag_id(name) {
+            return Ok(id);
+        }
+        let id = next_id(&mut self.last_tag_id);
+        self.tags.push(Tag {
+            id,
+            name: name.to_string(),
+            created_at: Utc::now(),
+        });
+        Ok(id)
+    }
+
+    /// タグとその記事との関連を削除する
+    fn remove_tag(&mut self, tag_id: i64) {
+        self.article_tags.retain(|(_, id)| *id != tag_id);
+        self.tags.retain(|tag| tag.id != tag_id);
+    }
+
+    /// 記事に付いているタグ名（タグ名順）
+    fn article_tag_names(&self, article_id: i64) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .article_tags
+            .iter()
+            .filter(|(id, _)| *id == article_id)
+            .filter_map(|(_, tag_id)| self.tags.iter().find(|tag| tag.id == *tag_id))
+            .map(|tag| tag.name.clone())
+            .collect();
+        names.sort_by_key(|name| name.to_lowercase());
+        names
+    }
+
+    /// 記事が取得条件に一致するかを判定する（`limit` / `offset` 以外）
+    fn matches(&self, query: &ArticleQuery, article: &Article) -> bool {
+        if !self.matches_filters(query, article) {
+            return false;
+        }
+
+        // SQLite 版と同じく、元の記事が同じ条件で取得される場合だけ重複記事を除く
+        if query.collapse_duplicates {
+            if let Some(original) = article.duplicate_of.and_then(|id| self.article(id)) {
+                if self.matches_filters(query, original) {
+                    return false;
+                }
+            }
+        }
+
+        if let Some(cursor) = &query.cursor {
+            if !cursor.is_followed_by(article, query.sort) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// 記事が絞り込みの条件に一致するかを判定する（重複記事とカーソル位置以外）
+    fn matches_filters(&self, query: &ArticleQuery, article: &Article) -> bool {
+        if !query.feed_ids.is_empty() && !query.feed_ids.contains(&article.feed_id) {
+            return false;
+        }
+        if query.exclude_feed_ids.contains(&article.feed_id) {
+            return false;
+        }
+        if query.exclude_paused
+            && self
+                .feeds
+                .iter()
+                .any(|feed| feed.id == article.feed_id && feed.paused)
+        {
+            return false;
+        }
+
+        if !query.categories.is_empty() {
+            let feed_category = self
+                .feeds
+                .iter()
+                .find(|feed| feed.id == article.feed_id)
+                .and_then(|feed| feed.category.as_deref());
+            let Some(feed_category) = feed_category else {
+                return false;
+            };
+            if !query
+                .categories
+                .iter()
+                .any(|category| is_in_category(feed_category, category))
+            {
+                return false;
+            }
+        }
+
+        if !query.tags.is_empty() {
+            let names = self.article_tag_names(article.id);
+            if !query.tags.iter().any(|tag| {
+                names
+                    .iter()
+                    .any(|name| name.eq_ignore_ascii_case(tag.trim()))
+            }) {
+                return false;
+            }
+        }
+
+        if query
+            .is_read
+            .is_some_and(|is_read| article.is_read != is_read)
+        {
+            return false;
+        }
+        if query
+            .is_favorite
+            .is_some_and(|is_favorite| article.is_favorite != is_favorite)
+        {
+            return false;
+        }
+
+        // SQLite 版と同じく、公開日時のない記事は（取得日時で判定しない限り）日付範囲の条件に一致しない
+        if query.published_after.is_some() || query.published_before.is_some() {
+            let date = match article.published_at {
+                Some(published) => Some(published),
+                None if query.undated_by_created_at => Some(article.created_at),
+                None => None,
+            };
+            let Some(published) = date else {
+                return false;
+            };
+            if query.published_after.is_some_and(|after| published < after)
+                || query
+                    .published_before
+                    .is_some_and(|before| published >= before)
+            {
+                return false;
+            }
+        }
+
+        // LIKE と同じく、ASCII の大文字小文字は区別しない
+        let title = article.title.to_ascii_lowercase();
+        let content = article
+            .content
+            .as_deref()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        for keywords in [&query.keywords, &query.search_terms] {
+            if !keywords.is_empty()
+                && !keywords.iter().any(|keyword| {
+                    let keyword = keyword.to_ascii_lowercase();
+                    title.contains(&keyword) || content.contains(&keyword)
+                })
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// フィードのカテゴリが指定したカテゴリ（またはそのサブカテゴリ）かを判定する
+fn is_in_category(feed_category: &str, category: &str) -> bool {
+    let feed_segments = category_path_segments(feed_category);
+    let segments = category_path_segments(category);
+    !segments.is_empty()
+        && feed_segments.len() >= segments.len()
+        && segments
+            .iter()
+            .zip(&feed_segments)
+            .all(|(a, b)| a.to_lowercase() == b.to_lowercase())
+}
+
+/// 最後に採番したIDを進めて、新しいIDを返す
+fn next_id(last_id: &mut i64) -> i64 {
+    *last_id += 1;
+    *last_id
+}
+
+// =============================================================================
+// Storage トレイトの実装
+// =============================================================================
+
+impl Storage for MemoryStorage {
+    fn add_feed(&self, feed: &Feed) -> Result<i64> {
+        let url = dedup::normalize_feed_url(&feed.url)?;
+        if let Some(existing) = self.find_feed_by_url(&url)? {
+            anyhow::bail!("Feed already exists: {} (ID {})", existing.url, existing.id);
+        }
+
+        let mut data = self.data();
+        let id = next_id(&mut data.last_feed_id);
+        data.feeds.push(Feed {
+            id,
+            url,
+            ..feed.clone()
+        });
+        Ok(id)
+    }
+
+    fn get_feeds(&self, category: Option<&str>) -> Result<Vec<Feed>> {
+        let data = self.data();
+        let mut feeds: Vec<Feed> = data
+            .feeds
+            .iter()
+            .filter(|feed| match category {
+                Some(category) => feed
+                    .category
+                    .as_deref()
+                    .is_some_and(|path| is_in_category(path, category)),
+                None => true,
+            })
+            .cloned()
+            .collect();
+        feeds.sort_by(|a, b| b.priority.cmp(&a.priority).then(a.id.cmp(&b.id)));
+        Ok(feeds)
+    }
+
+    fn get_feed(&self, id: i64) -> Result<Option<Feed>> {
+        Ok(self.data().feeds.iter().find(|feed| feed.id == id).cloned())
+    }
+
+    fn remove_feed(&self, id: i64) -> Result<bool> {
+        let mut data = self.data();
+        let before = data.feeds.len();
+        data.feeds.retain(|feed| feed.id != id);
+        if data.feeds.len() == before {
+            return Ok(false);
+        }
+
+        // SQLite 版の ON DELETE CASCADE と同じく、記事と記事に紐づくデータも削除する
+        let removed: Vec<i64> = data
+            .articles
+            .iter()
+            .filter(|article| article.feed_id == id)
+            .map(|article| article.id)
+            .collect();
+        data.articles.retain(|article| article.feed_id != id);
+        // SQLite 版の ON DELETE SET NULL と同じく、削除した記事を元とする関連付けを外す
+        for article in &mut data.articles {
+            if article
+                .duplicate_of
+                .is_some_and(|original| removed.contains(&original))
+            {
+                article.duplicate_of = None;
+            }
+        }
+        data.article_tags
+            .retain(|(article_id, _)| !removed.contains(article_id));
+        data.queue
+            .retain(|(article_id, _)| !removed.contains(article_id));
+        data.notes
+            .retain(|article_id, _| !removed.contains(article_id));
+        data.highlights
+            .retain(|highlight| !removed.contains(&highlight.article_id));
+        Ok(true)
+    }
+
+    fn rename_feed(&self, feed_id: i64, custom_name: Option<&str>) -> Result<()> {
+        if let Some(feed) = self.data().feed_mut(feed_id) {
+            feed.custom_name = custom_name.map(str::to_string);
+        }
+        Ok(())
+    }
+
+    fn update_feed_url(&self, feed_id: i64, new_url: &str) -> Result<()> {
+        let url = dedup::normalize_feed_url(new_url)?;
+        if let Some(existing) = self.find_feed_by_url(&url)? {
+            if existing.id != feed_id {
+                anyhow::bail!("Feed already exists: {} (ID {})", existing.url, existing.id);
+            }
+        }
+        if let Some(feed) = self.data().feed_mut(feed_id) {
+            feed.url = url;
+            feed.updated_at = Utc::now();
+        }
+        Ok(())
+    }
+
+    fn find_feed_by_url(&self, url: &str) -> Result<Option<Feed>> {
+        let key = dedup::feed_url_key(url);
+        Ok(self
+            .data()
+            .feeds
+            .iter()
+            .filter(|feed| dedup::feed_url_key(&feed.url) == key)
+            .min_by_key(|feed| feed.id)
+            .cloned())
+    }
+
+    fn set_feed_priority(&self, feed_id: i64, priority: i64) -> Result<()> {
+        if let Some(feed) = self.data().feed_mut(feed_id) {
+            feed.priority = priority;
+        }
+        Ok(())
+    }
+
+    fn set_feed_paused(&self, feed_id: i64, paused: bool) -> Result<bool> {
+        match self.data().feed_mut(feed_id) {
+            Some(feed) => {
+                feed.paused = paused;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn fill_feed_site_url(&self, feed_id: i64, site_url: &str) -> Result<bool> {
+        match self.data().feed_mut(feed_id) {
+            Some(feed) if feed.site_url.is_none() => {
+                feed.site_url = Some(site_url.to_string());
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn add_article(&self, article: &Article) -> Result<Option<i64>> {
+        let mut data = self.data();
+        data.ensure_feed(article.feed_id)?;
+        Ok(data.insert_article(article))
+    }
+
+    fn add_articles(&self, articles: &[Article]) -> Result<Vec<i64>> {
+        let mut data = self.data();
+        // 途中で失敗して一部だけ追加されることがないよう、先に全件を検証する
+        for article in articles {
+            data.ensure_feed(article.feed_id)?;
+        }
+        Ok(articles
+            .iter()
+            .filter_map(|article| data.insert_article(article))
+            .collect())
+    }
+
+    fn get_article(&self, id: i64) -> Result<Option<Article>> {
+        let data = self.data();
+        Ok(data
+            .article(id)
+            .map(|article| data.with_duplicate_count(article)))
+    }
+
+    fn query_articles(&self, query: &ArticleQuery) -> Result<Vec<Article>> {
+        let data = self.data();
+        let mut articles: Vec<Article> = data
+            .articles
+            .iter()
+            .filter(|article| data.matches(query, article))
+            .map(|article| data.with_duplicate_count(article))
+            .collect();
+
+        // SQLite と同じく、公開日時のない記事は最小の値として並べる
+        articles.sort_by_key(|article| (article.published_at, article.id));
+        if query.sort == SortOrder::Newest {
+            articles.reverse();
+        }
+
+        Ok(articles
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    fn get_article_counts(&self, feed_id: i64) -> Result<(usize, usize)> {
+        let data = self.data();
+        let articles = data
+            .articles
+            .iter()
+            .filter(|article| article.feed_id == feed_id);
+        let total = articles.clone().count();
+        let unread = articles.filter(|article| !article.is_read).count();
+        Ok((total, unread))
+    }
+
+    fn mark_as_read(&self, id: i64) -> Result<bool> {
+        Ok(self.data().set_read(id, true))
+    }
+
+    fn mark_as_unread(&self, id: i64) -> Result<bool> {
+        Ok(self.data().set_read(id, false))
+    }
+
+    fn mark_all_read_with_filter(
+        &self,
+        feed_id: Option<i64>,
+        before_date: Option<&str>,
+    ) -> Result<usize> {
+        // SQLite 版と同じく、RFC3339 文字列のまま比較する
+        Ok(self.data().set_read_where(true, |article| {
+            feed_id.is_none_or(|feed_id| article.feed_id == feed_id)
+                && before_date.is_none_or(|date| {
+                    article
+                        .published_at
+                        .is_some_and(|published| published.to_rfc3339().as_str() < date)
+                })
+        }))
+    }
+
+    fn mark_all_unread_by_feed(&self, feed_id: i64) -> Result<usize> {
+        Ok(self
+            .data()
+            .set_read_where(false, |article| article.feed_id == feed_id))
+    }
+
+    fn mark_all_unread(&self) -> Result<usize> {
+        Ok(self.data().set_read_where(false, |_| true))
+    }
+
+    fn mark_opened(&self, id: i64) -> Result<bool> {
+        let mut data = self.data();
+        let Some(article) = data.article_mut(id) else {
+            return Ok(false);
+        };
+        article.first_opened_at.get_or_insert_with(Utc::now);
+        Ok(true)
+    }
+
+    fn add_favorite(&self, id: i64) -> Result<bool> {
+        let mut data = self.data();
+        let Some(article) = data.article_mut(id) else {
+            return Ok(false);
+        };
+        article.is_favorite = true;
+        Ok(true)
+    }
+
+    fn remove_favorite(&self, id: i64) -> Result<bool> {
+        let mut data = self.data();
+        let Some(article) = data.article_mut(id) else {
+            return Ok(false);
+        };
+        article.is_favorite = false;
+        Ok(true)
+    }
+
+    fn get_tags(&self) -> Result<Vec<(Tag, usize)>> {
+        let data = self.data();
+        let mut tags: Vec<(Tag, usize)> = data
+            .tags
+            .iter()
+            .map(|tag| {
+                let count = data
+                    .article_tags
+                    .iter()
+                    .filter(|(_, tag_id)| *tag_id == tag.id)
+                    .count();
+                (tag.clone(), count)
+            })
+            .collect();
+        tags.sort_by_key(|(tag, _)| tag.name.to_lowercase());
+        Ok(tags)
+    }
+
+    fn tag_article(&self, article_id: i64, names: &[&str]) -> Result<usize> {
+        let mut data = self.data();
+        data.ensure_article(article_id)?;
+
+        let mut added = 0;
+        for name in names {
+            let tag_id = data.get_or_create_tag(name)?;
+            if !data.article_tags.contains(&(article_id, tag_id)) {
+                data.article_tags.push((article_id, tag_id));
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+
+    fn untag_article(&self, article_id: i64, names: &[&str]) -> Result<usize> {
+        let mut data = self.data();
+        let mut removed = 0;
+        for name in names {
+            let Some(tag_id) = data.tag_id(name) else {
+                continue;
+            };
+            let before = data.article_tags.len();
+            data.article_tags
+                .retain(|&link| link != (article_id, tag_id));
+            removed += before - data.article_tags.len();
+        }
+        Ok(removed)
+    }
+
+    fn get_tags_for_articles(&self, article_ids: &[i64]) -> Result<HashMap<i64, Vec<String>>> {
+        let data = self.data();
+        Ok(article_ids
+            .iter()
+            .map(|&id| (id, data.article_tag_names(id)))
+            .filter(|(_, names)| !names.is_empty())
+            .collect())
+    }
+
+    fn rename_tag(&self, old_name: &str, new_name: &str) -> Result<bool> {
+        let new_name = normalize_tag_name(new_name)?;
+        let mut data = self.data();
+
+        let Some(source_id) = data.tag_id(old_name) else {
+            return Ok(false);
+        };
+        if data.tag_id(new_name).is_some_and(|id| id != source_id) {
+            anyhow::bail!(
+                "Tag '{}' already exists. Merge the tags instead of renaming.",
+                new_name
+            );
+        }
+
+        if let Some(tag) = data.tags.iter_mut().find(|tag| tag.id == source_id) {
+            tag.name = new_name.to_string();
+        }
+        Ok(true)
+    }
+
+    fn merge_tags(&self, source: &str, target: &str) -> Result<usize> {
+        let mut data = self.data();
+        let source_id = data
+            .tag_id(source)
+            .with_context(|| format!("Tag not found: {}", source))?;
+        let target_id = data.get_or_create_tag(target)?;
+        if target_id == source_id {
+            return Ok(0);
+        }
+
+        let article_ids: Vec<i64> = data
+            .article_tags
+            .iter()
+            .filter(|(_, tag_id)| *tag_id == source_id)
+            .map(|(article_id, _)| *article_id)
+            .collect();
+        let mut moved = 0;
+        for article_id in article_ids {
+            if !data.article_tags.contains(&(article_id, target_id)) {
+                data.article_tags.push((article_id, target_id));
+                moved += 1;
+            }
+        }
+        data.remove_tag(source_id);
+        Ok(moved)
+    }
+
+    fn delete_tag(&self, name: &str) -> Result<bool> {
+        let mut data = self.data();
+        let Some(tag_id) = data.tag_id(name) else {
+            return Ok(false);
+        };
+        data.remove_tag(tag_id);
+        Ok(true)
+    }
+
+    fn add_to_queue(&self, article_id: i64) -> Result<bool> {
+        let mut data = self.data();
+        data.ensure_article(article_id)?;
+        if data.queue.iter().any(|&(id, _)| id == article_id) {
+            return Ok(false);
+        }
+        data.queue.push((article_id, Utc::now()));
+        Ok(true)
+    }
+
+    fn remove_from_queue(&self, article_id: i64) -> Result<bool> {
+        let mut data = self.data();
+        let before = data.queue.len();
+        data.queue.retain(|&(id, _)| id != article_id);
+        Ok(data.queue.len() < before)
+    }
+
+    fn get_queue(&self) -> Result<Vec<QueueEntry>> {
+        let data = self.data();
+        Ok(data
+            .queue
+            .iter()
+            .enumerate()
+            .filter_map(|(position, &(article_id, added_at))| {
+                Some(QueueEntry {
+                    article: data.with_duplicate_count(data.article(article_id)?),
+                    position: position as i64,
+                    added_at,
+                })
+            })
+            .collect())
+    }
+
+    fn move_in_queue(&self, article_id: i64, position: usize) -> Result<bool> {
+        let mut data = self.data();
+        let Some(current) = data.queue.iter().position(|&(id, _)| id == article_id) else {
+            return Ok(false);
+        };
+        let entry = data.queue.remove(current);
+        let position = position.min(data.queue.len());
+        data.queue.insert(position, entry);
+        Ok(true)
+    }
+
+    fn clear_queue(&self) -> Result<usize> {
+        let mut data = self.data();
+        let count = data.queue.len();
+        data.queue.clear();
+        Ok(count)
+    }
+
+    fn get_note(&self, article_id: i64) -> Result<Option<Note>> {
+        Ok(self.data().notes.get(&article_id).cloned())
+    }
+
+    fn save_note(&self, article_id: i64, body: &str) -> Result<()> {
+        let mut data = self.data();
+        data.ensure_article(article_id)?;
+
+        if body.trim().is_empty() {
+            data.notes.remove(&article_id);
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let note = data.notes.entry(article_id).or_insert_with(|| Note {
+            article_id,
+            body: String::new(),
+            created_at: now,
+            updated_at: now,
+        });
+        note.body = body.to_string();
+        note.updated_at = now;
+        Ok(())
+    }
+
+    fn delete_note(&self, article_id: i64) -> Result<bool> {
+        Ok(self.data().notes.remove(&article_id).is_some())
+    }
+
+    fn get_notes(&self) -> Result<Vec<Note>> {
+        let mut notes: Vec<Note> = self.data().notes.values().cloned().collect();
+        notes.sort_by_key(|note| std::cmp::Reverse(note.updated_at));
+        Ok(notes)
+    }
+
+    fn add_highlight(&self, article_id: i64, start: usize, end: usize) -> Result<Highlight> {
+        let mut data = self.data();
+        let content = html::render_text(
+            data.ensure_article(article_id)?
+                .content
+                .as_deref()
+                .unwrap_or_default(),
+        );
+
+        let length = content.chars().count();
+        if start >= end || end > length {
+            anyhow::bail!(
+                "Invalid highlight range {}..{} (article content has {} characters)",
+                start,
+                end,
+                length
+            );
+        }
+
+        let highlight = Highlight {
+            id: next_id(&mut data.last_highlight_id),
+            article_id,
+            start,
+            end,
+            text: content.chars().skip(start).take(end - start).collect(),
+            created_at: Utc::now(),
+        };
+        data.highlights.push(highlight.clone());
+        Ok(highlight)
+    }
+
+    fn get_highlights(&self, article_id: i64) -> Result<Vec<Highlight>> {
+        let mut highlights: Vec<Highlight> = self
+            .data()
+            .highlights
+            .iter()
+            .filter(|highlight| highlight.article_id == article_id)
+            .cloned()
+            .collect();
+        highlights.sort_by_key(|highlight| (highlight.start, highlight.id));
+        Ok(highlights)
+    }
+
+    fn delete_highlight(&self, id: i64) -> Result<bool> {
+        let mut data = self.data();
+        let before = data.highlights.len();
+        data.highlights.retain(|highlight| highlight.id != id);
+        Ok(data.highlights.len() < before)
+    }
+
+    fn try_acquire_fetch_lease(&self) -> Result<Option<FetchLease>> {
+        let mut data = self.data();
+        if data
+            .fetch_lease
+            .as_ref()
+            .is_some_and(|lease| lease.expires_at >= Utc::now())
+        {
+            return Ok(None);
+        }
+
+        let lease = new_fetch_lease();
+        data.fetch_lease = Some(lease.clone());
+        Ok(Some(lease))
+    }
+
+    fn renew_fetch_lease(&self, lease: &mut FetchLease) -> Result<bool> {
+        let mut data = self.data();
+        let Some(held) = data
+            .fetch_lease
+            .as_mut()
+            .filter(|held| held.holder == lease.holder)
+        else {
+            return Ok(false);
+        };
+        held.expires_at = Utc::now() + chrono::Duration::seconds(FETCH_LEASE_TTL_SECS);
+        lease.expires_at = held.expires_at;
+        Ok(true)
+    }
+
+    fn release_fetch_lease(&self, lease: &FetchLease) -> Result<()> {
+        let mut data = self.data();
+        if data
+            .fetch_lease
+            .as_ref()
+            .is_some_and(|held| held.holder == lease.holder)
+        {
+            data.fetch_lease = None;
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use chrono::TimeZone;
+    use rusqlite::Connection;
+
+    /// 同じ操作を行い、結果を比較できる形で返す
+    ///
+    /// SQLite 版とインメモリ版で結果が一致することを確認するために使います。
+    fn exercise(storage: &dyn Storage) -> Vec<String> {
+        let mut feed = Feed::new(
+            "https://example.com/feed".to_string(),
+            "Example".to_string(),
+            None,
+        );
+        let feed_id = storage.add_feed(&feed).unwrap();
+        feed.url = "https://other.example.com/feed".to_string();
+        let other_id = storage.add_feed(&feed).unwrap();
+        storage.set_feed_priority(other_id, 5).unwrap();
+        // 表記が違うだけの同じURLは登録できない
+        feed.url = "http://www.example.com/feed/?utm_source=x".to_string();
+        assert!(storage.add_feed(&feed).is_err());
+        assert_eq!(
+            storage
+                .find_feed_by_url(&feed.url)
+                .unwrap()
+                .map(|feed| feed.id),
+            Some(feed_id)
+        );
+
+        let mut ids = Vec::new();
+        for day in 1..=6 {
+            let published =
+                (day != 3).then(|| Utc.with_ymd_and_hms(2025, 1, day, 0, 0, 0).unwrap());
+            let article = Article::new(
+                if day % 2 == 0 { other_id } else { feed_id },
+                format!(
+                    "Article {} about {}",
+                    day,
+                    if day < 4 { "Rust" } else { "Go" }
+                ),
+                Some(format!("https://example.com/{}", day)),
+                None,
+                published,
+            );
+            ids.push(storage.add_article(&article).unwrap().unwrap());
+        }
+        // 同じフィード・同じURLの記事は追加されない
+        let duplicate = Article::new(
+            feed_id,
+            "Duplicate".to_string(),
+            Some("https://example.com/1".to_string()),
+            None,
+            None,
+        );
+        assert_eq!(storage.add_article(&duplicate).unwrap(), None);
+
+        // まとめて追加すると重複分は除かれ、存在しないフィードが混ざると1件も追加されない
+        let mut batch = vec![duplicate.clone(), duplicate];
+        batch[1].url = Some("https://example.com/7".to_string());
+        assert_eq!(storage.add_articles(&batch).unwrap().len(), 1);
+        batch[0].feed_id = other_id + 100;
+        batch[1].url = Some("https://example.com/8".to_string());
+        assert!(storage.add_articles(&batch).is_err());
+
+        storage.mark_as_read(ids[0]).unwrap();
+        storage.toggle_favorite(ids[1]).unwrap();
+        storage.tag_article(ids[2], &["rust", "Later"]).unwrap();
+        storage.add_to_queue(ids[3]).unwrap();
+        storage.add_to_queue(ids[4]).unwrap();
+        storage.move_in_queue(ids[4], 0).unwrap();
+        storage.mark_as_read(ids[3]).unwrap();
+        storage.toggle_read_status(ids[3]).unwrap();
+
+        let mut results = Vec::new();
+        let titles = |articles: Vec<Article>| {
+            articles
+                .into_iter()
+                .map(|a| a.title)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let feeds = storage.get_feeds(None).unwrap();
+        results.push(format!(
+            "{:?}",
+            feeds.iter().map(|f| f.id).collect::<Vec<_>>()
+        ));
+        results.push(format!(
+            "{:?}",
+            storage.get_article_counts(feed_id).unwrap()
+        ));
+        results.push(titles(
+            storage.query_articles(&ArticleQuery::new()).unwrap(),
+        ));
+        results.push(titles(
+            storage
+                .query_articles(&ArticleQuery::new().unread_only().keyword("rust"))
+                .unwrap(),
+        ));
+        // 検索語はキーワードの絞り込みを広げず、さらに絞り込む
+        let searched = titles(
+            storage
+                .query_articles(
+                    &ArticleQuery::new()
+                        .keywords_from_str("go, 2")
+                        .search_from_str("rust"),
+                )
+                .unwrap(),
+        );
+        assert_eq!(searched, "Article 2 about Rust");
+        results.push(searched);
+        results.push(titles(
+            storage
+                .query_articles(&ArticleQuery::new().tag("LATER").sort(SortOrder::Oldest))
+                .unwrap(),
+        ));
+        results.push(titles(storage.get_favorite_articles(10).unwrap()));
+
+        // 一時停止中のフィードの記事は除外できる
+        assert!(storage.set_feed_paused(other_id, true).unwrap());
+        assert!(!storage.set_feed_paused(other_id + 100, true).unwrap());
+        results.push(titles(
+            storage
+                .query_articles(&ArticleQuery::new().exclude_paused())
+                .unwrap(),
+        ));
+        storage.set_feed_paused(other_id, false).unwrap();
+
+        // トラッキング用パラメータだけが違うURLは重複記事になり、元の記事が既読なら既読になる
+        let copy = Article::new(
+            other_id,
+            "Copy".to_string(),
+            Some("http://example.com/1?utm_source=other".to_string()),
+            None,
+            None,
+        );
+        let copy_id = storage.add_article(&copy).unwrap().unwrap();
+        let copy = storage.get_article(copy_id).unwrap().unwrap();
+        results.push(format!(
+            "{:?} {} {}",
+            copy.duplicate_of.map(|id| id == ids[0]),
+            copy.is_read,
+            storage
+                .get_article(ids[0])
+                .unwrap()
+                .unwrap()
+                .duplicate_count
+        ));
+        results.push(titles(
+            storage
+                .query_articles(&ArticleQuery::new().collapse_duplicates())
+                .unwrap(),
+        ));
+        storage.set_feed_paused(feed_id, true).unwrap();
+        results.push(titles(
+            storage
+                .query_articles(&ArticleQuery::new().exclude_paused().collapse_duplicates())
+                .unwrap(),
+        ));
+        storage.set_feed_paused(feed_id, false).unwrap();
+
+        // カーソルで全ページを辿る
+        let mut cursor = None;
+        loop {
+            let page = storage
+                .query_articles_page(&ArticleQuery::new(), cursor.as_deref(), 4)
+                .unwrap();
+            results.push(titles(page.articles));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        let tags = storage.get_tags().unwrap();
+        results.push(format!(
+            "{:?}",
+            tags.iter()
+                .map(|(tag, count)| (tag.name.clone(), *count))
+                .collect::<Vec<_>>()
+        ));
+        results.push(format!(
+            "{:?}",
+            storage.get_article(ids[3]).unwrap().unwrap().is_read
+        ));
+
+        results.push(format!(
+            "{:?}",
+            storage
+                .get_queue()
+                .unwrap()
+                .iter()
+                .map(|entry| (entry.article.id, entry.position))
+                .collect::<Vec<_>>()
+        ));
+
+        // 既読にした記事はキューから外れる
+        assert!(!storage.remove_from_queue(ids[0]).unwrap());
+        assert!(storage.remove_from_queue(ids[4]).unwrap());
+        storage.add_to_queue(ids[5]).unwrap();
+        results.push(format!(
+            "{:?} {}",
+            storage
+                .next_in_queue()
+                .unwrap()
+                .map(|article| article.id == ids[5]),
+            storage.clear_queue().unwrap()
+        ));
+        assert!(storage.next_in_queue().unwrap().is_none());
+
+        // タグの名前の変更・統合・削除（タグ名は大文字小文字を区別しない）
+        storage.tag_article(ids[1], &["go", "later"]).unwrap();
+        results.push(format!(
+            "{}",
+            storage
+                .untag_article(ids[1], &["LATER", "missing"])
+                .unwrap()
+        ));
+        storage.tag_article(ids[0], &["later"]).unwrap();
+        assert!(storage.rename_tag("rust", "Go").is_err());
+        assert!(storage.merge_tags("missing", "rust").is_err());
+        results.push(format!(
+            "{} {} {} {} {}",
+            storage.rename_tag("rust", "Rust").unwrap(),
+            storage.rename_tag("missing", "other").unwrap(),
+            storage.merge_tags("later", "rust").unwrap(),
+            storage.delete_tag("GO").unwrap(),
+            storage.delete_tag("go").unwrap()
+        ));
+        results.push(format!(
+            "{:?} {:?} {}",
+            storage.get_article_tags(ids[0]).unwrap(),
+            storage.get_article_tags(ids[1]).unwrap(),
+            storage.get_tags().unwrap().len()
+        ));
+
+        // メモとハイライトの削除
+        storage.save_note(ids[0], "first").unwrap();
+        storage.save_note(ids[1], "second").unwrap();
+        results.push(format!(
+            "{} {} {:?} {}",
+            storage.delete_note(ids[0]).unwrap(),
+            storage.delete_note(ids[0]).unwrap(),
+            storage
+                .get_notes()
+                .unwrap()
+                .iter()
+                .map(|note| note.body.as_str())
+                .collect::<Vec<_>>(),
+            storage.delete_highlight(1).unwrap()
+        ));
+
+        assert!(storage.remove_feed(feed_id).unwrap());
+        results.push(format!(
+            "{:?}",
+            storage.get_article_counts(feed_id).unwrap()
+        ));
+        results.push(titles(
+            storage.query_articles(&ArticleQuery::new()).unwrap(),
+        ));
+
+        results
+    }
+
+    /// インメモリ版が SQLite 版と同じ結果を返すことを確認するテスト
+    #[test]
+    fn test_memory_storage_matches_database() {
+        let db = Database::from_connection(Connection::open_in_memory().unwrap()).unwrap();
+        db.init().unwrap();
+
+        let expected = exercise(&db);
+        let actual = exercise(&MemoryStorage::new());
+
+        assert_eq!(actual, expected);
+    }
+
+    /// フェッチのリースが同時に1つしか取得できないことを確認するテスト
+    #[test]
+    fn test_memory_fetch_lease_is_exclusive() {
+        let storage = MemoryStorage::new();
+        let shared = storage.clone();
+
+        let mut lease = storage.try_acquire_fetch_lease().unwrap().unwrap();
+        assert!(shared.try_acquire_fetch_lease().unwrap().is_none());
+        assert!(storage.renew_fetch_lease(&mut lease).unwrap());
+
+        storage.release_fetch_lease(&lease).unwrap();
+        assert!(shared.try_acquire_fetch_lease().unwrap().is_some());
+    }
+}
@@ -6,6 +6,10 @@
 //!
 //! - [`Feed`] - RSSフィードのメタデータ
 //! - [`Article`] - 個別の記事データ
+//! - [`Tag`] - 記事に付けるタグ
+//! - [`Category`] - フィードを分類する階層カテゴリ（フォルダ）
+//! - [`QueueEntry`] - 「あとで読む」キューの項目
+//! - [`Note`] / [`Highlight`] - 記事に付けるメモとハイライト
 //!
 //! ## Serdeについて
 //!
@@ -33,8 +37,11 @@ use serde::{Deserialize, Serialize};
 /// | `created_at` | `DateTime<Utc>` | 作成日時 |
 /// | `updated_at` | `DateTime<Utc>` | 更新日時 |
 /// | `custom_name` | `Option<String>` | カスタム名（NULLの場合はtitleを使用） |
-/// | `category` | `Option<String>` | カテゴリ（任意） |
+/// | `category` | `Option<String>` | カテゴリのパス（任意、読み取り専用） |
 /// | `priority` | `i64` | 優先順位（デフォルト0、高いほど優先） |
+/// | `category_id` | `Option<i64>` | 所属するカテゴリのID（任意） |
+/// | `paused` | `bool` | 一時停止中かどうか |
+/// | `site_url` | `Option<String>` | フィードを配信しているサイトのURL（任意） |
 ///
 /// # Derive マクロの説明
 ///
@@ -94,15 +101,38 @@ pub struct Feed {
     /// ユーザーが設定した任意の名前。NULLの場合は `title` が表示に使われます。
     pub custom_name: Option<String>,
 
-    /// カテゴリ（オプショナル）
+    /// カテゴリのパス（オプショナル）
     ///
-    /// フィードをグループ化するためのカテゴリ。例: "Tech", "News", "Blogs"
+    /// 所属するカテゴリを `/` 区切りのパスで表したもの。例: "Tech", "Tech/Rust"
+    /// データベースから読み込む際に `category_id` から組み立てられます。
+    /// `Database::add_feed` では `category_id` が未設定の場合にこのパスが使われます。
+    /// 登録済みのフィードのカテゴリを変更する場合は `Database::set_feed_category` を使ってください。
     pub category: Option<String>,
 
     /// 優先順位
     ///
     /// 表示順序を決定する優先度。デフォルトは0で、値が高いほど優先的に表示されます。
     pub priority: i64,
+
+    /// 所属するカテゴリのID（オプショナル）
+    ///
+    /// `categories` テーブルの id を参照します。
+    #[serde(default)]
+    pub category_id: Option<i64>,
+
+    /// 一時停止中かどうか
+    ///
+    /// 一時停止中のフィードは一括更新（`rustfeed fetch` など）で取得されず、
+    /// フィードを指定しない記事一覧にも表示されません。
+    #[serde(default)]
+    pub paused: bool,
+
+    /// フィードを配信しているサイトのURL（オプショナル）
+    ///
+    /// フィードの `<link>` や OPML の `htmlUrl` から取得します。
+    /// OPML に書き出すときの `htmlUrl` に使われます。
+    #[serde(default)]
+    pub site_url: Option<String>,
 }
 
 impl Feed {
@@ -118,7 +148,8 @@ impl Feed {
     ///
     /// 新しい `Feed` インスタンス。`id` は 0 に初期化され、
     /// `created_at` と `updated_at` は現在時刻に設定されます。
-    /// `custom_name` と `category` は None、`priority` は 0 に初期化されます。
+    /// `custom_name`、`category`、`category_id` は None、`priority` は 0、
+    /// `paused` は false、`site_url` は None に初期化されます。
     ///
     /// # 所有権について
     ///
@@ -152,6 +183,9 @@ impl Feed {
             custom_name: None, // デフォルトはNone（titleを使用）
             category: None,    // デフォルトはNone（カテゴリなし）
             priority: 0,       // デフォルト優先順位は0
+            category_id: None,
+            paused: false,
+            site_url: None,
         }
     }
 
@@ -182,6 +216,10 @@ impl Feed {
 /// | `published_at` | `Option<DateTime<Utc>>` | 公開日時（任意） |
 /// | `is_read` | `bool` | 既読フラグ |
 /// | `created_at` | `DateTime<Utc>` | 取得日時 |
+/// | `read_at` | `Option<DateTime<Utc>>` | 既読にした日時（未読ならNone） |
+/// | `first_opened_at` | `Option<DateTime<Utc>>` | 初めて開いた日時（任意） |
+/// | `duplicate_of` | `Option<i64>` | 重複記事の場合、元の記事のID |
+/// | `duplicate_count` | `usize` | この記事を元とする重複記事の数（読み取り専用） |
 ///
 /// # 例
 ///
@@ -238,6 +276,30 @@ pub struct Article {
 
     /// この記事をデータベースに保存した日時
     pub created_at: DateTime<Utc>,
+
+    /// 既読にした日時（未読の場合は None）
+    ///
+    /// 未読に戻すとクリアされます。過去に読んだ記録は読書履歴に残ります。
+    #[serde(default)]
+    pub read_at: Option<DateTime<Utc>>,
+
+    /// 記事を初めて開いた日時（まだ開いていない場合は None）
+    #[serde(default)]
+    pub first_opened_at: Option<DateTime<Utc>>,
+
+    /// 重複記事の場合、元の記事のID（元の記事自身や重複のない記事は None）
+    ///
+    /// 別のフィードから届いた同じ記事を、追加時に元の記事へ関連付けます
+    /// （判定方法は [`crate::dedup`] を参照）。重複記事のどれかを既読にすると、
+    /// 元の記事と他の重複記事も既読になります。
+    #[serde(default)]
+    pub duplicate_of: Option<i64>,
+
+    /// この記事を元とする重複記事の数
+    ///
+    /// データベースから読み込む際に求められます（保存はされません）。
+    #[serde(default)]
+    pub duplicate_count: usize,
 }
 
 impl Article {
@@ -286,6 +348,243 @@ impl Article {
             is_read: false,     // 新規記事は未読状態で作成
             is_favorite: false, // 新規記事はお気に入りでない状態で作成
             created_at: Utc::now(),
+            read_at: None,
+            first_opened_at: None,
+            duplicate_of: None,
+            duplicate_count: 0,
+        }
+    }
+}
+
+// =============================================================================
+// Tag 構造体
+// =============================================================================
+
+/// 記事に付けるタグ
+///
+/// 記事とタグは多対多の関係で、`article_tags` テーブルで関連付けられます。
+/// タグ名は大文字小文字を区別せずに一意です（"Rust" と "rust" は同じタグ）。
+///
+/// # フィールド
+///
+/// | フィールド | 型 | 説明 |
+/// |------------|-----|------|
+/// | `id` | `i64` | データベースで自動採番されるID |
+/// | `name` | `String` | タグ名 |
+/// | `created_at` | `DateTime<Utc>` | 作成日時 |
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tag {
+    /// データベースで自動採番されるユニークID
+    pub id: i64,
+
+    /// タグ名
+    pub name: String,
+
+    /// 作成日時（UTC）
+    pub created_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// Category 構造体
+// =============================================================================
+
+/// フィードを分類する階層カテゴリ（フォルダ）
+///
+/// カテゴリは `parent_id` で親カテゴリを参照し、木構造を作ります。
+/// 同じ親の下ではカテゴリ名は大文字小文字を区別せずに一意です。
+/// カテゴリは `/` 区切りのパス（例: "Tech/Rust"）で指定します。
+///
+/// # フィールド
+///
+/// | フィールド | 型 | 説明 |
+/// |------------|-----|------|
+/// | `id` | `i64` | データベースで自動採番されるID |
+/// | `name` | `String` | カテゴリ名（`/` を含まない） |
+/// | `parent_id` | `Option<i64>` | 親カテゴリのID（トップレベルは None） |
+/// | `position` | `i64` | 同じ親の中での並び順（小さいほど先） |
+/// | `path` | `String` | ルートからのパス（例: "Tech/Rust"） |
+/// | `depth` | `usize` | 階層の深さ（トップレベルは0） |
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Category {
+    /// データベースで自動採番されるユニークID
+    pub id: i64,
+
+    /// カテゴリ名
+    pub name: String,
+
+    /// 親カテゴリのID（トップレベルは None）
+    pub parent_id: Option<i64>,
+
+    /// 同じ親の中での並び順
+    pub position: i64,
+
+    /// ルートからのパス（読み込み時に組み立てられる）
+    pub path: String,
+
+    /// 階層の深さ（トップレベルは0）
+    pub depth: usize,
+}
+
+// =============================================================================
+// QueueEntry 構造体
+// =============================================================================
+
+/// 「あとで読む」キューの項目
+///
+/// お気に入り（長期保存）とは別に、これから読む記事を順番付きで管理します。
+/// 記事が既読になると自動的にキューから外れます。
+///
+/// # フィールド
+///
+/// | フィールド | 型 | 説明 |
+/// |------------|-----|------|
+/// | `article` | `Article` | キューに入っている記事 |
+/// | `position` | `i64` | キュー内の順番（小さいほど先） |
+/// | `added_at` | `DateTime<Utc>` | キューに追加した日時 |
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueEntry {
+    /// キューに入っている記事
+    #[serde(flatten)]
+    pub article: Article,
+
+    /// キュー内の順番（小さいほど先）
+    pub position: i64,
+
+    /// キューに追加した日時（UTC）
+    pub added_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// Note / Highlight 構造体
+// =============================================================================
+
+/// 記事に付ける自由記述のメモ
+///
+/// メモは1つの記事につき1つです。
+///
+/// # フィールド
+///
+/// | フィールド | 型 | 説明 |
+/// |------------|-----|------|
+/// | `article_id` | `i64` | メモを付けた記事のID |
+/// | `body` | `String` | メモの本文 |
+/// | `created_at` | `DateTime<Utc>` | 作成日時 |
+/// | `updated_at` | `DateTime<Utc>` | 最終更新日時 |
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Note {
+    /// メモを付けた記事のID
+    pub article_id: i64,
+
+    /// メモの本文
+    pub body: String,
+
+    /// 作成日時（UTC）
+    pub created_at: DateTime<Utc>,
+
+    /// 最終更新日時（UTC）
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 記事の本文中で保存したハイライト範囲
+///
+/// 範囲は記事の `content` 内の文字オフセット（バイトではなく文字単位）で、
+/// `start` を含み `end` を含みません。本文が後から変わっても内容が分かるように、
+/// ハイライトした時点のテキストも保存します。
+///
+/// # フィールド
+///
+/// | フィールド | 型 | 説明 |
+/// |------------|-----|------|
+/// | `id` | `i64` | データベースで自動採番されるID |
+/// | `article_id` | `i64` | ハイライトした記事のID |
+/// | `start` | `usize` | 表示用のテキスト上の開始位置（文字単位、含む） |
+/// | `end` | `usize` | 表示用のテキスト上の終了位置（文字単位、含まない） |
+/// | `text` | `String` | ハイライトしたテキスト |
+/// | `created_at` | `DateTime<Utc>` | 作成日時 |
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Highlight {
+    /// データベースで自動採番されるユニークID
+    pub id: i64,
+
+    /// ハイライトした記事のID
+    pub article_id: i64,
+
+    /// 開始位置（文字単位、含む）
+    ///
+    /// 本文そのものではなく、`html::render_text` で変換した表示用のテキスト上の位置です。
+    pub start: usize,
+
+    /// 終了位置（文字単位、含まない）
+    pub end: usize,
+
+    /// ハイライトしたテキスト
+    pub text: String,
+
+    /// 作成日時（UTC）
+    pub created_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// 読書履歴
+// =============================================================================
+
+/// 読書履歴に記録される出来事の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryEvent {
+    /// 記事を開いた（プレビュー・ブラウザ・本文の取得）
+    Opened,
+    /// 既読にした
+    Read,
+    /// 未読に戻した
+    Unread,
+}
+
+impl HistoryEvent {
+    /// データベースに保存する文字列表現を返す
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HistoryEvent::Opened => "opened",
+            HistoryEvent::Read => "read",
+            HistoryEvent::Unread => "unread",
         }
     }
+
+    /// データベースの文字列表現から変換する（不明な値は None）
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "opened" => Some(HistoryEvent::Opened),
+            "read" => Some(HistoryEvent::Read),
+            "unread" => Some(HistoryEvent::Unread),
+            _ => None,
+        }
+    }
+}
+
+/// 読書履歴の1件（記事の状態が変わった記録）
+///
+/// 履歴は追記のみで、記事を未読に戻しても過去の記録は消えません。
+///
+/// # フィールド
+///
+/// | フィールド | 型 | 説明 |
+/// |------------|-----|------|
+/// | `history_id` | `i64` | データベースで自動採番されるID |
+/// | `article` | `Article` | 対象の記事（現在の状態） |
+/// | `event` | `HistoryEvent` | 出来事の種類 |
+/// | `occurred_at` | `DateTime<Utc>` | 出来事が起きた日時 |
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// データベースで自動採番されるユニークID
+    pub history_id: i64,
+
+    /// 対象の記事（現在の状態）
+    #[serde(flatten)]
+    pub article: Article,
+
+    /// 出来事の種類
+    pub event: HistoryEvent,
+
+    /// 出来事が起きた日時（UTC）
+    pub occurred_at: DateTime<Utc>,
 }
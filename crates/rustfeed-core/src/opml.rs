@@ -0,0 +1,406 @@
+//! # OPML モジュール
+//!
+//! 他のフィードリーダーとの購読リストのやり取りに使う OPML の読み込みと書き出しを提供します。
+//!
+//! ## 概要
+//!
+//! OPML 1.0 / 2.0 の `<outline>` を次のように解釈します。
+//!
+//! - `xmlUrl` 属性を持つ `<outline>` はフィード（[`OpmlFeed`]）
+//! - `xmlUrl` 属性を持たず、子の `<outline>` を持つものはカテゴリ
+//!   （入れ子は `Tech/Rust` のようなカテゴリのパスになる）
+//! - フィードの `text` 属性（なければ `title` 属性）はカスタム名
+//!
+//! 読み込んだフィードは `Database::import_opml` で登録します。
+//! 登録済みのURL（[`crate::dedup::feed_url_key`] が同じもの）は登録せず、
+//! フィードごとの結果（[`OpmlImportEntry`]）を返します。
+//!
+//! [`write_opml`] は逆に、フィードを OPML 2.0 で書き出します。カテゴリは入れ子の `<outline>`、
+//! 表示名（カスタム名、なければタイトル）は `text` になるため、書き出したファイルを
+//! そのまま読み込み直せます。
+//!
+//! ## 使用例
+//!
+//! ```rust
+//! use rustfeed_core::opml::parse_opml;
+//!
+//! let feeds = parse_opml(
+//!     r#"<opml version="2.0"><body>
+//!          <outline text="Tech">
+//!            <outline text="Rust Blog" xmlUrl="https://blog.rust-lang.org/feed.xml"/>
+//!          </outline>
+//!        </body></opml>"#,
+//! )?;
+//! assert_eq!(feeds[0].category.as_deref(), Some("Tech"));
+//! assert_eq!(feeds[0].name.as_deref(), Some("Rust Blog"));
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, Event};
+use quick_xml::{Reader, Writer};
+use serde::Serialize;
+
+use crate::db::category_path_segments;
+use crate::models::Feed;
+use crate::xml::write_text_element;
+
+// =============================================================================
+// データ型
+// =============================================================================
+
+/// OPML から読み込んだフィード
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OpmlFeed {
+    /// フィードのURL（`xmlUrl` 属性）
+    pub url: String,
+
+    /// カスタム名（`text` 属性、なければ `title` 属性）
+    pub name: Option<String>,
+
+    /// サイトのURL（`htmlUrl` 属性）
+    pub html_url: Option<String>,
+
+    /// カテゴリのパス（親の `<outline>` の名前を `/` でつないだもの）
+    pub category: Option<String>,
+}
+
+/// OPML のフィード1件の登録結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OpmlImportEntry {
+    /// OPML に書かれていたフィードのURL
+    pub url: String,
+
+    /// カスタム名
+    pub name: Option<String>,
+
+    /// カテゴリのパス
+    pub category: Option<String>,
+
+    /// 登録結果
+    pub status: OpmlImportStatus,
+}
+
+/// OPML のフィードの登録結果の種類
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OpmlImportStatus {
+    /// 新しく登録した
+    Added { feed_id: i64 },
+
+    /// 同じURLのフィードが登録済みのため登録しなかった
+    AlreadySubscribed { feed_id: i64 },
+
+    /// 登録できなかった（URLが不正など）
+    Failed { error: String },
+}
+
+// =============================================================================
+// 読み込み
+// =============================================================================
+
+/// OPML の文字列からフィードを読み込む
+///
+/// ルート要素が `<opml>` でない場合や、XML として解釈できない場合はエラーになります。
+/// 名前のないカテゴリの `<outline>` は、カテゴリの階層に含めません。
+pub fn parse_opml(xml: &str) -> Result<Vec<OpmlFeed>> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut feeds = Vec::new();
+    // 開いている `<outline>` ごとのカテゴリ名（フィードや名前のないものは None）
+    let mut outlines: Vec<Option<String>> = Vec::new();
+    let mut seen_root = false;
+
+    loop {
+        let event = reader
+            .read_event()
+            .with_context(|| format!("Invalid OPML at byte {}", reader.buffer_position()))?;
+        match event {
+            Event::Start(element) | Event::Empty(element) if !seen_root => {
+                if element.local_name().as_ref() != b"opml" {
+                    bail!("Not an OPML file: the root element must be <opml>");
+                }
+                seen_root = true;
+            }
+            Event::Start(element) if element.local_name().as_ref() == b"outline" => {
+                let outline = read_outline(&reader, &element, &outlines)?;
+                let category = match outline {
+                    Some(feed) => {
+                        feeds.push(feed);
+                        None
+                    }
+                    None => outline_name(&reader, &element)?,
+                };
+                outlines.push(category);
+            }
+            Event::Empty(element) if element.local_name().as_ref() == b"outline" => {
+                if let Some(feed) = read_outline(&reader, &element, &outlines)? {
+                    feeds.push(feed);
+                }
+            }
+            Event::End(element) if element.local_name().as_ref() == b"outline" => {
+                outlines.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !seen_root {
+        bail!("Not an OPML file: the root element must be <opml>");
+    }
+    Ok(feeds)
+}
+
+/// `<outline>` がフィードならその内容を返す（カテゴリなら `None`）
+fn read_outline(
+    reader: &Reader<&[u8]>,
+    element: &BytesStart,
+    outlines: &[Option<String>],
+) -> Result<Option<OpmlFeed>> {
+    let Some(url) = attribute(reader, element, "xmlUrl")? else {
+        return Ok(None);
+    };
+    let path: Vec<&str> = outlines.iter().flatten().map(String::as_str).collect();
+    Ok(Some(OpmlFeed {
+        url,
+        name: outline_name(reader, element)?,
+        html_url: attribute(reader, element, "htmlUrl")?,
+        category: (!path.is_empty()).then(|| path.join("/")),
+    }))
+}
+
+/// `<outline>` の名前（`text` 属性、なければ `title` 属性）
+fn outline_name(reader: &Reader<&[u8]>, element: &BytesStart) -> Result<Option<String>> {
+    match attribute(reader, element, "text")? {
+        Some(text) => Ok(Some(text)),
+        None => attribute(reader, element, "title"),
+    }
+}
+
+/// 属性の値を取得する（属性名の大文字と小文字は区別しない、空の値は `None`）
+///
+/// OPML 1.0 を書き出すリーダーには `xmlurl` のように小文字で書くものがあるため、
+/// 大文字と小文字を区別せずに探します。
+fn attribute(reader: &Reader<&[u8]>, element: &BytesStart, name: &str) -> Result<Option<String>> {
+    for attr in element.attributes() {
+        let attr = attr.context("Invalid OPML attribute")?;
+        if attr
+            .key
+            .local_name()
+            .as_ref()
+            .eq_ignore_ascii_case(name.as_bytes())
+        {
+            let value = attr
+                .decode_and_unescape_value(reader)
+                .context("Invalid OPML attribute")?;
+            let value = value.trim();
+            return Ok((!value.is_empty()).then(|| value.to_string()));
+        }
+    }
+    Ok(None)
+}
+
+// =============================================================================
+// 書き出し
+// =============================================================================
+
+/// フィードを OPML 2.0 で書き出す
+///
+/// フィードのカテゴリのパスを入れ子の `<outline>` にします。カテゴリとフィードは
+/// `feeds` に現れた順に並べ、各階層ではカテゴリをフィードより先に書きます。
+///
+/// # 引数
+///
+/// * `title` - `<head>` の `<title>` に書くタイトル
+/// * `feeds` - 書き出すフィード
+pub fn write_opml(title: &str, feeds: &[Feed]) -> Result<String> {
+    let mut root = Folder::default();
+    for feed in feeds {
+        let segments = feed
+            .category
+            .as_deref()
+            .map(category_path_segments)
+            .unwrap_or_default();
+        root.insert(&segments, feed);
+    }
+
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    writer.write_event(Event::Start(
+        BytesStart::new("opml").with_attributes([("version", "2.0")]),
+    ))?;
+
+    writer.write_event(Event::Start(BytesStart::new("head")))?;
+    write_text_element(&mut writer, "title", title)?;
+    write_text_element(&mut writer, "dateCreated", &Utc::now().to_rfc2822())?;
+    writer.write_event(Event::End(BytesEnd::new("head")))?;
+
+    writer.write_event(Event::Start(BytesStart::new("body")))?;
+    root.write(&mut writer)?;
+    writer.write_event(Event::End(BytesEnd::new("body")))?;
+    writer.write_event(Event::End(BytesEnd::new("opml")))?;
+
+    let mut xml = String::from_utf8(writer.into_inner()).context("OPML is not valid UTF-8")?;
+    xml.push('\n');
+    Ok(xml)
+}
+
+/// 書き出すカテゴリ1つ分（サブカテゴリとフィード）
+#[derive(Default)]
+struct Folder<'a> {
+    name: &'a str,
+    folders: Vec<Folder<'a>>,
+    feeds: Vec<&'a Feed>,
+}
+
+impl<'a> Folder<'a> {
+    /// カテゴリのパスをたどって（なければ作って）フィードを追加する
+    fn insert(&mut self, segments: &[&'a str], feed: &'a Feed) {
+        let Some((first, rest)) = segments.split_first() else {
+            self.feeds.push(feed);
+            return;
+        };
+        let index = match self.folders.iter().position(|folder| folder.name == *first) {
+            Some(index) => index,
+            None => {
+                self.folders.push(Folder {
+                    name: first,
+                    ..Folder::default()
+                });
+                self.folders.len() - 1
+            }
+        };
+        self.folders[index].insert(rest, feed);
+    }
+
+    /// サブカテゴリとフィードを `<outline>` として書き出す
+    fn write(&self, writer: &mut Writer<Vec<u8>>) -> Result<()> {
+        for folder in &self.folders {
+            writer.write_event(Event::Start(
+                BytesStart::new("outline")
+                    .with_attributes([("text", folder.name), ("title", folder.name)]),
+            ))?;
+            folder.write(writer)?;
+            writer.write_event(Event::End(BytesEnd::new("outline")))?;
+        }
+        for feed in &self.feeds {
+            let name = feed.custom_name.as_deref().unwrap_or(&feed.title);
+            let mut outline = BytesStart::new("outline").with_attributes([
+                ("type", "rss"),
+                ("text", name),
+                ("title", name),
+                ("xmlUrl", feed.url.as_str()),
+            ]);
+            if let Some(site_url) = &feed.site_url {
+                outline.push_attribute(("htmlUrl", site_url.as_str()));
+            }
+            writer.write_event(Event::Empty(outline))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// OPML 2.0 の入れ子のカテゴリと OPML 1.0 の小文字の属性名を読み込めることを確認するテスト
+    #[test]
+    fn test_parse_opml() {
+        let feeds = parse_opml(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <opml version="2.0">
+              <head><title>Subscriptions</title></head>
+              <body>
+                <outline text="Tech" title="Tech">
+                  <outline text="Rust">
+                    <outline type="rss" text="Rust Blog" title="The Rust Blog"
+                             xmlUrl="https://blog.rust-lang.org/feed.xml"
+                             htmlUrl="https://blog.rust-lang.org/"/>
+                  </outline>
+                  <outline title="Tom &amp; Jerry" xmlUrl="https://example.com/tj.xml"/>
+                </outline>
+                <outline xmlUrl="https://example.com/plain.xml"/>
+              </body>
+            </opml>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            feeds,
+            vec![
+                OpmlFeed {
+                    url: "https://blog.rust-lang.org/feed.xml".to_string(),
+                    name: Some("Rust Blog".to_string()),
+                    html_url: Some("https://blog.rust-lang.org/".to_string()),
+                    category: Some("Tech/Rust".to_string()),
+                },
+                OpmlFeed {
+                    url: "https://example.com/tj.xml".to_string(),
+                    name: Some("Tom & Jerry".to_string()),
+                    html_url: None,
+                    category: Some("Tech".to_string()),
+                },
+                OpmlFeed {
+                    url: "https://example.com/plain.xml".to_string(),
+                    name: None,
+                    html_url: None,
+                    category: None,
+                },
+            ]
+        );
+
+        // OPML 1.0（属性名が小文字）
+        let feeds = parse_opml(
+            r#"<opml version="1.0"><body>
+                 <outline text="News"><outline text="HN" xmlurl="https://hnrss.org/frontpage"/></outline>
+               </body></opml>"#,
+        )
+        .unwrap();
+        assert_eq!(feeds[0].url, "https://hnrss.org/frontpage");
+        assert_eq!(feeds[0].category.as_deref(), Some("News"));
+
+        assert!(parse_opml("<rss><channel/></rss>").is_err());
+        assert!(parse_opml("<opml><body><outline").is_err());
+    }
+
+    /// 書き出した OPML を読み込むと、カテゴリ・表示名・URLが元に戻ることを確認するテスト
+    #[test]
+    fn test_write_opml_round_trip() {
+        let feed = |url: &str, title: &str, custom: Option<&str>, category: Option<&str>| Feed {
+            custom_name: custom.map(str::to_string),
+            category: category.map(str::to_string),
+            site_url: Some(url.replace("/feed", "/")),
+            ..Feed::new(url.to_string(), title.to_string(), None)
+        };
+        let feeds = vec![
+            feed("https://a.example.com/feed", "A", None, Some("Tech/Rust")),
+            feed("https://b.example.com/feed", "B", Some("B & co"), None),
+            feed("https://c.example.com/feed", "C", None, Some("Tech")),
+            feed("https://d.example.com/feed", "D", None, Some("Tech/Rust")),
+        ];
+
+        let xml = write_opml("rustfeed subscriptions", &feeds).unwrap();
+        assert!(xml.contains(r#"<opml version="2.0">"#));
+        assert!(xml.contains("B &amp; co"));
+
+        let expected = |url: &str, name: &str, category: Option<&str>| OpmlFeed {
+            url: url.to_string(),
+            name: Some(name.to_string()),
+            html_url: Some(url.replace("/feed", "/")),
+            category: category.map(str::to_string),
+        };
+        assert_eq!(
+            parse_opml(&xml).unwrap(),
+            vec![
+                expected("https://a.example.com/feed", "A", Some("Tech/Rust")),
+                expected("https://d.example.com/feed", "D", Some("Tech/Rust")),
+                expected("https://c.example.com/feed", "C", Some("Tech")),
+                expected("https://b.example.com/feed", "B & co", None),
+            ]
+        );
+    }
+}
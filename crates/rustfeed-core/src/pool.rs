@@ -0,0 +1,224 @@
+//! # コネクションプールモジュール
+//!
+//! 複数の tokio タスクやスレッドから同じデータベースを使うための
+//! [`DbPool`] を提供します。
+//!
+//! ## 概要
+//!
+//! [`Database`] は `rusqlite::Connection` を1つ持つだけで、`Sync` ではありません。
+//! `DbPool` は同じデータベースファイルへの接続を必要に応じて開き、
+//! 使い終わった接続を再利用します。`Clone` は安価（`Arc` の複製）なので、
+//! 各タスクにクローンを渡して使います。
+//!
+//! - [`DbPool::get`] は接続を1つ借ります（同期処理・UIスレッド向け）
+//! - [`DbPool::run`] は `spawn_blocking` 上で接続を借りて処理を実行します（非同期タスク向け）
+//!
+//! 接続ごとに独立しているため、バックグラウンドで記事を保存している間も
+//! 別の接続から記事一覧を読み込めます。
+//!
+//! ## 使用例
+//!
+//! ```rust,no_run
+//! use rustfeed_core::pool::DbPool;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let pool = DbPool::open()?;
+//!
+//! // バックグラウンドタスクで使う
+//! let background = pool.clone();
+//! let feeds = tokio::spawn(async move {
+//!     background.run(|db| db.get_feeds(None)).await
+//! })
+//! .await??;
+//!
+//! // 同期的に使う
+//! let db = pool.get()?;
+//! let unread = db.query_articles(&rustfeed_core::ArticleQuery::new().unread_only())?;
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::Result;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::db::Database;
+use crate::storage::run_blocking;
+
+/// プールに保持しておく未使用の接続数の上限
+const MAX_IDLE_CONNECTIONS: usize = 4;
+
+/// 同じデータベースファイルへの接続を共有するプール
+///
+/// クローンは全て同じプールを指します。
+#[derive(Clone)]
+pub struct DbPool {
+    inner: Arc<PoolInner>,
+}
+
+/// プールの実体（全てのクローンで共有される）
+struct PoolInner {
+    /// データベースファイルのパス
+    path: PathBuf,
+
+    /// 返却された未使用の接続
+    idle: Mutex<Vec<Database>>,
+}
+
+impl DbPool {
+    /// 既定のパス（`~/.rustfeed/rustfeed.db`）のデータベースでプールを作成する
+    ///
+    /// 最初の接続でテーブルの初期化（[`Database::init`]）を行います。
+    pub fn open() -> Result<Self> {
+        Self::open_at(&Database::get_db_path()?)
+    }
+
+    /// 指定したパスのデータベースでプールを作成する
+    ///
+    /// 最初の接続でテーブルの初期化（[`Database::init`]）を行い、
+    /// その接続をプールに入れておきます。
+    pub fn open_at(path: &Path) -> Result<Self> {
+        let db = Database::new_at(path)?;
+        db.init()?;
+
+        Ok(Self {
+            inner: Arc::new(PoolInner {
+                path: path.to_path_buf(),
+                idle: Mutex::new(vec![db]),
+            }),
+        })
+    }
+
+    /// 接続を1つ借りる
+    ///
+    /// 未使用の接続があれば再利用し、なければ新しく開きます。
+    /// 返された [`PooledDatabase`] がドロップされると接続はプールに戻ります。
+    pub fn get(&self) -> Result<PooledDatabase> {
+        let reused = self.inner.lock_idle().pop();
+        let db = match reused {
+            Some(db) => db,
+            None => Database::new_at(&self.inner.path)?,
+        };
+
+        Ok(PooledDatabase {
+            db: Some(db),
+            pool: Arc::clone(&self.inner),
+        })
+    }
+
+    /// ブロッキング用のスレッド（`spawn_blocking`）で接続を借りて処理を実行する
+    ///
+    /// SQLite の処理が非同期ランタイムのワーカースレッドを止めないようにします。
+    ///
+    /// # 引数
+    ///
+    /// * `f` - 借りた接続を使う処理
+    pub async fn run<F, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Database) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        run_blocking(self.clone(), move |pool| f(&*pool.get()?)).await
+    }
+
+    /// データベースファイルのパスを取得する
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+}
+
+impl PoolInner {
+    /// 未使用の接続の一覧をロックする
+    ///
+    /// 他のスレッドがパニックしても接続の一覧自体は壊れないため、
+    /// ロックの poison は無視します。
+    fn lock_idle(&self) -> std::sync::MutexGuard<'_, Vec<Database>> {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// プールから借りた接続
+///
+/// `Deref` により [`Database`] のメソッドをそのまま呼べます。
+/// ドロップするとプールに戻ります。
+pub struct PooledDatabase {
+    /// 借りている接続（ドロップ時に取り出すため Option）
+    db: Option<Database>,
+
+    /// 返却先のプール
+    pool: Arc<PoolInner>,
+}
+
+impl Deref for PooledDatabase {
+    type Target = Database;
+
+    fn deref(&self) -> &Database {
+        self.db
+            .as_ref()
+            .expect("pooled connection is only taken on drop")
+    }
+}
+
+impl Drop for PooledDatabase {
+    fn drop(&mut self) {
+        if let Some(db) = self.db.take() {
+            let mut idle = self.pool.lock_idle();
+            if idle.len() < MAX_IDLE_CONNECTIONS {
+                idle.push(db);
+            }
+        }
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// テスト用の一時データベースファイルのパス（テストごとに別名）
+    fn temp_db_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rustfeed-{}-{}.db", name, std::process::id()))
+    }
+
+    /// 書き込み中の接続があっても、別のタスクから読み込めることを確認するテスト
+    #[tokio::test]
+    async fn test_pool_reads_while_another_connection_writes() {
+        let path = temp_db_path("pool-test");
+        let _ = std::fs::remove_file(&path);
+        let pool = DbPool::open_at(&path).unwrap();
+
+        // 別の接続（他のタスクや fetch を想定）でトランザクションを開き、コミットせずに書き込む
+        let writer = rusqlite::Connection::open(&path).unwrap();
+        writer
+            .execute_batch(
+                "BEGIN IMMEDIATE;
+                 INSERT INTO feeds (url, title, created_at, updated_at)
+                 VALUES ('https://example.com/feed', 'Feed', '2025-01-01T00:00:00+00:00',
+                         '2025-01-01T00:00:00+00:00');",
+            )
+            .unwrap();
+
+        // 別のタスクからはコミット前のデータは見えないが、読み込みは待たされずに成功する
+        let reader = pool.clone();
+        let feeds = tokio::spawn(async move { reader.run(|db| db.get_feeds(None)).await })
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(feeds.is_empty());
+
+        writer.execute_batch("COMMIT").unwrap();
+        drop(writer);
+
+        // コミット後は見える（返却された接続が再利用される）
+        let feeds = pool.run(|db| db.get_feeds(None)).await.unwrap();
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(pool.inner.lock_idle().len(), 1);
+
+        drop(pool);
+        let _ = std::fs::remove_file(&path);
+    }
+}
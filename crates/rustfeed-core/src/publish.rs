@@ -0,0 +1,518 @@
+//! # フィードの配信モジュール
+//!
+//! 記事を Atom・RSS 2.0・JSON Feed 1.1 のフィードとして書き出す機能を提供します。
+//!
+//! ## 概要
+//!
+//! お気に入りやタグ（例: `security`）で絞り込んだ記事を、静的ホスティングでそのまま
+//! 配信できるフィードにします。記事の本文は [`crate::html::sanitize_html`] で無害化してから
+//! 埋め込みます。
+//!
+//! | 形式 | 記事のID | 本文 |
+//! |------|----------|------|
+//! | Atom | 記事のURL（なければ `urn:rustfeed:article:<ID>`） | `<content type="html">` |
+//! | RSS 2.0 | `<guid>`（URLがあれば permalink） | `<description>` |
+//! | JSON Feed 1.1 | `id` | `content_html` |
+//!
+//! ## 使用例
+//!
+//! ```rust
+//! use rustfeed_core::publish::{write_feed, FeedMetadata, PublishFormat};
+//!
+//! let metadata = FeedMetadata {
+//!     title: "Team favorites".to_string(),
+//!     home_page_url: Some("https://example.com/".to_string()),
+//!     ..FeedMetadata::default()
+//! };
+//! let xml = write_feed(PublishFormat::Atom, &metadata, &[])?;
+//! assert!(xml.contains("<title>Team favorites</title>"));
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+use serde::Serialize;
+
+use crate::export::ExportArticle;
+use crate::html::{is_safe_url, sanitize_html};
+use crate::xml::write_text_element;
+
+/// フィードを生成したソフトウェアの名前
+const GENERATOR: &str = "rustfeed";
+
+// =============================================================================
+// データ型
+// =============================================================================
+
+/// 配信するフィードの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublishFormat {
+    /// Atom（RFC 4287）
+    Atom,
+    /// RSS 2.0
+    Rss,
+    /// JSON Feed 1.1
+    JsonFeed,
+}
+
+impl PublishFormat {
+    /// 出力ファイルの一般的な拡張子
+    pub fn extension(self) -> &'static str {
+        match self {
+            PublishFormat::Atom => "atom",
+            PublishFormat::Rss => "xml",
+            PublishFormat::JsonFeed => "json",
+        }
+    }
+}
+
+impl FromStr for PublishFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "atom" => Ok(PublishFormat::Atom),
+            "rss" => Ok(PublishFormat::Rss),
+            "jsonfeed" | "json" => Ok(PublishFormat::JsonFeed),
+            _ => bail!("Unsupported format: '{}'. Use atom, rss or jsonfeed.", s),
+        }
+    }
+}
+
+impl fmt::Display for PublishFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PublishFormat::Atom => "atom",
+            PublishFormat::Rss => "rss",
+            PublishFormat::JsonFeed => "jsonfeed",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 配信するフィードの情報
+#[derive(Debug, Clone, Default)]
+pub struct FeedMetadata {
+    /// フィードのタイトル
+    pub title: String,
+
+    /// フィードの説明（オプショナル）
+    pub description: Option<String>,
+
+    /// フィードに対応するWebページのURL（オプショナル、RSS では必須）
+    pub home_page_url: Option<String>,
+
+    /// フィード自身を配信するURL（オプショナル）
+    ///
+    /// Atom ではフィードのIDにも使います。
+    pub feed_url: Option<String>,
+}
+
+impl FeedMetadata {
+    /// フィードのID（配信URL、なければサイトのURL、なければタイトルから作るURN）
+    fn id(&self) -> String {
+        self.feed_url
+            .clone()
+            .or_else(|| self.home_page_url.clone())
+            .unwrap_or_else(|| {
+                let slug: String = self
+                    .title
+                    .chars()
+                    .map(|c| if c.is_alphanumeric() { c } else { '-' })
+                    .collect();
+                format!("urn:rustfeed:feed:{}", slug.to_lowercase())
+            })
+    }
+}
+
+/// フィードを書き出す
+///
+/// # 引数
+///
+/// * `format` - フィードの形式
+/// * `metadata` - フィードのタイトル・URLなど
+/// * `articles` - フィードに含める記事（この順に並べる）
+///
+/// # エラー
+///
+/// RSS でサイトのURL（`home_page_url`）と配信URL（`feed_url`）がどちらもない場合や、
+/// URLが http(s) でない場合
+pub fn write_feed(
+    format: PublishFormat,
+    metadata: &FeedMetadata,
+    articles: &[ExportArticle],
+) -> Result<String> {
+    for url in [&metadata.home_page_url, &metadata.feed_url]
+        .into_iter()
+        .flatten()
+    {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            bail!("Feed URLs must be http(s): {}", url);
+        }
+    }
+
+    match format {
+        PublishFormat::Atom => write_atom(metadata, articles),
+        PublishFormat::Rss => write_rss(metadata, articles),
+        PublishFormat::JsonFeed => write_json_feed(metadata, articles),
+    }
+}
+
+/// 記事のID（URLがあればURL、なければURN）
+fn article_id(entry: &ExportArticle) -> String {
+    article_url(entry)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("urn:rustfeed:article:{}", entry.article.id))
+}
+
+/// 記事のURL（http(s) のものだけ）
+fn article_url(entry: &ExportArticle) -> Option<&str> {
+    entry
+        .article
+        .url
+        .as_deref()
+        .filter(|url| is_safe_url(url) && !url.starts_with("mailto:"))
+}
+
+/// 記事の日時（公開日時、なければ取得日時）
+fn article_date(entry: &ExportArticle) -> DateTime<Utc> {
+    entry
+        .article
+        .published_at
+        .unwrap_or(entry.article.created_at)
+}
+
+/// フィードの更新日時（最も新しい記事の日時、記事がなければ現在時刻）
+fn feed_updated(articles: &[ExportArticle]) -> DateTime<Utc> {
+    articles
+        .iter()
+        .map(article_date)
+        .max()
+        .unwrap_or_else(Utc::now)
+}
+
+/// XML の文書を文字列にする
+fn finish_xml(writer: Writer<Vec<u8>>) -> Result<String> {
+    let mut xml = String::from_utf8(writer.into_inner()).context("Feed is not valid UTF-8")?;
+    xml.push('\n');
+    Ok(xml)
+}
+
+// =============================================================================
+// Atom
+// =============================================================================
+
+/// Atom で書き出す
+fn write_atom(metadata: &FeedMetadata, articles: &[ExportArticle]) -> Result<String> {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    writer.write_event(Event::Start(
+        BytesStart::new("feed").with_attributes([("xmlns", "http://www.w3.org/2005/Atom")]),
+    ))?;
+
+    write_text_element(&mut writer, "id", &metadata.id())?;
+    write_text_element(&mut writer, "title", &metadata.title)?;
+    if let Some(description) = &metadata.description {
+        write_text_element(&mut writer, "subtitle", description)?;
+    }
+    write_text_element(&mut writer, "updated", &feed_updated(articles).to_rfc3339())?;
+    write_text_element(&mut writer, "generator", GENERATOR)?;
+    if let Some(url) = &metadata.home_page_url {
+        write_link(&mut writer, "alternate", url)?;
+    }
+    if let Some(url) = &metadata.feed_url {
+        write_link(&mut writer, "self", url)?;
+    }
+
+    for entry in articles {
+        let article = &entry.article;
+        writer.write_event(Event::Start(BytesStart::new("entry")))?;
+        write_text_element(&mut writer, "id", &article_id(entry))?;
+        write_text_element(&mut writer, "title", &article.title)?;
+        let date = article_date(entry).to_rfc3339();
+        write_text_element(&mut writer, "updated", &date)?;
+        if article.published_at.is_some() {
+            write_text_element(&mut writer, "published", &date)?;
+        }
+        if let Some(url) = article_url(entry) {
+            write_link(&mut writer, "alternate", url)?;
+        }
+
+        // Atom ではフィードかエントリのどちらかに著者が必要
+        writer.write_event(Event::Start(BytesStart::new("author")))?;
+        write_text_element(
+            &mut writer,
+            "name",
+            entry.feed_title.as_deref().unwrap_or(GENERATOR),
+        )?;
+        writer.write_event(Event::End(BytesEnd::new("author")))?;
+
+        for tag in &entry.tags {
+            writer.write_event(Event::Empty(
+                BytesStart::new("category").with_attributes([("term", tag.as_str())]),
+            ))?;
+        }
+        if let Some(content) = &article.content {
+            writer.write_event(Event::Start(
+                BytesStart::new("content").with_attributes([("type", "html")]),
+            ))?;
+            writer.write_event(Event::Text(BytesText::new(&sanitize_html(content))))?;
+            writer.write_event(Event::End(BytesEnd::new("content")))?;
+        }
+        writer.write_event(Event::End(BytesEnd::new("entry")))?;
+    }
+
+    writer.write_event(Event::End(BytesEnd::new("feed")))?;
+    finish_xml(writer)
+}
+
+/// Atom の `<link>` を書き出す
+fn write_link(writer: &mut Writer<Vec<u8>>, rel: &str, href: &str) -> Result<()> {
+    writer.write_event(Event::Empty(
+        BytesStart::new("link").with_attributes([("rel", rel), ("href", href)]),
+    ))?;
+    Ok(())
+}
+
+// =============================================================================
+// RSS 2.0
+// =============================================================================
+
+/// RSS 2.0 で書き出す
+fn write_rss(metadata: &FeedMetadata, articles: &[ExportArticle]) -> Result<String> {
+    let Some(link) = metadata
+        .home_page_url
+        .as_deref()
+        .or(metadata.feed_url.as_deref())
+    else {
+        bail!("RSS feeds need a link to a web page or to the feed itself");
+    };
+
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    writer.write_event(Event::Start(BytesStart::new("rss").with_attributes([
+        ("version", "2.0"),
+        ("xmlns:atom", "http://www.w3.org/2005/Atom"),
+    ])))?;
+    writer.write_event(Event::Start(BytesStart::new("channel")))?;
+
+    write_text_element(&mut writer, "title", &metadata.title)?;
+    write_text_element(&mut writer, "link", link)?;
+    write_text_element(
+        &mut writer,
+        "description",
+        metadata.description.as_deref().unwrap_or(&metadata.title),
+    )?;
+    write_text_element(
+        &mut writer,
+        "lastBuildDate",
+        &feed_updated(articles).to_rfc2822(),
+    )?;
+    write_text_element(&mut writer, "generator", GENERATOR)?;
+    if let Some(url) = &metadata.feed_url {
+        writer.write_event(Event::Empty(BytesStart::new("atom:link").with_attributes(
+            [
+                ("href", url.as_str()),
+                ("rel", "self"),
+                ("type", "application/rss+xml"),
+            ],
+        )))?;
+    }
+
+    for entry in articles {
+        let article = &entry.article;
+        writer.write_event(Event::Start(BytesStart::new("item")))?;
+        write_text_element(&mut writer, "title", &article.title)?;
+        if let Some(url) = article_url(entry) {
+            write_text_element(&mut writer, "link", url)?;
+        }
+        let permalink = if article_url(entry).is_some() {
+            "true"
+        } else {
+            "false"
+        };
+        writer.write_event(Event::Start(
+            BytesStart::new("guid").with_attributes([("isPermaLink", permalink)]),
+        ))?;
+        writer.write_event(Event::Text(BytesText::new(&article_id(entry))))?;
+        writer.write_event(Event::End(BytesEnd::new("guid")))?;
+        write_text_element(&mut writer, "pubDate", &article_date(entry).to_rfc2822())?;
+        for tag in &entry.tags {
+            write_text_element(&mut writer, "category", tag)?;
+        }
+        if let Some(content) = &article.content {
+            write_text_element(&mut writer, "description", &sanitize_html(content))?;
+        }
+        writer.write_event(Event::End(BytesEnd::new("item")))?;
+    }
+
+    writer.write_event(Event::End(BytesEnd::new("channel")))?;
+    writer.write_event(Event::End(BytesEnd::new("rss")))?;
+    finish_xml(writer)
+}
+
+// =============================================================================
+// JSON Feed 1.1
+// =============================================================================
+
+/// JSON Feed のトップレベル
+#[derive(Serialize)]
+struct JsonFeed<'a> {
+    version: &'static str,
+    title: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    home_page_url: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    feed_url: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    items: Vec<JsonFeedItem<'a>>,
+}
+
+/// JSON Feed の記事
+#[derive(Serialize)]
+struct JsonFeedItem<'a> {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<&'a str>,
+    title: &'a str,
+    content_html: String,
+    date_published: String,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    tags: &'a [String],
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    authors: Vec<JsonFeedAuthor<'a>>,
+}
+
+/// JSON Feed の著者
+#[derive(Serialize)]
+struct JsonFeedAuthor<'a> {
+    name: &'a str,
+}
+
+/// JSON Feed 1.1 で書き出す
+fn write_json_feed(metadata: &FeedMetadata, articles: &[ExportArticle]) -> Result<String> {
+    let feed = JsonFeed {
+        version: "https://jsonfeed.org/version/1.1",
+        title: &metadata.title,
+        home_page_url: metadata.home_page_url.as_deref(),
+        feed_url: metadata.feed_url.as_deref(),
+        description: metadata.description.as_deref(),
+        items: articles
+            .iter()
+            .map(|entry| JsonFeedItem {
+                id: article_id(entry),
+                url: article_url(entry),
+                title: &entry.article.title,
+                content_html: entry
+                    .article
+                    .content
+                    .as_deref()
+                    .map(sanitize_html)
+                    .unwrap_or_default(),
+                date_published: article_date(entry).to_rfc3339(),
+                tags: &entry.tags,
+                authors: entry
+                    .feed_title
+                    .iter()
+                    .map(|name| JsonFeedAuthor { name })
+                    .collect(),
+            })
+            .collect(),
+    };
+    let mut json = serde_json::to_string_pretty(&feed).context("Failed to serialize JSON Feed")?;
+    json.push('\n');
+    Ok(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Article;
+
+    fn sample(id: i64, url: Option<&str>) -> ExportArticle {
+        ExportArticle {
+            article: Article {
+                id,
+                feed_id: 1,
+                title: format!("Article <{}> & co", id),
+                url: url.map(str::to_string),
+                content: Some("<p onclick=\"x()\">Body<script>bad()</script></p>".to_string()),
+                published_at: None,
+                is_read: false,
+                is_favorite: true,
+                created_at: Utc::now(),
+                read_at: None,
+                first_opened_at: None,
+                duplicate_of: None,
+                duplicate_count: 0,
+            },
+            feed_title: Some("Source Blog".to_string()),
+            tags: vec!["security".to_string()],
+            note: None,
+            highlights: Vec::new(),
+        }
+    }
+
+    /// 3つの形式で書き出したフィードを feed-rs で読み込めることを確認するテスト
+    #[test]
+    fn test_write_feed_round_trip() {
+        let articles = vec![sample(1, Some("https://example.com/1")), sample(2, None)];
+        let metadata = FeedMetadata {
+            title: "Security picks".to_string(),
+            description: Some("Tagged security".to_string()),
+            home_page_url: Some("https://example.com/".to_string()),
+            feed_url: Some("https://example.com/security.xml".to_string()),
+        };
+
+        for format in [
+            PublishFormat::Atom,
+            PublishFormat::Rss,
+            PublishFormat::JsonFeed,
+        ] {
+            let document = write_feed(format, &metadata, &articles).unwrap();
+            assert!(!document.contains("<script>"), "{}", format);
+            assert!(!document.contains("onclick"), "{}", format);
+
+            let parsed = feed_rs::parser::parse(document.as_bytes())
+                .unwrap_or_else(|e| panic!("{}: {}", format, e));
+            assert_eq!(
+                parsed.title.map(|t| t.content).as_deref(),
+                Some("Security picks")
+            );
+            assert_eq!(parsed.entries.len(), 2, "{}", format);
+            let first = &parsed.entries[0];
+            assert_eq!(
+                first.title.as_ref().map(|t| t.content.as_str()),
+                Some("Article <1> & co")
+            );
+            assert_eq!(first.links[0].href, "https://example.com/1");
+            assert_eq!(first.categories[0].term, "security");
+            assert_eq!(parsed.entries[1].id, "urn:rustfeed:article:2");
+        }
+    }
+
+    /// RSS にはリンクが必要で、http(s) 以外のURLは使えないことを確認するテスト
+    #[test]
+    fn test_write_feed_errors() {
+        let metadata = FeedMetadata {
+            title: "No link".to_string(),
+            ..FeedMetadata::default()
+        };
+        assert!(write_feed(PublishFormat::Rss, &metadata, &[]).is_err());
+        assert!(write_feed(PublishFormat::Atom, &metadata, &[]).is_ok());
+
+        let metadata = FeedMetadata {
+            feed_url: Some("javascript:alert(1)".to_string()),
+            ..metadata
+        };
+        assert!(write_feed(PublishFormat::JsonFeed, &metadata, &[]).is_err());
+        assert!("jsonfeed".parse::<PublishFormat>().is_ok());
+        assert!("opml".parse::<PublishFormat>().is_err());
+    }
+}
@@ -0,0 +1,536 @@
+//! # 記事クエリモジュール
+//!
+//! 記事の検索条件を型付きで表現する [`ArticleQuery`] を提供します。
+//!
+//! ## 概要
+//!
+//! フィード・カテゴリ・タグ・既読/お気に入り状態・日付範囲・キーワード・
+//! 並び順・件数（LIMIT/OFFSET）をひとつの構造体にまとめ、
+//! 1つのSQL文に変換します。全ての条件が WHERE 句に入るため、
+//! `LIMIT` は必ずフィルタ適用後の結果に対して効きます。
+//!
+//! 大量の記事をページ単位で読み込む場合は、[`ArticleCursor`] による
+//! キーセットページネーション（`Storage::query_articles_page`）を使います。
+//!
+//! ## 使用例
+//!
+//! ```rust,no_run
+//! use rustfeed_core::{db::Database, query::ArticleQuery};
+//!
+//! let db = Database::open()?;
+//!
+//! // 未読かつ "rust" を含む記事を20件
+//! let query = ArticleQuery::new()
+//!     .unread_only()
+//!     .keywords_from_str("rust")
+//!     .limit(20);
+//! let articles = db.query_articles(&query)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::ToSql;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::db::{category_path_segments, CATEGORY_PATHS_CTE};
+use crate::models::Article;
+
+// =============================================================================
+// 並び順
+// =============================================================================
+
+/// 記事の並び順
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// 公開日時の新しい順（デフォルト）
+    #[default]
+    Newest,
+    /// 公開日時の古い順
+    Oldest,
+}
+
+/// 並び替えに使う公開日時の式
+///
+/// 公開日時のない記事を空文字列として扱い、NULL を含めて `(SORT_KEY, id)` の
+/// 行値で比較できるようにします。インデックス（`idx_articles_sort_key` など）も
+/// 同じ式で作成しているため、式を変える場合はそちらも合わせてください。
+pub(crate) const SORT_KEY: &str = "COALESCE(published_at, '')";
+
+impl SortOrder {
+    /// ORDER BY 句を返す
+    ///
+    /// キーセットページネーションのため、並びは必ず `(SORT_KEY, id)` で一意に決まります。
+    fn order_by(self) -> String {
+        match self {
+            SortOrder::Newest => format!("{} DESC, id DESC", SORT_KEY),
+            SortOrder::Oldest => format!("{} ASC, id ASC", SORT_KEY),
+        }
+    }
+}
+
+// =============================================================================
+// ページネーション用カーソル
+// =============================================================================
+
+/// キーセットページネーション用のカーソル
+///
+/// ページの最後の記事の `(published_at, id)` を保持し、
+/// 次のページは「その記事より後ろに並ぶ記事」として WHERE 句で取得します。
+/// OFFSET と違い読み飛ばす行をスキャンしないため、
+/// 記事数が多いフィードでも深いページを一定の速度で取得できます。
+///
+/// 文字列表現（[`fmt::Display`] / [`FromStr`]）は不透明な値として扱い、
+/// 呼び出し側で中身を解釈しないでください。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArticleCursor {
+    /// 最後の記事の公開日時（DBに保存されている RFC3339 文字列、NULL の場合は None）
+    published_at: Option<String>,
+    /// 最後の記事のID
+    id: i64,
+}
+
+impl ArticleCursor {
+    /// 記事の位置を指すカーソルを作成する
+    pub fn from_article(article: &Article) -> Self {
+        Self {
+            published_at: article.published_at.map(|dt| dt.to_rfc3339()),
+            id: article.id,
+        }
+    }
+
+    /// 記事がカーソル位置より後ろに並ぶかを判定する（メモリ上で絞り込む場合に使う）
+    ///
+    /// [`ArticleCursor::condition`] と同じく、公開日時のない記事は最小の値（空文字列）として扱います。
+    pub(crate) fn is_followed_by(&self, article: &Article, sort: SortOrder) -> bool {
+        let key = (article.published_at.map(|dt| dt.to_rfc3339()), article.id);
+        let cursor = (self.published_at.clone(), self.id);
+        match sort {
+            SortOrder::Newest => key < cursor,
+            SortOrder::Oldest => key > cursor,
+        }
+    }
+
+    /// カーソル位置より後ろの記事を選ぶ条件とパラメータを組み立てる
+    ///
+    /// 公開日時のない記事は [`SORT_KEY`] で空文字列として比較するため、
+    /// 降順では末尾、昇順では先頭に並びます。
+    fn condition(&self, sort: SortOrder) -> (String, Vec<Box<dyn ToSql>>) {
+        let operator = match sort {
+            SortOrder::Newest => "<",
+            SortOrder::Oldest => ">",
+        };
+        (
+            format!("({}, id) {} (?, ?)", SORT_KEY, operator),
+            vec![
+                Box::new(self.published_at.clone().unwrap_or_default()),
+                Box::new(self.id),
+            ],
+        )
+    }
+}
+
+impl fmt::Display for ArticleCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // IDにはコロンが含まれないため、最初のコロンで分割できる
+        write!(
+            f,
+            "{}:{}",
+            self.id,
+            self.published_at.as_deref().unwrap_or_default()
+        )
+    }
+}
+
+impl FromStr for ArticleCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (id, published_at) = s
+            .split_once(':')
+            .with_context(|| format!("Invalid cursor: {}", s))?;
+        let id = id
+            .parse()
+            .with_context(|| format!("Invalid cursor: {}", s))?;
+        let published_at = if published_at.is_empty() {
+            None
+        } else {
+            Some(published_at.to_string())
+        };
+        Ok(Self { published_at, id })
+    }
+}
+
+/// ページ単位で取得した記事
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArticlePage {
+    /// このページの記事
+    pub articles: Vec<Article>,
+    /// 次のページを取得するためのカーソル（最後のページの場合は None）
+    pub next_cursor: Option<String>,
+}
+
+// =============================================================================
+// ArticleQuery 構造体
+// =============================================================================
+
+/// 記事の取得条件
+///
+/// 各フィールドは空（`Vec` が空、`Option` が `None`）の場合は条件なしを意味します。
+/// 複数の条件を指定した場合は全て AND で結合されます。
+///
+/// # フィールド
+///
+/// | フィールド | 説明 |
+/// |------------|------|
+/// | `feed_ids` | 対象フィードID（いずれかに一致） |
+/// | `exclude_feed_ids` | 除外するフィードID |
+/// | `exclude_paused` | 一時停止中のフィードの記事を除外する |
+/// | `collapse_duplicates` | 重複記事を除き、元の記事だけにする |
+/// | `categories` | 対象カテゴリのパス（いずれかに一致、サブカテゴリを含む） |
+/// | `tags` | 対象タグ（いずれかが付いている記事） |
+/// | `is_read` | 既読状態（`Some(false)` で未読のみ） |
+/// | `is_favorite` | お気に入り状態 |
+/// | `published_after` | この日時以降に公開された記事（境界を含む） |
+/// | `published_before` | この日時より前に公開された記事（境界を含まない） |
+/// | `undated_by_created_at` | 公開日時のない記事を取得日時で日付範囲に含める |
+/// | `keywords` | タイトルまたは本文に含まれるキーワード（OR条件） |
+/// | `search_terms` | タイトルまたは本文に含まれる検索語（OR条件、`keywords` とはAND条件） |
+/// | `sort` | 並び順 |
+/// | `cursor` | このカーソル位置より後ろの記事のみ（キーセットページネーション） |
+/// | `limit` / `offset` | 取得件数と読み飛ばす件数 |
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArticleQuery {
+    pub feed_ids: Vec<i64>,
+    pub exclude_feed_ids: Vec<i64>,
+    pub exclude_paused: bool,
+    pub collapse_duplicates: bool,
+    pub categories: Vec<String>,
+    pub tags: Vec<String>,
+    pub is_read: Option<bool>,
+    pub is_favorite: Option<bool>,
+    pub published_after: Option<DateTime<Utc>>,
+    pub published_before: Option<DateTime<Utc>>,
+    pub undated_by_created_at: bool,
+    pub keywords: Vec<String>,
+    pub search_terms: Vec<String>,
+    pub sort: SortOrder,
+    #[serde(skip)]
+    pub cursor: Option<ArticleCursor>,
+    pub limit: Option<usize>,
+    pub offset: usize,
+}
+
+impl ArticleQuery {
+    /// 条件なしのクエリを作成する
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 特定のフィードに絞り込む（複数回呼ぶとOR条件）
+    pub fn feed(mut self, feed_id: i64) -> Self {
+        self.feed_ids.push(feed_id);
+        self
+    }
+
+    /// 指定したフィードを除外する
+    pub fn exclude_feeds(mut self, feed_ids: &[i64]) -> Self {
+        self.exclude_feed_ids.extend_from_slice(feed_ids);
+        self
+    }
+
+    /// 一時停止中のフィードの記事を除外する
+    pub fn exclude_paused(mut self) -> Self {
+        self.exclude_paused = true;
+        self
+    }
+
+    /// 重複記事（`duplicate_of` が設定された記事）を除き、元の記事だけにする
+    ///
+    /// 元の記事が他の条件（一時停止中のフィードやカテゴリなど）で除外される場合は、
+    /// 代わりにその重複記事が含まれます。
+    pub fn collapse_duplicates(mut self) -> Self {
+        self.collapse_duplicates = true;
+        self
+    }
+
+    /// 特定のカテゴリ（とそのサブカテゴリ）に絞り込む（複数回呼ぶとOR条件）
+    ///
+    /// カテゴリは "Tech/Rust" のような `/` 区切りのパスで指定します。
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.categories.push(category.into());
+        self
+    }
+
+    /// 特定のタグが付いた記事に絞り込む（複数回呼ぶとOR条件）
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// 未読記事のみに絞り込む
+    pub fn unread_only(mut self) -> Self {
+        self.is_read = Some(false);
+        self
+    }
+
+    /// お気に入り記事のみに絞り込む
+    pub fn favorites_only(mut self) -> Self {
+        self.is_favorite = Some(true);
+        self
+    }
+
+    /// 公開日時の下限（この日時を含む）
+    pub fn published_after(mut self, after: DateTime<Utc>) -> Self {
+        self.published_after = Some(after);
+        self
+    }
+
+    /// 公開日時の上限（この日時を含まない）
+    pub fn published_before(mut self, before: DateTime<Utc>) -> Self {
+        self.published_before = Some(before);
+        self
+    }
+
+    /// 公開日時のない記事は、取得日時（`created_at`）で日付範囲を判定する
+    ///
+    /// 指定しない場合、公開日時のない記事は日付範囲の条件に一致しません。
+    pub fn undated_by_created_at(mut self) -> Self {
+        self.undated_by_created_at = true;
+        self
+    }
+
+    /// キーワードを追加する（OR条件）
+    pub fn keyword(mut self, keyword: impl Into<String>) -> Self {
+        self.keywords.push(keyword.into());
+        self
+    }
+
+    /// カンマ区切りのキーワード文字列を追加する
+    ///
+    /// 例: `"rust, cargo"` → `["rust", "cargo"]`（空の要素は無視）
+    pub fn keywords_from_str(mut self, filter: &str) -> Self {
+        self.keywords.extend(split_keywords(filter));
+        self
+    }
+
+    /// カンマ区切りの検索語を追加する
+    ///
+    /// 検索語同士はOR条件ですが、[`Self::keywords_from_str`] のキーワードとはAND条件になり、
+    /// キーワードで絞り込んだ結果をさらに検索語で絞り込みます。
+    pub fn search_from_str(mut self, search: &str) -> Self {
+        self.search_terms.extend(split_keywords(search));
+        self
+    }
+
+    /// 並び順を設定する
+    pub fn sort(mut self, sort: SortOrder) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// カーソル位置より後ろの記事のみに絞り込む
+    pub fn after_cursor(mut self, cursor: ArticleCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// 取得件数の上限を設定する
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// 読み飛ばす件数を設定する
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// WHERE 句の条件とパラメータを組み立てる
+    ///
+    /// 条件は AND で結合される前の個別の式として返されます。
+    /// パラメータは条件中の `?` と同じ順序で並びます。
+    pub(crate) fn conditions(&self) -> (Vec<String>, Vec<Box<dyn ToSql>>) {
+        let (mut conditions, mut params) = self.filter_conditions();
+
+        // 重複記事（元の記事が同じ条件で取得される場合だけ除く）
+        if self.collapse_duplicates {
+            let (filters, filter_params) = self.filter_conditions();
+            if filters.is_empty() {
+                conditions.push("duplicate_of IS NULL".to_string());
+            } else {
+                conditions.push(format!(
+                    "(duplicate_of IS NULL OR duplicate_of NOT IN (SELECT id FROM articles WHERE {}))",
+                    filters.join(" AND ")
+                ));
+                params.extend(filter_params);
+            }
+        }
+
+        // カーソル位置
+        if let Some(cursor) = &self.cursor {
+            let (condition, cursor_params) = cursor.condition(self.sort);
+            conditions.push(condition);
+            params.extend(cursor_params);
+        }
+
+        (conditions, params)
+    }
+
+    /// 絞り込みの条件とパラメータを組み立てる（重複記事とカーソル位置以外）
+    fn filter_conditions(&self) -> (Vec<String>, Vec<Box<dyn ToSql>>) {
+        let mut conditions: Vec<String> = Vec::new();
+        let mut params: Vec<Box<dyn ToSql>> = Vec::new();
+
+        // フィードIDフィルタ
+        if !self.feed_ids.is_empty() {
+            conditions.push(format!(
+                "feed_id IN ({})",
+                placeholders(self.feed_ids.len())
+            ));
+            for id in &self.feed_ids {
+                params.push(Box::new(*id));
+            }
+        }
+
+        // 除外フィード
+        if !self.exclude_feed_ids.is_empty() {
+            conditions.push(format!(
+                "feed_id NOT IN ({})",
+                placeholders(self.exclude_feed_ids.len())
+            ));
+            for id in &self.exclude_feed_ids {
+                params.push(Box::new(*id));
+            }
+        }
+
+        // 一時停止中のフィード
+        if self.exclude_paused {
+            conditions.push("feed_id NOT IN (SELECT id FROM feeds WHERE paused = 1)".to_string());
+        }
+
+        // カテゴリフィルタ（サブカテゴリに属するフィードも含める）
+        if !self.categories.is_empty() {
+            conditions.push(format!(
+                "feed_id IN (SELECT id FROM feeds WHERE category_id IN (
+                    WITH RECURSIVE {},
+                    category_tree(id) AS (
+                        SELECT id FROM category_paths WHERE path COLLATE NOCASE IN ({})
+                        UNION ALL
+                        SELECT c.id FROM categories c JOIN category_tree t ON c.parent_id = t.id
+                    )
+                    SELECT id FROM category_tree))",
+                CATEGORY_PATHS_CTE,
+                placeholders(self.categories.len())
+            ));
+            for category in &self.categories {
+                params.push(Box::new(category_path_segments(category).join("/")));
+            }
+        }
+
+        // タグフィルタ（article_tags テーブルをサブクエリで参照）
+        if !self.tags.is_empty() {
+            conditions.push(format!(
+                "id IN (SELECT at.article_id FROM article_tags at
+                        JOIN tags t ON t.id = at.tag_id
+                        WHERE t.name IN ({}))",
+                placeholders(self.tags.len())
+            ));
+            for tag in &self.tags {
+                params.push(Box::new(tag.trim().to_string()));
+            }
+        }
+
+        // 既読/お気に入り状態
+        if let Some(is_read) = self.is_read {
+            conditions.push("is_read = ?".to_string());
+            params.push(Box::new(is_read as i32));
+        }
+        if let Some(is_favorite) = self.is_favorite {
+            conditions.push("is_favorite = ?".to_string());
+            params.push(Box::new(is_favorite as i32));
+        }
+
+        // 日付範囲
+        // published_at は RFC3339（UTC）文字列で保存されているため、
+        // 同じ形式の文字列との比較で日時の大小比較になる
+        let date = if self.undated_by_created_at {
+            "COALESCE(published_at, created_at)"
+        } else {
+            "published_at"
+        };
+        if let Some(after) = self.published_after {
+            conditions.push(format!("{} >= ?", date));
+            params.push(Box::new(after.to_rfc3339()));
+        }
+        if let Some(before) = self.published_before {
+            conditions.push(format!("{} < ?", date));
+            params.push(Box::new(before.to_rfc3339()));
+        }
+
+        // キーワードフィルタ（タイトルまたは本文、キーワード間はOR条件）
+        // 検索語はキーワードとは別のグループとして AND 条件で絞り込む
+        for keywords in [&self.keywords, &self.search_terms] {
+            if keywords.is_empty() {
+                continue;
+            }
+            let keyword_conditions: Vec<&str> = keywords
+                .iter()
+                .map(|_| "(title LIKE ? OR content LIKE ?)")
+                .collect();
+            conditions.push(format!("({})", keyword_conditions.join(" OR ")));
+            for keyword in keywords {
+                let pattern = format!("%{}%", keyword);
+                params.push(Box::new(pattern.clone()));
+                params.push(Box::new(pattern));
+            }
+        }
+
+        (conditions, params)
+    }
+
+    /// SELECT 文全体とパラメータを組み立てる
+    ///
+    /// `columns` は取得するカラムのリスト（カンマ区切り）です。
+    pub(crate) fn to_sql(&self, columns: &str) -> (String, Vec<Box<dyn ToSql>>) {
+        let (conditions, mut params) = self.conditions();
+
+        let mut sql = format!("SELECT {} FROM articles", columns);
+        if !conditions.is_empty() {
+            sql.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+        }
+        sql.push_str(&format!(" ORDER BY {}", self.sort.order_by()));
+
+        // SQLite では OFFSET には LIMIT が必要なため、上限なしは -1 を指定する
+        if self.limit.is_some() || self.offset > 0 {
+            sql.push_str(" LIMIT ? OFFSET ?");
+            params.push(Box::new(
+                self.limit.and_then(|l| i64::try_from(l).ok()).unwrap_or(-1),
+            ));
+            params.push(Box::new(self.offset as i64));
+        }
+
+        (sql, params)
+    }
+}
+
+/// `?, ?, ?` のようなプレースホルダ列を作る
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
+}
+
+/// カンマ区切りの文字列をキーワードに分ける
+///
+/// 例: `"rust, cargo"` → `["rust", "cargo"]`（空の要素は無視）
+fn split_keywords(filter: &str) -> impl Iterator<Item = String> + '_ {
+    filter
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+}
@@ -0,0 +1,232 @@
+//! # 統計モジュール
+//!
+//! 購読状況や読書の傾向をまとめた [`Stats`] を提供します。
+//!
+//! ## 概要
+//!
+//! `Database::get_stats` が数回の集計クエリで全フィード分の数値をまとめて求め、
+//! このモジュールの構造体に詰めて返します。CLI の `rustfeed stats` はこれを
+//! 表または JSON として表示します。
+//!
+//! 時刻（時間帯・日付）は全て UTC で集計します。
+//!
+//! ## 使用例
+//!
+//! ```rust,no_run
+//! use rustfeed_core::{db::Database, stats::StatsOptions};
+//!
+//! let db = Database::open()?;
+//!
+//! // 60日間新着のないフィードを探す
+//! let stats = db.get_stats(&StatsOptions {
+//!     stale_days: 60,
+//!     ..StatsOptions::default()
+//! })?;
+//! for feed in &stats.stale_feeds {
+//!     println!("{}", feed.name);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+// =============================================================================
+// 集計条件
+// =============================================================================
+
+/// 統計の集計条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatsOptions {
+    /// この日数の間に新着記事がないフィードを「停滞中」とみなす
+    pub stale_days: u32,
+
+    /// 投稿頻度（週あたりの記事数）を求める期間の日数
+    pub frequency_days: u32,
+
+    /// 読書アクティビティ（ヒートマップ）を集計する日数（今日を含む）
+    ///
+    /// 1日ごとの件数を返すため、日数が多いほど結果も大きくなります。
+    pub activity_days: u32,
+}
+
+impl Default for StatsOptions {
+    fn default() -> Self {
+        Self {
+            stale_days: 30,
+            frequency_days: 30,
+            activity_days: 12 * 7,
+        }
+    }
+}
+
+// =============================================================================
+// 集計結果
+// =============================================================================
+
+/// 統計情報全体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stats {
+    /// 集計した日時（UTC）
+    pub generated_at: DateTime<Utc>,
+
+    /// 集計条件
+    pub options: StatsOptions,
+
+    /// 全体の合計
+    pub totals: Totals,
+
+    /// フィードごとの統計（フィード一覧と同じ順）
+    pub feeds: Vec<FeedStats>,
+
+    /// カテゴリごとの統計（パス順、未分類は最後）
+    pub categories: Vec<CategoryStats>,
+
+    /// `stale_days` 日間新着のないフィード（最後の記事が古い順）
+    pub stale_feeds: Vec<StaleFeed>,
+
+    /// 記事が公開された時間帯（UTC、0〜23時）ごとの記事数
+    pub publish_hours: [usize; 24],
+
+    /// 記事を既読にした時間帯（UTC、0〜23時）ごとの回数
+    pub reading_hours: [usize; 24],
+
+    /// 日ごとの既読数（`activity_days` 日分、古い順、0件の日も含む）
+    pub reading_activity: Vec<DailyActivity>,
+}
+
+/// 全体の合計
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Totals {
+    /// フィード数
+    pub feeds: usize,
+    /// 記事数
+    pub articles: usize,
+    /// 未読記事数
+    pub unread: usize,
+    /// 既読記事数
+    pub read: usize,
+    /// お気に入り記事数
+    pub favorites: usize,
+    /// 既読率（0.0〜1.0）
+    pub read_ratio: f64,
+}
+
+/// フィードごとの統計
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedStats {
+    /// フィードID
+    pub feed_id: i64,
+    /// 表示名（カスタム名があればカスタム名）
+    pub name: String,
+    /// カテゴリのパス（未分類は None）
+    pub category: Option<String>,
+    /// 記事数
+    pub total: usize,
+    /// 未読記事数
+    pub unread: usize,
+    /// 既読記事数
+    pub read: usize,
+    /// 既読率（0.0〜1.0）
+    pub read_ratio: f64,
+    /// `frequency_days` 日間の記事数
+    pub recent_articles: usize,
+    /// 週あたりの記事数（`frequency_days` 日間の平均）
+    pub articles_per_week: f64,
+    /// 最新記事の日時（公開日時、なければ取得日時）
+    pub last_article_at: Option<DateTime<Utc>>,
+}
+
+/// カテゴリごとの統計（サブカテゴリは別の行として集計する）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryStats {
+    /// カテゴリのパス（未分類は None）
+    pub category: Option<String>,
+    /// フィード数
+    pub feeds: usize,
+    /// 記事数
+    pub total: usize,
+    /// 未読記事数
+    pub unread: usize,
+    /// 既読記事数
+    pub read: usize,
+    /// 既読率（0.0〜1.0）
+    pub read_ratio: f64,
+}
+
+/// 新着記事が途絶えているフィード
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleFeed {
+    /// フィードID
+    pub feed_id: i64,
+    /// 表示名
+    pub name: String,
+    /// 最新記事の日時（記事が1件もない場合は None）
+    pub last_article_at: Option<DateTime<Utc>>,
+    /// 最新記事からの経過日数（記事が1件もない場合は None）
+    pub days_since_last_article: Option<i64>,
+}
+
+/// 1日分の読書アクティビティ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyActivity {
+    /// 日付（UTC）
+    pub date: NaiveDate,
+    /// その日に既読にした記事数
+    pub reads: usize,
+}
+
+/// `now` から `days` 日前の日時を求める（表せる範囲を超える場合は最も古い日時）
+pub(crate) fn days_before(now: DateTime<Utc>, days: u32) -> DateTime<Utc> {
+    now.checked_sub_signed(chrono::Duration::days(i64::from(days)))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+/// 既読率を求める（記事がない場合は 0.0）
+pub(crate) fn read_ratio(read: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        read as f64 / total as f64
+    }
+}
+
+/// フィードごとの統計からカテゴリごとの統計を組み立てる
+pub(crate) fn aggregate_categories(feeds: &[FeedStats]) -> Vec<CategoryStats> {
+    let mut categories: Vec<CategoryStats> = Vec::new();
+
+    for feed in feeds {
+        let index = match categories.iter().position(|c| c.category == feed.category) {
+            Some(index) => index,
+            None => {
+                categories.push(CategoryStats {
+                    category: feed.category.clone(),
+                    feeds: 0,
+                    total: 0,
+                    unread: 0,
+                    read: 0,
+                    read_ratio: 0.0,
+                });
+                categories.len() - 1
+            }
+        };
+        let category = &mut categories[index];
+        category.feeds += 1;
+        category.total += feed.total;
+        category.unread += feed.unread;
+        category.read += feed.read;
+    }
+
+    for category in &mut categories {
+        category.read_ratio = read_ratio(category.read, category.total);
+    }
+
+    // パス順に並べ、未分類（None）は最後にする
+    categories.sort_by(|a, b| match (&a.category, &b.category) {
+        (Some(a), Some(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    categories
+}
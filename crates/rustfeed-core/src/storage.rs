@@ -0,0 +1,770 @@
+//! # ストレージ抽象化モジュール
+//!
+//! フィードと記事の保存先を抽象化する [`Storage`] トレイトを提供します。
+//!
+//! ## 概要
+//!
+//! CLI・TUI・GUI は記事の一覧表示や既読管理などの基本操作をこのトレイト経由で行うため、
+//! 保存先を差し替えられます。
+//!
+//! | 実装 | 用途 |
+//! |------|------|
+//! | [`Database`] | SQLite（`~/.rustfeed/rustfeed.db`）への接続1つ |
+//! | [`DbPool`] | 呼び出しごとにプールから接続を借りる（タスク間で共有できる） |
+//! | [`MemoryStorage`](crate::memory::MemoryStorage) | メモリ上のみ（テストや組み込み用） |
+//!
+//! カテゴリの管理・統計・読書履歴などの SQLite 固有の機能は
+//! [`Database`] のメソッドとして残っています。
+//!
+//! ## 使用例
+//!
+//! ```rust
+//! use rustfeed_core::{memory::MemoryStorage, storage::Storage, ArticleQuery, Feed};
+//!
+//! fn unread_count(storage: &dyn Storage) -> anyhow::Result<usize> {
+//!     Ok(storage.query_articles(&ArticleQuery::new().unread_only())?.len())
+//! }
+//!
+//! let storage = MemoryStorage::new();
+//! storage.add_feed(&Feed::new("https://example.com/feed".into(), "Example".into(), None))?;
+//! assert_eq!(unread_count(&storage)?, 0);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::db::{Database, FetchLease};
+use crate::html;
+use crate::models::{Article, Feed, Highlight, Note, QueueEntry, Tag};
+use crate::pool::DbPool;
+use crate::query::{ArticleCursor, ArticlePage, ArticleQuery};
+
+/// スレッド間で共有できるストレージ
+///
+/// TUI のようにバックグラウンドタスクからも同じストレージを使う場合に使います。
+pub type SharedStorage = Arc<dyn Storage + Send + Sync>;
+
+// =============================================================================
+// Storage トレイト
+// =============================================================================
+
+/// フィードと記事の保存先
+///
+/// 各メソッドの意味は同名の [`Database`] のメソッドと同じです。
+/// 戻り値の `bool` は、特に記載がなければ「対象が存在して更新できたか」を表します。
+pub trait Storage {
+    // -------------------------------------------------------------------------
+    // フィード
+    // -------------------------------------------------------------------------
+
+    /// フィードを追加し、採番されたIDを返す
+    fn add_feed(&self, feed: &Feed) -> Result<i64>;
+
+    /// フィードを取得する（優先順位の降順、同じ優先順位ではID順）
+    ///
+    /// カテゴリを指定した場合は、そのサブカテゴリに属するフィードも含まれます。
+    fn get_feeds(&self, category: Option<&str>) -> Result<Vec<Feed>>;
+
+    /// IDでフィードを取得する
+    fn get_feed(&self, id: i64) -> Result<Option<Feed>>;
+
+    /// フィードとその記事を削除する
+    fn remove_feed(&self, id: i64) -> Result<bool>;
+
+    /// フィードのカスタム名を設定する（None でクリア）
+    fn rename_feed(&self, feed_id: i64, custom_name: Option<&str>) -> Result<()>;
+
+    /// フィードのURLを更新する（URLは正規化して保存する）
+    fn update_feed_url(&self, feed_id: i64, new_url: &str) -> Result<()>;
+
+    /// URLが同じとみなせる登録済みのフィードを探す（[`crate::dedup::feed_url_key`] で比較）
+    fn find_feed_by_url(&self, url: &str) -> Result<Option<Feed>>;
+
+    /// フィードの優先順位を設定する
+    fn set_feed_priority(&self, feed_id: i64, priority: i64) -> Result<()>;
+
+    /// フィードの一時停止を設定・解除する（フィードが存在しなければ false）
+    fn set_feed_paused(&self, feed_id: i64, paused: bool) -> Result<bool>;
+
+    /// フィードのサイトのURLが未設定なら設定する（設定した場合は true）
+    fn fill_feed_site_url(&self, feed_id: i64, site_url: &str) -> Result<bool>;
+
+    /// URLとタイトルで新しいフィードを追加する（戻り値のURLは正規化後のもの）
+    fn add_feed_simple(&self, url: &str, title: &str) -> Result<Feed> {
+        let url = crate::dedup::normalize_feed_url(url)?;
+        let mut feed = Feed::new(url, title.to_string(), None);
+        feed.id = self.add_feed(&feed)?;
+        Ok(feed)
+    }
+
+    // -------------------------------------------------------------------------
+    // 記事
+    // -------------------------------------------------------------------------
+
+    /// 記事を追加する（同じフィードに同じURLの記事がある場合は `None`）
+    fn add_article(&self, article: &Article) -> Result<Option<i64>>;
+
+    /// 複数の記事をまとめて追加し、新規挿入された記事のIDを返す
+    ///
+    /// 全件が1つの単位で反映され、エラー時は1件も追加されません。
+    fn add_articles(&self, articles: &[Article]) -> Result<Vec<i64>>;
+
+    /// IDで記事を取得する
+    fn get_article(&self, id: i64) -> Result<Option<Article>>;
+
+    /// 条件に一致する記事を取得する
+    fn query_articles(&self, query: &ArticleQuery) -> Result<Vec<Article>>;
+
+    /// フィードの (総記事数, 未読記事数) を取得する
+    fn get_article_counts(&self, feed_id: i64) -> Result<(usize, usize)>;
+
+    /// 条件に一致する記事を1ページ分取得する（キーセットページネーション）
+    ///
+    /// `query` の `limit` / `offset` は無視されます。`cursor` には前のページの
+    /// `next_cursor` を渡し（最初のページは `None`）、続きがある場合は戻り値の
+    /// `next_cursor` に次のカーソルが入ります。
+    ///
+    /// `OFFSET n` は先頭から n 行を読み飛ばすため、ページが深くなるほど遅くなります。
+    /// カーソル（最後の記事の `(published_at, id)`）を WHERE 句の条件にすることで、
+    /// インデックス上の位置から直接読み始められます。
+    fn query_articles_page(
+        &self,
+        query: &ArticleQuery,
+        cursor: Option<&str>,
+        page_size: usize,
+    ) -> Result<ArticlePage> {
+        let mut page_query = query.clone();
+        page_query.cursor = cursor.map(str::parse::<ArticleCursor>).transpose()?;
+        page_query.offset = 0;
+        // 次のページの有無を判定するため1件多く取得する
+        page_query.limit = Some(page_size + 1);
+
+        let mut articles = self.query_articles(&page_query)?;
+
+        let next_cursor = if articles.len() > page_size {
+            articles.truncate(page_size);
+            articles
+                .last()
+                .map(|article| ArticleCursor::from_article(article).to_string())
+        } else {
+            None
+        };
+
+        Ok(ArticlePage {
+            articles,
+            next_cursor,
+        })
+    }
+
+    /// キーワード（カンマ区切り）で記事を検索する
+    fn search_articles(&self, query: &str, limit: i64) -> Result<Vec<Article>> {
+        let query = ArticleQuery::new()
+            .keywords_from_str(query)
+            .limit(limit.max(0) as usize);
+        self.query_articles(&query)
+    }
+
+    /// お気に入り記事を取得する（公開日時の降順）
+    fn get_favorite_articles(&self, limit: usize) -> Result<Vec<Article>> {
+        self.query_articles(&ArticleQuery::new().favorites_only().limit(limit))
+    }
+
+    // -------------------------------------------------------------------------
+    // 既読・お気に入り
+    // -------------------------------------------------------------------------
+
+    /// 記事を既読にする（「あとで読む」キューからは外れる）
+    fn mark_as_read(&self, id: i64) -> Result<bool>;
+
+    /// 記事を未読に戻す
+    fn mark_as_unread(&self, id: i64) -> Result<bool>;
+
+    /// 記事を一括で既読にし、更新した件数を返す
+    ///
+    /// `before_date` は RFC3339 形式で、公開日時がそれより前の記事のみが対象になります。
+    fn mark_all_read_with_filter(
+        &self,
+        feed_id: Option<i64>,
+        before_date: Option<&str>,
+    ) -> Result<usize>;
+
+    /// フィードの記事を全て未読に戻し、更新した件数を返す
+    fn mark_all_unread_by_feed(&self, feed_id: i64) -> Result<usize>;
+
+    /// 全記事を未読に戻し、更新した件数を返す
+    fn mark_all_unread(&self) -> Result<usize>;
+
+    /// 記事の既読/未読を反転する
+    fn toggle_read_status(&self, id: i64) -> Result<bool> {
+        match self.get_article(id)? {
+            Some(article) if article.is_read => self.mark_as_unread(id),
+            Some(_) => self.mark_as_read(id),
+            None => Ok(false),
+        }
+    }
+
+    /// 記事を開いたことを記録する（既読にはしない）
+    fn mark_opened(&self, id: i64) -> Result<bool>;
+
+    /// 記事をお気に入りに追加する
+    fn add_favorite(&self, id: i64) -> Result<bool>;
+
+    /// 記事をお気に入りから削除する
+    fn remove_favorite(&self, id: i64) -> Result<bool>;
+
+    /// お気に入りを切り替え、切り替え後の状態を返す
+    fn toggle_favorite(&self, id: i64) -> Result<bool> {
+        let article = self
+            .get_article(id)?
+            .with_context(|| format!("Article not found with ID: {}", id))?;
+        if article.is_favorite {
+            self.remove_favorite(id)?;
+        } else {
+            self.add_favorite(id)?;
+        }
+        Ok(!article.is_favorite)
+    }
+
+    // -------------------------------------------------------------------------
+    // タグ・キュー・メモ
+    // -------------------------------------------------------------------------
+
+    /// 全てのタグを記事数とともに取得する（タグ名順）
+    fn get_tags(&self) -> Result<Vec<(Tag, usize)>>;
+
+    /// 記事にタグを付け、新しく付けたタグの数を返す
+    fn tag_article(&self, article_id: i64, names: &[&str]) -> Result<usize>;
+
+    /// 記事からタグを外し、外したタグの数を返す
+    fn untag_article(&self, article_id: i64, names: &[&str]) -> Result<usize>;
+
+    /// 記事に付いているタグ名を取得する（タグ名順）
+    fn get_article_tags(&self, article_id: i64) -> Result<Vec<String>> {
+        Ok(self
+            .get_tags_for_articles(&[article_id])?
+            .remove(&article_id)
+            .unwrap_or_default())
+    }
+
+    /// 複数の記事に付いているタグ名をまとめて取得する
+    fn get_tags_for_articles(&self, article_ids: &[i64]) -> Result<HashMap<i64, Vec<String>>>;
+
+    /// タグの名前を変更する（変更後の名前のタグが既にあればエラー）
+    fn rename_tag(&self, old_name: &str, new_name: &str) -> Result<bool>;
+
+    /// `source` タグを `target` タグに統合し、新しく `target` タグが付いた記事の数を返す
+    fn merge_tags(&self, source: &str, target: &str) -> Result<usize>;
+
+    /// タグを削除する（記事との関連も削除される）
+    fn delete_tag(&self, name: &str) -> Result<bool>;
+
+    /// 記事を「あとで読む」キューの末尾に追加する（既に入っていれば `false`）
+    fn add_to_queue(&self, article_id: i64) -> Result<bool>;
+
+    /// 記事をキューから外す
+    fn remove_from_queue(&self, article_id: i64) -> Result<bool>;
+
+    /// キューの内容を順番通りに取得する
+    fn get_queue(&self) -> Result<Vec<QueueEntry>>;
+
+    /// キューの先頭の記事を取得する（キューからは外さない）
+    fn next_in_queue(&self) -> Result<Option<Article>> {
+        Ok(self
+            .get_queue()?
+            .into_iter()
+            .next()
+            .map(|entry| entry.article))
+    }
+
+    /// キュー内の記事の順番を変更する（0始まり）
+    fn move_in_queue(&self, article_id: i64, position: usize) -> Result<bool>;
+
+    /// キューを空にし、キューから外した記事の数を返す
+    fn clear_queue(&self) -> Result<usize>;
+
+    /// 記事のメモを取得する
+    fn get_note(&self, article_id: i64) -> Result<Option<Note>>;
+
+    /// 記事のメモを保存する（空白だけの場合は削除する）
+    fn save_note(&self, article_id: i64, body: &str) -> Result<()>;
+
+    /// 記事のメモを削除する
+    fn delete_note(&self, article_id: i64) -> Result<bool>;
+
+    /// 全てのメモを更新日時の新しい順に取得する
+    fn get_notes(&self) -> Result<Vec<Note>>;
+
+    /// 本文中の範囲（文字オフセット）をハイライトとして保存する
+    fn add_highlight(&self, article_id: i64, start: usize, end: usize) -> Result<Highlight>;
+
+    /// 本文中で最初に見つかったテキストをハイライトとして保存する
+    ///
+    /// テキストは表示用のテキスト（[`html::render_text`]）の中から探します。
+    /// 本文にテキストが見つからない場合はエラーを返します。
+    fn add_highlight_for_text(&self, article_id: i64, text: &str) -> Result<Highlight> {
+        let article = self
+            .get_article(article_id)?
+            .with_context(|| format!("Article not found with ID: {}", article_id))?;
+        let content = html::render_text(&article.content.unwrap_or_default());
+
+        let text = text.trim();
+        let byte_start = match content.find(text) {
+            Some(index) if !text.is_empty() => index,
+            _ => anyhow::bail!("Text not found in article {}: {}", article_id, text),
+        };
+        let start = content[..byte_start].chars().count();
+        let end = start + text.chars().count();
+
+        self.add_highlight(article_id, start, end)
+    }
+
+    /// 記事のハイライトを本文中の位置順に取得する
+    fn get_highlights(&self, article_id: i64) -> Result<Vec<Highlight>>;
+
+    /// ハイライトを削除する
+    fn delete_highlight(&self, id: i64) -> Result<bool>;
+
+    // -------------------------------------------------------------------------
+    // フェッチのリース
+    // -------------------------------------------------------------------------
+
+    /// フェッチのリースの取得を試みる（他のフェッチが実行中なら `None`）
+    fn try_acquire_fetch_lease(&self) -> Result<Option<FetchLease>>;
+
+    /// フェッチのリースの有効期限を延長する（まだ保持していれば `true`）
+    fn renew_fetch_lease(&self, lease: &mut FetchLease) -> Result<bool>;
+
+    /// フェッチのリースを解放する
+    fn release_fetch_lease(&self, lease: &FetchLease) -> Result<()>;
+}
+
+/// ストレージの処理をブロッキング用のスレッド（`spawn_blocking`）で実行する
+///
+/// SQLite の処理が非同期ランタイムのワーカースレッドを止めないようにします。
+///
+/// # 引数
+///
+/// * `storage` - 処理に使うストレージ（[`SharedStorage`] や [`DbPool`] のクローン）
+/// * `f` - ストレージを使う処理
+pub async fn run_blocking<S, F, T>(storage: S, f: F) -> Result<T>
+where
+    S: Send + 'static,
+    F: FnOnce(&S) -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(move || f(&storage))
+        .await
+        .context("Storage task failed")?
+}
+
+/// 新しく発行するフェッチのリース（保持者の識別子と有効期限）
+pub(crate) fn new_fetch_lease() -> FetchLease {
+    let now = Utc::now();
+    FetchLease {
+        holder: format!(
+            "pid {} at {}",
+            std::process::id(),
+            now.timestamp_nanos_opt().unwrap_or_default()
+        ),
+        expires_at: now + chrono::Duration::seconds(crate::db::FETCH_LEASE_TTL_SECS),
+    }
+}
+
+// =============================================================================
+// Database（SQLite）の実装
+// =============================================================================
+
+impl Storage for Database {
+    fn add_feed(&self, feed: &Feed) -> Result<i64> {
+        Database::add_feed(self, feed)
+    }
+
+    fn get_feeds(&self, category: Option<&str>) -> Result<Vec<Feed>> {
+        Database::get_feeds(self, category)
+    }
+
+    fn get_feed(&self, id: i64) -> Result<Option<Feed>> {
+        Database::get_feed(self, id)
+    }
+
+    fn remove_feed(&self, id: i64) -> Result<bool> {
+        Database::remove_feed(self, id)
+    }
+
+    fn rename_feed(&self, feed_id: i64, custom_name: Option<&str>) -> Result<()> {
+        Database::rename_feed(self, feed_id, custom_name)
+    }
+
+    fn update_feed_url(&self, feed_id: i64, new_url: &str) -> Result<()> {
+        Database::update_feed_url(self, feed_id, new_url)
+    }
+
+    fn find_feed_by_url(&self, url: &str) -> Result<Option<Feed>> {
+        Database::find_feed_by_url(self, url)
+    }
+
+    fn set_feed_priority(&self, feed_id: i64, priority: i64) -> Result<()> {
+        Database::set_feed_priority(self, feed_id, priority)
+    }
+
+    fn set_feed_paused(&self, feed_id: i64, paused: bool) -> Result<bool> {
+        Database::set_feed_paused(self, feed_id, paused)
+    }
+
+    fn fill_feed_site_url(&self, feed_id: i64, site_url: &str) -> Result<bool> {
+        Database::fill_feed_site_url(self, feed_id, site_url)
+    }
+
+    fn add_article(&self, article: &Article) -> Result<Option<i64>> {
+        Database::add_article(self, article)
+    }
+
+    fn add_articles(&self, articles: &[Article]) -> Result<Vec<i64>> {
+        Database::add_articles(self, articles)
+    }
+
+    fn get_article(&self, id: i64) -> Result<Option<Article>> {
+        Database::get_article(self, id)
+    }
+
+    fn query_articles(&self, query: &ArticleQuery) -> Result<Vec<Article>> {
+        Database::query_articles(self, query)
+    }
+
+    fn get_article_counts(&self, feed_id: i64) -> Result<(usize, usize)> {
+        Database::get_article_counts(self, feed_id)
+    }
+
+    fn mark_as_read(&self, id: i64) -> Result<bool> {
+        Database::mark_as_read(self, id)
+    }
+
+    fn mark_as_unread(&self, id: i64) -> Result<bool> {
+        Database::mark_as_unread(self, id)
+    }
+
+    fn mark_all_read_with_filter(
+        &self,
+        feed_id: Option<i64>,
+        before_date: Option<&str>,
+    ) -> Result<usize> {
+        Database::mark_all_read_with_filter(self, feed_id, before_date)
+    }
+
+    fn mark_all_unread_by_feed(&self, feed_id: i64) -> Result<usize> {
+        Database::mark_all_unread_by_feed(self, feed_id)
+    }
+
+    fn mark_all_unread(&self) -> Result<usize> {
+        Database::mark_all_unread(self)
+    }
+
+    fn toggle_read_status(&self, id: i64) -> Result<bool> {
+        Database::toggle_read_status(self, id)
+    }
+
+    fn mark_opened(&self, id: i64) -> Result<bool> {
+        Database::mark_opened(self, id)
+    }
+
+    fn add_favorite(&self, id: i64) -> Result<bool> {
+        Database::add_favorite(self, id)
+    }
+
+    fn remove_favorite(&self, id: i64) -> Result<bool> {
+        Database::remove_favorite(self, id)
+    }
+
+    fn toggle_favorite(&self, id: i64) -> Result<bool> {
+        Database::toggle_favorite(self, id)
+    }
+
+    fn get_tags(&self) -> Result<Vec<(Tag, usize)>> {
+        Database::get_tags(self)
+    }
+
+    fn tag_article(&self, article_id: i64, names: &[&str]) -> Result<usize> {
+        Database::tag_article(self, article_id, names)
+    }
+
+    fn untag_article(&self, article_id: i64, names: &[&str]) -> Result<usize> {
+        Database::untag_article(self, article_id, names)
+    }
+
+    fn get_tags_for_articles(&self, article_ids: &[i64]) -> Result<HashMap<i64, Vec<String>>> {
+        Database::get_tags_for_articles(self, article_ids)
+    }
+
+    fn rename_tag(&self, old_name: &str, new_name: &str) -> Result<bool> {
+        Database::rename_tag(self, old_name, new_name)
+    }
+
+    fn merge_tags(&self, source: &str, target: &str) -> Result<usize> {
+        Database::merge_tags(self, source, target)
+    }
+
+    fn delete_tag(&self, name: &str) -> Result<bool> {
+        Database::delete_tag(self, name)
+    }
+
+    fn add_to_queue(&self, article_id: i64) -> Result<bool> {
+        Database::add_to_queue(self, article_id)
+    }
+
+    fn remove_from_queue(&self, article_id: i64) -> Result<bool> {
+        Database::remove_from_queue(self, article_id)
+    }
+
+    fn get_queue(&self) -> Result<Vec<QueueEntry>> {
+        Database::get_queue(self)
+    }
+
+    fn move_in_queue(&self, article_id: i64, position: usize) -> Result<bool> {
+        Database::move_in_queue(self, article_id, position)
+    }
+
+    fn clear_queue(&self) -> Result<usize> {
+        Database::clear_queue(self)
+    }
+
+    fn get_note(&self, article_id: i64) -> Result<Option<Note>> {
+        Database::get_note(self, article_id)
+    }
+
+    fn save_note(&self, article_id: i64, body: &str) -> Result<()> {
+        Database::save_note(self, article_id, body)
+    }
+
+    fn delete_note(&self, article_id: i64) -> Result<bool> {
+        Database::delete_note(self, article_id)
+    }
+
+    fn get_notes(&self) -> Result<Vec<Note>> {
+        Database::get_notes(self)
+    }
+
+    fn add_highlight(&self, article_id: i64, start: usize, end: usize) -> Result<Highlight> {
+        Database::add_highlight(self, article_id, start, end)
+    }
+
+    fn get_highlights(&self, article_id: i64) -> Result<Vec<Highlight>> {
+        Database::get_highlights(self, article_id)
+    }
+
+    fn delete_highlight(&self, id: i64) -> Result<bool> {
+        Database::delete_highlight(self, id)
+    }
+
+    fn try_acquire_fetch_lease(&self) -> Result<Option<FetchLease>> {
+        Database::try_acquire_fetch_lease(self)
+    }
+
+    fn renew_fetch_lease(&self, lease: &mut FetchLease) -> Result<bool> {
+        Database::renew_fetch_lease(self, lease)
+    }
+
+    fn release_fetch_lease(&self, lease: &FetchLease) -> Result<()> {
+        Database::release_fetch_lease(self, lease)
+    }
+}
+
+// =============================================================================
+// DbPool の実装（呼び出しごとに接続を借りる）
+// =============================================================================
+
+impl Storage for DbPool {
+    fn add_feed(&self, feed: &Feed) -> Result<i64> {
+        self.get()?.add_feed(feed)
+    }
+
+    fn get_feeds(&self, category: Option<&str>) -> Result<Vec<Feed>> {
+        self.get()?.get_feeds(category)
+    }
+
+    fn get_feed(&self, id: i64) -> Result<Option<Feed>> {
+        self.get()?.get_feed(id)
+    }
+
+    fn remove_feed(&self, id: i64) -> Result<bool> {
+        self.get()?.remove_feed(id)
+    }
+
+    fn rename_feed(&self, feed_id: i64, custom_name: Option<&str>) -> Result<()> {
+        self.get()?.rename_feed(feed_id, custom_name)
+    }
+
+    fn update_feed_url(&self, feed_id: i64, new_url: &str) -> Result<()> {
+        self.get()?.update_feed_url(feed_id, new_url)
+    }
+
+    fn find_feed_by_url(&self, url: &str) -> Result<Option<Feed>> {
+        self.get()?.find_feed_by_url(url)
+    }
+
+    fn set_feed_priority(&self, feed_id: i64, priority: i64) -> Result<()> {
+        self.get()?.set_feed_priority(feed_id, priority)
+    }
+
+    fn set_feed_paused(&self, feed_id: i64, paused: bool) -> Result<bool> {
+        self.get()?.set_feed_paused(feed_id, paused)
+    }
+
+    fn fill_feed_site_url(&self, feed_id: i64, site_url: &str) -> Result<bool> {
+        self.get()?.fill_feed_site_url(feed_id, site_url)
+    }
+
+    fn add_article(&self, article: &Article) -> Result<Option<i64>> {
+        self.get()?.add_article(article)
+    }
+
+    fn add_articles(&self, articles: &[Article]) -> Result<Vec<i64>> {
+        self.get()?.add_articles(articles)
+    }
+
+    fn get_article(&self, id: i64) -> Result<Option<Article>> {
+        self.get()?.get_article(id)
+    }
+
+    fn query_articles(&self, query: &ArticleQuery) -> Result<Vec<Article>> {
+        self.get()?.query_articles(query)
+    }
+
+    fn get_article_counts(&self, feed_id: i64) -> Result<(usize, usize)> {
+        self.get()?.get_article_counts(feed_id)
+    }
+
+    fn mark_as_read(&self, id: i64) -> Result<bool> {
+        self.get()?.mark_as_read(id)
+    }
+
+    fn mark_as_unread(&self, id: i64) -> Result<bool> {
+        self.get()?.mark_as_unread(id)
+    }
+
+    fn mark_all_read_with_filter(
+        &self,
+        feed_id: Option<i64>,
+        before_date: Option<&str>,
+    ) -> Result<usize> {
+        self.get()?.mark_all_read_with_filter(feed_id, before_date)
+    }
+
+    fn mark_all_unread_by_feed(&self, feed_id: i64) -> Result<usize> {
+        self.get()?.mark_all_unread_by_feed(feed_id)
+    }
+
+    fn mark_all_unread(&self) -> Result<usize> {
+        self.get()?.mark_all_unread()
+    }
+
+    fn toggle_read_status(&self, id: i64) -> Result<bool> {
+        self.get()?.toggle_read_status(id)
+    }
+
+    fn mark_opened(&self, id: i64) -> Result<bool> {
+        self.get()?.mark_opened(id)
+    }
+
+    fn add_favorite(&self, id: i64) -> Result<bool> {
+        self.get()?.add_favorite(id)
+    }
+
+    fn remove_favorite(&self, id: i64) -> Result<bool> {
+        self.get()?.remove_favorite(id)
+    }
+
+    fn toggle_favorite(&self, id: i64) -> Result<bool> {
+        self.get()?.toggle_favorite(id)
+    }
+
+    fn get_tags(&self) -> Result<Vec<(Tag, usize)>> {
+        self.get()?.get_tags()
+    }
+
+    fn tag_article(&self, article_id: i64, names: &[&str]) -> Result<usize> {
+        self.get()?.tag_article(article_id, names)
+    }
+
+    fn untag_article(&self, article_id: i64, names: &[&str]) -> Result<usize> {
+        self.get()?.untag_article(article_id, names)
+    }
+
+    fn get_tags_for_articles(&self, article_ids: &[i64]) -> Result<HashMap<i64, Vec<String>>> {
+        self.get()?.get_tags_for_articles(article_ids)
+    }
+
+    fn rename_tag(&self, old_name: &str, new_name: &str) -> Result<bool> {
+        self.get()?.rename_tag(old_name, new_name)
+    }
+
+    fn merge_tags(&self, source: &str, target: &str) -> Result<usize> {
+        self.get()?.merge_tags(source, target)
+    }
+
+    fn delete_tag(&self, name: &str) -> Result<bool> {
+        self.get()?.delete_tag(name)
+    }
+
+    fn add_to_queue(&self, article_id: i64) -> Result<bool> {
+        self.get()?.add_to_queue(article_id)
+    }
+
+    fn remove_from_queue(&self, article_id: i64) -> Result<bool> {
+        self.get()?.remove_from_queue(article_id)
+    }
+
+    fn get_queue(&self) -> Result<Vec<QueueEntry>> {
+        self.get()?.get_queue()
+    }
+
+    fn move_in_queue(&self, article_id: i64, position: usize) -> Result<bool> {
+        self.get()?.move_in_queue(article_id, position)
+    }
+
+    fn clear_queue(&self) -> Result<usize> {
+        self.get()?.clear_queue()
+    }
+
+    fn get_note(&self, article_id: i64) -> Result<Option<Note>> {
+        self.get()?.get_note(article_id)
+    }
+
+    fn save_note(&self, article_id: i64, body: &str) -> Result<()> {
+        self.get()?.save_note(article_id, body)
+    }
+
+    fn delete_note(&self, article_id: i64) -> Result<bool> {
+        self.get()?.delete_note(article_id)
+    }
+
+    fn get_notes(&self) -> Result<Vec<Note>> {
+        self.get()?.get_notes()
+    }
+
+    fn add_highlight(&self, article_id: i64, start: usize, end: usize) -> Result<Highlight> {
+        self.get()?.add_highlight(article_id, start, end)
+    }
+
+    fn get_highlights(&self, article_id: i64) -> Result<Vec<Highlight>> {
+        self.get()?.get_highlights(article_id)
+    }
+
+    fn delete_highlight(&self, id: i64) -> Result<bool> {
+        self.get()?.delete_highlight(id)
+    }
+
+    fn try_acquire_fetch_lease(&self) -> Result<Option<FetchLease>> {
+        self.get()?.try_acquire_fetch_lease()
+    }
+
+    fn renew_fetch_lease(&self, lease: &mut FetchLease) -> Result<bool> {
+        self.get()?.renew_fetch_lease(lease)
+    }
+
+    fn release_fetch_lease(&self, lease: &FetchLease) -> Result<()> {
+        self.get()?.release_fetch_lease(lease)
+    }
+}
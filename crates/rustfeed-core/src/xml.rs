@@ -0,0 +1,22 @@
+//! # XML ユーティリティモジュール
+//!
+//! OPML（[`crate::opml`]）や Atom・RSS（[`crate::publish`]）の書き出しで共通に使う、
+//! `quick_xml` の [`Writer`] への書き出しの補助関数を提供します。
+
+use anyhow::Result;
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+
+/// テキストだけを持つ要素を書き出す
+///
+/// テキストはエスケープして書き出します（`<title>A &amp; B</title>` など）。
+pub(crate) fn write_text_element(
+    writer: &mut Writer<Vec<u8>>,
+    name: &str,
+    text: &str,
+) -> Result<()> {
+    writer.write_event(Event::Start(BytesStart::new(name)))?;
+    writer.write_event(Event::Text(BytesText::new(text)))?;
+    writer.write_event(Event::End(BytesEnd::new(name)))?;
+    Ok(())
+}
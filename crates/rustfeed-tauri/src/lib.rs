@@ -4,24 +4,101 @@
 //! フロントエンド（React）からTauri Commandsを呼び出すことで、
 //! フィードと記事の管理が可能になります。
 
-use rustfeed_core::db::Database;
+use rustfeed_core::{
+    config::AppConfig,
+    db::{Database, FetchLease},
+    storage::run_blocking,
+    DbPool, Storage, Tag,
+};
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
 
 /// アプリケーション状態
 ///
-/// データベース接続をMutexで保護し、複数スレッドから安全にアクセスできるようにします。
-pub struct AppState {
-    pub db: Mutex<Database>,
+/// フィードと記事の保存先（通常は SQLite の接続プール）を保持します。
+/// プールの場合はコマンドごとに接続を借りるため、フィード更新中でも他のコマンドが待たされません。
+///
+/// フィード・記事の操作は [`Storage`] トレイト経由で行うため、
+/// `AppState<MemoryStorage>` のようにインメモリのストレージでもテストできます。
+pub struct AppState<S = DbPool> {
+    pub storage: S,
 }
 
 impl AppState {
     /// 新しいAppStateを作成
     ///
-    /// データベースを開き、状態として保持します。
+    /// データベースを開き（初期化を含む）、接続プールを状態として保持します。
+    /// 旧設定の `disabled_feeds` は初回だけ一時停止として取り込みます。
     pub fn new() -> anyhow::Result<Self> {
-        let db = Database::open()?;
-        Ok(Self { db: Mutex::new(db) })
+        let pool = DbPool::open()?;
+        let config = AppConfig::load()?;
+        pool.get()?
+            .import_disabled_feeds(&config.general.disabled_feeds)?;
+        Ok(Self::from_storage(pool))
+    }
+
+    /// SQLite 固有の処理（カテゴリ・統計など）を `spawn_blocking` 上で実行し、
+    /// エラーを文字列に変換する
+    pub async fn with_db<F, T>(&self, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&Database) -> anyhow::Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.storage.run(f).await.map_err(|e| e.to_string())
+    }
+}
+
+impl<S> AppState<S>
+where
+    S: Storage + Clone + Send + Sync + 'static,
+{
+    /// 任意のストレージで AppState を作成する
+    pub fn from_storage(storage: S) -> Self {
+        Self { storage }
+    }
+
+    /// フィード・記事の処理を `spawn_blocking` 上で実行し、エラーを文字列に変換する
+    ///
+    /// Tauri Commands はフロントエンドにエラーを文字列で返すため、その変換もまとめて行います。
+    pub async fn with_storage<F, T>(&self, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&S) -> anyhow::Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        run_blocking(self.storage.clone(), f)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// フェッチのリースを取得する
+    ///
+    /// cron の `rustfeed fetch` や TUI がフェッチ中の場合はエラーを返します。
+    pub async fn acquire_fetch_lease(&self) -> Result<FetchLease, String> {
+        self.with_storage(|storage| storage.try_acquire_fetch_lease())
+            .await?
+            .ok_or_else(|| "別のプロセスがフィードを更新中です".to_string())
+    }
+
+    /// フェッチのリースを延長する（フィードごとに呼ぶ）
+    ///
+    /// 期限切れで他のプロセスに取られていた場合はエラーを返します。
+    /// その場合、呼び出し側は同時に更新しないよう更新を止めてください。
+    pub async fn renew_fetch_lease(&self, lease: &FetchLease) -> Result<(), String> {
+        let mut lease = lease.clone();
+        let renewed = self
+            .with_storage(move |storage| storage.renew_fetch_lease(&mut lease))
+            .await?;
+        if renewed {
+            Ok(())
+        } else {
+            Err("別のプロセスがフィードの更新を始めたため中断しました".to_string())
+        }
+    }
+
+    /// フェッチのリースを解放する
+    pub async fn release_fetch_lease(&self, lease: FetchLease) {
+        let _ = self
+            .with_storage(move |storage| storage.release_fetch_lease(&lease))
+            .await;
     }
 }
 
@@ -38,3 +115,11 @@ pub struct FetchResult {
     pub new_articles: usize,
     pub errors: Vec<String>,
 }
+
+/// 記事数付きのタグ（タグサイドバー用）
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TagSummary {
+    #[serde(flatten)]
+    pub tag: Tag,
+    pub article_count: usize,
+}
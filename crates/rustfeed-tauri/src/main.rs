@@ -9,8 +9,14 @@
     windows_subsystem = "windows"
 )]
 
-use rustfeed_core::models::{Article, Feed};
-use rustfeed_tauri::{AppState, FetchResult};
+use rustfeed_core::models::{
+    Article, Category, Feed, Highlight, HistoryEntry, HistoryEvent, Note, QueueEntry,
+};
+use rustfeed_core::opml::{self, OpmlImportEntry};
+use rustfeed_core::query::{ArticlePage, ArticleQuery};
+use rustfeed_core::stats::{Stats, StatsOptions};
+use rustfeed_core::Storage;
+use rustfeed_tauri::{AppState, FetchResult, TagSummary};
 use tauri::State;
 
 // =============================================================================
@@ -20,8 +26,9 @@ use tauri::State;
 /// 全フィードを取得
 #[tauri::command]
 async fn get_feeds(state: State<'_, AppState>) -> Result<Vec<Feed>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.get_feeds(None).map_err(|e| e.to_string())
+    state
+        .with_storage(move |storage| storage.get_feeds(None))
+        .await
 }
 
 /// フィードを追加
@@ -32,26 +39,27 @@ async fn add_feed(url: String, state: State<'_, AppState>) -> Result<Feed, Strin
         .await
         .map_err(|e| format!("フィードの取得に失敗: {}", e))?;
 
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-
     // データベースに追加
-    db.add_feed_simple(&url, &feed_info.title)
-        .map_err(|e| e.to_string())
+    state
+        .with_storage(move |storage| storage.add_feed_simple(&url, &feed_info.title))
+        .await
 }
 
 /// フィードを削除
 #[tauri::command]
 async fn delete_feed(id: i64, state: State<'_, AppState>) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.remove_feed(id).map_err(|e| e.to_string())?;
+    state
+        .with_storage(move |storage| storage.remove_feed(id))
+        .await?;
     Ok(())
 }
 
 /// フィードをリネーム
 #[tauri::command]
 async fn rename_feed(id: i64, title: String, state: State<'_, AppState>) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.rename_feed(id, Some(&title)).map_err(|e| e.to_string())
+    state
+        .with_storage(move |storage| storage.rename_feed(id, Some(&title)))
+        .await
 }
 
 /// 記事一覧を取得
@@ -61,106 +69,497 @@ async fn get_articles(
     unread_only: bool,
     favorites_only: bool,
     search: Option<String>,
+    tag: Option<String>,
     limit: i64,
     state: State<'_, AppState>,
 ) -> Result<Vec<Article>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+    // フィルタ条件を1つのクエリにまとめて記事を取得
+    let mut query = ArticleQuery::new().limit(limit.max(0) as usize);
+    // フィード指定がない場合は一時停止中のフィードを除外する
+    query = match feed_id {
+        Some(id) => query.feed(id),
+        None => query.exclude_paused(),
+    };
+    if unread_only {
+        query = query.unread_only();
+    }
+    if favorites_only {
+        query = query.favorites_only();
+    }
+    if let Some(search) = search {
+        query = query.keywords_from_str(&search);
+    }
+    if let Some(tag) = tag {
+        query = query.tag(tag);
+    }
 
-    // フィルタ条件に基づいて記事を取得
+    state
+        .with_storage(move |storage| storage.query_articles(&query))
+        .await
+}
+
+/// 記事一覧をページ単位で取得（遅延読み込み用）
+///
+/// `cursor` には前回の結果の `next_cursor` を渡します（最初のページは None）。
+#[tauri::command]
+async fn get_articles_page(
+    feed_id: Option<i64>,
+    unread_only: bool,
+    favorites_only: bool,
+    search: Option<String>,
+    tag: Option<String>,
+    cursor: Option<String>,
+    page_size: usize,
+    state: State<'_, AppState>,
+) -> Result<ArticlePage, String> {
+    let mut query = ArticleQuery::new();
+    // フィード指定がない場合は一時停止中のフィードを除外する
+    query = match feed_id {
+        Some(id) => query.feed(id),
+        None => query.exclude_paused(),
+    };
+    if unread_only {
+        query = query.unread_only();
+    }
     if favorites_only {
-        db.get_favorite_articles(limit as usize)
-            .map_err(|e| e.to_string())
-    } else if let Some(query) = search {
-        db.search_articles(&query, limit)
-            .map_err(|e| e.to_string())
-    } else {
-        db.get_articles(unread_only, limit as usize, None, feed_id)
-            .map_err(|e| e.to_string())
+        query = query.favorites_only();
+    }
+    if let Some(search) = search {
+        query = query.keywords_from_str(&search);
+    }
+    if let Some(tag) = tag {
+        query = query.tag(tag);
     }
+
+    state
+        .with_storage(move |storage| {
+            storage.query_articles_page(&query, cursor.as_deref(), page_size)
+        })
+        .await
 }
 
 /// 記事を既読にする
 #[tauri::command]
 async fn mark_as_read(id: i64, state: State<'_, AppState>) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.mark_as_read(id).map_err(|e| e.to_string())?;
+    state
+        .with_storage(move |storage| storage.mark_as_read(id))
+        .await?;
     Ok(())
 }
 
 /// 記事を未読にする
 #[tauri::command]
 async fn mark_as_unread(id: i64, state: State<'_, AppState>) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.mark_as_unread(id).map_err(|e| e.to_string())?;
+    state
+        .with_storage(move |storage| storage.mark_as_unread(id))
+        .await?;
     Ok(())
 }
 
 /// お気に入りを切り替え
 #[tauri::command]
 async fn toggle_favorite(id: i64, state: State<'_, AppState>) -> Result<bool, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.toggle_favorite(id).map_err(|e| e.to_string())
+    state
+        .with_storage(move |storage| storage.toggle_favorite(id))
+        .await
 }
 
 /// 記事のコンテンツを取得
 #[tauri::command]
 async fn get_article_content(id: i64, state: State<'_, AppState>) -> Result<String, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let article = db
-        .get_article(id)
-        .map_err(|e| e.to_string())?
+    let article = state
+        .with_storage(move |storage| {
+            let article = storage.get_article(id)?;
+            if article.is_some() {
+                // 本文を表示する = 記事を開いたとして読書履歴に記録する
+                storage.mark_opened(id)?;
+            }
+            Ok(article)
+        })
+        .await?
         .ok_or_else(|| "記事が見つかりません".to_string())?;
     Ok(article.content.unwrap_or_default())
 }
 
-/// 単一フィードを更新
+/// タグ一覧を記事数とともに取得
 #[tauri::command]
-async fn fetch_feed(id: i64, state: State<'_, AppState>) -> Result<usize, String> {
-    // フィード情報を取得（スコープでロックを自動解放）
-    let feed_url = {
-        let db = state.db.lock().map_err(|e| e.to_string())?;
-        let feed = db
-            .get_feed(id)
-            .map_err(|e| e.to_string())?
-            .ok_or_else(|| "フィードが見つかりません".to_string())?;
-        feed.url
+async fn get_tags(state: State<'_, AppState>) -> Result<Vec<TagSummary>, String> {
+    let tags = state
+        .with_storage(move |storage| storage.get_tags())
+        .await?;
+    Ok(tags
+        .into_iter()
+        .map(|(tag, article_count)| TagSummary { tag, article_count })
+        .collect())
+}
+
+/// 記事に付いているタグを取得
+#[tauri::command]
+async fn get_article_tags(id: i64, state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    state.with_db(move |db| db.get_article_tags(id)).await
+}
+
+/// 記事にタグを付ける
+#[tauri::command]
+async fn tag_article(
+    id: i64,
+    tags: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    state
+        .with_storage(move |storage| {
+            let names: Vec<&str> = tags.iter().map(String::as_str).collect();
+            storage.tag_article(id, &names)
+        })
+        .await
+}
+
+/// 記事からタグを外す
+#[tauri::command]
+async fn untag_article(
+    id: i64,
+    tags: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    state
+        .with_db(move |db| {
+            let names: Vec<&str> = tags.iter().map(String::as_str).collect();
+            db.untag_article(id, &names)
+        })
+        .await
+}
+
+/// タグの名前を変更
+#[tauri::command]
+async fn rename_tag(
+    old_name: String,
+    new_name: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state
+        .with_db(move |db| db.rename_tag(&old_name, &new_name))
+        .await
+}
+
+/// タグを統合
+#[tauri::command]
+async fn merge_tags(
+    source: String,
+    target: String,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    state
+        .with_db(move |db| db.merge_tags(&source, &target))
+        .await
+}
+
+/// タグを削除
+#[tauri::command]
+async fn delete_tag(name: String, state: State<'_, AppState>) -> Result<bool, String> {
+    state.with_db(move |db| db.delete_tag(&name)).await
+}
+
+/// 「あとで読む」キューを順番通りに取得
+#[tauri::command]
+async fn get_queue(state: State<'_, AppState>) -> Result<Vec<QueueEntry>, String> {
+    state.with_storage(move |storage| storage.get_queue()).await
+}
+
+/// 記事を「あとで読む」キューの末尾に追加（既に入っている場合は false）
+#[tauri::command]
+async fn add_to_queue(id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+    state
+        .with_storage(move |storage| storage.add_to_queue(id))
+        .await
+}
+
+/// 記事を「あとで読む」キューから外す
+#[tauri::command]
+async fn remove_from_queue(id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+    state
+        .with_storage(move |storage| storage.remove_from_queue(id))
+        .await
+}
+
+/// キュー内の記事の順番を変更
+#[tauri::command]
+async fn move_in_queue(
+    id: i64,
+    position: usize,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state
+        .with_storage(move |storage| storage.move_in_queue(id, position))
+        .await
+}
+
+/// 「あとで読む」キューを空にする
+#[tauri::command]
+async fn clear_queue(state: State<'_, AppState>) -> Result<usize, String> {
+    state.with_db(move |db| db.clear_queue()).await
+}
+
+/// 読書履歴を新しい順に取得（`all` が false の場合は既読にした記録のみ）
+#[tauri::command]
+async fn get_history(
+    limit: Option<usize>,
+    all: bool,
+    state: State<'_, AppState>,
+) -> Result<Vec<HistoryEntry>, String> {
+    let events: &[HistoryEvent] = if all { &[] } else { &[HistoryEvent::Read] };
+    state
+        .with_db(move |db| db.get_history(events, None, None, limit))
+        .await
+}
+
+/// 購読状況と読書の傾向を集計（`stale_days` 省略時は30日）
+#[tauri::command]
+async fn get_stats(stale_days: Option<u32>, state: State<'_, AppState>) -> Result<Stats, String> {
+    let defaults = StatsOptions::default();
+    let options = StatsOptions {
+        stale_days: stale_days.unwrap_or(defaults.stale_days),
+        ..defaults
     };
+    state.with_db(move |db| db.get_stats(&options)).await
+}
 
-    // フィードを取得（ロック解放後にawait）
-    let feed_data = rustfeed_core::feed::fetch_feed(&feed_url)
+/// 記事のメモを取得
+#[tauri::command]
+async fn get_note(id: i64, state: State<'_, AppState>) -> Result<Option<Note>, String> {
+    state
+        .with_storage(move |storage| storage.get_note(id))
         .await
-        .map_err(|e| format!("フィードの取得に失敗: {}", e))?;
+}
 
-    // 記事をデータベースに保存
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let count = rustfeed_core::feed::save_articles(&db, id, &feed_data)
-        .map_err(|e| format!("記事の保存に失敗: {}", e))?;
+/// 記事のメモを保存（空文字列の場合は削除）
+#[tauri::command]
+async fn save_note(id: i64, body: String, state: State<'_, AppState>) -> Result<(), String> {
+    state
+        .with_storage(move |storage| storage.save_note(id, &body))
+        .await
+}
 
-    Ok(count)
+/// 記事のメモを削除
+#[tauri::command]
+async fn delete_note(id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+    state.with_db(move |db| db.delete_note(id)).await
 }
 
-/// 全フィードを更新
+/// 記事のハイライト一覧を本文中の位置順で取得
 #[tauri::command]
-async fn fetch_all_feeds(state: State<'_, AppState>) -> Result<FetchResult, String> {
-    // フィード情報を取得（スコープでロックを自動解放）
-    let feeds = {
-        let db = state.db.lock().map_err(|e| e.to_string())?;
-        db.get_feeds(None).map_err(|e| e.to_string())?
+async fn get_highlights(id: i64, state: State<'_, AppState>) -> Result<Vec<Highlight>, String> {
+    state
+        .with_storage(move |storage| storage.get_highlights(id))
+        .await
+}
+
+/// 本文の範囲（文字単位のオフセット）をハイライトとして保存
+#[tauri::command]
+async fn add_highlight(
+    id: i64,
+    start: usize,
+    end: usize,
+    state: State<'_, AppState>,
+) -> Result<Highlight, String> {
+    state
+        .with_storage(move |storage| storage.add_highlight(id, start, end))
+        .await
+}
+
+/// ハイライトを削除
+#[tauri::command]
+async fn delete_highlight(highlight_id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+    state
+        .with_db(move |db| db.delete_highlight(highlight_id))
+        .await
+}
+
+/// カテゴリ一覧を木構造の順で取得
+#[tauri::command]
+async fn get_categories(state: State<'_, AppState>) -> Result<Vec<Category>, String> {
+    state.with_db(move |db| db.get_categories()).await
+}
+
+/// カテゴリを作成（途中の階層も作成される）
+#[tauri::command]
+async fn create_category(path: String, state: State<'_, AppState>) -> Result<i64, String> {
+    state.with_db(move |db| db.create_category(&path)).await
+}
+
+/// カテゴリの名前を変更
+#[tauri::command]
+async fn rename_category(
+    path: String,
+    new_name: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state
+        .with_db(move |db| db.rename_category(&path, &new_name))
+        .await
+}
+
+/// カテゴリを統合
+#[tauri::command]
+async fn merge_categories(
+    source: String,
+    target: String,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    state
+        .with_db(move |db| db.merge_categories(&source, &target))
+        .await
+}
+
+/// カテゴリを削除（フィードとサブカテゴリは親カテゴリに移動）
+#[tauri::command]
+async fn delete_category(path: String, state: State<'_, AppState>) -> Result<bool, String> {
+    state.with_db(move |db| db.delete_category(&path)).await
+}
+
+/// カテゴリを別の親の下に移動（parent が None ならトップレベル）
+#[tauri::command]
+async fn move_category(
+    path: String,
+    parent: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state
+        .with_db(move |db| db.move_category(&path, parent.as_deref()))
+        .await
+}
+
+/// 兄弟カテゴリの中での並び順を変更
+#[tauri::command]
+async fn reorder_category(
+    path: String,
+    position: usize,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state
+        .with_db(move |db| db.reorder_category(&path, position))
+        .await
+}
+
+/// フィードのカテゴリを設定（category が None ならカテゴリをクリア）
+#[tauri::command]
+async fn set_feed_category(
+    id: i64,
+    category: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state
+        .with_db(move |db| db.set_feed_category(id, category.as_deref()))
+        .await
+}
+
+/// フィードを一時停止・再開
+#[tauri::command]
+async fn set_feed_paused(id: i64, paused: bool, state: State<'_, AppState>) -> Result<(), String> {
+    let found = state
+        .with_storage(move |storage| storage.set_feed_paused(id, paused))
+        .await?;
+    if found {
+        Ok(())
+    } else {
+        Err("フィードが見つかりません".to_string())
+    }
+}
+
+/// OPML の購読リストからフィードを登録
+///
+/// `content` は OPML ファイルの内容です（ファイルはフロントエンドで読み込む）。
+/// フィードごとの登録結果を返します。
+#[tauri::command]
+async fn import_opml(
+    content: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<OpmlImportEntry>, String> {
+    state
+        .with_db(move |db| db.import_opml(&opml::parse_opml(&content)?))
+        .await
+}
+
+/// フィードを OPML 2.0 で書き出す
+///
+/// `category` を指定した場合は、そのカテゴリ（サブカテゴリを含む）のフィードだけを書き出します。
+/// OPML の内容を返します（ファイルへの保存はフロントエンドで行う）。
+#[tauri::command]
+async fn export_opml(
+    category: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    state
+        .with_db(move |db| {
+            let feeds = db.get_feeds(category.as_deref())?;
+            opml::write_opml("rustfeed subscriptions", &feeds)
+        })
+        .await
+}
+
+/// 単一フィードを更新
+#[tauri::command]
+async fn fetch_feed(id: i64, state: State<'_, AppState>) -> Result<usize, String> {
+    // フィード情報を取得
+    let feed_url = state
+        .with_storage(move |storage| storage.get_feed(id))
+        .await?
+        .ok_or_else(|| "フィードが見つかりません".to_string())?
+        .url;
+
+    // 他のプロセスと同時に更新しないようリースを取る
+    let lease = state.acquire_fetch_lease().await?;
+
+    // フィードを取得して記事を保存（プールの別の接続で行うため、他の読み込みを妨げない）
+    let result = match rustfeed_core::feed::fetch_feed(&feed_url).await {
+        Ok(feed_data) => state
+            .with_storage(move |storage| {
+                rustfeed_core::feed::save_articles(storage, id, &feed_data)
+            })
+            .await
+            .map_err(|e| format!("記事の保存に失敗: {}", e)),
+        Err(e) => Err(format!("フィードの取得に失敗: {}", e)),
     };
 
+    // 失敗した場合もリースは解放する
+    state.release_fetch_lease(lease).await;
+    result
+}
+
+/// 全フィードを更新（一時停止中のフィードは除く）
+#[tauri::command]
+async fn fetch_all_feeds(state: State<'_, AppState>) -> Result<FetchResult, String> {
+    // フィード情報を取得
+    let feeds: Vec<Feed> = state
+        .with_storage(move |storage| storage.get_feeds(None))
+        .await?
+        .into_iter()
+        .filter(|feed| !feed.paused)
+        .collect();
+
+    // 他のプロセスと同時に更新しないようリースを取る
+    let lease = state.acquire_fetch_lease().await?;
+
     let total_feeds = feeds.len();
     let mut new_articles = 0;
     let mut errors = Vec::new();
 
     for feed in feeds {
-        // フィードを取得（ロック解放後にawait）
+        // リースを失っていたら、残りのフィードは更新せずに止める
+        if let Err(e) = state.renew_fetch_lease(&lease).await {
+            errors.push(e);
+            break;
+        }
+
+        // フィードを取得
         match rustfeed_core::feed::fetch_feed(&feed.url).await {
             Ok(feed_data) => {
-                // 記事を保存（新しいスコープでロック）
-                let save_result = {
-                    let db = state.db.lock().map_err(|e| e.to_string())?;
-                    rustfeed_core::feed::save_articles(&db, feed.id, &feed_data)
-                };
+                // 記事を保存（プールの別の接続で行うため、他の読み込みを妨げない）
+                let feed_id = feed.id;
+                let save_result = state
+                    .with_storage(move |storage| {
+                        rustfeed_core::feed::save_articles(storage, feed_id, &feed_data)
+                    })
+                    .await;
                 match save_result {
                     Ok(count) => new_articles += count,
                     Err(e) => errors.push(format!("{}: {}", feed.title, e)),
@@ -170,6 +569,8 @@ async fn fetch_all_feeds(state: State<'_, AppState>) -> Result<FetchResult, Stri
         }
     }
 
+    state.release_fetch_lease(lease).await;
+
     Ok(FetchResult {
         total_feeds,
         new_articles,
@@ -199,10 +600,42 @@ fn main() {
             delete_feed,
             rename_feed,
             get_articles,
+            get_articles_page,
             mark_as_read,
             mark_as_unread,
             toggle_favorite,
             get_article_content,
+            get_tags,
+            get_article_tags,
+            tag_article,
+            untag_article,
+            rename_tag,
+            merge_tags,
+            delete_tag,
+            get_queue,
+            add_to_queue,
+            remove_from_queue,
+            move_in_queue,
+            clear_queue,
+            get_history,
+            get_stats,
+            get_note,
+            save_note,
+            delete_note,
+            get_highlights,
+            add_highlight,
+            delete_highlight,
+            get_categories,
+            create_category,
+            rename_category,
+            merge_categories,
+            delete_category,
+            move_category,
+            reorder_category,
+            set_feed_category,
+            set_feed_paused,
+            import_opml,
+            export_opml,
             fetch_feed,
             fetch_all_feeds,
             get_app_version,
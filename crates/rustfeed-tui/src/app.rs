@@ -3,14 +3,34 @@
 //! TUIアプリケーションの状態とメインループを管理します。
 
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::{
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
+    },
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
 use ratatui::{prelude::*, Terminal};
-use rustfeed_core::{config::AppConfig, db::Database, feed, Article, Feed};
+use rustfeed_core::{
+    editor, feed, html, storage::run_blocking, Article, ArticleQuery, Feed, SharedStorage, Tag,
+};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
 
 use crate::ui;
 
+/// 1回に読み込む記事数
+const ARTICLES_PAGE_SIZE: usize = 50;
+
+/// 末尾からこの件数以内に選択が近づいたら次のページを読み込む
+const LOAD_MORE_THRESHOLD: usize = 5;
+
+/// フィード一覧で「あとで読む」キュー（仮想フィード）を表示する行
+///
+/// 実際のフィードはこの次の行から並ぶため、`feeds[i]` は `i + 1` 行目に表示されます。
+const READ_LATER_ROW: usize = 0;
+
 /// フィード更新の結果を表すメッセージ
 pub enum FetchMessage {
     /// 更新開始（フィード名）
@@ -19,6 +39,10 @@ pub enum FetchMessage {
     FeedDone(String, usize, Option<String>),
     /// 全フィード更新完了（合計新規記事数）
     AllDone(usize),
+    /// 更新を開始できなかった（他のプロセスがフェッチ中など）
+    Failed(String),
+    /// リースを失ったため途中で更新を止めた（それまでの新規記事数, 理由）
+    Stopped(usize, String),
 }
 
 /// アプリケーションのフォーカス状態
@@ -26,32 +50,53 @@ pub enum FetchMessage {
 pub enum Focus {
     /// フィード一覧にフォーカス
     Feeds,
+    /// タグ一覧にフォーカス
+    Tags,
     /// 記事一覧にフォーカス
     Articles,
 }
 
+/// 記事一覧の表示元
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArticleSource {
+    /// 選択中のフィードの記事
+    Feed,
+    /// 選択中のタグが付いた記事
+    Tag,
+    /// 「あとで読む」キューの記事
+    Queue,
+}
+
 /// アプリケーション状態
 pub struct App {
-    /// データベース接続
-    pub db: Database,
-    /// アプリケーション設定
-    pub config: AppConfig,
+    /// フィードと記事の保存先（通常は SQLite の接続プール、テストではインメモリ）
+    pub storage: SharedStorage,
     /// 終了フラグ
     pub should_quit: bool,
     /// 現在のフォーカス
     pub focus: Focus,
     /// フィード一覧
     pub feeds: Vec<Feed>,
-    /// 選択中のフィードインデックス
+    /// フィード一覧で選択中の行（[`READ_LATER_ROW`] は「あとで読む」キュー）
     pub selected_feed: usize,
-    /// 記事一覧
+    /// タグ一覧（タグ, 記事数）
+    pub tags: Vec<(Tag, usize)>,
+    /// 選択中のタグインデックス
+    pub selected_tag: usize,
+    /// 記事一覧の表示元
+    pub source: ArticleSource,
+    /// 記事一覧（読み込み済みのページ）
     pub articles: Vec<Article>,
+    /// 次のページを読み込むためのカーソル（最後まで読み込んだ場合は None）
+    pub articles_cursor: Option<String>,
     /// 選択中の記事インデックス
     pub selected_article: usize,
     /// ステータスメッセージ
     pub status_message: Option<String>,
     /// フィードリストの表示可能行数（スクロール計算用）
     pub feeds_list_height: u16,
+    /// タグリストの表示可能行数（スクロール計算用）
+    pub tags_list_height: u16,
     /// 記事リストの表示可能行数（スクロール計算用）
     pub articles_list_height: u16,
     /// プレビューモードが有効かどうか
@@ -62,6 +107,10 @@ pub struct App {
     pub preview_content: Vec<String>,
     /// プレビュー画面の表示可能行数
     pub preview_height: u16,
+    /// プレビュー中の記事のハイライト（本文中で強調表示するテキスト）
+    pub preview_highlights: Vec<String>,
+    /// メモの編集待ちの記事ID（メインループでエディタを起動する）
+    pub pending_note_edit: Option<i64>,
     /// フィード更新中フラグ
     pub is_fetching: bool,
     /// フィード更新メッセージ受信用チャンネル
@@ -76,34 +125,46 @@ pub struct App {
     pub search_query: String,
     /// 検索が有効かどうか（検索結果を表示中）
     pub search_active: bool,
+    /// タグ入力モードが有効かどうか
+    pub tag_input_mode: bool,
+    /// 入力中のタグ（カンマまたは空白区切り）
+    pub tag_input: String,
 }
 
 impl App {
     /// 新しいアプリケーション状態を作成
-    pub fn new(db: Database, config: AppConfig) -> Result<Self> {
-        let feeds = db.get_feeds(None)?;
-        let articles = if !feeds.is_empty() {
-            db.get_articles(false, 50, None, Some(feeds[0].id))?
+    pub fn new(storage: SharedStorage) -> Result<Self> {
+        let feeds = storage.get_feeds(None)?;
+        let tags = storage.get_tags()?;
+        // フィードがあれば最初のフィードを、なければ「あとで読む」キューを表示する
+        let (selected_feed, source) = if feeds.is_empty() {
+            (READ_LATER_ROW, ArticleSource::Queue)
         } else {
-            Vec::new()
+            (READ_LATER_ROW + 1, ArticleSource::Feed)
         };
 
-        Ok(Self {
-            db,
-            config,
+        let mut app = Self {
+            storage,
             should_quit: false,
             focus: Focus::Feeds,
             feeds,
-            selected_feed: 0,
-            articles,
+            selected_feed,
+            tags,
+            selected_tag: 0,
+            source,
+            articles: Vec::new(),
+            articles_cursor: None,
             selected_article: 0,
             status_message: None,
             feeds_list_height: 10,    // 初期値、UIで更新される
+            tags_list_height: 10,     // 初期値、UIで更新される
             articles_list_height: 10, // 初期値、UIで更新される
             show_preview: false,
             preview_scroll: 0,
             preview_content: Vec::new(),
             preview_height: 10,       // 初期値、UIで更新される
+            preview_highlights: Vec::new(),
+            pending_note_edit: None,
             is_fetching: false,
             fetch_rx: None,
             fetching_feed: None,
@@ -111,7 +172,12 @@ impl App {
             search_mode: false,
             search_query: String::new(),
             search_active: false,
-        })
+            tag_input_mode: false,
+            tag_input: String::new(),
+        };
+        app.load_articles_for_source()?;
+
+        Ok(app)
     }
 
     /// メインループを実行
@@ -137,6 +203,11 @@ impl App {
                             continue;
                         }
                         self.handle_key(key.code, key.modifiers).await?;
+
+                        // メモの編集はターミナルを一時的に明け渡す必要があるため、ここで行う
+                        if let Some(article_id) = self.pending_note_edit.take() {
+                            self.edit_note(terminal, article_id)?;
+                        }
                     }
                 }
             }
@@ -157,6 +228,11 @@ impl App {
             return self.handle_search_key(key);
         }
 
+        // タグ入力モード時は専用のキー処理
+        if self.tag_input_mode {
+            return self.handle_tag_input_key(key);
+        }
+
         // プレビューモード時は専用のキー処理
         if self.show_preview {
             return self.handle_preview_key(key, modifiers);
@@ -207,47 +283,86 @@ impl App {
             }
 
             // 左右でフォーカス切り替え
+            // 記事一覧からは表示元（フィードまたはタグ）のペインに戻る
             KeyCode::Left | KeyCode::Char('h') => {
-                self.focus = Focus::Feeds;
+                self.focus = match (self.focus, self.source) {
+                    (Focus::Articles, ArticleSource::Tag) => Focus::Tags,
+                    _ => Focus::Feeds,
+                };
                 // 検索をクリア
                 if self.search_active {
                     self.clear_search()?;
                 }
-                self.status_message = Some("Feeds".to_string());
-            }
-            KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter => {
-                if self.focus == Focus::Feeds && !self.feeds.is_empty() {
-                    self.focus = Focus::Articles;
-                    // 検索をクリア
-                    if self.search_active {
-                        self.clear_search()?;
-                    } else {
-                        self.load_articles_for_selected_feed()?;
+                self.status_message = Some(
+                    match self.focus {
+                        Focus::Tags => "Tags",
+                        _ => "Feeds",
                     }
-                    self.status_message = Some("Articles".to_string());
+                    .to_string(),
+                );
+            }
+            // フィード一覧には常に「あとで読む」の行がある
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter
+                if self.focus == Focus::Feeds
+                    || (self.focus == Focus::Tags && !self.tags.is_empty()) =>
+            {
+                self.source = match self.focus {
+                    Focus::Tags => ArticleSource::Tag,
+                    _ if self.selected_feed == READ_LATER_ROW => ArticleSource::Queue,
+                    _ => ArticleSource::Feed,
+                };
+                self.focus = Focus::Articles;
+                // 検索をクリア
+                if self.search_active {
+                    self.clear_search()?;
+                } else {
+                    self.load_articles_for_source()?;
                 }
+                self.status_message = Some("Articles".to_string());
             }
 
             // Tab でフォーカス切り替え
             KeyCode::Tab => {
                 self.focus = match self.focus {
-                    Focus::Feeds => Focus::Articles,
+                    Focus::Feeds => Focus::Tags,
+                    Focus::Tags => Focus::Articles,
                     Focus::Articles => Focus::Feeds,
                 };
             }
 
             // 既読/未読トグル
-            KeyCode::Char('r') => {
-                if self.focus == Focus::Articles && !self.articles.is_empty() {
-                    self.toggle_read()?;
-                }
+            KeyCode::Char('r') if self.focus == Focus::Articles && !self.articles.is_empty() => {
+                self.toggle_read()?;
             }
 
             // お気に入りトグル
-            KeyCode::Char('f') => {
-                if self.focus == Focus::Articles && !self.articles.is_empty() {
-                    self.toggle_favorite()?;
-                }
+            KeyCode::Char('f') if self.focus == Focus::Articles && !self.articles.is_empty() => {
+                self.toggle_favorite()?;
+            }
+
+            // タグ付け
+            KeyCode::Char('t') if self.focus == Focus::Articles && !self.articles.is_empty() => {
+                self.start_tag_input();
+            }
+
+            // 「あとで読む」キューへの追加/削除
+            KeyCode::Char('L') if self.focus == Focus::Articles && !self.articles.is_empty() => {
+                self.toggle_queue()?;
+            }
+
+            // キュー表示中は J/K でキュー内の順番を変更
+            KeyCode::Char('K') if self.is_queue_view() && !self.articles.is_empty() => {
+                self.move_queue_item(true)?;
+            }
+            KeyCode::Char('J') if self.is_queue_view() && !self.articles.is_empty() => {
+                self.move_queue_item(false)?;
+            }
+
+            // フィードの一時停止/再開
+            KeyCode::Char('P')
+                if self.selected_stored_feed().is_some() && self.focus == Focus::Feeds =>
+            {
+                self.toggle_feed_paused()?;
             }
 
             // フィード更新
@@ -256,17 +371,13 @@ impl App {
             }
 
             // 記事をブラウザで開く
-            KeyCode::Char('o') => {
-                if self.focus == Focus::Articles && !self.articles.is_empty() {
-                    self.open_article_in_browser()?;
-                }
+            KeyCode::Char('o') if self.focus == Focus::Articles && !self.articles.is_empty() => {
+                self.open_article_in_browser()?;
             }
 
             // プレビュー表示
-            KeyCode::Char('p') => {
-                if self.focus == Focus::Articles && !self.articles.is_empty() {
-                    self.open_preview();
-                }
+            KeyCode::Char('p') if self.focus == Focus::Articles && !self.articles.is_empty() => {
+                self.open_preview()?;
             }
 
             // 検索モード開始
@@ -275,10 +386,8 @@ impl App {
             }
 
             // 検索クリア
-            KeyCode::Esc => {
-                if self.search_active {
-                    self.clear_search()?;
-                }
+            KeyCode::Esc if self.search_active => {
+                self.clear_search()?;
             }
 
             _ => {}
@@ -292,8 +401,12 @@ impl App {
         match self.focus {
             Focus::Feeds => {
                 if self.selected_feed > 0 {
-                    self.selected_feed -= 1;
-                    let _ = self.load_articles_for_selected_feed();
+                    self.select_feed(self.selected_feed - 1);
+                }
+            }
+            Focus::Tags => {
+                if self.selected_tag > 0 {
+                    self.select_tag(self.selected_tag - 1);
                 }
             }
             Focus::Articles => {
@@ -308,15 +421,20 @@ impl App {
     fn move_down(&mut self) {
         match self.focus {
             Focus::Feeds => {
-                if self.selected_feed < self.feeds.len().saturating_sub(1) {
-                    self.selected_feed += 1;
-                    let _ = self.load_articles_for_selected_feed();
+                if self.selected_feed < self.last_feed_row() {
+                    self.select_feed(self.selected_feed + 1);
+                }
+            }
+            Focus::Tags => {
+                if self.selected_tag < self.tags.len().saturating_sub(1) {
+                    self.select_tag(self.selected_tag + 1);
                 }
             }
             Focus::Articles => {
                 if self.selected_article < self.articles.len().saturating_sub(1) {
                     self.selected_article += 1;
                 }
+                self.load_more_articles();
             }
         }
     }
@@ -326,8 +444,11 @@ impl App {
         match self.focus {
             Focus::Feeds => {
                 let page_size = self.feeds_list_height.saturating_sub(2) as usize;
-                self.selected_feed = self.selected_feed.saturating_sub(page_size);
-                let _ = self.load_articles_for_selected_feed();
+                self.select_feed(self.selected_feed.saturating_sub(page_size));
+            }
+            Focus::Tags => {
+                let page_size = self.tags_list_height.saturating_sub(2) as usize;
+                self.select_tag(self.selected_tag.saturating_sub(page_size));
             }
             Focus::Articles => {
                 let page_size = self.articles_list_height.saturating_sub(2) as usize;
@@ -341,14 +462,19 @@ impl App {
         match self.focus {
             Focus::Feeds => {
                 let page_size = self.feeds_list_height.saturating_sub(2) as usize;
-                let max_index = self.feeds.len().saturating_sub(1);
-                self.selected_feed = (self.selected_feed + page_size).min(max_index);
-                let _ = self.load_articles_for_selected_feed();
+                let max_index = self.last_feed_row();
+                self.select_feed((self.selected_feed + page_size).min(max_index));
+            }
+            Focus::Tags => {
+                let page_size = self.tags_list_height.saturating_sub(2) as usize;
+                let max_index = self.tags.len().saturating_sub(1);
+                self.select_tag((self.selected_tag + page_size).min(max_index));
             }
             Focus::Articles => {
                 let page_size = self.articles_list_height.saturating_sub(2) as usize;
                 let max_index = self.articles.len().saturating_sub(1);
                 self.selected_article = (self.selected_article + page_size).min(max_index);
+                self.load_more_articles();
             }
         }
     }
@@ -358,8 +484,11 @@ impl App {
         match self.focus {
             Focus::Feeds => {
                 let half_page = (self.feeds_list_height.saturating_sub(2) / 2) as usize;
-                self.selected_feed = self.selected_feed.saturating_sub(half_page.max(1));
-                let _ = self.load_articles_for_selected_feed();
+                self.select_feed(self.selected_feed.saturating_sub(half_page.max(1)));
+            }
+            Focus::Tags => {
+                let half_page = (self.tags_list_height.saturating_sub(2) / 2) as usize;
+                self.select_tag(self.selected_tag.saturating_sub(half_page.max(1)));
             }
             Focus::Articles => {
                 let half_page = (self.articles_list_height.saturating_sub(2) / 2) as usize;
@@ -373,14 +502,19 @@ impl App {
         match self.focus {
             Focus::Feeds => {
                 let half_page = (self.feeds_list_height.saturating_sub(2) / 2) as usize;
-                let max_index = self.feeds.len().saturating_sub(1);
-                self.selected_feed = (self.selected_feed + half_page.max(1)).min(max_index);
-                let _ = self.load_articles_for_selected_feed();
+                let max_index = self.last_feed_row();
+                self.select_feed((self.selected_feed + half_page.max(1)).min(max_index));
+            }
+            Focus::Tags => {
+                let half_page = (self.tags_list_height.saturating_sub(2) / 2) as usize;
+                let max_index = self.tags.len().saturating_sub(1);
+                self.select_tag((self.selected_tag + half_page.max(1)).min(max_index));
             }
             Focus::Articles => {
                 let half_page = (self.articles_list_height.saturating_sub(2) / 2) as usize;
                 let max_index = self.articles.len().saturating_sub(1);
                 self.selected_article = (self.selected_article + half_page.max(1)).min(max_index);
+                self.load_more_articles();
             }
         }
     }
@@ -390,8 +524,12 @@ impl App {
         match self.focus {
             Focus::Feeds => {
                 if self.selected_feed != 0 {
-                    self.selected_feed = 0;
-                    let _ = self.load_articles_for_selected_feed();
+                    self.select_feed(0);
+                }
+            }
+            Focus::Tags => {
+                if self.selected_tag != 0 {
+                    self.select_tag(0);
                 }
             }
             Focus::Articles => {
@@ -404,38 +542,152 @@ impl App {
     fn jump_to_bottom(&mut self) {
         match self.focus {
             Focus::Feeds => {
-                let max_index = self.feeds.len().saturating_sub(1);
+                let max_index = self.last_feed_row();
                 if self.selected_feed != max_index {
-                    self.selected_feed = max_index;
-                    let _ = self.load_articles_for_selected_feed();
+                    self.select_feed(max_index);
+                }
+            }
+            Focus::Tags => {
+                let max_index = self.tags.len().saturating_sub(1);
+                if self.selected_tag != max_index {
+                    self.select_tag(max_index);
                 }
             }
             Focus::Articles => {
                 self.selected_article = self.articles.len().saturating_sub(1);
+                self.load_more_articles();
             }
         }
     }
 
-    /// 選択中のフィードの記事を読み込む
-    fn load_articles_for_selected_feed(&mut self) -> Result<()> {
-        if let Some(feed) = self.feeds.get(self.selected_feed) {
-            let previous_selected = self.selected_article;
-            self.articles = self.db.get_articles(false, 50, None, Some(feed.id))?;
-            // 選択位置を保持（記事数が減った場合は調整）
-            if self.articles.is_empty() {
-                self.selected_article = 0;
-            } else {
-                self.selected_article = previous_selected.min(self.articles.len() - 1);
-            }
+    /// フィード一覧の最後の行（先頭に「あとで読む」の行があるため `feeds.len()` と同じ）
+    fn last_feed_row(&self) -> usize {
+        self.feeds.len()
+    }
+
+    /// フィード一覧で選択中のフィード（「あとで読む」の行を選択中は None）
+    pub fn selected_stored_feed(&self) -> Option<&Feed> {
+        self.selected_feed
+            .checked_sub(READ_LATER_ROW + 1)
+            .and_then(|index| self.feeds.get(index))
+    }
+
+    /// 「あとで読む」キューを表示中かどうか（検索結果の表示中は除く）
+    fn is_queue_view(&self) -> bool {
+        self.focus == Focus::Articles && self.source == ArticleSource::Queue && !self.search_active
+    }
+
+    /// フィード一覧の行を選択し、その記事を表示する
+    fn select_feed(&mut self, index: usize) {
+        self.selected_feed = index;
+        self.source = if index == READ_LATER_ROW {
+            ArticleSource::Queue
+        } else {
+            ArticleSource::Feed
+        };
+        let _ = self.load_articles_for_source();
+    }
+
+    /// タグを選択し、そのタグが付いた記事を表示する
+    fn select_tag(&mut self, index: usize) {
+        self.selected_tag = index;
+        self.source = ArticleSource::Tag;
+        let _ = self.load_articles_for_source();
+    }
+
+    /// 現在の表示元（フィードまたはタグ）に対応する記事の取得条件
+    ///
+    /// 「あとで読む」キューは独自の順番を持つため、取得条件では表せません（None を返す）。
+    fn source_query(&self) -> Option<ArticleQuery> {
+        match self.source {
+            // 1つのフィードの記事は、他のフィードの記事の重複でも全て表示する
+            ArticleSource::Feed => self
+                .selected_stored_feed()
+                .map(|feed| ArticleQuery::new().feed(feed.id)),
+            // タグは複数のフィードにまたがるため、重複記事は元の記事だけにまとめる
+            ArticleSource::Tag => self.tags.get(self.selected_tag).map(|(tag, _)| {
+                ArticleQuery::new()
+                    .tag(tag.name.clone())
+                    .collapse_duplicates()
+            }),
+            ArticleSource::Queue => None,
+        }
+    }
+
+    /// 選択中のフィード（またはタグ）の記事を最初のページから読み込む
+    fn load_articles_for_source(&mut self) -> Result<()> {
+        self.load_articles_page(ARTICLES_PAGE_SIZE)
+    }
+
+    /// 読み込み済みの件数を保ったまま記事を再読み込みする
+    ///
+    /// 既読切り替えなどの後に使い、スクロールして読み込んだページを失わないようにします。
+    fn reload_articles(&mut self) -> Result<()> {
+        self.load_articles_page(self.articles.len().max(ARTICLES_PAGE_SIZE))
+    }
+
+    /// 選択中のフィード（またはタグ）の記事を先頭から `page_size` 件読み込む
+    fn load_articles_page(&mut self, page_size: usize) -> Result<()> {
+        let previous_selected = self.selected_article;
+
+        if self.source == ArticleSource::Queue {
+            // キューは件数が少ないため、ページングせずに全件をキューの順番で読み込む
+            self.articles = self
+                .storage
+                .get_queue()?
+                .into_iter()
+                .map(|entry| entry.article)
+                .collect();
+            self.articles_cursor = None;
+        } else if let Some(query) = self.source_query() {
+            let page = self.storage.query_articles_page(&query, None, page_size)?;
+            self.articles = page.articles;
+            self.articles_cursor = page.next_cursor;
+        } else {
+            return Ok(());
+        }
+
+        // 選択位置を保持（記事数が減った場合は調整）
+        if self.articles.is_empty() {
+            self.selected_article = 0;
+        } else {
+            self.selected_article = previous_selected.min(self.articles.len() - 1);
+        }
+        Ok(())
+    }
+
+    /// 次のページを読み込み、失敗した場合はステータスラインに表示する
+    ///
+    /// カーソル移動から呼ばれるため、読み込みに失敗しても移動そのものは取り消さない
+    fn load_more_articles(&mut self) {
+        if let Err(e) = self.load_more_articles_if_needed() {
+            self.status_message = Some(format!("Failed to load articles: {}", e));
+        }
+    }
+
+    /// 選択位置がリスト末尾に近づいたら次のページを追加で読み込む（無限スクロール）
+    fn load_more_articles_if_needed(&mut self) -> Result<()> {
+        if self.search_active || self.selected_article + LOAD_MORE_THRESHOLD < self.articles.len() {
+            return Ok(());
         }
+
+        let (Some(query), Some(cursor)) = (self.source_query(), &self.articles_cursor) else {
+            return Ok(());
+        };
+
+        let page = self
+            .storage
+            .query_articles_page(&query, Some(cursor), ARTICLES_PAGE_SIZE)?;
+        self.articles.extend(page.articles);
+        self.articles_cursor = page.next_cursor;
         Ok(())
     }
 
     /// 既読/未読をトグル
     fn toggle_read(&mut self) -> Result<()> {
         if let Some(article) = self.articles.get(self.selected_article) {
-            self.db.toggle_read_status(article.id)?;
-            self.load_articles_for_selected_feed()?;
+            self.storage.toggle_read_status(article.id)?;
+            self.reload_articles()?;
             self.status_message = Some("Toggled read status".to_string());
         }
         Ok(())
@@ -445,13 +697,60 @@ impl App {
     fn toggle_favorite(&mut self) -> Result<()> {
         if let Some(article) = self.articles.get(self.selected_article) {
             if article.is_favorite {
-                self.db.remove_favorite(article.id)?;
+                self.storage.remove_favorite(article.id)?;
                 self.status_message = Some("Removed from favorites".to_string());
             } else {
-                self.db.add_favorite(article.id)?;
+                self.storage.add_favorite(article.id)?;
                 self.status_message = Some("Added to favorites".to_string());
             }
-            self.load_articles_for_selected_feed()?;
+            self.reload_articles()?;
+        }
+        Ok(())
+    }
+
+    /// 選択中のフィードを一時停止する（一時停止中の場合は再開する）
+    fn toggle_feed_paused(&mut self) -> Result<()> {
+        if let Some(feed) = self.selected_stored_feed() {
+            let (id, paused) = (feed.id, !feed.paused);
+            let name = feed.display_name().to_string();
+            self.storage.set_feed_paused(id, paused)?;
+            self.feeds = self.storage.get_feeds(None)?;
+            self.status_message = Some(if paused {
+                format!("Paused {}", name)
+            } else {
+                format!("Resumed {}", name)
+            });
+        }
+        Ok(())
+    }
+
+    /// 選択中の記事を「あとで読む」キューに追加する（既に入っている場合は外す）
+    fn toggle_queue(&mut self) -> Result<()> {
+        if let Some(article) = self.articles.get(self.selected_article) {
+            if self.storage.add_to_queue(article.id)? {
+                self.status_message = Some("Added to Read Later".to_string());
+            } else {
+                self.storage.remove_from_queue(article.id)?;
+                self.status_message = Some("Removed from Read Later".to_string());
+            }
+            if self.is_queue_view() {
+                self.reload_articles()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// キュー表示中に、選択中の記事の順番を1つ上（または下）に移動する
+    fn move_queue_item(&mut self, up: bool) -> Result<()> {
+        if let Some(article) = self.articles.get(self.selected_article) {
+            let target = if up {
+                self.selected_article.saturating_sub(1)
+            } else {
+                (self.selected_article + 1).min(self.articles.len() - 1)
+            };
+            self.storage.move_in_queue(article.id, target)?;
+            self.selected_article = target;
+            self.reload_articles()?;
         }
         Ok(())
     }
@@ -463,10 +762,11 @@ impl App {
                 match Self::open_url(url) {
                     Ok(_) => {
                         self.status_message = Some("Opened in browser".to_string());
+                        self.storage.mark_opened(article.id)?;
                         // 記事を既読にする
                         if !article.is_read {
-                            self.db.mark_as_read(article.id)?;
-                            self.load_articles_for_selected_feed()?;
+                            self.storage.mark_as_read(article.id)?;
+                            self.reload_articles()?;
                         }
                     }
                     Err(e) => {
@@ -508,26 +808,110 @@ impl App {
 
 
     /// プレビューを開く
-    fn open_preview(&mut self) {
+    fn open_preview(&mut self) -> Result<()> {
         if let Some(article) = self.articles.get(self.selected_article) {
-            // HTMLコンテンツをテキストに変換
-            let content = article.content.as_deref().unwrap_or("(No content available)");
-            let text = html2text::from_read(content.as_bytes(), 80);
-            
-            // 行ごとに分割して保存
-            self.preview_content = text.lines().map(|s| s.to_string()).collect();
+            // 読書履歴に記録する
+            self.storage.mark_opened(article.id)?;
+            self.build_preview();
             self.preview_scroll = 0;
             self.show_preview = true;
         }
+        Ok(())
+    }
+
+    /// 選択中の記事の本文・メモ・ハイライトからプレビューの内容を組み立てる
+    fn build_preview(&mut self) {
+        let Some(article) = self.articles.get(self.selected_article) else {
+            return;
+        };
+
+        // HTMLコンテンツをテキストに変換
+        let content = article
+            .content
+            .as_deref()
+            .unwrap_or("(No content available)");
+        // ハイライトの位置と合わせるため、コアと同じ変換を使う
+        let text = html::render_text(content);
+
+        // 行ごとに分割して保存
+        self.preview_content = text.lines().map(|s| s.to_string()).collect();
+
+        // メモとハイライトを本文の後ろに表示する
+        let note = self.storage.get_note(article.id).ok().flatten();
+        let highlights = self.storage.get_highlights(article.id).unwrap_or_default();
+
+        if let Some(note) = note {
+            self.preview_content.push(String::new());
+            self.preview_content.push("── Note ──".to_string());
+            self.preview_content
+                .extend(note.body.lines().map(|line| line.to_string()));
+        }
+        if !highlights.is_empty() {
+            self.preview_content.push(String::new());
+            self.preview_content.push("── Highlights ──".to_string());
+            self.preview_content.extend(
+                highlights
+                    .iter()
+                    .map(|highlight| format!("> {}", highlight.text)),
+            );
+        }
+        self.preview_highlights = highlights
+            .into_iter()
+            .map(|highlight| highlight.text)
+            .collect();
     }
 
     /// プレビューを閉じる
     fn close_preview(&mut self) {
         self.show_preview = false;
         self.preview_content.clear();
+        self.preview_highlights.clear();
         self.preview_scroll = 0;
     }
 
+    /// ターミナルを一時的に元に戻して `$EDITOR` で記事のメモを編集する
+    fn edit_note<B: Backend>(&mut self, terminal: &mut Terminal<B>, article_id: i64) -> Result<()> {
+        let initial = self
+            .storage
+            .get_note(article_id)?
+            .map(|note| note.body)
+            .unwrap_or_default();
+
+        // エディタがターミナルを使えるよう、TUIの状態を解除する
+        disable_raw_mode()?;
+        execute!(std::io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+
+        let edited = editor::edit_text(&initial, &format!("note-{}", article_id));
+
+        // TUIの状態に戻して画面全体を再描画する
+        enable_raw_mode()?;
+        execute!(std::io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        terminal.clear()?;
+
+        match edited {
+            Ok(body) if body == initial => {
+                self.status_message = Some("Note unchanged".to_string());
+            }
+            Ok(body) => {
+                self.storage.save_note(article_id, &body)?;
+                self.status_message = Some(if body.trim().is_empty() {
+                    "Note removed".to_string()
+                } else {
+                    "Note saved".to_string()
+                });
+            }
+            Err(e) => {
+                self.status_message = Some(format!("Failed to edit note: {}", e));
+            }
+        }
+
+        // メモの表示を更新（スクロール位置は保つ）
+        if self.show_preview {
+            self.build_preview();
+        }
+        Ok(())
+    }
+
     /// プレビューモード時のキー処理
     fn handle_preview_key(&mut self, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
         match key {
@@ -578,6 +962,14 @@ impl App {
                 self.open_article_in_browser()?;
             }
 
+            // メモを編集（エディタはメインループで起動する）
+            KeyCode::Char('n') => {
+                self.pending_note_edit = self
+                    .articles
+                    .get(self.selected_article)
+                    .map(|article| article.id);
+            }
+
             _ => {}
         }
         Ok(())
@@ -596,14 +988,19 @@ impl App {
     }
 
 
-    /// フィード更新を開始（バックグラウンドで実行）
+    /// フィード更新を開始（バックグラウンドで実行、一時停止中のフィードは除く）
     fn start_fetch(&mut self) {
         if self.is_fetching {
             self.status_message = Some("Already fetching...".to_string());
             return;
         }
 
-        let feeds = self.feeds.clone();
+        let feeds: Vec<Feed> = self
+            .feeds
+            .iter()
+            .filter(|feed| !feed.paused)
+            .cloned()
+            .collect();
         if feeds.is_empty() {
             self.status_message = Some("No feeds to fetch".to_string());
             return;
@@ -617,35 +1014,69 @@ impl App {
         self.status_message = Some("Starting fetch...".to_string());
 
         // バックグラウンドタスクを起動
+        // 保存は spawn_blocking 上で行うため、UIからの読み込みを妨げない
+        let storage = Arc::clone(&self.storage);
         tokio::spawn(async move {
-            // バックグラウンドタスク用のDB接続を作成
-            let db = match Database::new() {
-                Ok(d) => d,
-                Err(_) => return,
+            // 他のプロセス（cron の fetch など）と同時に更新しないようリースを取る
+            let lease = match run_blocking(Arc::clone(&storage), |storage| {
+                storage.try_acquire_fetch_lease()
+            })
+            .await
+            {
+                Ok(Some(lease)) => lease,
+                Ok(None) => {
+                    let message = "Another rustfeed process is already fetching".to_string();
+                    let _ = tx.send(FetchMessage::Failed(message)).await;
+                    return;
+                }
+                Err(e) => {
+                    let _ = tx.send(FetchMessage::Failed(e.to_string())).await;
+                    return;
+                }
             };
 
             let mut total_new = 0;
 
             for stored_feed in feeds {
                 let feed_name = stored_feed.display_name().to_string();
-                
+
+                // フィードが多い場合にリースが期限切れにならないよう延長する
+                // 期限切れで他のプロセスに取られていたら、同時に更新しないようここで止める
+                let mut renewed = lease.clone();
+                let renewal = run_blocking(Arc::clone(&storage), move |storage| {
+                    storage.renew_fetch_lease(&mut renewed)
+                })
+                .await;
+                let reason = match renewal {
+                    Ok(true) => None,
+                    Ok(false) => Some("fetch lease was taken over by another process".to_string()),
+                    Err(e) => Some(e.to_string()),
+                };
+                if let Some(reason) = reason {
+                    let _ = tx.send(FetchMessage::Stopped(total_new, reason)).await;
+                    return;
+                }
+
                 // 更新開始を通知
                 let _ = tx.send(FetchMessage::Started(feed_name.clone())).await;
 
                 // フィードを取得
                 match feed::fetch_feed(&stored_feed.url).await {
-                    Ok((_feed_info, articles)) => {
-                        let mut new_count = 0;
-
-                        for mut article in articles {
-                            article.feed_id = stored_feed.id;
-                            if let Ok(Some(_)) = db.add_article(&article) {
-                                new_count += 1;
+                    Ok(feed_data) => {
+                        let feed_id = stored_feed.id;
+                        let saved = run_blocking(Arc::clone(&storage), move |storage| {
+                            feed::save_articles(storage.as_ref(), feed_id, &feed_data)
+                        })
+                        .await;
+
+                        let message = match saved {
+                            Ok(new_count) => {
+                                total_new += new_count;
+                                FetchMessage::FeedDone(feed_name, new_count, None)
                             }
-                        }
-
-                        total_new += new_count;
-                        let _ = tx.send(FetchMessage::FeedDone(feed_name, new_count, None)).await;
+                            Err(e) => FetchMessage::FeedDone(feed_name, 0, Some(e.to_string())),
+                        };
+                        let _ = tx.send(message).await;
                     }
                     Err(e) => {
                         let _ = tx.send(FetchMessage::FeedDone(
@@ -657,6 +1088,8 @@ impl App {
                 }
             }
 
+            let _ = run_blocking(storage, move |storage| storage.release_fetch_lease(&lease)).await;
+
             // 全完了を通知
             let _ = tx.send(FetchMessage::AllDone(total_new)).await;
         });
@@ -706,8 +1139,26 @@ impl App {
                         total_new
                     ));
                     // フィードと記事を再読み込み
-                    self.feeds = self.db.get_feeds(None)?;
-                    self.load_articles_for_selected_feed()?;
+                    self.feeds = self.storage.get_feeds(None)?;
+                    self.reload_articles()?;
+                }
+                FetchMessage::Failed(error) => {
+                    self.is_fetching = false;
+                    self.fetching_feed = None;
+                    self.fetch_rx = None;
+                    self.status_message = Some(format!("Fetch not started: {}", error));
+                }
+                FetchMessage::Stopped(total_new, reason) => {
+                    self.is_fetching = false;
+                    self.fetching_feed = None;
+                    self.fetch_rx = None;
+                    self.status_message = Some(format!(
+                        "Fetch stopped ({} new articles): {}",
+                        total_new, reason
+                    ));
+                    // 止めるまでに保存した記事を表示する
+                    self.feeds = self.storage.get_feeds(None)?;
+                    self.reload_articles()?;
                 }
             }
         }
@@ -756,13 +1207,13 @@ impl App {
 
     /// 検索を実行
     fn execute_search(&mut self) -> Result<()> {
-        // 全フィードから検索（フィルタとして検索クエリを使用）
-        self.articles = self.db.get_articles(
-            false,  // unread_only
-            100,    // limit（検索結果は多めに）
-            Some(&self.search_query),  // filter
-            None,   // feed_id（全フィード対象）
-        )?;
+        // 全フィードから検索（一時停止中のフィードは除外し、重複記事はまとめる）
+        let query = ArticleQuery::new()
+            .keywords_from_str(&self.search_query)
+            .exclude_paused()
+            .collapse_duplicates()
+            .limit(100); // 検索結果は多めに
+        self.articles = self.storage.query_articles(&query)?;
 
         self.search_active = true;
         self.selected_article = 0;
@@ -779,8 +1230,149 @@ impl App {
     fn clear_search(&mut self) -> Result<()> {
         self.search_active = false;
         self.search_query.clear();
-        self.load_articles_for_selected_feed()?;
+        self.load_articles_for_source()?;
         self.status_message = Some("Search cleared".to_string());
         Ok(())
     }
+
+    /// タグ入力モードを開始
+    fn start_tag_input(&mut self) {
+        self.tag_input_mode = true;
+        self.tag_input.clear();
+    }
+
+    /// タグ入力モード時のキー処理
+    fn handle_tag_input_key(&mut self, key: KeyCode) -> Result<()> {
+        match key {
+            // タグを付ける
+            KeyCode::Enter => {
+                self.tag_input_mode = false;
+                self.apply_tag_input()?;
+            }
+
+            // キャンセル
+            KeyCode::Esc => {
+                self.tag_input_mode = false;
+                self.tag_input.clear();
+            }
+
+            // 文字入力
+            KeyCode::Char(c) => {
+                self.tag_input.push(c);
+            }
+
+            // バックスペース
+            KeyCode::Backspace => {
+                self.tag_input.pop();
+            }
+
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// 入力されたタグを選択中の記事に付ける
+    fn apply_tag_input(&mut self) -> Result<()> {
+        let input = std::mem::take(&mut self.tag_input);
+        let names: Vec<&str> = input
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(article) = self.articles.get(self.selected_article) {
+            let added = self.storage.tag_article(article.id, &names)?;
+            self.status_message = Some(format!("Added {} tag(s)", added));
+            self.tags = self.storage.get_tags()?;
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustfeed_core::{MemoryStorage, Storage};
+
+    /// インメモリのストレージで、~/.rustfeed に触れずに操作を確認するテスト
+    #[test]
+    fn test_app_with_memory_storage() {
+        let storage = MemoryStorage::new();
+        let feed_id = storage
+            .add_feed(&Feed::new(
+                "https://example.com/feed".to_string(),
+                "Example".to_string(),
+                None,
+            ))
+            .unwrap();
+        for i in 1..=3 {
+            let article = Article::new(feed_id, format!("Article {}", i), None, None, None);
+            storage.add_article(&article).unwrap();
+        }
+
+        let mut app = App::new(Arc::new(storage.clone())).unwrap();
+        assert_eq!(app.source, ArticleSource::Feed);
+        assert_eq!(app.articles.len(), 3);
+
+        // 既読の切り替えとキューへの追加がストレージに反映される
+        let id = app.articles[0].id;
+        app.toggle_read().unwrap();
+        assert!(app.articles[0].is_read);
+        app.toggle_queue().unwrap();
+
+        let queue = storage.get_queue().unwrap();
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].article.id, id);
+        assert!(storage.get_article(id).unwrap().unwrap().is_read);
+    }
+
+    /// タグの記事一覧では、他のフィードから届いた重複記事を元の記事にまとめることを確認するテスト
+    #[test]
+    fn test_tag_view_collapses_duplicates() {
+        let storage = MemoryStorage::new();
+        let mut ids = Vec::new();
+        for (feed_url, article_url) in [
+            (
+                "https://blog.example.com/feed",
+                "https://blog.example.com/post",
+            ),
+            (
+                "https://planet.example.com/feed",
+                "https://blog.example.com/post?utm_source=planet",
+            ),
+        ] {
+            let feed_id = storage
+                .add_feed(&Feed::new(
+                    feed_url.to_string(),
+                    "Example".to_string(),
+                    None,
+                ))
+                .unwrap();
+            let article = Article::new(
+                feed_id,
+                "Post".to_string(),
+                Some(article_url.to_string()),
+                None,
+                None,
+            );
+            let id = storage.add_article(&article).unwrap().unwrap();
+            storage.tag_article(id, &["rust"]).unwrap();
+            ids.push(id);
+        }
+
+        let mut app = App::new(Arc::new(storage)).unwrap();
+        app.select_tag(0);
+        assert_eq!(app.source, ArticleSource::Tag);
+        assert_eq!(
+            app.articles.iter().map(|a| a.id).collect::<Vec<_>>(),
+            vec![ids[0]]
+        );
+    }
 }
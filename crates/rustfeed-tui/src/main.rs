@@ -6,7 +6,7 @@
 //!
 //! - インタラクティブなフィード・記事閲覧
 //! - キーボードナビゲーション
-//! - 2ペインレイアウト（フィード一覧 + 記事一覧）
+//! - 2ペインレイアウト（フィード一覧・タグ一覧 + 記事一覧）
 //!
 //! ## 使用方法
 //!
@@ -20,6 +20,10 @@
 //! - `Enter`: 選択
 //! - `r`: 既読/未読切り替え
 //! - `f`: お気に入り切り替え
+//! - `t`: 記事にタグを付ける
+//! - `L`: 「あとで読む」キューに追加/削除（キュー表示中は `J`/`K` で順番を変更）
+//! - `n`（プレビュー中）: 記事のメモを `$EDITOR` で編集
+//! - `Tab`: フィード / タグ / 記事のペインを切り替え
 //! - `R`: フィード更新
 //! - `q`: 終了
 
@@ -35,21 +39,25 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::prelude::*;
-use rustfeed_core::{config::AppConfig, db::Database};
+use rustfeed_core::{config::AppConfig, DbPool};
 use std::io;
+use std::sync::Arc;
 
 /// アプリケーションのエントリーポイント
 #[tokio::main]
 async fn main() -> Result<()> {
-    // データベースを初期化
-    let db = Database::new()?;
-    db.init()?;
+    // データベースを初期化（UIとバックグラウンドの更新で接続を共有するプール）
+    let pool = DbPool::open()?;
 
     // 設定を読み込み
     let config = AppConfig::load()?;
 
+    // 旧設定の disabled_feeds を一時停止として取り込む（初回のみ）
+    pool.get()?
+        .import_disabled_feeds(&config.general.disabled_feeds)?;
+
     // アプリケーション状態を初期化
-    let mut app = App::new(db, config)?;
+    let mut app = App::new(Arc::new(pool))?;
 
     // ターミナルをセットアップ
     enable_raw_mode()?;
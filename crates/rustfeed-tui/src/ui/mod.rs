@@ -13,7 +13,7 @@ use ratatui::{
     Frame,
 };
 
-use crate::app::{App, Focus};
+use crate::app::{App, ArticleSource, Focus};
 
 /// メイン描画関数
 pub fn render(frame: &mut Frame, app: &mut App) {
@@ -44,12 +44,25 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         ])
         .split(chunks[1]);
 
+    // 左ペインを上下に分割（フィード一覧 + タグ一覧）
+    let sidebar_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(65), // フィード一覧
+            Constraint::Percentage(35), // タグ一覧
+        ])
+        .split(main_chunks[0]);
+
     // リスト高さを更新（ボーダー分を引いた内部の高さ）
-    app.feeds_list_height = main_chunks[0].height;
+    app.feeds_list_height = sidebar_chunks[0].height;
+    app.tags_list_height = sidebar_chunks[1].height;
     app.articles_list_height = main_chunks[1].height;
 
     // フィード一覧を描画
-    render_feeds(frame, app, main_chunks[0]);
+    render_feeds(frame, app, sidebar_chunks[0]);
+
+    // タグ一覧を描画
+    render_tags(frame, app, sidebar_chunks[1]);
 
     // 記事一覧を描画
     render_articles(frame, app, main_chunks[1]);
@@ -61,7 +74,11 @@ pub fn render(frame: &mut Frame, app: &mut App) {
 /// ヘッダーを描画
 fn render_header(frame: &mut Frame, area: Rect) {
     let header = Paragraph::new("rustfeed-tui - RSS Reader")
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
         .block(
             Block::default()
                 .borders(Borders::ALL)
@@ -72,14 +89,26 @@ fn render_header(frame: &mut Frame, area: Rect) {
 
 /// フィード一覧を描画
 fn render_feeds(frame: &mut Frame, app: &App, area: Rect) {
-    let items: Vec<ListItem> = app
-        .feeds
-        .iter()
-        .map(|feed| {
+    // 先頭の行は「あとで読む」キュー（仮想フィード）
+    let read_later = ListItem::new(Span::styled(
+        "⏱ Read Later",
+        Style::default().fg(Color::Magenta),
+    ));
+    let items: Vec<ListItem> = std::iter::once(read_later)
+        .chain(app.feeds.iter().map(|feed| {
             let name = feed.display_name();
-            ListItem::new(name.to_string())
-        })
+            // 一時停止中のフィードは薄く表示する
+            if feed.paused {
+                ListItem::new(Span::styled(
+                    format!("⏸ {}", name),
+                    Style::default().fg(Color::DarkGray),
+                ))
+            } else {
+                ListItem::new(name.to_string())
+            }
+        }))
         .collect();
+    let row_count = items.len();
 
     let border_style = if app.focus == Focus::Feeds {
         Style::default().fg(Color::Yellow)
@@ -102,20 +131,22 @@ fn render_feeds(frame: &mut Frame, app: &App, area: Rect) {
         .highlight_symbol("> ");
 
     let mut state = ListState::default();
-    state.select(Some(app.selected_feed));
+    if matches!(app.source, ArticleSource::Feed | ArticleSource::Queue) {
+        state.select(Some(app.selected_feed));
+    }
 
     frame.render_stateful_widget(feeds_list, area, &mut state);
 
     // スクロールバーを描画（アイテムが表示領域より多い場合のみ）
     let visible_height = area.height.saturating_sub(2) as usize; // ボーダー分を引く
-    if app.feeds.len() > visible_height {
+    if row_count > visible_height {
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
             .begin_symbol(Some("↑"))
             .end_symbol(Some("↓"))
             .track_symbol(Some("│"))
             .thumb_symbol("█");
 
-        let mut scrollbar_state = ScrollbarState::new(app.feeds.len())
+        let mut scrollbar_state = ScrollbarState::new(row_count)
             .position(app.selected_feed)
             .viewport_content_length(visible_height);
 
@@ -131,10 +162,54 @@ fn render_feeds(frame: &mut Frame, app: &App, area: Rect) {
     }
 }
 
+/// タグ一覧を描画
+fn render_tags(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .tags
+        .iter()
+        .map(|(tag, count)| {
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("#{}", tag.name), Style::default().fg(Color::Green)),
+                Span::styled(
+                    format!(" ({})", count),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+
+    let border_style = if app.focus == Focus::Tags {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default().fg(Color::Gray)
+    };
+
+    let tags_list = List::new(items)
+        .block(
+            Block::default()
+                .title(" Tags ")
+                .borders(Borders::ALL)
+                .border_style(border_style),
+        )
+        .highlight_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .bg(Color::DarkGray),
+        )
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default();
+    if app.source == ArticleSource::Tag {
+        state.select(Some(app.selected_tag));
+    }
+
+    frame.render_stateful_widget(tags_list, area, &mut state);
+}
+
 /// 記事一覧を描画
 fn render_articles(frame: &mut Frame, app: &App, area: Rect) {
-    // 検索モード時は上部に検索入力欄を表示
-    let (search_area, list_area) = if app.search_mode {
+    // 検索モード・タグ入力モード時は上部に入力欄を表示
+    let (search_area, list_area) = if app.search_mode || app.tag_input_mode {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Length(3), Constraint::Min(0)])
@@ -144,13 +219,21 @@ fn render_articles(frame: &mut Frame, app: &App, area: Rect) {
         (None, area)
     };
 
-    // 検索入力欄を描画
+    // 検索入力欄（またはタグ入力欄）を描画
     if let Some(search_area) = search_area {
-        let search_input = Paragraph::new(format!("/{}", app.search_query))
+        let (input, input_title) = if app.tag_input_mode {
+            (
+                format!("#{}", app.tag_input),
+                " Add tags (comma separated) ",
+            )
+        } else {
+            (format!("/{}", app.search_query), " Search ")
+        };
+        let search_input = Paragraph::new(input)
             .style(Style::default().fg(Color::Yellow))
             .block(
                 Block::default()
-                    .title(" Search ")
+                    .title(input_title)
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(Color::Yellow)),
             );
@@ -168,6 +251,16 @@ fn render_articles(frame: &mut Frame, app: &App, area: Rect) {
                 .map(|dt| dt.format("%m/%d").to_string())
                 .unwrap_or_else(|| "-----".to_string());
 
+            // 重複記事は薄く表示し、元の記事には重複記事の数を添える
+            let (title_style, duplicates) = match (article.duplicate_of, article.duplicate_count) {
+                (Some(_), _) => (
+                    Style::default().fg(Color::DarkGray),
+                    " (duplicate)".to_string(),
+                ),
+                (None, 0) => (Style::default(), String::new()),
+                (None, count) => (Style::default(), format!(" (+{})", count)),
+            };
+
             let line = Line::from(vec![
                 Span::styled(
                     read_marker,
@@ -179,7 +272,8 @@ fn render_articles(frame: &mut Frame, app: &App, area: Rect) {
                 ),
                 Span::styled(fav_marker, Style::default().fg(Color::Red)),
                 Span::styled(format!("{} ", date), Style::default().fg(Color::DarkGray)),
-                Span::raw(&article.title),
+                Span::styled(&article.title, title_style),
+                Span::styled(duplicates, Style::default().fg(Color::DarkGray)),
             ]);
 
             ListItem::new(line)
@@ -194,10 +288,19 @@ fn render_articles(frame: &mut Frame, app: &App, area: Rect) {
 
     // タイトルを決定（検索中は検索クエリを表示）
     let title = if app.search_active {
-        format!(" Search: '{}' ({} results) ", app.search_query, app.articles.len())
-    } else if app.feeds.is_empty() {
-        " Articles ".to_string()
-    } else if let Some(feed) = app.feeds.get(app.selected_feed) {
+        format!(
+            " Search: '{}' ({} results) ",
+            app.search_query,
+            app.articles.len()
+        )
+    } else if app.source == ArticleSource::Tag {
+        app.tags
+            .get(app.selected_tag)
+            .map(|(tag, _)| format!(" #{} ", tag.name))
+            .unwrap_or_else(|| " Articles ".to_string())
+    } else if app.source == ArticleSource::Queue {
+        format!(" Read Later ({}) ", app.articles.len())
+    } else if let Some(feed) = app.selected_stored_feed() {
         format!(" {} ", feed.display_name())
     } else {
         " Articles ".to_string()
@@ -249,7 +352,8 @@ fn render_articles(frame: &mut Frame, app: &App, area: Rect) {
 
 /// フッター（ヘルプ）を描画
 fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
-    let help_text = "q:Quit j/k:Move g/G:Top/End Tab:Switch r:Read f:Fav o:Open p:Preview R:Refresh";
+    let help_text =
+        "q:Quit j/k:Move g/G:Top/End Tab:Switch r:Read f:Fav t:Tag L:Later o:Open p:Preview P:Pause R:Refresh";
 
     // 更新中の場合は進捗を表示
     let status = if app.is_fetching {
@@ -281,7 +385,6 @@ fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(footer, area);
 }
 
-
 /// プレビュー画面を描画（オーバーレイ）
 fn render_preview(frame: &mut Frame, app: &mut App) {
     let area = frame.area();
@@ -330,7 +433,7 @@ fn render_preview(frame: &mut Frame, app: &mut App) {
         .iter()
         .skip(app.preview_scroll)
         .take(visible_height)
-        .map(|s| Line::from(s.as_str()))
+        .map(|s| highlight_line(s, &app.preview_highlights))
         .collect();
 
     let content_paragraph = Paragraph::new(content_lines)
@@ -371,10 +474,34 @@ fn render_preview(frame: &mut Frame, app: &mut App) {
         app.preview_scroll + 1,
         app.preview_content.len().max(1)
     );
-    let help_text = "Esc/p/q:Close  j/k:Scroll  PgUp/Dn  ^u/d:Half  g/G:Top/End  o:Open";
-    
+    let help_text = "Esc/p/q:Close  j/k:Scroll  PgUp/Dn  ^u/d:Half  g/G:Top/End  o:Open  n:Note";
+
     let footer = Paragraph::new(format!("{}{}", help_text, scroll_info))
         .style(Style::default().fg(Color::DarkGray))
         .block(Block::default().borders(Borders::ALL));
     frame.render_widget(footer, chunks[2]);
 }
+
+/// ハイライトしたテキストを含む行を強調表示する
+///
+/// プレビューは80桁で折り返されるため、1行に収まるハイライトのみ強調されます。
+fn highlight_line<'a>(line: &'a str, highlights: &[String]) -> Line<'a> {
+    for text in highlights {
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        if let Some(start) = line.find(text) {
+            let end = start + text.len();
+            return Line::from(vec![
+                Span::raw(&line[..start]),
+                Span::styled(
+                    &line[start..end],
+                    Style::default().fg(Color::Black).bg(Color::Yellow),
+                ),
+                Span::raw(&line[end..]),
+            ]);
+        }
+    }
+    Line::from(line)
+}